@@ -0,0 +1,57 @@
+//! Measures the time it takes to read a small document, as services that read many snippets
+//! do, with and without the small document path.
+//!
+//! ```text
+//! cargo bench --bench small_documents
+//! ```
+
+use std::{hint::black_box, time::Instant};
+
+use facet::Facet;
+use facet_yaml::DeserializeOptions;
+
+#[derive(Facet)]
+#[allow(dead_code)]
+struct Event {
+    id: u64,
+    kind: String,
+    source: Source,
+    tags: Vec<String>,
+}
+
+#[derive(Facet)]
+#[allow(dead_code)]
+struct Source {
+    host: String,
+    port: u16,
+    #[facet(yaml(alias = "zone"))]
+    region: String,
+}
+
+const SNIPPET: &str =
+    "id: 42\nkind: deploy\nsource:\n  host: web-1\n  port: 8080\n  region: eu\ntags: [a, b]\n";
+
+/// The fastest of several runs, in nanoseconds per document.
+fn measure(options: &DeserializeOptions) -> f64 {
+    const DOCUMENTS: u32 = 5_000;
+    (0..50)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..DOCUMENTS {
+                let event: Event =
+                    facet_yaml::from_str_with_options(black_box(SNIPPET), options).unwrap();
+                black_box(event);
+            }
+            start.elapsed().as_nanos() as f64 / f64::from(DOCUMENTS)
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+fn main() {
+    let small = measure(&DeserializeOptions::new());
+    // Every document is over the threshold, so keys are remembered
+    let large = measure(&DeserializeOptions::new().small_document_threshold(0));
+    println!("Small document path: {small:.0} ns/document");
+    println!("Regular path: {large:.0} ns/document");
+    println!("Speedup: {:.1}%", (large / small - 1.0) * 100.0);
+}
@@ -0,0 +1,242 @@
+//! YAML specific attributes, written as `#[facet(yaml(...))]`.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use facet_core::{Def, Field, FieldAttribute, FieldFlags, Shape, ShapeAttribute};
+
+#[cfg(feature = "deserialize")]
+use crate::RenameRule;
+
+/// Options from the `#[facet(yaml(...))]` attributes of a field.
+///
+/// Both the deserializer and the serializer read all of them, so an attribute meant for one
+/// isn't rejected as unknown by the other.
+#[derive(Debug, Default)]
+#[cfg_attr(
+    not(all(feature = "deserialize", feature = "serialize")),
+    allow(dead_code)
+)]
+pub(crate) struct FieldAttributes {
+    /// Dotted path to the value in the YAML document, relative to the mapping of the struct.
+    pub path: Option<Vec<String>>,
+    /// Other keys the field is read from, such as its name in older versions of a format.
+    pub aliases: Vec<String>,
+    /// Whether string values are always quoted when serialized.
+    pub quote: bool,
+    /// Whether collections are written in flow style when serialized, e.g. `[1, 2]`.
+    pub flow: bool,
+    /// Whether the field is never read from documents, and set to its default instead.
+    pub skip_deserializing: bool,
+    /// Whether documents must have the field, even if it or its struct has a default.
+    pub required: bool,
+    /// Whether the field is left out when serialized if it's an empty collection.
+    pub skip_empty: bool,
+    /// Whether the value is its own YAML document, embedded as a string.
+    pub embedded: bool,
+    /// Name of the group of fields that exclude each other, of which documents may only have
+    /// one.
+    pub exclusive: Option<String>,
+    /// Key of the items of a sequence that a map field is read from, each item being keyed by
+    /// its value of that key, and that the map is written back as.
+    pub key: Option<String>,
+    /// Naming convention of the keys of the structs in the field, including those nested
+    /// further, or `Some(None)` when they're read by their names only.
+    #[cfg(feature = "deserialize")]
+    pub rename_all: Option<Option<RenameRule>>,
+}
+
+impl FieldAttributes {
+    /// Whether a field has any YAML attribute, without reading them.
+    #[cfg(feature = "deserialize")]
+    pub fn any(field: &Field) -> bool {
+        field.attributes.iter().any(|attribute| {
+            #[allow(irrefutable_let_patterns)]
+            let FieldAttribute::Arbitrary(text) = attribute else {
+                return false;
+            };
+            text.trim_start().starts_with("yaml")
+        })
+    }
+
+    /// Read the YAML attributes of a field.
+    pub fn of(field: &Field) -> Result<Self, String> {
+        let mut attributes = Self::default();
+
+        for attribute in field.attributes {
+            #[allow(irrefutable_let_patterns)]
+            let FieldAttribute::Arbitrary(text) = attribute else {
+                continue;
+            };
+            let Some(arguments) = yaml_arguments(text) else {
+                continue;
+            };
+
+            for (key, value) in arguments {
+                match (key.as_str(), value) {
+                    ("path", Some(path)) => {
+                        if path.is_empty() || path.split('.').any(str::is_empty) {
+                            return Err(format!(
+                                "Invalid yaml path `{path}` on field '{}'",
+                                field.name
+                            ));
+                        }
+                        attributes.path = Some(path.split('.').map(ToString::to_string).collect());
+                    }
+                    ("alias", Some(alias)) => {
+                        if alias.is_empty() {
+                            return Err(format!("Empty yaml alias on field '{}'", field.name));
+                        }
+                        attributes.aliases.push(alias);
+                    }
+                    ("quote", None) => attributes.quote = true,
+                    ("flow", None) => attributes.flow = true,
+                    ("skip_empty", None) => attributes.skip_empty = true,
+                    ("embedded", None) => attributes.embedded = true,
+                    ("skip_deserializing", None) => {
+                        if !field.flags.contains(FieldFlags::DEFAULT) {
+                            return Err(format!(
+                                "Field '{}' is skipped when deserializing, so it needs \
+                                 `#[facet(default)]`",
+                                field.name
+                            ));
+                        }
+                        attributes.skip_deserializing = true;
+                    }
+                    ("required", None) => attributes.required = true,
+                    ("exclusive", Some(group)) => {
+                        if group.is_empty() {
+                            return Err(format!(
+                                "Empty yaml exclusive group on field '{}'",
+                                field.name
+                            ));
+                        }
+                        attributes.exclusive = Some(group);
+                    }
+                    ("key", Some(key)) => {
+                        if key.is_empty() {
+                            return Err(format!("Empty yaml key on field '{}'", field.name));
+                        }
+                        if !matches!(field.shape().def, Def::Map(_)) {
+                            return Err(format!(
+                                "Field '{}' is read from a keyed list, so it needs to be a map",
+                                field.name
+                            ));
+                        }
+                        attributes.key = Some(key);
+                    }
+                    ("rename_all", Some(name)) => {
+                        #[cfg(feature = "deserialize")]
+                        {
+                            let rule = RenameRule::from_name(&name);
+                            if rule.is_none() && name != "none" {
+                                return Err(format!(
+                                    "Unknown naming convention `{name}` on field '{}'",
+                                    field.name
+                                ));
+                            }
+                            attributes.rename_all = Some(rule);
+                        }
+                        // Only the deserializer reads keys by their convention
+                        #[cfg(not(feature = "deserialize"))]
+                        let _ = name;
+                    }
+                    (key, _) => {
+                        return Err(format!(
+                            "Unknown yaml attribute `{key}` on field '{}'",
+                            field.name
+                        ));
+                    }
+                }
+            }
+        }
+
+        if attributes.required && attributes.skip_deserializing {
+            return Err(format!(
+                "Field '{}' can't be both required and skipped when deserializing",
+                field.name
+            ));
+        }
+
+        Ok(attributes)
+    }
+}
+
+/// Name of the key that holds the name of the variant of an internally tagged enum, from its
+/// `#[facet(tag = "...")]` attribute.
+pub(crate) fn enum_tag(shape: &Shape) -> Option<&'static str> {
+    shape.attributes.iter().find_map(|attribute| {
+        let ShapeAttribute::Arbitrary(text) = attribute else {
+            return None;
+        };
+        text.trim()
+            .strip_prefix("tag")?
+            .trim_start()
+            .strip_prefix('=')?
+            .trim()
+            .strip_prefix('"')?
+            .strip_suffix('"')
+    })
+}
+
+/// Whether an enum is untagged, from its `#[facet(untagged)]` attribute, so its variants are
+/// written as their content only.
+pub(crate) fn is_untagged(shape: &Shape) -> bool {
+    shape.attributes.iter().any(|attribute| {
+        matches!(attribute, ShapeAttribute::Arbitrary(text) if text.trim() == "untagged")
+    })
+}
+
+/// Parse the arguments of a `yaml(key = "value", flag)` attribute, or return `None` if it isn't
+/// a YAML attribute.
+///
+/// The text comes from the derive macro, so it may have whitespace between the tokens.
+fn yaml_arguments(text: &str) -> Option<Vec<(String, Option<String>)>> {
+    let text = text.trim();
+    let inner = text
+        .strip_prefix("yaml")?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')?;
+
+    let mut arguments = Vec::new();
+    let mut chars = inner.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace() || *c == ',').is_some() {}
+
+        let mut key = String::new();
+        while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+            key.push(c);
+        }
+        if key.is_empty() {
+            break;
+        }
+
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.next_if_eq(&'=').is_none() {
+            arguments.push((key, None));
+            continue;
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+        let mut value = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => break,
+                    '\\' => value.extend(chars.next()),
+                    c => value.push(c),
+                }
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| *c != ',' && !c.is_whitespace()) {
+                value.push(c);
+            }
+        }
+        arguments.push((key, Some(value)));
+    }
+
+    Some(arguments)
+}
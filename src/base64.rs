@@ -0,0 +1,58 @@
+//! Encode and decode the base64 text of `!!binary` scalars.
+
+#[cfg(feature = "serialize")]
+use alloc::string::String;
+#[cfg(feature = "deserialize")]
+use alloc::vec::Vec;
+
+/// The characters of the standard base64 alphabet, by their value.
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encode bytes as base64 text, padded with `=`.
+#[cfg(feature = "serialize")]
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (index, byte)| {
+            group | (u32::from(*byte) << (16 - 8 * index))
+        });
+        // A chunk of n bytes fills n + 1 characters, and the rest is padding
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let value = (group >> (18 - 6 * index)) & 0x3f;
+                text.push(char::from(ALPHABET[value as usize]));
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+/// Decode base64 text, ignoring the whitespace that block scalars break it with, or get `None`
+/// if it isn't valid base64.
+#[cfg(feature = "deserialize")]
+pub(crate) fn decode(text: &str) -> Option<Vec<u8>> {
+    let text: Vec<u8> = text.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    if !text.len().is_multiple_of(4) {
+        return None;
+    }
+    let data = text
+        .strip_suffix(b"==")
+        .or_else(|| text.strip_suffix(b"="))
+        .unwrap_or(&text);
+
+    let mut bytes = Vec::with_capacity(data.len() / 4 * 3);
+    for chunk in data.chunks(4) {
+        let mut group = 0u32;
+        for (index, c) in chunk.iter().enumerate() {
+            let value = ALPHABET.iter().position(|a| a == c)?;
+            group |= (value as u32) << (18 - 6 * index);
+        }
+        // n characters hold n - 1 whole bytes
+        for index in 0..chunk.len() - 1 {
+            bytes.push((group >> (16 - 8 * index)) as u8);
+        }
+    }
+    Some(bytes)
+}
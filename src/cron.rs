@@ -0,0 +1,208 @@
+//! Cron expressions that are validated while a document is loaded.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use facet::Facet;
+
+/// A cron schedule expression, such as `*/15 9-17 * * MON-FRI`.
+///
+/// Five fields (minute, hour, day of month, month, day of week) are accepted, optionally preceded
+/// by a seconds field, as well as the `@yearly`, `@annually`, `@monthly`, `@weekly`, `@daily`,
+/// `@midnight` and `@hourly` shorthands. Each field can be a `*`, a value, a range (`1-5`) or a
+/// list of those (`1,3,5`), optionally followed by a step (`*/10`). Months and days of the week
+/// can also be written as their three-letter English names.
+///
+/// When used as a field in a deserialized type the expression is checked while loading, so a
+/// typo is reported with its location in the document instead of when the scheduler runs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Facet)]
+#[facet(transparent)]
+pub struct CronExpr(String);
+
+impl CronExpr {
+    /// Parse and validate a cron expression.
+    pub fn parse(expr: &str) -> Result<Self, CronError> {
+        let expr = expr.trim();
+
+        if let Some(shorthand) = expr.strip_prefix('@') {
+            return match shorthand {
+                "yearly" | "annually" | "monthly" | "weekly" | "daily" | "midnight" | "hourly" => {
+                    Ok(Self(expr.to_string()))
+                }
+                _ => Err(CronError(format!("unknown cron shorthand `{expr}`"))),
+            };
+        }
+
+        let parts: Vec<&str> = expr.split_whitespace().collect();
+        let fields = match parts.len() {
+            5 => &FIELDS[1..],
+            6 => &FIELDS[..],
+            len => {
+                return Err(CronError(format!(
+                    "expected 5 or 6 fields in cron expression `{expr}`, found {len}"
+                )));
+            }
+        };
+
+        for (part, field) in parts.iter().zip(fields) {
+            field.validate(part)?;
+        }
+
+        Ok(Self(parts.join(" ")))
+    }
+
+    /// The expression as it was written, with the whitespace between fields normalized.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl core::str::FromStr for CronExpr {
+    type Err = CronError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl core::fmt::Display for CronExpr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Error from parsing an invalid cron expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronError(String);
+
+impl core::fmt::Display for CronError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl core::error::Error for CronError {}
+
+/// Description of a single field of a cron expression.
+struct CronField {
+    /// Name of the field, used in errors.
+    name: &'static str,
+    /// Smallest allowed value.
+    min: u32,
+    /// Largest allowed value.
+    max: u32,
+    /// Names that can be used instead of numbers, starting at `min`.
+    aliases: &'static [&'static str],
+}
+
+/// All fields, starting with the optional seconds field.
+const FIELDS: [CronField; 6] = [
+    CronField {
+        name: "second",
+        min: 0,
+        max: 59,
+        aliases: &[],
+    },
+    CronField {
+        name: "minute",
+        min: 0,
+        max: 59,
+        aliases: &[],
+    },
+    CronField {
+        name: "hour",
+        min: 0,
+        max: 23,
+        aliases: &[],
+    },
+    CronField {
+        name: "day of month",
+        min: 1,
+        max: 31,
+        aliases: &[],
+    },
+    CronField {
+        name: "month",
+        min: 1,
+        max: 12,
+        aliases: &[
+            "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+        ],
+    },
+    CronField {
+        name: "day of week",
+        // Both 0 and 7 are Sunday
+        min: 0,
+        max: 7,
+        aliases: &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"],
+    },
+];
+
+impl CronField {
+    /// Check that a field only contains values, ranges and steps within bounds.
+    fn validate(&self, field: &str) -> Result<(), CronError> {
+        for item in field.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, Some(step)),
+                None => (item, None),
+            };
+
+            if let Some(step) = step {
+                match step.parse::<u32>() {
+                    Ok(step) if step > 0 => {}
+                    _ => return Err(self.error(field, &format!("invalid step `{step}`"))),
+                }
+            }
+
+            match range {
+                "*" | "?" => {}
+                _ => match range.split_once('-') {
+                    Some((start, end)) => {
+                        let start = self.value(field, start)?;
+                        let end = self.value(field, end)?;
+                        if start > end {
+                            return Err(
+                                self.error(field, &format!("range `{range}` starts after it ends"))
+                            );
+                        }
+                    }
+                    None => {
+                        self.value(field, range)?;
+                    }
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parse a single value, either a number or a name.
+    fn value(&self, field: &str, value: &str) -> Result<u32, CronError> {
+        if let Some(index) = self
+            .aliases
+            .iter()
+            .position(|alias| alias.eq_ignore_ascii_case(value))
+        {
+            return Ok(self.min + index as u32);
+        }
+
+        match value.parse::<u32>() {
+            Ok(number) if (self.min..=self.max).contains(&number) => Ok(number),
+            Ok(_) => Err(self.error(
+                field,
+                &format!(
+                    "`{value}` is out of range, expected {}-{}",
+                    self.min, self.max
+                ),
+            )),
+            Err(_) => Err(self.error(field, &format!("invalid value `{value}`"))),
+        }
+    }
+
+    /// Create an error for this field.
+    fn error(&self, field: &str, reason: &str) -> CronError {
+        CronError(format!("invalid {} field `{field}`: {reason}", self.name))
+    }
+}
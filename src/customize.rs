@@ -0,0 +1,139 @@
+//! Types that choose how they're written in YAML documents, instead of following their shape.
+
+use alloc::string::String;
+
+use facet_core::{Facet, Shape};
+use facet_reflect::Partial;
+#[cfg(feature = "serialize")]
+use facet_reflect::Peek;
+
+use crate::{DeserializeError, Value};
+
+/// How a type is written in YAML documents, and read back from them, when it's registered with
+/// [`DeserializeOptions::customize`](crate::DeserializeOptions::customize) and
+/// [`SerializeOptions::customize`](crate::SerializeOptions::customize).
+///
+/// Types are read and written by their shape otherwise, so a struct is always a mapping of its
+/// fields. This is the way out for types whose documents look different, such as a protobuf
+/// `Duration` that is written as `1.5s` rather than as its `seconds` and `nanos`, without writing
+/// a deserializer for the types that hold it. The type is converted from and to a [`Value`],
+/// which can be a scalar, a sequence or a mapping, and can have a [`TAG`](Self::TAG).
+///
+/// ```
+/// use facet::Facet;
+/// use facet_yaml::{DeserializeOptions, Value, YamlCustomize};
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Duration {
+///     seconds: i64,
+///     nanos: i32,
+/// }
+///
+/// impl YamlCustomize for Duration {
+///     fn to_yaml(&self) -> Value {
+///         let seconds = self.seconds as f64 + f64::from(self.nanos) / 1e9;
+///         Value::String(format!("{seconds}s"))
+///     }
+///
+///     fn from_yaml(value: Value) -> Result<Self, String> {
+///         let seconds = value
+///             .as_str()
+///             .and_then(|text| text.strip_suffix('s'))
+///             .and_then(|seconds| seconds.parse::<f64>().ok())
+///             .ok_or("Expected a duration such as `1.5s`")?;
+///         Ok(Duration {
+///             seconds: seconds.trunc() as i64,
+///             nanos: (seconds.fract() * 1e9).round() as i32,
+///         })
+///     }
+/// }
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Retry {
+///     attempts: u32,
+///     backoff: Duration,
+/// }
+///
+/// let options = DeserializeOptions::new().customize::<Duration>();
+/// let retry: Retry =
+///     facet_yaml::from_str_with_options("attempts: 3\nbackoff: 1.5s", &options).unwrap();
+/// assert_eq!(retry.backoff, Duration { seconds: 1, nanos: 500_000_000 });
+///
+/// # #[cfg(feature = "serialize")] {
+/// let options = facet_yaml::SerializeOptions::new().customize::<Duration>();
+/// let yaml = facet_yaml::to_string_with_options(&retry, &options).unwrap();
+/// assert_eq!(yaml, "---\nattempts: 3\nbackoff: 1.5s");
+/// # }
+/// ```
+pub trait YamlCustomize: Sized + for<'facet> Facet<'facet> {
+    /// The tag the nodes of the type are written with, such as `!duration`, or `None` to write
+    /// them without one, which is the default.
+    ///
+    /// Collections with a tag are written in flow style, e.g. `!range {start: 1, end: 5}`. Nodes
+    /// are read with the tag or without any, but not with another one.
+    const TAG: Option<&'static str> = None;
+
+    /// Convert the value to the node it's written as.
+    fn to_yaml(&self) -> Value;
+
+    /// Convert a node back to a value, or return why it can't be, which is reported where the
+    /// node is in the document.
+    fn from_yaml(value: Value) -> Result<Self, String>;
+}
+
+/// A registered [`YamlCustomize`], without its type.
+#[derive(Clone, Copy)]
+pub(crate) struct Customization {
+    /// The shape of the type.
+    pub shape: &'static Shape,
+    /// The tag of the nodes of the type.
+    pub tag: Option<&'static str>,
+    /// Read the value from a node into a partial value of the type.
+    pub read: for<'facet> fn(&mut Partial<'facet>, Value) -> Result<(), DeserializeError>,
+    /// Convert a value of the type to its node, or `None` if it's of another type.
+    #[cfg(feature = "serialize")]
+    pub write: for<'mem, 'facet> fn(Peek<'mem, 'facet>) -> Option<Value>,
+}
+
+impl Customization {
+    /// Get the customization of a type.
+    pub fn of<T: YamlCustomize>() -> Self {
+        Self {
+            shape: T::SHAPE,
+            tag: T::TAG,
+            read: read::<T>,
+            #[cfg(feature = "serialize")]
+            write: write::<T>,
+        }
+    }
+}
+
+impl core::fmt::Debug for Customization {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Customization")
+            .field("shape", &self.shape.type_identifier)
+            .field("tag", &self.tag)
+            .finish()
+    }
+}
+
+/// Read a customized type from a node.
+fn read<T: YamlCustomize>(wip: &mut Partial<'_>, value: Value) -> Result<(), DeserializeError> {
+    let value = T::from_yaml(value).map_err(DeserializeError::from)?;
+    wip.set(value).map_err(DeserializeError::reflect)?;
+    Ok(())
+}
+
+/// Convert a customized type to a node.
+#[cfg(feature = "serialize")]
+fn write<T: YamlCustomize>(peek: Peek<'_, '_>) -> Option<Value> {
+    peek.get::<T>().ok().map(T::to_yaml)
+}
+
+/// Find the customization registered for a shape.
+pub(crate) fn find(customized: &[Customization], shape: &Shape) -> Option<Customization> {
+    customized
+        .iter()
+        .find(|customization| customization.shape.id == shape.id)
+        .copied()
+}
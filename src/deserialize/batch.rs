@@ -0,0 +1,185 @@
+//! Deserialize many YAML files at once, with a single report of what went wrong.
+
+use alloc::{string::String, vec::Vec};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use facet_core::Facet;
+
+use super::{DeserializeError, DeserializeOptions, from_str_with_options};
+
+/// Deserializes every file in `paths` into a value of type `T`, with the same options.
+///
+/// Every file is read and deserialized even when some of them fail, so the report lists all
+/// the problems at once. Its [`Display`](core::fmt::Display) implementation prints one line per
+/// error and warning, prefixed with the file name, which is what validation tools usually show.
+///
+/// ```no_run
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Manifest {
+///     name: String,
+/// }
+///
+/// let report = facet_yaml::from_paths::<Manifest, _>(
+///     ["deploy/web.yaml", "deploy/worker.yaml"],
+///     &facet_yaml::DeserializeOptions::default(),
+/// );
+/// if !report.is_ok() {
+///     eprint!("{report}");
+///     std::process::exit(1);
+/// }
+/// for (path, manifest) in report.values() {
+///     println!("{}: {}", path.display(), manifest.name);
+/// }
+/// ```
+pub fn from_paths<T, P>(
+    paths: impl IntoIterator<Item = P>,
+    options: &DeserializeOptions,
+) -> BatchReport<T>
+where
+    T: for<'facet> Facet<'facet>,
+    P: AsRef<Path>,
+{
+    let files = paths
+        .into_iter()
+        .map(|path| {
+            let path = path.as_ref().to_path_buf();
+            let mut warnings = Vec::new();
+
+            if !matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("yaml" | "yml")
+            ) {
+                warnings.push("File doesn't have a .yaml or .yml extension".into());
+            }
+
+            let result = match fs::read_to_string(&path) {
+                Ok(contents) => {
+                    // Only comments or whitespace
+                    let empty = contents.trim_start_matches('\u{feff}').lines().all(|line| {
+                        let line = line.trim();
+                        line.is_empty() || line.starts_with('#')
+                    });
+                    if empty {
+                        warnings.push("File is empty, the default value is used".into());
+                    }
+                    from_str_with_options(&contents, options).map_err(FileError::Deserialize)
+                }
+                Err(err) => Err(FileError::Io(err)),
+            };
+
+            FileReport {
+                path,
+                result,
+                warnings,
+            }
+        })
+        .collect();
+
+    BatchReport { files }
+}
+
+/// The outcome of [`from_paths`], with one entry per file in the order they were given.
+#[derive(Debug)]
+pub struct BatchReport<T> {
+    /// What happened to each file.
+    pub files: Vec<FileReport<T>>,
+}
+
+impl<T> BatchReport<T> {
+    /// Whether every file was deserialized.
+    pub fn is_ok(&self) -> bool {
+        self.files.iter().all(|file| file.result.is_ok())
+    }
+
+    /// The values of the files that were deserialized.
+    pub fn values(&self) -> impl Iterator<Item = (&Path, &T)> {
+        self.files
+            .iter()
+            .filter_map(|file| Some((file.path.as_path(), file.result.as_ref().ok()?)))
+    }
+
+    /// The errors of the files that failed.
+    pub fn errors(&self) -> impl Iterator<Item = (&Path, &FileError)> {
+        self.files
+            .iter()
+            .filter_map(|file| Some((file.path.as_path(), file.result.as_ref().err()?)))
+    }
+
+    /// The warnings of every file.
+    pub fn warnings(&self) -> impl Iterator<Item = (&Path, &str)> {
+        self.files.iter().flat_map(|file| {
+            file.warnings
+                .iter()
+                .map(|warning| (file.path.as_path(), warning.as_str()))
+        })
+    }
+
+    /// Get the values of all the files, or the report if any of them failed.
+    pub fn into_values(self) -> Result<Vec<(PathBuf, T)>, Self> {
+        if !self.is_ok() {
+            return Err(self);
+        }
+        Ok(self
+            .files
+            .into_iter()
+            .filter_map(|file| Some((file.path, file.result.ok()?)))
+            .collect())
+    }
+}
+
+impl<T> core::fmt::Display for BatchReport<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for file in &self.files {
+            if let Err(err) = &file.result {
+                writeln!(f, "{}: {err}", file.path.display())?;
+            }
+            for warning in &file.warnings {
+                writeln!(f, "{}: warning: {warning}", file.path.display())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What happened to one file of a [`BatchReport`].
+#[derive(Debug)]
+pub struct FileReport<T> {
+    /// The file, as it was given.
+    pub path: PathBuf,
+    /// The deserialized value, or why there isn't one.
+    pub result: Result<T, FileError>,
+    /// Problems that didn't stop the file from being deserialized.
+    pub warnings: Vec<String>,
+}
+
+/// Error from reading and deserializing a YAML file.
+#[derive(Debug)]
+pub enum FileError {
+    /// The file couldn't be read.
+    Io(std::io::Error),
+    /// The contents of the file couldn't be deserialized.
+    Deserialize(DeserializeError),
+}
+
+impl core::fmt::Display for FileError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FileError::Io(err) => write!(f, "Failed to read file: {err}"),
+            FileError::Deserialize(err) => core::fmt::Display::fmt(err, f),
+        }
+    }
+}
+
+impl core::error::Error for FileError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            FileError::Io(err) => Some(err),
+            FileError::Deserialize(err) => Some(err),
+        }
+    }
+}
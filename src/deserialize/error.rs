@@ -1,37 +1,543 @@
 //! Errors from parsing YAML documents.
 
 use alloc::{
+    boxed::Box,
     format,
     string::{String, ToString},
+    vec::Vec,
 };
-use facet_reflect::ReflectError;
+use facet_core::{Shape, StructType};
+use facet_reflect::{Partial, ReflectError};
+use yaml_rust2::ScanError;
 
-/// Any error
+use super::node::Span;
+use crate::ErrorCode;
+
+/// Error from deserializing a YAML document.
 #[derive(Debug, Clone)]
-pub struct AnyErr(pub String);
+pub struct DeserializeError {
+    /// What kind of error it is.
+    code: ErrorCode,
+    /// What went wrong.
+    message: Box<str>,
+    /// Where in the value it went wrong, empty for the root.
+    path: String,
+    /// Where in the document it went wrong.
+    span: Option<Span>,
+    /// The type that was being deserialized.
+    expected: Option<Box<str>>,
+    /// The state of the fields of the struct that failed, if it was a struct.
+    fields: Option<Box<FieldReport>>,
+    /// The line of the document the error is on, shown below the message.
+    #[cfg(feature = "std")]
+    source_line: Option<Box<str>>,
+}
+
+impl DeserializeError {
+    /// Create an error that isn't located yet.
+    ///
+    /// Text that isn't changed afterwards is kept in boxed strings, so the `Result`s the error
+    /// is returned in stay small.
+    pub(crate) fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into().into(),
+            path: String::new(),
+            span: None,
+            expected: None,
+            fields: None,
+            #[cfg(feature = "std")]
+            source_line: None,
+        }
+    }
+
+    /// Create an error for a deserialization that the options stopped.
+    pub(crate) fn cancelled() -> Self {
+        Self::new(ErrorCode::Cancelled, "Deserialization was cancelled")
+    }
+
+    /// Create an error for a failure of `facet-reflect` while building the value.
+    pub(crate) fn reflect(err: ReflectError) -> Self {
+        let code = match err {
+            ReflectError::UninitializedField { .. } => ErrorCode::MissingField,
+            _ => ErrorCode::Reflect,
+        };
+        Self::new(code, err.to_string())
+    }
+
+    /// Stable identifier of the kind of error, e.g. [`ErrorCode::UnknownField`] for a key that
+    /// isn't a field of the struct.
+    ///
+    /// ```
+    /// use facet::Facet;
+    /// use facet_yaml::ErrorCode;
+    ///
+    /// #[derive(Debug, Facet)]
+    /// struct Server {
+    ///     port: u16,
+    /// }
+    ///
+    /// let err = facet_yaml::from_str::<Server>("port: 70000").unwrap_err();
+    /// assert_eq!(err.code(), ErrorCode::OutOfRange);
+    /// assert_eq!(err.code().to_string(), "E004");
+    /// ```
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    /// Description of what went wrong, without its location.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Path to the value that failed, such as `servers[2].port`, or an empty string when the
+    /// document itself failed.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Location in the document of the node that failed, if known.
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    /// Name of the Rust type that the failing node was deserialized into, if known.
+    pub fn expected(&self) -> Option<&str> {
+        self.expected.as_deref()
+    }
+
+    /// Which fields of the innermost struct that failed were set from the document, set from
+    /// their defaults, or missing, when the struct couldn't be completed.
+    ///
+    /// ```
+    /// use facet::Facet;
+    /// use facet_yaml::FieldState;
+    ///
+    /// #[derive(Debug, Facet)]
+    /// struct Server {
+    ///     host: String,
+    ///     port: u16,
+    ///     #[facet(default)]
+    ///     tls: bool,
+    /// }
+    ///
+    /// let err = facet_yaml::from_str::<Server>("host: example.com").unwrap_err();
+    /// let fields = err.fields().unwrap();
+    /// assert_eq!(fields.state("port"), Some(FieldState::Missing));
+    /// assert_eq!(fields.to_string(), "Server { host: set, port: missing, tls: default }");
+    /// ```
+    pub fn fields(&self) -> Option<&FieldReport> {
+        self.fields.as_deref()
+    }
+
+    /// Render the error as a JSON object, for tools that consume errors programmatically.
+    ///
+    /// The object has the keys `code`, `message`, `path`, `line`, `column`, `offset` and
+    /// `expected`, where the location and the expected type are `null` when unknown.
+    pub fn to_json(&self) -> String {
+        let (line, column, offset) = match self.span {
+            Some(span) => (
+                span.line.to_string(),
+                span.column.to_string(),
+                span.offset.to_string(),
+            ),
+            None => ("null".into(), "null".into(), "null".into()),
+        };
+        let expected = match &self.expected {
+            Some(expected) => json_string(expected),
+            None => "null".into(),
+        };
+
+        format!(
+            r#"{{"code":"{}","message":{},"path":{},"line":{line},"column":{column},"offset":{offset},"expected":{expected}}}"#,
+            self.code,
+            json_string(&self.message),
+            json_string(&self.path),
+        )
+    }
+
+    /// Render the error as a GitHub Actions workflow command, so it's shown as an annotation on
+    /// `file` in pull requests.
+    ///
+    /// ```text
+    /// ::error file=config.yaml,line=4,col=11::`servers[2].port`: Value 70000 out of range for u16
+    /// ```
+    pub fn to_github_annotation(&self, file: &str) -> String {
+        let mut annotation = format!("::error file={}", github_property(file));
+        if let Some(span) = self.span {
+            annotation += &format!(",line={},col={}", span.line, span.column);
+        }
+
+        let message = if self.path.is_empty() {
+            github_data(&self.message)
+        } else {
+            github_data(&format!("`{}`: {}", self.path, self.message))
+        };
+        annotation += &format!("::{message}");
+        annotation
+    }
+
+    /// Render the error over several lines, for people reading it in a terminal.
+    ///
+    /// Along with the code and the location, it shows the type that was expected, the
+    /// [`fields`](Self::fields) of a struct that couldn't be completed, and a hint for some kinds
+    /// of errors. With `std`, errors of documents that were deserialized from a string also show
+    /// the line they're on, with a caret under the column:
+    ///
+    /// ```text
+    /// E004: Value 70000 out of range for u16
+    ///  --> `servers[1].port` (line 5, column 11)
+    ///   |
+    /// 5 |     port: 70000
+    ///   |           ^
+    ///   = expected: u16
+    ///   = help: use a value that fits in the type, or a larger type
+    /// ```
+    ///
+    /// [`Display`](core::fmt::Display) renders the first two lines as a single one instead.
+    pub fn fmt_verbose(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)?;
+
+        #[cfg(feature = "std")]
+        let snippet = self.source_line.as_deref().zip(self.span);
+        #[cfg(not(feature = "std"))]
+        let snippet: Option<(&str, Span)> = None;
+        let gutter = match snippet {
+            Some((_, span)) => " ".repeat(span.line.to_string().len()),
+            None => String::new(),
+        };
+
+        if !self.path.is_empty() || self.span.is_some() {
+            write!(f, "\n{gutter}-->")?;
+            self.fmt_location(f)?;
+        }
+        if let Some((line, span)) = snippet {
+            // Tabs are kept so the caret lines up however wide they're shown
+            let indent: String = line
+                .chars()
+                .take(span.column - 1)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            write!(
+                f,
+                "\n{gutter} |\n{} | {line}\n{gutter} | {indent}^",
+                span.line
+            )?;
+        }
+        if let Some(expected) = &self.expected {
+            write!(f, "\n{gutter} = expected: {expected}")?;
+        }
+        if let Some(fields) = &self.fields {
+            write!(f, "\n{gutter} = fields: {fields}")?;
+        }
+        if let Some(help) = help(self.code) {
+            write!(f, "\n{gutter} = help: {help}")?;
+        }
+        Ok(())
+    }
+
+    /// Wrap the error so it's displayed with [`fmt_verbose`](Self::fmt_verbose).
+    ///
+    /// ```
+    /// use facet::Facet;
+    ///
+    /// #[derive(Debug, Facet)]
+    /// struct Server {
+    ///     port: u16,
+    /// }
+    ///
+    /// let err = facet_yaml::from_str::<Server>("port: 70000").unwrap_err();
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "Value 70000 out of range for u16 at `port` (line 1, column 7)"
+    /// );
+    /// assert!(err.verbose().to_string().starts_with("E004: Value 70000 out of range for u16\n"));
+    /// ```
+    pub fn verbose(&self) -> impl core::fmt::Display + '_ {
+        Verbose(self)
+    }
+
+    /// Write where the error happened, after a space, if it's known.
+    fn fmt_location(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match (self.path.is_empty(), self.span) {
+            (false, Some(span)) => write!(f, " `{}` ({span})", self.path),
+            (false, None) => write!(f, " `{}`", self.path),
+            (true, Some(span)) => write!(f, " {span}"),
+            (true, None) => Ok(()),
+        }
+    }
+
+    /// Set where in the document the error happened.
+    pub(crate) fn at(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Remember the node where the error happened, unless a more specific one was already set.
+    pub(crate) fn located(mut self, span: Span, shape: &Shape) -> Self {
+        if self.span.is_none() {
+            self.span = Some(span);
+            self.expected = Some(shape.to_string().into());
+        }
+        self
+    }
+
+    /// Remember the state of the fields of the struct that failed, unless an inner struct
+    /// already failed.
+    pub(crate) fn with_fields(mut self, fields: FieldReport) -> Self {
+        if self.fields.is_none() {
+            self.fields = Some(Box::new(fields));
+        }
+        self
+    }
+
+    /// Forget where the error happened, for values that weren't loaded from a document.
+    pub(crate) fn unlocated(mut self) -> Self {
+        self.span = None;
+        self
+    }
+
+    /// Keep the line of the document the error is on, to show it when the error is displayed.
+    #[cfg(feature = "std")]
+    pub(crate) fn with_source(mut self, yaml: &str) -> Self {
+        if let Some(span) = self.span {
+            self.source_line = yaml
+                .lines()
+                .nth(span.line.saturating_sub(1))
+                .map(Into::into);
+        }
+        self
+    }
+
+    /// Keep the line of the document the error is on, which is only shown with `std`.
+    #[cfg(not(feature = "std"))]
+    pub(crate) fn with_source(self, _yaml: &str) -> Self {
+        self
+    }
+
+    /// Prefix the path with a struct field or map key.
+    pub(crate) fn in_field(self, name: &str) -> Self {
+        self.prefixed(name)
+    }
+
+    /// Prefix the path with a list index.
+    pub(crate) fn in_index(self, index: usize) -> Self {
+        self.prefixed(&format!("[{index}]"))
+    }
+
+    /// Prefix the path with a segment, separating it from a following field with a dot.
+    fn prefixed(mut self, segment: &str) -> Self {
+        self.path = if self.path.is_empty() || self.path.starts_with('[') {
+            format!("{segment}{}", self.path)
+        } else {
+            format!("{segment}.{}", self.path)
+        };
+        self
+    }
+}
 
-impl core::fmt::Display for AnyErr {
+/// A single line with the message and the location, for logs:
+///
+/// ```text
+/// Value 70000 out of range for u16 at `servers[1].port` (line 5, column 11)
+/// ```
+///
+/// See [`DeserializeError::verbose`] for a longer form, with the line of the document.
+impl core::fmt::Display for DeserializeError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.message)?;
+        if !self.path.is_empty() || self.span.is_some() {
+            f.write_str(" at")?;
+        }
+        self.fmt_location(f)
+    }
+}
+
+impl core::error::Error for DeserializeError {}
+
+/// The fields of a struct that couldn't be deserialized, see [`DeserializeError::fields`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldReport {
+    /// Name of the struct.
+    type_name: String,
+    /// Each field, in declaration order.
+    fields: Vec<(&'static str, FieldState)>,
+}
+
+/// What happened to a field of a struct, see [`FieldReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldState {
+    /// The field was read from the document.
+    Set,
+    /// The field wasn't in the document, and was set from its default or the default of the
+    /// struct.
+    Default,
+    /// The field wasn't in the document, and has no default.
+    Missing,
+}
+
+impl FieldReport {
+    /// Find out which fields of a partially built struct are set.
+    pub(crate) fn new(
+        wip: &Partial<'_>,
+        shape: &Shape,
+        sd: &StructType,
+        defaulted: &[bool],
+    ) -> Self {
+        let fields = sd
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let state = match wip.is_field_set(index) {
+                    Ok(true) if defaulted[index] => FieldState::Default,
+                    Ok(true) => FieldState::Set,
+                    Ok(false) | Err(_) => FieldState::Missing,
+                };
+                (field.name, state)
+            })
+            .collect();
+        Self {
+            type_name: shape.to_string(),
+            fields,
+        }
+    }
+
+    /// Name of the struct.
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    /// Each field of the struct with its state, in declaration order.
+    pub fn fields(&self) -> &[(&'static str, FieldState)] {
+        &self.fields
+    }
+
+    /// The state of a field, by its name in Rust.
+    pub fn state(&self, field: &str) -> Option<FieldState> {
+        self.fields
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, state)| *state)
     }
 }
 
-impl core::error::Error for AnyErr {}
+/// Shows the struct with the state of each field, like
+/// `Server { host: set, port: missing, tls: default }`.
+impl core::fmt::Display for FieldReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} {{", self.type_name)?;
+        for (index, (name, state)) in self.fields.iter().enumerate() {
+            let separator = if index == 0 { " " } else { ", " };
+            write!(f, "{separator}{name}: {state}")?;
+        }
+        f.write_str(if self.fields.is_empty() { "}" } else { " }" })
+    }
+}
 
-impl From<String> for AnyErr {
+impl core::fmt::Display for FieldState {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::Set => "set",
+            Self::Default => "default",
+            Self::Missing => "missing",
+        })
+    }
+}
+
+/// Displays an error with [`DeserializeError::fmt_verbose`].
+struct Verbose<'a>(&'a DeserializeError);
+
+impl core::fmt::Display for Verbose<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.0.fmt_verbose(f)
+    }
+}
+
+/// A hint for fixing the document, for the kinds of errors that usually have the same fix.
+fn help(code: ErrorCode) -> Option<&'static str> {
+    Some(match code {
+        ErrorCode::UnknownField => "check the spelling of the key, or remove it",
+        ErrorCode::MissingField => "add the field to the mapping",
+        ErrorCode::OutOfRange => "use a value that fits in the type, or a larger type",
+        ErrorCode::Syntax => "check the indentation and the quotes around this location",
+        ErrorCode::DocumentCount => "remove the `---` separators between documents",
+        ErrorCode::InvalidKey => "quote the key, so it's read as a string",
+        ErrorCode::UnknownAnchor => "define the anchor with `&name` before the alias",
+        ErrorCode::ConflictingFields => "keep only one of the fields",
+        _ => return None,
+    })
+}
+
+/// Renders the same text as [`Display`](core::fmt::Display), prefixed with the error code.
+#[cfg(feature = "defmt")]
+impl defmt::Format for DeserializeError {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "{}: {=str}", self.code, &*self.message);
+        match (self.path.is_empty(), self.span) {
+            (false, Some(span)) => defmt::write!(f, " at `{=str}` ({})", self.path.as_str(), span),
+            (false, None) => defmt::write!(f, " at `{=str}`", self.path.as_str()),
+            (true, Some(span)) => defmt::write!(f, " at {}", span),
+            (true, None) => {}
+        }
+    }
+}
+
+impl From<String> for DeserializeError {
     fn from(s: String) -> Self {
-        Self(s)
+        Self::new(ErrorCode::Custom, s)
     }
 }
 
-impl From<&str> for AnyErr {
+impl From<&str> for DeserializeError {
     fn from(s: &str) -> Self {
-        Self(s.to_string())
+        Self::new(ErrorCode::Custom, s)
     }
 }
 
-impl From<ReflectError> for AnyErr {
+impl From<ReflectError> for DeserializeError {
     fn from(value: ReflectError) -> Self {
-        Self(format!("Reflection error: {value}"))
+        let mut err = Self::reflect(value);
+        err.message = format!("Reflection error: {}", err.message).into();
+        err
+    }
+}
+
+impl From<ScanError> for DeserializeError {
+    fn from(value: ScanError) -> Self {
+        let mut err = Self::new(ErrorCode::Syntax, value.info());
+        err.span = Some((*value.marker()).into());
+        err
     }
 }
+
+/// Quote and escape a string for JSON.
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Escape the message of a GitHub workflow command.
+fn github_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property of a GitHub workflow command.
+fn github_property(s: &str) -> String {
+    github_data(s).replace(':', "%3A").replace(',', "%2C")
+}
@@ -0,0 +1,243 @@
+//! What has been learned about the fields of struct types, kept across documents.
+
+use alloc::{
+    collections::{BTreeMap, btree_map::Entry},
+    rc::Rc,
+    string::String,
+    vec::Vec,
+};
+use core::{cell::RefCell, ops::Deref};
+use facet_core::{Shape, StructType};
+
+use super::{DeserializeError, DeserializeOptions, RenameRule};
+use crate::{ErrorCode, attributes::FieldAttributes};
+
+/// Field lookups of every struct type seen so far, keyed by the address of its shape.
+#[derive(Debug, Default)]
+pub(crate) struct FieldCache {
+    /// The attributes of the fields of each struct type, or `None` for types without any.
+    structs: RefCell<BTreeMap<usize, Option<Rc<StructFields>>>>,
+    /// Index of the field for each key seen so far in each struct type with each naming
+    /// convention, or `None` for keys that aren't fields.
+    keys: RefCell<BTreeMap<(usize, Option<RenameRule>), Keys>>,
+    /// Whether the field of each key is looked up again every time, instead of being
+    /// remembered.
+    forget_keys: bool,
+}
+
+/// Index of the field for each key, or `None` for keys that aren't fields.
+type Keys = BTreeMap<String, Option<usize>>;
+
+/// The attributes of the fields of a struct type, shared with the cache they're read from.
+pub(crate) struct Fields(Option<Rc<StructFields>>);
+
+/// The attributes of the fields of a struct type.
+#[derive(Debug)]
+pub(crate) struct StructFields {
+    /// Index of the field for each alias.
+    aliases: BTreeMap<String, usize>,
+    /// Fields that are read from a nested path instead of their own key.
+    paths: Vec<(usize, Vec<String>)>,
+    /// Fields that are never read, whose keys are ignored.
+    skipped: Vec<usize>,
+    /// Fields that documents must have, even if they have a default.
+    required: Vec<usize>,
+    /// Groups of fields that exclude each other.
+    exclusive: Vec<Vec<usize>>,
+    /// Naming conventions set by fields for the structs in them.
+    rename_all: Vec<(usize, Option<RenameRule>)>,
+    /// Fields whose value is a YAML document embedded in a string.
+    embedded: Vec<usize>,
+    /// Map fields read from a sequence of items, along with the key of the items.
+    keyed: Vec<(usize, String)>,
+}
+
+/// The fields of struct types without YAML attributes, which most are.
+static NO_ATTRIBUTES: StructFields = StructFields {
+    aliases: BTreeMap::new(),
+    paths: Vec::new(),
+    skipped: Vec::new(),
+    required: Vec::new(),
+    exclusive: Vec::new(),
+    rename_all: Vec::new(),
+    embedded: Vec::new(),
+    keyed: Vec::new(),
+};
+
+impl FieldCache {
+    /// Create a cache for a single document, which doesn't remember the field of each key when
+    /// the document is smaller than the
+    /// [threshold](DeserializeOptions::small_document_threshold) of the options.
+    ///
+    /// Each key of a small document is usually looked up once, so remembering it would only
+    /// cost an allocation.
+    pub fn for_document(yaml: &str, options: &DeserializeOptions) -> Self {
+        Self {
+            forget_keys: yaml.len() < options.small_document_threshold,
+            ..Self::default()
+        }
+    }
+
+    /// Get the attributes of the fields of a struct type, reading them the first time.
+    ///
+    /// Types whose fields have no `#[facet(yaml(...))]` attribute are only scanned for one.
+    pub fn fields(
+        &self,
+        shape: &'static Shape,
+        sd: &StructType,
+    ) -> Result<Fields, DeserializeError> {
+        let mut structs = self.structs.borrow_mut();
+        let fields = match structs.entry(shape_id(shape)) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let fields = if sd.fields.iter().any(FieldAttributes::any) {
+                    Some(Rc::new(StructFields::new(sd)?))
+                } else {
+                    None
+                };
+                entry.insert(fields)
+            }
+        };
+        Ok(Fields(fields.clone()))
+    }
+
+    /// Get the index of the field for a key read with a naming convention, using `find` the
+    /// first time the key is seen with that convention.
+    ///
+    /// Aliases from `#[facet(yaml(alias = "..."))]` are known up front.
+    pub fn field_index(
+        &self,
+        shape: &'static Shape,
+        fields: &StructFields,
+        key: &str,
+        rule: Option<RenameRule>,
+        find: impl FnOnce(&str) -> Option<usize>,
+    ) -> Option<usize> {
+        if let Some(index) = fields.aliases.get(key) {
+            return Some(*index);
+        }
+        if self.forget_keys {
+            return find(key);
+        }
+        let mut keys = self.keys.borrow_mut();
+        let keys = keys.entry((shape_id(shape), rule)).or_default();
+        match keys.get(key) {
+            Some(index) => *index,
+            None => {
+                let index = find(key);
+                keys.insert(key.into(), index);
+                index
+            }
+        }
+    }
+}
+
+/// Identify a struct type by the address of its shape.
+fn shape_id(shape: &'static Shape) -> usize {
+    core::ptr::from_ref(shape) as usize
+}
+
+impl Deref for Fields {
+    type Target = StructFields;
+
+    fn deref(&self) -> &StructFields {
+        self.0.as_deref().unwrap_or(&NO_ATTRIBUTES)
+    }
+}
+
+impl StructFields {
+    /// Read the attributes of the fields of a struct type.
+    fn new(sd: &StructType) -> Result<Self, DeserializeError> {
+        let mut aliases = BTreeMap::new();
+        let mut paths = Vec::new();
+        let mut skipped = Vec::new();
+        let mut required = Vec::new();
+        let mut groups: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        let mut rename_all = Vec::new();
+        let mut embedded = Vec::new();
+        let mut keyed = Vec::new();
+        for (index, field) in sd.fields.iter().enumerate() {
+            let attributes = FieldAttributes::of(field)
+                .map_err(|message| DeserializeError::new(ErrorCode::InvalidAttribute, message))?;
+            for alias in attributes.aliases {
+                aliases.insert(alias, index);
+            }
+            if let Some(path) = attributes.path {
+                paths.push((index, path));
+            }
+            if attributes.skip_deserializing {
+                skipped.push(index);
+            }
+            if attributes.required {
+                required.push(index);
+            }
+            if let Some(group) = attributes.exclusive {
+                groups.entry(group).or_default().push(index);
+            }
+            if let Some(rule) = attributes.rename_all {
+                rename_all.push((index, rule));
+            }
+            if attributes.embedded {
+                embedded.push(index);
+            }
+            if let Some(key) = attributes.key {
+                keyed.push((index, key));
+            }
+        }
+        Ok(Self {
+            aliases,
+            paths,
+            skipped,
+            required,
+            exclusive: groups.into_values().collect(),
+            rename_all,
+            embedded,
+            keyed,
+        })
+    }
+
+    /// Get the fields that are read from a nested path, along with their index.
+    pub fn paths(&self) -> &[(usize, Vec<String>)] {
+        &self.paths
+    }
+
+    /// Get the fields that are never read, from `#[facet(yaml(skip_deserializing))]`.
+    pub fn skipped(&self) -> &[usize] {
+        &self.skipped
+    }
+
+    /// Get the fields that documents must have, from `#[facet(yaml(required))]`.
+    pub fn required(&self) -> &[usize] {
+        &self.required
+    }
+
+    /// Get the groups of fields that exclude each other, from
+    /// `#[facet(yaml(exclusive = "..."))]`.
+    pub fn exclusive(&self) -> &[Vec<usize>] {
+        &self.exclusive
+    }
+
+    /// Get the naming convention a field sets for the structs in it, from
+    /// `#[facet(yaml(rename_all = "..."))]`, or `None` if it doesn't set one.
+    pub fn rename_all(&self, index: usize) -> Option<Option<RenameRule>> {
+        self.rename_all
+            .iter()
+            .find(|(i, _)| *i == index)
+            .map(|(_, rule)| *rule)
+    }
+
+    /// Whether the value of a field is a YAML document embedded in a string, from
+    /// `#[facet(yaml(embedded))]`.
+    pub fn embedded(&self, index: usize) -> bool {
+        self.embedded.contains(&index)
+    }
+
+    /// Get the key of the items of a sequence that a map field is read from, from
+    /// `#[facet(yaml(key = "..."))]`, or `None` if it's read from a mapping.
+    pub fn keyed(&self, index: usize) -> Option<&str> {
+        self.keyed
+            .iter()
+            .find(|(i, _)| *i == index)
+            .map(|(_, key)| key.as_str())
+    }
+}
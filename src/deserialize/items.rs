@@ -0,0 +1,104 @@
+//! Deserialize the items of a sequence one by one, keeping the ones that are valid.
+
+use alloc::{format, vec::Vec};
+use facet_core::Facet;
+use facet_reflect::Partial;
+
+use super::{
+    DeserializeError, DeserializeOptions, Deserializer, FieldCache,
+    node::{self, Node, NodeValue},
+    yaml_type,
+};
+use crate::ErrorCode;
+
+/// Deserializes each item of a YAML sequence into a value of type `T`, returning the items
+/// that could be deserialized along with an error for each of the others.
+///
+/// This is meant for ingest pipelines that would rather keep the good records of a batch than
+/// reject all of them because of one bad record. Reading the sequence into a `Vec<T>` with
+/// [`from_str_with_options`](crate::from_str_with_options) fails at the first bad item, and
+/// [`validate`](crate::validate) reports every problem without returning any value. Here each
+/// item is deserialized on its own: the ones that fail are left out, and the first error of each
+/// of them is reported with its index at the start of the path.
+///
+/// The document itself must be a sequence, or empty. Otherwise, and when it isn't valid YAML, the
+/// error is returned instead.
+///
+/// ```
+/// use facet::Facet;
+/// use facet_yaml::DeserializeOptions;
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Reading {
+///     sensor: String,
+///     celsius: f32,
+/// }
+///
+/// let yaml = "
+/// - {sensor: a, celsius: 21.5}
+/// - {sensor: b, celsius: warm}
+/// - {sensor: c, celsius: 19}
+/// ";
+/// let (readings, errors) =
+///     facet_yaml::from_str_items::<Reading>(yaml, &DeserializeOptions::default()).unwrap();
+/// assert_eq!(readings.len(), 2);
+/// assert_eq!(readings[1].sensor, "c");
+/// assert_eq!(errors.len(), 1);
+/// assert_eq!(errors[0].path(), "[1].celsius");
+/// ```
+pub fn from_str_items<'input: 'facet, 'facet, T: Facet<'facet>>(
+    yaml: &'input str,
+    options: &DeserializeOptions,
+) -> Result<(Vec<T>, Vec<DeserializeError>), DeserializeError> {
+    // The parser would otherwise treat a byte-order mark as part of the first scalar
+    let yaml = yaml.strip_prefix('\u{feff}').unwrap_or(yaml);
+
+    let docs = node::load(yaml, options).map_err(|e| e.with_source(yaml))?;
+    let items = match docs.as_slice() {
+        // Only comments, directives or whitespace
+        [] => return Ok((Vec::new(), Vec::new())),
+        [doc] => match &doc.value {
+            NodeValue::Array(items) => items,
+            NodeValue::Null => return Ok((Vec::new(), Vec::new())),
+            _ => {
+                return Err(DeserializeError::new(
+                    ErrorCode::TypeMismatch,
+                    format!("Expected a YAML array, got: {}", yaml_type(doc)),
+                )
+                .located(doc.span, Vec::<T>::SHAPE)
+                .with_source(yaml));
+            }
+        },
+        _ => {
+            return Err(DeserializeError::new(
+                ErrorCode::DocumentCount,
+                "Expected exactly one YAML document",
+            ));
+        }
+    };
+
+    let fields = FieldCache::default();
+    let deserializer = Deserializer::new(options, &fields);
+    let mut values = Vec::new();
+    let mut errors = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        match item_value(&deserializer, item) {
+            Ok(value) => values.push(value),
+            // The remaining items would be cancelled as well
+            Err(err) if err.code() == ErrorCode::Cancelled => return Err(err),
+            Err(err) => errors.push(err.in_index(index).with_source(yaml)),
+        }
+    }
+    Ok((values, errors))
+}
+
+/// Deserialize an item of the sequence on its own.
+fn item_value<'facet, T: Facet<'facet>>(
+    deserializer: &Deserializer<'_>,
+    item: &Node,
+) -> Result<T, DeserializeError> {
+    let mut typed_partial = Partial::alloc::<T>()?;
+    deserializer.deserialize_value(typed_partial.inner_mut(), item)?;
+    let boxed_value = typed_partial.build().map_err(DeserializeError::reflect)?;
+    Ok(*boxed_value)
+}
@@ -3,478 +3,2073 @@
 #[cfg(not(feature = "alloc"))]
 compile_error!("feature `alloc` is required");
 
+#[cfg(feature = "std")]
+mod batch;
 mod error;
+mod fields;
+mod items;
+mod node;
+mod number;
+mod options;
+mod pipeline;
+#[cfg(feature = "figment")]
+mod provider;
+pub mod schema;
+mod template;
+mod validate;
+mod value;
 
 use alloc::{
+    borrow::Cow,
     format,
     string::{String, ToString},
+    vec::Vec,
 };
-use error::AnyErr;
+#[cfg(feature = "std")]
+pub use batch::{BatchReport, FileError, FileReport, from_paths};
+use core::cell::{Cell, RefCell};
+pub use error::{DeserializeError, FieldReport, FieldState};
 use facet_core::{
-    Def, Facet, FieldFlags, NumericType, PrimitiveType, SequenceType, Type, UserType,
+    Def, EnumType, Facet, FieldFlags, NumericType, PrimitiveType, SequenceType, Shape, StructKind,
+    StructType, Type, UserType, Variant,
 };
 use facet_reflect::Partial;
-use yaml_rust2::{Yaml, YamlLoader};
+use fields::{FieldCache, StructFields};
+pub use items::from_str_items;
+pub use node::Span;
+use node::{Node, NodeValue, Scratch};
+pub use number::Number;
+pub use options::{DeserializeOptions, PlainScalar, RenameRule};
+pub use pipeline::{
+    DropPath, InjectDefault, Pipeline, PipelineEvent, RenameKey, Transform, TransformContext,
+};
+#[cfg(feature = "figment")]
+pub use provider::YamlProvider;
+pub use template::template;
+pub use validate::validate;
+pub use value::{Map, Value, from_str_recovering};
+
+use crate::{ErrorCode, attributes};
 
 /// Deserializes a YAML string into a value of type `T` that implements `Facet`.
-pub fn from_str<'input: 'facet, 'facet, T: Facet<'facet>>(yaml: &'input str) -> Result<T, AnyErr> {
+///
+/// Fields whose type is an `Option` are `None` when they're missing, unless they have a
+/// `#[facet(default)]` attribute or the struct has a default, which are used instead.
+///
+/// Fields with a `#[facet(yaml(skip_deserializing))]` attribute, such as values computed when
+/// the document is rendered, are never read: their keys are ignored and they're set to their
+/// default. It's the counterpart of `#[facet(skip_serializing)]`.
+///
+/// Fields with a `#[facet(yaml(required))]` attribute must be in the document, even if they or
+/// the struct have a default, for values such as a cluster name that shouldn't be left out by
+/// accident. Of the fields with the same `#[facet(yaml(exclusive = "group"))]` attribute, such
+/// as a password and a password file, documents may only have one.
+///
+/// A field with a `#[facet(yaml(rename_all = "camelCase"))]` attribute reads the keys of the
+/// struct in it, and of the structs nested further, in that naming convention, so that it
+/// doesn't have to be repeated on every nested type. A nested field can set another convention,
+/// or `"none"` to read the keys by the names of the fields only.
+///
+/// Fields with a `#[facet(yaml(embedded))]` attribute are read from a string with their own
+/// YAML document, such as the values of a Helm chart embedded in another document. Errors in it
+/// are located at the string.
+///
+/// Map fields with a `#[facet(yaml(key = "name"))]` attribute are read from a sequence of
+/// mappings, each the value of the map for its `name`, which is a common idiom of manifests:
+///
+/// ```yaml
+/// containers:
+///   - name: web
+///     image: nginx
+///   - name: cache
+///     image: redis
+/// ```
+///
+/// The key is taken out of each item, unless the values are structs with a field of that name.
+pub fn from_str<'input: 'facet, 'facet, T: Facet<'facet>>(
+    yaml: &'input str,
+) -> Result<T, DeserializeError> {
+    from_str_with_options(yaml, &DeserializeOptions::default())
+}
+
+/// Deserializes a YAML string into a value of type `T`, with custom options.
+pub fn from_str_with_options<'input: 'facet, 'facet, T: Facet<'facet>>(
+    yaml: &'input str,
+    options: &DeserializeOptions,
+) -> Result<T, DeserializeError> {
     let mut typed_partial = Partial::alloc::<T>()?;
     {
         let wip = typed_partial.inner_mut();
-        from_str_value(wip, yaml)?;
+        let fields = FieldCache::for_document(yaml, options);
+        from_str_value(wip, yaml, options, &fields, None, &mut Scratch::default())?;
     }
-    let boxed_value = typed_partial.build().map_err(|e| AnyErr(e.to_string()))?;
+    let boxed_value = typed_partial.build().map_err(DeserializeError::reflect)?;
     Ok(*boxed_value)
 }
 
-fn yaml_type(ty: &Yaml) -> &'static str {
-    match ty {
-        Yaml::Real(_) => "real number",
-        Yaml::Integer(_) => "integer",
-        Yaml::String(_) => "string",
-        Yaml::Boolean(_) => "boolean",
-        Yaml::Array(_) => "array",
-        Yaml::Hash(_) => "hash/map",
-        Yaml::Alias(_) => "alias",
-        Yaml::Null => "null",
-        Yaml::BadValue => "bad value",
+/// Deserializes only the values at the given paths of a YAML string into a value of type `T`.
+///
+/// Paths are dotted lists of mapping keys and sequence indices, where `*` matches any key or
+/// index. Everything that isn't on a path or below one is skipped while parsing, without being
+/// built or bound, which is a lot cheaper than [`from_str`] when only a few values of a large
+/// document are needed. The parts that are left out are missing from the document `T` sees, so
+/// their fields need defaults.
+///
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Manifest {
+///     metadata: Metadata,
+///     spec: Spec,
+/// }
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Metadata {
+///     name: String,
+/// }
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Spec {
+///     replicas: u32,
+/// }
+///
+/// let yaml = "
+/// metadata:
+///   name: web
+///   labels: {app: web, tier: frontend}
+/// spec:
+///   replicas: 3
+///   template: {containers: [{name: web, image: nginx}]}
+/// ";
+/// let manifest: Manifest =
+///     facet_yaml::from_str_projected(yaml, &["spec.replicas", "metadata.name"]).unwrap();
+/// assert_eq!(manifest.spec.replicas, 3);
+/// assert_eq!(manifest.metadata.name, "web");
+/// ```
+pub fn from_str_projected<'input: 'facet, 'facet, T: Facet<'facet>>(
+    yaml: &'input str,
+    paths: &[&str],
+) -> Result<T, DeserializeError> {
+    let projection = paths
+        .iter()
+        .map(|path| {
+            if path.split('.').any(str::is_empty) {
+                return Err(DeserializeError::new(
+                    ErrorCode::InvalidPath,
+                    format!("Invalid projection path `{path}`"),
+                ));
+            }
+            Ok(path.split('.').map(ToString::to_string).collect())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut typed_partial = Partial::alloc::<T>()?;
+    {
+        let wip = typed_partial.inner_mut();
+        let options = DeserializeOptions::default();
+        let fields = FieldCache::for_document(yaml, &options);
+        from_str_value(
+            wip,
+            yaml,
+            &options,
+            &fields,
+            Some(projection),
+            &mut Scratch::default(),
+        )?;
     }
+    let boxed_value = typed_partial.build().map_err(DeserializeError::reflect)?;
+    Ok(*boxed_value)
+}
+
+/// Deserializes YAML from bytes into a value of type `T`, without copying them into a `String`.
+///
+/// This is meant for large inputs that are already in memory, such as a memory-mapped file. The
+/// bytes must be UTF-8.
+///
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Config {
+///     name: String,
+/// }
+///
+/// let bytes = b"name: server";
+/// let config: Config = facet_yaml::from_slice(bytes).unwrap();
+/// assert_eq!(config.name, "server");
+/// ```
+pub fn from_slice<'input: 'facet, 'facet, T: Facet<'facet>>(
+    yaml: &'input [u8],
+) -> Result<T, DeserializeError> {
+    let yaml = core::str::from_utf8(yaml).map_err(|e| {
+        DeserializeError::new(
+            ErrorCode::InvalidUtf8,
+            format!("Input is not valid UTF-8: {e}"),
+        )
+    })?;
+    from_str(yaml)
+}
+
+/// Deserializes many YAML documents with the same options.
+///
+/// What is learned about struct types along the way, such as the field each key is for and the
+/// YAML attributes of the fields, is kept for the next documents. This is faster than calling
+/// [`from_str_with_options`] for each document when there are many of them.
+///
+/// The sequences and mappings of each document are loaded into buffers that are kept for the
+/// next documents as well, instead of being allocated again every time, which spares the global
+/// allocator in pipelines that read documents one after the other.
+///
+/// ```
+/// use facet::Facet;
+/// use facet_yaml::{DeserializeOptions, YamlDeserializer};
+///
+/// #[derive(Facet)]
+/// struct Server {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// let deserializer = YamlDeserializer::new(DeserializeOptions::default());
+/// for yaml in ["host: a\nport: 80", "host: b\nport: 443"] {
+///     let server: Server = deserializer.from_str(yaml).unwrap();
+///     assert!(server.port > 0);
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct YamlDeserializer {
+    /// How to deserialize.
+    options: DeserializeOptions,
+    /// What is known about the struct types seen so far.
+    fields: FieldCache,
+    /// Buffers of the nodes of earlier documents, to load the next ones into.
+    scratch: RefCell<Scratch>,
 }
 
-fn yaml_to_u64(ty: &Yaml) -> Result<u64, AnyErr> {
-    match ty {
-        Yaml::Real(r) => r
-            .parse::<u64>()
-            .map_err(|_| AnyErr("Failed to parse real as u64".into())),
-        Yaml::Integer(i) => Ok(*i as u64),
-        Yaml::String(s) => s
-            .parse::<u64>()
-            .map_err(|_| AnyErr("Failed to parse string as u64".into())),
-        Yaml::Boolean(b) => Ok(if *b { 1 } else { 0 }),
-        _ => Err(AnyErr(format!("Cannot convert {} to u64", yaml_type(ty)))),
+impl YamlDeserializer {
+    /// Create a deserializer with the given options.
+    pub fn new(options: DeserializeOptions) -> Self {
+        Self {
+            options,
+            fields: FieldCache::default(),
+            scratch: RefCell::default(),
+        }
+    }
+
+    /// Deserializes a YAML string into a value of type `T`.
+    pub fn from_str<'input: 'facet, 'facet, T: Facet<'facet>>(
+        &self,
+        yaml: &'input str,
+    ) -> Result<T, DeserializeError> {
+        let mut typed_partial = Partial::alloc::<T>()?;
+        {
+            let wip = typed_partial.inner_mut();
+            let mut scratch = self.scratch.borrow_mut();
+            from_str_value(wip, yaml, &self.options, &self.fields, None, &mut scratch)?;
+        }
+        let boxed_value = typed_partial.build().map_err(DeserializeError::reflect)?;
+        Ok(*boxed_value)
     }
 }
 
-fn from_str_value<'facet>(wip: &mut Partial<'facet>, yaml: &str) -> Result<(), AnyErr> {
-    let docs = YamlLoader::load_from_str(yaml).map_err(|e| e.to_string())?;
-    if docs.len() != 1 {
-        return Err("Expected exactly one YAML document".into());
+fn yaml_type(ty: &Node) -> &'static str {
+    match ty.value {
+        NodeValue::Real(_) => "real number",
+        NodeValue::Integer(_) => "integer",
+        NodeValue::String(_) => "string",
+        NodeValue::Boolean(_) => "boolean",
+        NodeValue::Array(_) => "array",
+        NodeValue::Hash(_) => "hash/map",
+        NodeValue::Null => "null",
+        NodeValue::BadValue => "bad value",
+        NodeValue::Invalid(_) => "invalid node",
     }
-    deserialize_value(wip, &docs[0])?;
-    Ok(())
 }
 
-fn deserialize_value<'facet>(wip: &mut Partial<'facet>, value: &Yaml) -> Result<(), AnyErr> {
-    // Get the shape
-    let shape = wip.shape();
+/// Get the text of a scalar, which may be a number or a boolean, such as the name of a unit
+/// variant renamed to one, or a map key parsed into an id type.
+fn scalar_text(node: &Node) -> Option<Cow<'_, str>> {
+    match &node.value {
+        NodeValue::String(name) | NodeValue::Real(name) => Some(Cow::Borrowed(name.as_str())),
+        NodeValue::Integer(i) => Some(Cow::Owned(i.to_string())),
+        NodeValue::Boolean(b) => Some(Cow::Borrowed(if *b { "true" } else { "false" })),
+        _ => None,
+    }
+}
 
-    #[cfg(feature = "log")]
-    {
-        log::debug!("deserialize_value: shape={shape}");
-        log::debug!("Shape type: {:?}", shape.ty);
-        log::debug!("Shape attributes: {:?}", shape.attributes);
-        log::debug!("YAML value: {value:?}");
+/// Check that a complex key, which is a sequence or a mapping like `? [a, b]`, isn't read into
+/// a scalar key type, which it can never be.
+fn complex_key_error(key_shape: &'static Shape, key: &Node) -> Option<DeserializeError> {
+    let complex = matches!(key.value, NodeValue::Array(_) | NodeValue::Hash(_));
+    (complex && matches!(key_shape.def, Def::Scalar)).then(|| {
+        DeserializeError::new(
+            ErrorCode::InvalidKey,
+            format!(
+                "Complex keys, like `? [a, b]`, can only be read into keys that are structs, tuples or collections, not {key_shape}"
+            ),
+        )
+        .located(key.span, key_shape)
+    })
+}
+
+/// Turn a sequence of mappings into a mapping of each of them by its value of `key`, for map
+/// fields with a `#[facet(yaml(key = "..."))]` attribute. Mappings are read as they are.
+///
+/// The key is taken out of each item, unless the values of the map are structs with a field of
+/// that name.
+fn keyed_mapping<'n>(
+    key: &str,
+    shape: &'static Shape,
+    value: &'n Node,
+) -> Result<Cow<'n, Node>, DeserializeError> {
+    let NodeValue::Array(items) = &value.value else {
+        return Ok(Cow::Borrowed(value));
+    };
+    let keep_key = match shape.def {
+        Def::Map(md) => matches!(
+            md.v().ty,
+            Type::User(UserType::Struct(sd)) if sd.fields.iter().any(|field| field.name == key)
+        ),
+        _ => false,
+    };
+
+    let mut entries: Vec<(Node, Node)> = Vec::with_capacity(items.len());
+    for (index, item) in items.iter().enumerate() {
+        let NodeValue::Hash(fields) = &item.value else {
+            return Err(DeserializeError::new(
+                ErrorCode::TypeMismatch,
+                format!(
+                    "Expected a hash/map with a `{key}` key for each item, got: {}",
+                    yaml_type(item)
+                ),
+            )
+            .located(item.span, shape)
+            .in_index(index));
+        };
+        let Some(position) = fields.iter().position(|(k, _)| k.as_str() == Some(key)) else {
+            return Err(DeserializeError::new(
+                ErrorCode::MissingField,
+                format!("Missing `{key}` key of an item of a keyed list"),
+            )
+            .located(item.span, shape)
+            .in_index(index));
+        };
+        let name = fields[position].1.clone();
+        if let Some(first) = entries.iter().position(|(earlier, _)| *earlier == name) {
+            return Err(DeserializeError::new(
+                ErrorCode::DuplicateItem,
+                format!("Item has the same `{key}` as item {first}"),
+            )
+            .located(name.span, shape)
+            .in_index(index));
+        }
+        let mut fields = fields.clone();
+        if !keep_key {
+            fields.remove(position);
+        }
+        let item = Node {
+            value: NodeValue::Hash(fields),
+            span: item.span,
+            tag: item.tag.clone(),
+        };
+        entries.push((name, item));
+    }
+    Ok(Cow::Owned(Node {
+        value: NodeValue::Hash(entries),
+        span: value.span,
+        tag: value.tag.clone(),
+    }))
+}
+
+/// Get the key and value of a mapping with a single key.
+fn single_entry(node: &Node) -> Option<(&Node, &Node)> {
+    match &node.value {
+        NodeValue::Hash(entries) if entries.len() == 1 => Some((&entries[0].0, &entries[0].1)),
+        _ => None,
+    }
+}
+
+/// Whether a shape is a tuple of two, which a mapping with a single key can be read into.
+fn is_pair(shape: &Shape) -> bool {
+    matches!(
+        shape.ty,
+        Type::User(UserType::Struct(sd))
+            if matches!(sd.kind, StructKind::Tuple) && sd.fields.len() == 2
+    )
+}
+
+/// Whether a struct is a tuple struct with a single field, a newtype, which is read as its field.
+fn is_newtype(sd: &StructType) -> bool {
+    matches!(sd.kind, StructKind::TupleStruct) && sd.fields.len() == 1
+}
+
+/// Find the node at a path of mapping keys and sequence indices.
+fn find_path<'n>(node: &'n Node, path: &[String]) -> Option<&'n Node> {
+    path.iter()
+        .try_fold(node, |node, segment| match &node.value {
+            NodeValue::Hash(entries) => entries
+                .iter()
+                .find(|(k, _)| k.as_str() == Some(segment))
+                .map(|(_, v)| v),
+            NodeValue::Array(items) => items.get(segment.parse::<usize>().ok()?),
+            _ => None,
+        })
+}
+
+/// Create an error for each group of fields that exclude each other where more than one is set,
+/// given the key and span each field was read from, if it was.
+fn exclusive_conflicts(
+    groups: &[Vec<usize>],
+    keys: &[Option<(String, Span)>],
+    shape: &'static Shape,
+) -> Vec<DeserializeError> {
+    groups
+        .iter()
+        .filter_map(|group| {
+            let set: Vec<&(String, Span)> = group
+                .iter()
+                .filter_map(|index| keys[*index].as_ref())
+                .collect();
+            let ((last_key, last_span), others) = set.split_last()?;
+            if others.is_empty() {
+                return None;
+            }
+            let others = others
+                .iter()
+                .map(|(key, span)| format!("'{key}' ({span})"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            Some(
+                DeserializeError::new(
+                    ErrorCode::ConflictingFields,
+                    format!("Only one of {others} and '{last_key}' ({last_span}) may be set"),
+                )
+                .located(*last_span, shape),
+            )
+        })
+        .collect()
+}
+
+/// Whether a shape is a byte buffer, such as `Vec<u8>`, which can be read from a `!!binary`
+/// scalar.
+fn is_bytes(shape: &Shape) -> bool {
+    matches!(shape.def, Def::List(ld) if ld.t().is_type::<u8>())
+}
+
+/// Get the items of a sequence for a fixed-size array, such as `[u8; 32]`, which must have as
+/// many.
+fn array_items<'n>(
+    shape: &Shape,
+    len: usize,
+    value: &'n Node,
+) -> Result<&'n [Node], DeserializeError> {
+    let NodeValue::Array(items) = &value.value else {
+        return Err(DeserializeError::new(
+            ErrorCode::TypeMismatch,
+            format!("Expected a YAML array, got: {}", yaml_type(value)),
+        ));
+    };
+    if items.len() != len {
+        return Err(DeserializeError::new(
+            ErrorCode::TypeMismatch,
+            format!("Expected {len} items for {shape}, got {}", items.len()),
+        ));
+    }
+    Ok(items.as_slice())
+}
+
+/// Whether a shape is an `Option`, which is `None` when its field is missing.
+fn is_option(shape: &Shape) -> bool {
+    matches!(shape.def, Def::Option(_))
+}
+
+/// Decode the base64 text of a node read into a byte buffer, which is a `!!binary` scalar when
+/// `tagged` is set or else a string.
+fn decode_binary(text: &str, tagged: bool) -> Result<Vec<u8>, DeserializeError> {
+    crate::base64::decode(text).ok_or_else(|| {
+        let message = if tagged {
+            "Invalid base64 text in `!!binary` scalar"
+        } else {
+            "Invalid base64 text in string read as bytes"
+        };
+        DeserializeError::new(ErrorCode::InvalidScalar, message)
+    })
+}
+
+/// Parse float text, mapping the YAML infinity and NaN spellings to the ones Rust understands.
+fn parse_float<F: core::str::FromStr>(text: &str) -> Option<F> {
+    let text = match text {
+        ".inf" | ".Inf" | ".INF" | "+.inf" | "+.Inf" | "+.INF" => "inf",
+        "-.inf" | "-.Inf" | "-.INF" => "-inf",
+        ".nan" | ".NaN" | ".NAN" => "NaN",
+        text => text,
+    };
+    text.parse().ok()
+}
+
+/// Create the error for text that isn't a float, with a hint if it looks like a number written
+/// in a way that floats can't be.
+fn float_error(text: &str, type_name: &str) -> DeserializeError {
+    let unsigned = text.trim().trim_start_matches(['-', '+']);
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+
+    let hint = if let Some((whole, fraction)) = unsigned.split_once(',') {
+        (is_digits(whole) && is_digits(fraction)).then(|| {
+            format!("the decimal separator is a dot, e.g. `{whole}.{fraction}`, not a comma")
+        })
+    } else if let Some(digits) = unsigned.strip_prefix("0o") {
+        digits
+            .bytes()
+            .all(|b| (b'0'..=b'7').contains(&b))
+            .then(|| "octal numbers aren't accepted for floats, write it in decimal".into())
+    } else {
+        None
+    };
+
+    let message = format!("Failed to parse `{text}` as {type_name}");
+    DeserializeError::new(
+        ErrorCode::InvalidScalar,
+        match hint {
+            Some(hint) => format!("{message}: {hint}"),
+            None => message,
+        },
+    )
+}
+
+/// Whether float text has no digit before its decimal dot, e.g. `.5` or `-.5`, unlike the
+/// non-finite spellings such as `.inf`.
+fn has_leading_dot(text: &str) -> bool {
+    text.trim_start_matches(['-', '+'])
+        .strip_prefix('.')
+        .is_some_and(|fraction| fraction.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+/// Remove the underscores that YAML 1.1 allows between the digits of a number, e.g. in
+/// `1_000.5`, or get `None` if there are none or they aren't between digits.
+fn without_separators(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut found = false;
+    for (index, byte) in bytes.iter().enumerate() {
+        if *byte == b'_' {
+            let between_digits = index > 0
+                && bytes[index - 1].is_ascii_digit()
+                && bytes.get(index + 1).is_some_and(u8::is_ascii_digit);
+            if !between_digits {
+                return None;
+            }
+            found = true;
+        }
     }
+    found.then(|| text.replace('_', ""))
+}
+
+fn from_str_value<'facet>(
+    wip: &mut Partial<'facet>,
+    yaml: &str,
+    options: &DeserializeOptions,
+    fields: &FieldCache,
+    projection: Option<Vec<Vec<String>>>,
+    scratch: &mut Scratch,
+) -> Result<(), DeserializeError> {
+    // The parser would otherwise treat a byte-order mark as part of the first scalar
+    let yaml = yaml.strip_prefix('\u{feff}').unwrap_or(yaml);
 
-    // Handle transparent types - check if shape has the transparent attribute
-    if shape
-        .attributes
-        .contains(&facet_core::ShapeAttribute::Transparent)
+    let docs =
+        node::load_reusing(yaml, projection, options, scratch).map_err(|e| e.with_source(yaml))?;
+    let result = match docs.as_slice() {
+        // Only comments, directives or whitespace
+        [] => set_empty_document(wip),
+        [doc] => Deserializer::new(options, fields)
+            .deserialize_value(wip, doc)
+            .map_err(|e| e.with_source(yaml)),
+        _ => Err(DeserializeError::new(
+            ErrorCode::DocumentCount,
+            "Expected exactly one YAML document",
+        )),
+    };
+    for doc in docs {
+        scratch.recycle(doc);
+    }
+    result
+}
+
+/// Deserializes a [`Value`] into a value of type `T`, the way a document with the same content
+/// is, without consuming it.
+///
+/// This is meant for pipelines that look at a document before deciding what to bind it to,
+/// such as a manifest whose `kind` says which type the rest of it is. The whole value, keys and
+/// all, is first copied into the nodes that documents are loaded into, so this costs about as
+/// much as cloning it, on top of binding it. That is still cheaper than writing it back to YAML
+/// and parsing it again. Errors have a path but no location, since values don't remember where
+/// they were loaded from.
+///
+/// ```
+/// use facet::Facet;
+/// use facet_yaml::Value;
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Service {
+///     kind: String,
+///     port: u16,
+/// }
+///
+/// let value: Value = facet_yaml::from_str("kind: Service\nport: 80").unwrap();
+/// if value.get("kind").and_then(Value::as_str) == Some("Service") {
+///     let service: Service = facet_yaml::from_value_ref(&value).unwrap();
+///     assert_eq!(service.port, 80);
+/// }
+/// ```
+pub fn from_value_ref<T: for<'facet> Facet<'facet>>(value: &Value) -> Result<T, DeserializeError> {
+    from_node(&value.to_node())
+}
+
+/// Deserialize a [`Value`] into a value of type `T`, the way a document with the same content
+/// is. Errors have no location, since values don't remember where they were loaded from.
+#[cfg(feature = "serde-bridge")]
+pub(crate) fn from_value<T: for<'facet> Facet<'facet>>(
+    value: Value,
+) -> Result<T, DeserializeError> {
+    from_node(&value.into_node())
+}
+
+/// Deserialize a node converted from a [`Value`] into a value of type `T`.
+fn from_node<T: for<'facet> Facet<'facet>>(node: &Node) -> Result<T, DeserializeError> {
+    let mut typed_partial = Partial::alloc::<T>()?;
     {
-        #[cfg(feature = "log")]
-        log::debug!("Handling facet(transparent) type");
+        let wip = typed_partial.inner_mut();
+        let result = if node.value == NodeValue::Null {
+            set_empty_document(wip)
+        } else {
+            Deserializer::new(&DeserializeOptions::default(), &FieldCache::default())
+                .deserialize_value(wip, node)
+        };
+        result.map_err(DeserializeError::unlocated)?;
+    }
+    let boxed_value = typed_partial.build().map_err(DeserializeError::reflect)?;
+    Ok(*boxed_value)
+}
 
-        // For transparent types, push inner and deserialize as inner type
-        wip.begin_inner().map_err(|e| AnyErr(e.to_string()))?;
-        deserialize_value(wip, value)?;
-        wip.end().map_err(|e| AnyErr(e.to_string()))?;
+/// Set the value for a document without any content, which is the default of its type, or of
+/// each of its fields for structs.
+fn set_empty_document(wip: &mut Partial<'_>) -> Result<(), DeserializeError> {
+    let shape = wip.shape();
+    if wip.set_default().is_ok() {
         return Ok(());
     }
 
-    // First check the type system (Type)
-    if let Type::User(UserType::Struct(sd)) = &shape.ty {
-        if let Yaml::Hash(hash) = value {
-            // Process all fields in the YAML map
-            for (k, v) in hash {
-                let k = k
-                    .as_str()
-                    .ok_or_else(|| AnyErr(format!("Expected string key, got: {}", yaml_type(k))))?;
-                let field_index = wip
-                    .field_index(k)
-                    .ok_or_else(|| AnyErr(format!("Field '{k}' not found")))?;
+    // A struct without a default is still complete when each of its fields has one
+    match &shape.ty {
+        Type::User(UserType::Struct(sd))
+            if sd.fields.iter().all(|field| {
+                field.flags.contains(FieldFlags::DEFAULT) || is_option(field.shape())
+            }) =>
+        {
+            for index in 0..sd.fields.len() {
+                wip.set_nth_field_to_default(index)
+                    .map_err(DeserializeError::reflect)?;
+            }
+            Ok(())
+        }
+        _ => Err(DeserializeError::new(
+            ErrorCode::NoDefault,
+            format!("The YAML document is empty, and {shape} has no default value"),
+        )),
+    }
+}
 
-                #[cfg(feature = "log")]
-                log::debug!("Processing struct field '{k}' (index: {field_index})");
+/// Deserializes YAML nodes into partially built values.
+struct Deserializer<'opts> {
+    /// How to deserialize.
+    options: &'opts DeserializeOptions,
+    /// What is known about struct types, possibly from earlier documents.
+    fields: &'opts FieldCache,
+    /// Naming convention of the keys of the current struct, set by the nearest field with a
+    /// `rename_all` attribute, or else by the options.
+    rename_all: Cell<Option<RenameRule>>,
+}
+
+impl<'opts> Deserializer<'opts> {
+    /// Create a deserializer for the root of a document.
+    fn new(options: &'opts DeserializeOptions, fields: &'opts FieldCache) -> Self {
+        Self {
+            options,
+            fields,
+            rename_all: Cell::new(options.rename_all),
+        }
+    }
+}
+
+impl Deserializer<'_> {
+    /// Deserialize a node, locating any error at the innermost node that caused it.
+    fn deserialize_value<'facet>(
+        &self,
+        wip: &mut Partial<'facet>,
+        value: &Node,
+    ) -> Result<(), DeserializeError> {
+        if self.options.is_cancelled() {
+            return Err(DeserializeError::cancelled());
+        }
+        let shape = wip.shape();
+        self.deserialize_node(wip, value)
+            .map_err(|e| e.located(value.span, shape))
+    }
+
+    fn deserialize_node<'facet>(
+        &self,
+        wip: &mut Partial<'facet>,
+        value: &Node,
+    ) -> Result<(), DeserializeError> {
+        // Get the shape
+        let shape = wip.shape();
+
+        #[cfg(feature = "log")]
+        {
+            log::debug!("deserialize_value: shape={shape}");
+            log::debug!("Shape type: {:?}", shape.ty);
+            log::debug!("Shape attributes: {:?}", shape.attributes);
+            log::debug!("YAML value: {value:?}");
+        }
+
+        // Cron expressions are transparent too, but must be validated before they're set
+        #[cfg(feature = "cron")]
+        if shape.is_type::<crate::CronExpr>() {
+            #[cfg(feature = "log")]
+            log::debug!("Handling cron expression");
+
+            let expr = value.as_str().ok_or_else(|| {
+                DeserializeError::new(
+                    ErrorCode::TypeMismatch,
+                    format!(
+                        "Expected a cron expression string, got: {}",
+                        yaml_type(value)
+                    ),
+                )
+            })?;
+            let cron = crate::CronExpr::parse(expr)
+                .map_err(|e| DeserializeError::new(ErrorCode::InvalidScalar, e.to_string()))?;
+            wip.set(cron).map_err(DeserializeError::reflect)?;
+            return Ok(());
+        }
 
-                wip.begin_nth_field(field_index)
-                    .map_err(|e| AnyErr(format!("Field '{k}' error: {e}")))?;
-                deserialize_value(wip, v)?;
-                wip.end().map_err(|e| AnyErr(e.to_string()))?;
+        // Customized types read the node themselves
+        if let Some(customization) = self.options.customization(shape) {
+            #[cfg(feature = "log")]
+            log::debug!("Handling customized type");
+
+            if let (Some(tag), Some(own)) = (customization.tag, value.own_tag()) {
+                if own != tag {
+                    return Err(DeserializeError::new(
+                        ErrorCode::TypeMismatch,
+                        format!("Expected a node tagged `{tag}` for {shape}, got `{own}`"),
+                    ));
+                }
             }
+            return (customization.read)(wip, Value::from_node(value.clone()));
+        }
 
-            // Process any unset fields with defaults
-            for (index, field) in sd.fields.iter().enumerate() {
-                let is_set = wip.is_field_set(index).map_err(|e| AnyErr(e.to_string()))?;
-                if !is_set {
-                    // If field has default attribute, apply it
-                    if field.flags.contains(FieldFlags::DEFAULT) {
-                        #[cfg(feature = "log")]
-                        log::debug!("Setting default for field: {}", field.name);
+        // Dynamic values take any node as it is
+        if shape.is_type::<Value>() {
+            #[cfg(feature = "log")]
+            log::debug!("Handling dynamic value");
 
-                        wip.set_nth_field_to_default(index)
-                            .map_err(|e| AnyErr(e.to_string()))?;
+            wip.set(Value::from_node(value.clone()))
+                .map_err(DeserializeError::reflect)?;
+            return Ok(());
+        }
+
+        // Handle transparent types - check if shape has the transparent attribute
+        if shape
+            .attributes
+            .contains(&facet_core::ShapeAttribute::Transparent)
+        {
+            #[cfg(feature = "log")]
+            log::debug!("Handling facet(transparent) type");
+
+            // For transparent types, push inner and deserialize as inner type
+            wip.begin_inner().map_err(DeserializeError::reflect)?;
+            self.deserialize_value(wip, value)?;
+            wip.end().map_err(DeserializeError::reflect)?;
+            return Ok(());
+        }
+
+        // First check the type system (Type)
+        if let Type::User(UserType::Struct(sd)) = &shape.ty {
+            // Tuples are sequences, unless they're read from their fields by name
+            let is_tuple = matches!(sd.kind, StructKind::Tuple | StructKind::TupleStruct);
+            if is_newtype(sd) || (is_tuple && !matches!(value.value, NodeValue::Hash(_))) {
+                return self.deserialize_tuple(wip, shape, sd, value);
+            }
+
+            let no_entries = Vec::new();
+            let hash = match &value.value {
+                NodeValue::Hash(hash) => Some(hash),
+                // Unit structs are written as null, e.g. `fast_path: ~`, and so can other structs
+                // without fields be
+                NodeValue::Null
+                    if matches!(sd.kind, StructKind::Unit)
+                        || (self.options.unit_from_null && sd.fields.is_empty()) =>
+                {
+                    Some(&no_entries)
+                }
+                _ => None,
+            };
+            if let Some(hash) = hash {
+                let fields = self
+                    .fields
+                    .fields(shape, sd)
+                    .map_err(|e| e.located(value.span, shape))?;
+                // Fields that are read from a nested path instead of their own key
+                let paths = fields.paths();
+                // Fields that are never read, set to their default below
+                let skipped = fields.skipped();
+                // Fields of which only one may be set, along with the key each field is read
+                // from when there are any
+                let exclusive = fields.exclusive();
+                let mut keys = if exclusive.is_empty() {
+                    Vec::new()
+                } else {
+                    alloc::vec![None; sd.fields.len()]
+                };
+
+                // Process all fields in the YAML map
+                for (key, v) in hash {
+                    let k = key.as_str().ok_or_else(|| {
+                        DeserializeError::new(
+                            ErrorCode::InvalidKey,
+                            format!("Expected string key, got: {}", yaml_type(key)),
+                        )
+                        .located(key.span, shape)
+                    })?;
+                    let field_index =
+                        self.fields
+                            .field_index(shape, &fields, k, self.rename_all.get(), |k| {
+                                self.find_field(sd, k)
+                            });
+                    let field_index = match field_index {
+                        Some(index) if skipped.contains(&index) => continue,
+                        Some(index) if !paths.iter().any(|(i, _)| *i == index) => index,
+                        // Read below, along with the other paths
+                        _ if paths.iter().any(|(_, path)| path[0] == k) => continue,
+                        _ => {
+                            return Err(DeserializeError::new(
+                                ErrorCode::UnknownField,
+                                format!("Field '{k}' not found"),
+                            )
+                            .located(key.span, shape));
+                        }
+                    };
+
+                    #[cfg(feature = "log")]
+                    log::debug!("Processing struct field '{k}' (index: {field_index})");
+
+                    wip.begin_nth_field(field_index).map_err(|e| {
+                        DeserializeError::new(ErrorCode::Reflect, format!("Field '{k}' error: {e}"))
+                    })?;
+                    self.deserialize_field(wip, &fields, sd, field_index, v)
+                        .map_err(|e| e.in_field(k))?;
+                    wip.end().map_err(DeserializeError::reflect)?;
+                    if let Some(slot) = keys.get_mut(field_index) {
+                        *slot = Some((k.to_string(), key.span));
                     }
                 }
-            }
 
-            let mut default_instance: Option<Partial> = None;
+                for (index, path) in paths {
+                    let Some(v) = find_path(value, path) else {
+                        continue;
+                    };
+                    let path = path.join(".");
 
-            for (index, _field) in sd.fields.iter().enumerate() {
-                let is_set = wip.is_field_set(index).map_err(|e| AnyErr(e.to_string()))?;
-                if !is_set {
-                    let default_instance_mut = default_instance.get_or_insert_with(|| {
-                        // FIXME: those unwrap() don't look good
-                        let mut partial = Partial::alloc_shape(shape).unwrap();
-                        // FIXME: this'll blow up if the struct itself doesn't impl Default
-                        partial.set_default().unwrap();
-                        partial
-                    });
-                    wip.steal_nth_field(default_instance_mut, index)?;
+                    #[cfg(feature = "log")]
+                    log::debug!("Processing struct field at path '{path}' (index: {index})");
+
+                    wip.begin_nth_field(*index).map_err(|e| {
+                        DeserializeError::new(
+                            ErrorCode::Reflect,
+                            format!("Field at '{path}' error: {e}"),
+                        )
+                    })?;
+                    self.deserialize_field(wip, &fields, sd, *index, v)
+                        .map_err(|e| e.in_field(&path))?;
+                    wip.end().map_err(DeserializeError::reflect)?;
+                    if let Some(slot) = keys.get_mut(*index) {
+                        *slot = Some((path, v.span));
+                    }
+                }
+
+                if let Some(err) = exclusive_conflicts(exclusive, &keys, shape)
+                    .into_iter()
+                    .next()
+                {
+                    return Err(err);
                 }
+
+                // Report which fields were read, defaulted and missing along with any error
+                let mut defaulted = alloc::vec![false; sd.fields.len()];
+                self.set_unset_fields(wip, shape, sd, value, &mut defaulted)
+                    .map_err(|e| e.with_fields(FieldReport::new(wip, shape, sd, &defaulted)))?;
+            } else {
+                return Err(DeserializeError::new(
+                    ErrorCode::TypeMismatch,
+                    format!("Expected a YAML hash, got: {}", yaml_type(value)),
+                ));
             }
-        } else {
-            return Err(AnyErr(format!("Expected a YAML hash, got: {value:?}")));
+            return Ok(());
         }
-        return Ok(());
-    }
 
-    match shape.def {
-        Def::Scalar => {
-            #[cfg(feature = "log")]
-            {
-                log::debug!("Processing scalar type");
-                log::debug!("  shape: {shape}");
-                log::debug!("  shape.ty: {:?}", shape.ty);
-            }
-
-            // Check if it's a numeric type
-            if let Type::Primitive(PrimitiveType::Numeric(numeric_type)) = shape.ty {
-                let size = shape.layout.sized_layout().unwrap().size();
-                match numeric_type {
-                    NumericType::Integer { signed: false } => {
-                        let u = yaml_to_u64(value)?;
-                        match size {
-                            1 => {
-                                let val = u8::try_from(u).map_err(|_| {
-                                    AnyErr(format!("Value {u} out of range for u8"))
-                                })?;
-                                wip.set(val).map_err(|e| AnyErr(e.to_string()))?;
-                            }
-                            2 => {
-                                let val = u16::try_from(u).map_err(|_| {
-                                    AnyErr(format!("Value {u} out of range for u16"))
-                                })?;
-                                wip.set(val).map_err(|e| AnyErr(e.to_string()))?;
-                            }
-                            4 => {
-                                let val = u32::try_from(u).map_err(|_| {
-                                    AnyErr(format!("Value {u} out of range for u32"))
-                                })?;
-                                wip.set(val).map_err(|e| AnyErr(e.to_string()))?;
-                            }
-                            8 => {
-                                // Check if it's usize or u64
-                                if shape.is_type::<usize>() {
+        match shape.def {
+            Def::Scalar => {
+                #[cfg(feature = "log")]
+                {
+                    log::debug!("Processing scalar type");
+                    log::debug!("  shape: {shape}");
+                    log::debug!("  shape.ty: {:?}", shape.ty);
+                }
+
+                // Check if it's a numeric type
+                if let Type::Primitive(PrimitiveType::Numeric(numeric_type)) = shape.ty {
+                    let size = shape.layout.sized_layout().unwrap().size();
+                    match numeric_type {
+                        NumericType::Integer { signed: false } => {
+                            let u = self.yaml_to_u64(value)?;
+                            match size {
+                                1 => {
+                                    let val = u8::try_from(u).map_err(|_| {
+                                        DeserializeError::new(
+                                            ErrorCode::OutOfRange,
+                                            format!("Value {u} out of range for u8"),
+                                        )
+                                    })?;
+                                    wip.set(val).map_err(DeserializeError::reflect)?;
+                                }
+                                2 => {
+                                    let val = u16::try_from(u).map_err(|_| {
+                                        DeserializeError::new(
+                                            ErrorCode::OutOfRange,
+                                            format!("Value {u} out of range for u16"),
+                                        )
+                                    })?;
+                                    wip.set(val).map_err(DeserializeError::reflect)?;
+                                }
+                                4 => {
+                                    let val = u32::try_from(u).map_err(|_| {
+                                        DeserializeError::new(
+                                            ErrorCode::OutOfRange,
+                                            format!("Value {u} out of range for u32"),
+                                        )
+                                    })?;
+                                    wip.set(val).map_err(DeserializeError::reflect)?;
+                                }
+                                8 => {
+                                    // Check if it's usize or u64
+                                    if shape.is_type::<usize>() {
+                                        let val = usize::try_from(u).map_err(|_| {
+                                            DeserializeError::new(
+                                                ErrorCode::OutOfRange,
+                                                format!("Value {u} out of range for usize"),
+                                            )
+                                        })?;
+                                        wip.set(val).map_err(DeserializeError::reflect)?;
+                                    } else {
+                                        wip.set(u).map_err(DeserializeError::reflect)?;
+                                    }
+                                }
+                                16 => {
+                                    let val = u128::from(u);
+                                    wip.set(val).map_err(DeserializeError::reflect)?;
+                                }
+                                _ => {
+                                    // Handle usize
                                     let val = usize::try_from(u).map_err(|_| {
-                                        AnyErr(format!("Value {u} out of range for usize"))
+                                        DeserializeError::new(
+                                            ErrorCode::OutOfRange,
+                                            format!("Value {u} out of range for usize"),
+                                        )
                                     })?;
-                                    wip.set(val).map_err(|e| AnyErr(e.to_string()))?;
-                                } else {
-                                    wip.set(u).map_err(|e| AnyErr(e.to_string()))?;
+                                    wip.set(val).map_err(DeserializeError::reflect)?;
                                 }
                             }
-                            16 => {
-                                let val = u128::from(u);
-                                wip.set(val).map_err(|e| AnyErr(e.to_string()))?;
-                            }
-                            _ => {
-                                // Handle usize
-                                let val = usize::try_from(u).map_err(|_| {
-                                    AnyErr(format!("Value {u} out of range for usize"))
-                                })?;
-                                wip.set(val).map_err(|e| AnyErr(e.to_string()))?;
-                            }
                         }
-                    }
-                    NumericType::Integer { signed: true } => {
-                        let i = match value {
-                            Yaml::Integer(i) => *i,
-                            Yaml::Real(r) => r
-                                .parse::<i64>()
-                                .map_err(|_| AnyErr("Failed to parse real as i64".into()))?,
-                            Yaml::String(s) => s
-                                .parse::<i64>()
-                                .map_err(|_| AnyErr("Failed to parse string as i64".into()))?,
-                            Yaml::Boolean(b) => {
-                                if *b {
-                                    1
-                                } else {
-                                    0
+                        NumericType::Integer { signed: true } => {
+                            let i = self.yaml_to_i64(value)?;
+                            match size {
+                                1 => {
+                                    let val = i8::try_from(i).map_err(|_| {
+                                        DeserializeError::new(
+                                            ErrorCode::OutOfRange,
+                                            format!("Value {i} out of range for i8"),
+                                        )
+                                    })?;
+                                    wip.set(val).map_err(DeserializeError::reflect)?;
                                 }
-                            }
-                            _ => {
-                                return Err(AnyErr(format!(
-                                    "Cannot convert {} to i64",
-                                    yaml_type(value)
-                                )));
-                            }
-                        };
-                        match size {
-                            1 => {
-                                let val = i8::try_from(i).map_err(|_| {
-                                    AnyErr(format!("Value {i} out of range for i8"))
-                                })?;
-                                wip.set(val).map_err(|e| AnyErr(e.to_string()))?;
-                            }
-                            2 => {
-                                let val = i16::try_from(i).map_err(|_| {
-                                    AnyErr(format!("Value {i} out of range for i16"))
-                                })?;
-                                wip.set(val).map_err(|e| AnyErr(e.to_string()))?;
-                            }
-                            4 => {
-                                let val = i32::try_from(i).map_err(|_| {
-                                    AnyErr(format!("Value {i} out of range for i32"))
-                                })?;
-                                wip.set(val).map_err(|e| AnyErr(e.to_string()))?;
-                            }
-                            8 => {
-                                // Check if it's isize or i64
-                                if shape.is_type::<isize>() {
+                                2 => {
+                                    let val = i16::try_from(i).map_err(|_| {
+                                        DeserializeError::new(
+                                            ErrorCode::OutOfRange,
+                                            format!("Value {i} out of range for i16"),
+                                        )
+                                    })?;
+                                    wip.set(val).map_err(DeserializeError::reflect)?;
+                                }
+                                4 => {
+                                    let val = i32::try_from(i).map_err(|_| {
+                                        DeserializeError::new(
+                                            ErrorCode::OutOfRange,
+                                            format!("Value {i} out of range for i32"),
+                                        )
+                                    })?;
+                                    wip.set(val).map_err(DeserializeError::reflect)?;
+                                }
+                                8 => {
+                                    // Check if it's isize or i64
+                                    if shape.is_type::<isize>() {
+                                        let val = isize::try_from(i).map_err(|_| {
+                                            DeserializeError::new(
+                                                ErrorCode::OutOfRange,
+                                                format!("Value {i} out of range for isize"),
+                                            )
+                                        })?;
+                                        wip.set(val).map_err(DeserializeError::reflect)?;
+                                    } else {
+                                        wip.set(i).map_err(DeserializeError::reflect)?;
+                                    }
+                                }
+                                16 => {
+                                    let val = i128::from(i);
+                                    wip.set(val).map_err(DeserializeError::reflect)?;
+                                }
+                                _ => {
+                                    // Handle isize
                                     let val = isize::try_from(i).map_err(|_| {
-                                        AnyErr(format!("Value {i} out of range for isize"))
+                                        DeserializeError::new(
+                                            ErrorCode::OutOfRange,
+                                            format!("Value {i} out of range for isize"),
+                                        )
                                     })?;
-                                    wip.set(val).map_err(|e| AnyErr(e.to_string()))?;
-                                } else {
-                                    wip.set(i).map_err(|e| AnyErr(e.to_string()))?;
+                                    wip.set(val).map_err(DeserializeError::reflect)?;
                                 }
                             }
-                            16 => {
-                                let val = i128::from(i);
-                                wip.set(val).map_err(|e| AnyErr(e.to_string()))?;
-                            }
-                            _ => {
-                                // Handle isize
-                                let val = isize::try_from(i).map_err(|_| {
-                                    AnyErr(format!("Value {i} out of range for isize"))
-                                })?;
-                                wip.set(val).map_err(|e| AnyErr(e.to_string()))?;
+                        }
+                        NumericType::Float => {
+                            // Determine float type based on size (f32 is 4 bytes, f64 is 8 bytes),
+                            // parsing straight into it so f32 values aren't rounded twice
+                            if size == 4 {
+                                let val = self.yaml_to_float::<f32>(value)?;
+                                wip.set(val).map_err(DeserializeError::reflect)?;
+                            } else {
+                                let val = self.yaml_to_float::<f64>(value)?;
+                                wip.set(val).map_err(DeserializeError::reflect)?;
                             }
                         }
                     }
-                    NumericType::Float => {
-                        // Handle floating point numbers
-                        let f = match value {
-                            Yaml::Real(r) => r
-                                .parse::<f64>()
-                                .map_err(|_| AnyErr("Failed to parse real as f64".into()))?,
-                            Yaml::Integer(i) => *i as f64,
-                            Yaml::String(s) => s
-                                .parse::<f64>()
-                                .map_err(|_| AnyErr("Failed to parse string as f64".into()))?,
-                            _ => {
-                                return Err(AnyErr(format!(
-                                    "Cannot convert {} to f64",
-                                    yaml_type(value)
-                                )));
-                            }
-                        };
-                        // Determine float type based on size (f32 is 4 bytes, f64 is 8 bytes)
-                        if size == 4 {
-                            let val = f as f32;
-                            wip.set(val).map_err(|e| AnyErr(e.to_string()))?;
-                        } else {
-                            wip.set(f).map_err(|e| AnyErr(e.to_string()))?;
+                } else if shape.is_type::<bool>() {
+                    // Handle boolean values
+                    let b = match &value.value {
+                        NodeValue::Boolean(b) => *b,
+                        NodeValue::Integer(i) => *i != 0,
+                        NodeValue::String(s) => {
+                            let s = s.to_lowercase();
+                            s == "true" || s == "yes" || s == "1"
+                        }
+                        _ => {
+                            return Err(DeserializeError::new(
+                                ErrorCode::TypeMismatch,
+                                format!("Cannot convert {} to bool", yaml_type(value)),
+                            ));
                         }
+                    };
+                    wip.set(b).map_err(DeserializeError::reflect)?;
+                } else if shape.is_type::<String>() {
+                    // For strings, set directly
+                    let s = value
+                        .as_str()
+                        .ok_or_else(|| {
+                            DeserializeError::new(
+                                ErrorCode::TypeMismatch,
+                                format!("Expected string, got: {}", yaml_type(value)),
+                            )
+                        })?
+                        .to_string();
+                    wip.set(s).map_err(DeserializeError::reflect)?;
+                } else if shape.is_type::<Cow<'_, str>>() {
+                    // Cow<str> can't be parsed from a string, so it owns a copy of it
+                    let s = value.as_str().ok_or_else(|| {
+                        DeserializeError::new(
+                            ErrorCode::TypeMismatch,
+                            format!("Expected string, got: {}", yaml_type(value)),
+                        )
+                    })?;
+                    wip.set(Cow::<str>::Owned(s.to_string()))
+                        .map_err(DeserializeError::reflect)?;
+                } else {
+                    // Try parse_from_str first for any scalar type that supports it, from the
+                    // text of numbers as well, such as map keys like `42` for a newtype id
+                    let s = scalar_text(value).ok_or_else(|| {
+                        DeserializeError::new(
+                            ErrorCode::TypeMismatch,
+                            format!("Expected string, got: {}", yaml_type(value)),
+                        )
+                    })?;
+                    if wip.parse_from_str(&s).is_err() {
+                        // If parsing fails, fall back to setting as String
+                        wip.set(s.to_string()).map_err(|_| {
+                            DeserializeError::new(
+                                ErrorCode::InvalidScalar,
+                                format!("Cannot parse '{s}' as {shape}"),
+                            )
+                        })?;
                     }
                 }
-            } else if shape.is_type::<bool>() {
-                // Handle boolean values
-                let b = match value {
-                    Yaml::Boolean(b) => *b,
-                    Yaml::Integer(i) => *i != 0,
-                    Yaml::String(s) => {
-                        let s = s.to_lowercase();
-                        s == "true" || s == "yes" || s == "1"
+            }
+            Def::List(_) => {
+                #[cfg(feature = "log")]
+                log::debug!("Processing list type");
+
+                self.deserialize_as_list(wip, value)?;
+            }
+            Def::Map(_) => {
+                #[cfg(feature = "log")]
+                log::debug!("Processing map type");
+
+                self.deserialize_as_map(wip, value)?;
+            }
+            Def::Set(_) => {
+                // facet-reflect 0.30 can describe sets but not build them
+                return Err(DeserializeError::new(
+                    ErrorCode::Unsupported,
+                    format!("Sets such as {shape} can't be deserialized yet, read a list instead"),
+                )
+                .located(value.span, shape));
+            }
+            Def::Option(_) => {
+                #[cfg(feature = "log")]
+                log::debug!("Processing option type");
+
+                // Handle Option<T>
+                if let NodeValue::Null = value.value {
+                    // Null maps to None, also for list items and map values which are never
+                    // pre-initialized
+                    wip.set_default().map_err(DeserializeError::reflect)?;
+                } else {
+                    // Non-null maps to Some(value)
+                    wip.begin_some().map_err(DeserializeError::reflect)?;
+                    self.deserialize_value(wip, value)?;
+                    wip.end().map_err(DeserializeError::reflect)?;
+                }
+            }
+
+            Def::Pointer(smart_ptr_def) => {
+                #[cfg(feature = "log")]
+                log::debug!("Processing smart pointer type");
+
+                // Check the pointee type before calling begin_smart_ptr
+                let pointee_shape = smart_ptr_def.pointee().ok_or_else(|| {
+                    DeserializeError::new(
+                        ErrorCode::Unsupported,
+                        "SmartPointer must have a pointee shape",
+                    )
+                })?;
+
+                #[cfg(feature = "log")]
+                log::debug!("Smart pointer pointee shape: {pointee_shape}");
+
+                // Begin smart pointer
+                wip.begin_smart_ptr().map_err(DeserializeError::reflect)?;
+
+                // For smart pointers to slices, the shape doesn't change after begin_smart_ptr
+                // but the internal state changes to use a slice builder
+                match pointee_shape.ty {
+                    Type::Sequence(SequenceType::Slice(_)) => {
+                        #[cfg(feature = "log")]
+                        log::debug!("Smart pointer pointee is a slice, deserializing as list");
+                        // Slices are handled like lists
+                        self.deserialize_as_list(wip, value)?;
                     }
                     _ => {
-                        return Err(AnyErr(format!(
-                            "Cannot convert {} to bool",
-                            yaml_type(value)
-                        )));
+                        #[cfg(feature = "log")]
+                        log::debug!("Smart pointer pointee is not a slice, deserializing normally");
+                        // For other types, deserialize normally. A `str` pointee is built as a
+                        // `String`, which facet-reflect converts when the pointer ends
+                        self.deserialize_value(wip, value)?;
                     }
-                };
-                wip.set(b).map_err(|e| AnyErr(e.to_string()))?;
-            } else if shape.is_type::<String>() {
-                // For strings, set directly
-                let s = value
-                    .as_str()
-                    .ok_or_else(|| AnyErr(format!("Expected string, got: {}", yaml_type(value))))?
-                    .to_string();
-                wip.set(s).map_err(|e| AnyErr(e.to_string()))?;
-            } else {
-                // Try parse_from_str first for any scalar type that supports it
-                let s = value
-                    .as_str()
-                    .ok_or_else(|| AnyErr(format!("Expected string, got: {}", yaml_type(value))))?;
-                if wip.parse_from_str(s).is_err() {
-                    // If parsing fails, fall back to setting as String
-                    wip.set(s.to_string()).map_err(|e| AnyErr(e.to_string()))?;
                 }
+
+                // End smart pointer
+                wip.end().map_err(DeserializeError::reflect)?;
             }
+            Def::Slice(_) => {
+                #[cfg(feature = "log")]
+                log::debug!("Processing slice type");
+
+                // Slices are deserialized like lists
+                self.deserialize_as_list(wip, value)?;
+            }
+            Def::Array(ad) => {
+                #[cfg(feature = "log")]
+                log::debug!("Processing array type");
+
+                let items = array_items(shape, ad.n, value)?;
+                if items.is_empty() {
+                    // There are no items to mark a zero-length array as initialized
+                    wip.set_default().map_err(DeserializeError::reflect)?;
+                }
+                for (index, item) in items.iter().enumerate() {
+                    wip.begin_nth_field(index)
+                        .map_err(DeserializeError::reflect)?;
+                    self.deserialize_value(wip, item)
+                        .map_err(|e| e.in_index(index))?;
+                    wip.end().map_err(DeserializeError::reflect)?;
+                }
+            }
+            _ => match &shape.ty {
+                Type::User(UserType::Enum(ed)) => {
+                    #[cfg(feature = "log")]
+                    log::debug!("Processing enum type");
+
+                    self.deserialize_enum(wip, ed, value)?;
+                }
+                _ => {
+                    return Err(DeserializeError::new(
+                        ErrorCode::Unsupported,
+                        format!("Unsupported type: {shape:?}"),
+                    ));
+                }
+            },
         }
-        Def::List(_) => {
-            #[cfg(feature = "log")]
-            log::debug!("Processing list type");
+        Ok(())
+    }
 
-            deserialize_as_list(wip, value)?;
+    /// Deserialize a tuple or a tuple struct from a sequence of its fields, or a newtype, a
+    /// tuple struct with a single field, from the field itself, e.g. `struct Meters(f64)` from
+    /// `3.5`.
+    fn deserialize_tuple<'facet>(
+        &self,
+        wip: &mut Partial<'facet>,
+        shape: &'static Shape,
+        sd: &StructType,
+        value: &Node,
+    ) -> Result<(), DeserializeError> {
+        if is_newtype(sd) {
+            wip.begin_nth_field(0).map_err(DeserializeError::reflect)?;
+            self.deserialize_value(wip, value)?;
+            wip.end().map_err(DeserializeError::reflect)?;
+            return Ok(());
         }
-        Def::Map(_) => {
-            #[cfg(feature = "log")]
-            log::debug!("Processing map type");
 
-            deserialize_as_map(wip, value)?;
+        let items = match &value.value {
+            NodeValue::Array(items) => items.as_slice(),
+            // `()` is written as null, like unit structs
+            NodeValue::Null if sd.fields.is_empty() => &[],
+            _ => {
+                return Err(DeserializeError::new(
+                    ErrorCode::TypeMismatch,
+                    format!("Expected a YAML array, got: {}", yaml_type(value)),
+                ));
+            }
+        };
+        if items.len() != sd.fields.len() {
+            return Err(DeserializeError::new(
+                ErrorCode::TypeMismatch,
+                format!(
+                    "Expected {} items for {shape}, got {}",
+                    sd.fields.len(),
+                    items.len()
+                ),
+            ));
         }
-        Def::Option(_) => {
-            #[cfg(feature = "log")]
-            log::debug!("Processing option type");
+        for (index, item) in items.iter().enumerate() {
+            wip.begin_nth_field(index)
+                .map_err(DeserializeError::reflect)?;
+            self.deserialize_value(wip, item)
+                .map_err(|e| e.in_index(index))?;
+            wip.end().map_err(DeserializeError::reflect)?;
+        }
+        Ok(())
+    }
 
-            // Handle Option<T>
-            if let Yaml::Null = value {
-                // Null maps to None - already handled by default
-            } else {
-                // Non-null maps to Some(value)
-                wip.begin_some().map_err(|e| AnyErr(e.to_string()))?;
-                deserialize_value(wip, value)?;
-                wip.end().map_err(|e| AnyErr(e.to_string()))?;
+    /// Set the fields of a struct that weren't in its hash from their defaults, recording
+    /// which ones were.
+    fn set_unset_fields<'facet>(
+        &self,
+        wip: &mut Partial<'facet>,
+        shape: &'static Shape,
+        sd: &StructType,
+        value: &Node,
+        defaulted: &mut [bool],
+    ) -> Result<(), DeserializeError> {
+        // Required fields aren't taken from any default
+        let fields = self
+            .fields
+            .fields(shape, sd)
+            .map_err(|e| e.located(value.span, shape))?;
+        for &index in fields.required() {
+            if !wip.is_field_set(index).map_err(DeserializeError::reflect)? {
+                return Err(DeserializeError::new(
+                    ErrorCode::MissingField,
+                    format!("Missing field '{}'", sd.fields[index].name),
+                )
+                .located(value.span, shape));
             }
         }
 
-        Def::Pointer(smart_ptr_def) => {
-            #[cfg(feature = "log")]
-            log::debug!("Processing smart pointer type");
+        // Process any unset fields with defaults. Options are `None` unless the default of the
+        // struct has them, which is only checked when one is missing
+        let mut struct_default = None;
+        for (index, field) in sd.fields.iter().enumerate() {
+            let is_set = wip.is_field_set(index).map_err(DeserializeError::reflect)?;
+            if !is_set {
+                // If field has default attribute, or is an option, apply it
+                let is_default = field.flags.contains(FieldFlags::DEFAULT)
+                    || (is_option(field.shape())
+                        && !*struct_default.get_or_insert_with(|| validate::has_default(shape)));
+                if is_default {
+                    #[cfg(feature = "log")]
+                    log::debug!("Setting default for field: {}", field.name);
 
-            // Check the pointee type before calling begin_smart_ptr
-            let pointee_shape = smart_ptr_def
-                .pointee()
-                .ok_or_else(|| AnyErr("SmartPointer must have a pointee shape".to_string()))?;
+                    wip.set_nth_field_to_default(index)
+                        .map_err(DeserializeError::reflect)?;
+                    defaulted[index] = true;
+                }
+            }
+        }
+
+        // The remaining fields are taken from the default of the struct, which is only built
+        // once, or are missing
+        let mut default_instance: Option<Partial> = None;
+        for (index, field) in sd.fields.iter().enumerate() {
+            if wip.is_field_set(index).map_err(DeserializeError::reflect)? {
+                continue;
+            }
+            if default_instance.is_none() {
+                if !*struct_default.get_or_insert_with(|| validate::has_default(shape)) {
+                    return Err(DeserializeError::new(
+                        ErrorCode::MissingField,
+                        format!("Missing field '{}'", field.name),
+                    )
+                    .located(value.span, shape));
+                }
+                let mut partial = Partial::alloc_shape(shape)?;
+                partial.set_default()?;
+                default_instance = Some(partial);
+            }
+            if let Some(default_instance) = &mut default_instance {
+                wip.steal_nth_field(default_instance, index)?;
+                defaulted[index] = true;
+            }
+        }
+        Ok(())
+    }
 
+    /// Deserialize an enum, from the name of a unit variant, or a mapping with a single key,
+    /// the name of the variant, whose value is its content.
+    ///
+    /// Names are matched as they're renamed with the `rename` and `rename_all` attributes of
+    /// facet. A unit variant renamed to a number or a boolean, like `#[facet(rename = "2")]`,
+    /// is read from that plain scalar too.
+    ///
+    /// The content of a variant with a single unnamed field is the field itself, the content of
+    /// one with more is a sequence of them, and the content of one with named fields is a
+    /// mapping of them.
+    ///
+    /// Enums with a `#[facet(tag = "...")]` attribute are internally tagged instead, see
+    /// [`Self::deserialize_tagged_enum`], and enums with a `#[facet(untagged)]` attribute have
+    /// no name at all, see [`Self::deserialize_untagged_enum`].
+    fn deserialize_enum<'facet>(
+        &self,
+        wip: &mut Partial<'facet>,
+        ed: &EnumType,
+        value: &Node,
+    ) -> Result<(), DeserializeError> {
+        let shape = wip.shape();
+        if let Some(variant) = self.variant_for_tag(ed, value) {
             #[cfg(feature = "log")]
-            log::debug!("Smart pointer pointee shape: {pointee_shape}");
+            log::debug!("Selecting variant '{}' by tag", variant.name);
 
-            // Begin smart pointer
-            wip.begin_smart_ptr().map_err(|e| AnyErr(e.to_string()))?;
+            wip.select_variant_named(variant.name)
+                .map_err(DeserializeError::reflect)?;
+            wip.begin_nth_field(0).map_err(DeserializeError::reflect)?;
+            self.deserialize_value(wip, value)?;
+            wip.end().map_err(DeserializeError::reflect)?;
+            return Ok(());
+        }
+        if attributes::is_untagged(shape) {
+            return self.deserialize_untagged_enum(wip, ed, value);
+        }
+        if let Some(tag) = attributes::enum_tag(shape) {
+            return self.deserialize_tagged_enum(wip, ed, tag, value);
+        }
 
-            // For smart pointers to slices, the shape doesn't change after begin_smart_ptr
-            // but the internal state changes to use a slice builder
-            match pointee_shape.ty {
-                Type::Sequence(SequenceType::Slice(_)) => {
-                    #[cfg(feature = "log")]
-                    log::debug!("Smart pointer pointee is a slice, deserializing as list");
-                    // Slices are handled like lists
-                    deserialize_as_list(wip, value)?;
+        let (name, content) = match &value.value {
+            NodeValue::Hash(entries) if entries.len() == 1 => {
+                let (key, content) = &entries[0];
+                let name = key.as_str().ok_or_else(|| {
+                    DeserializeError::new(
+                        ErrorCode::InvalidKey,
+                        format!("Expected the name of a variant, got: {}", yaml_type(key)),
+                    )
+                    .located(key.span, shape)
+                })?;
+                (Cow::Borrowed(name), Some(content))
+            }
+            _ => match scalar_text(value) {
+                Some(name) => (name, None),
+                None => {
+                    return Err(DeserializeError::new(
+                        ErrorCode::TypeMismatch,
+                        format!(
+                            "Expected the name of a variant, or a YAML hash with a single key, got: {}",
+                            yaml_type(value)
+                        ),
+                    ));
                 }
+            },
+        };
+        self.deserialize_variant(wip, ed, &name, content, false)
+    }
+
+    /// Get the base64 text of a node read into a byte buffer: a `!!binary` scalar, or a string
+    /// without a tag when [`DeserializeOptions::base64_strings`] is enabled.
+    fn base64_text<'n>(&self, node: &'n Node) -> Option<&'n str> {
+        node.binary().or(match (&node.value, &node.tag) {
+            (NodeValue::String(text), None) if self.options.base64_strings => Some(text.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Find the variant of an enum for the explicit tag of a node: the one holding the type
+    /// registered under the tag, or else the one holding a dynamic [`Value`].
+    fn variant_for_tag<'e>(&self, ed: &'e EnumType, node: &Node) -> Option<&'e Variant> {
+        let tag = node.own_tag()?;
+        let holds =
+            |variant: &Variant, shape: &Shape| match (variant.data.kind, variant.data.fields) {
+                (StructKind::Tuple | StructKind::TupleStruct, [field]) => field.shape() == shape,
+                _ => false,
+            };
+        let registered = self
+            .options
+            .tagged_shape(&tag)
+            .and_then(|shape| ed.variants.iter().find(|variant| holds(variant, shape)));
+        registered.or_else(|| {
+            ed.variants
+                .iter()
+                .find(|variant| holds(variant, Value::SHAPE))
+        })
+    }
+
+    /// Deserialize an internally tagged enum, whose variant is named by the `tag` key of a
+    /// hash, next to the fields of the variant, e.g. `kind: Service` and `port: 80`.
+    ///
+    /// The fields of a variant with a single unnamed field are those of the field, which is
+    /// usually a struct, so each variant can be a type of its own.
+    fn deserialize_tagged_enum<'facet>(
+        &self,
+        wip: &mut Partial<'facet>,
+        ed: &EnumType,
+        tag: &str,
+        value: &Node,
+    ) -> Result<(), DeserializeError> {
+        let shape = wip.shape();
+        let NodeValue::Hash(entries) = &value.value else {
+            return Err(DeserializeError::new(
+                ErrorCode::TypeMismatch,
+                format!(
+                    "Expected a YAML hash with a '{tag}' key for {shape}, got: {}",
+                    yaml_type(value)
+                ),
+            )
+            .located(value.span, shape));
+        };
+        let Some((_, name)) = entries.iter().find(|(key, _)| key.as_str() == Some(tag)) else {
+            return Err(DeserializeError::new(
+                ErrorCode::MissingField,
+                format!("Missing tag '{tag}' of {shape}"),
+            )
+            .located(value.span, shape));
+        };
+        let name = scalar_text(name).ok_or_else(|| {
+            DeserializeError::new(
+                ErrorCode::TypeMismatch,
+                format!("Expected the name of a variant, got: {}", yaml_type(name)),
+            )
+            .located(name.span, shape)
+            .in_field(tag)
+        })?;
+
+        // The content of the variant is the hash without its tag
+        let fields = Node {
+            value: NodeValue::Hash(
+                entries
+                    .iter()
+                    .filter(|(key, _)| key.as_str() != Some(tag))
+                    .cloned()
+                    .collect(),
+            ),
+            span: value.span,
+            tag: None,
+        };
+        let content = match ed.variants.iter().find(|variant| variant.name == name) {
+            Some(variant) => match (variant.data.kind, variant.data.fields.len()) {
+                (StructKind::Unit, _) => {
+                    let other = entries.iter().find(|(key, _)| key.as_str() != Some(tag));
+                    if let Some((key, _)) = other {
+                        return Err(DeserializeError::new(
+                            ErrorCode::UnknownField,
+                            format!(
+                                "Variant '{name}' has no fields, got: {}",
+                                key.as_str().unwrap_or(yaml_type(key))
+                            ),
+                        )
+                        .located(key.span, shape));
+                    }
+                    None
+                }
+                (StructKind::Struct, _) | (_, 1) => Some(&fields),
                 _ => {
-                    #[cfg(feature = "log")]
-                    log::debug!("Smart pointer pointee is not a slice, deserializing normally");
-                    // For other types, deserialize normally
-                    deserialize_value(wip, value)?;
+                    return Err(DeserializeError::new(
+                        ErrorCode::TypeMismatch,
+                        format!(
+                            "Variant '{name}' of {shape} has more than one unnamed field, so it \
+                             can't be internally tagged"
+                        ),
+                    )
+                    .located(value.span, shape));
                 }
+            },
+            // Reported as an unknown variant
+            None => None,
+        };
+        self.deserialize_variant(wip, ed, &name, content, true)
+    }
+
+    /// Deserialize an untagged enum as the first variant, in declaration order, that the node
+    /// is the content of, for types like a string or a list of them.
+    ///
+    /// The node is the content of a variant the same way it's the value of the name of the
+    /// variant in other enums, except that unit variants are null or their own name. Each
+    /// variant is tried by building a value of the enum with it, which is dropped, so the
+    /// node is only deserialized into `wip` once a variant fully matches.
+    fn deserialize_untagged_enum<'facet>(
+        &self,
+        wip: &mut Partial<'facet>,
+        ed: &EnumType,
+        value: &Node,
+    ) -> Result<(), DeserializeError> {
+        let shape = wip.shape();
+        let content = |variant: &Variant| match (variant.data.kind, &value.value) {
+            (StructKind::Unit, NodeValue::String(name)) if name == variant.name => None,
+            _ => Some(value),
+        };
+        let matches = |variant: &Variant| {
+            let mut partial = Partial::alloc_shape(shape).map_err(DeserializeError::from)?;
+            self.deserialize_variant(&mut partial, ed, variant.name, content(variant), true)?;
+            partial.build().map_err(DeserializeError::reflect)?;
+            Ok::<_, DeserializeError>(())
+        };
+
+        let Some(variant) = ed.variants.iter().find(|variant| matches(variant).is_ok()) else {
+            return Err(DeserializeError::new(
+                ErrorCode::TypeMismatch,
+                format!(
+                    "The YAML {} doesn't match any variant of {shape}",
+                    yaml_type(value)
+                ),
+            )
+            .located(value.span, shape));
+        };
+
+        #[cfg(feature = "log")]
+        log::debug!("Variant '{}' matches the untagged enum", variant.name);
+
+        self.deserialize_variant(wip, ed, variant.name, content(variant), true)
+    }
+
+    /// Select the variant with the given name and deserialize its content.
+    ///
+    /// The paths of errors in the content go through the name of the variant, unless the content
+    /// is `inline`: next to the tag of an internally tagged enum, or in place of an untagged one.
+    fn deserialize_variant<'facet>(
+        &self,
+        wip: &mut Partial<'facet>,
+        ed: &EnumType,
+        name: &str,
+        content: Option<&Node>,
+        inline: bool,
+    ) -> Result<(), DeserializeError> {
+        let shape = wip.shape();
+        let in_variant = |e: DeserializeError| if inline { e } else { e.in_field(name) };
+
+        let Some(variant) = ed.variants.iter().find(|variant| variant.name == name) else {
+            let names: Vec<String> = ed
+                .variants
+                .iter()
+                .map(|variant| format!("'{}'", variant.name))
+                .collect();
+            return Err(DeserializeError::new(
+                ErrorCode::UnknownVariant,
+                format!(
+                    "Unknown variant '{name}' of {shape}, expected one of {}",
+                    names.join(", ")
+                ),
+            ));
+        };
+
+        #[cfg(feature = "log")]
+        log::debug!("Selecting variant '{name}'");
+
+        wip.select_variant_named(name)
+            .map_err(DeserializeError::reflect)?;
+        let fields = variant.data.fields;
+
+        let content = match (variant.data.kind, content) {
+            (StructKind::Unit, None) => return Ok(()),
+            (StructKind::Unit, Some(content)) if matches!(content.value, NodeValue::Null) => {
+                return Ok(());
+            }
+            (StructKind::Unit, Some(content)) => {
+                return Err(DeserializeError::new(
+                    ErrorCode::TypeMismatch,
+                    format!(
+                        "Variant '{name}' has no fields, so it can only be null, got: {}",
+                        yaml_type(content)
+                    ),
+                )
+                .located(content.span, shape));
+            }
+            (_, None) => {
+                return Err(DeserializeError::new(
+                    ErrorCode::TypeMismatch,
+                    format!(
+                        "Variant '{name}' has fields, so it must be a YAML hash with its name as the key"
+                    ),
+                ));
             }
+            (_, Some(content)) => content,
+        };
 
-            // End smart pointer
-            wip.end().map_err(|e| AnyErr(e.to_string()))?;
+        match (variant.data.kind, &content.value) {
+            (StructKind::Tuple | StructKind::TupleStruct, _) if fields.len() == 1 => {
+                wip.begin_nth_field(0).map_err(DeserializeError::reflect)?;
+                self.deserialize_value(wip, content).map_err(in_variant)?;
+                wip.end().map_err(DeserializeError::reflect)?;
+            }
+            (StructKind::Tuple | StructKind::TupleStruct, NodeValue::Array(items)) => {
+                if items.len() != fields.len() {
+                    return Err(DeserializeError::new(
+                        ErrorCode::TypeMismatch,
+                        format!(
+                            "Expected {} items for variant '{name}', got {}",
+                            fields.len(),
+                            items.len()
+                        ),
+                    )
+                    .located(content.span, shape));
+                }
+                for (index, item) in items.iter().enumerate() {
+                    wip.begin_nth_field(index)
+                        .map_err(DeserializeError::reflect)?;
+                    self.deserialize_value(wip, item)
+                        .map_err(|e| in_variant(e.in_index(index)))?;
+                    wip.end().map_err(DeserializeError::reflect)?;
+                }
+            }
+            (StructKind::Struct, NodeValue::Hash(entries)) => {
+                for (key, v) in entries {
+                    let k = key.as_str().ok_or_else(|| {
+                        DeserializeError::new(
+                            ErrorCode::InvalidKey,
+                            format!("Expected string key, got: {}", yaml_type(key)),
+                        )
+                        .located(key.span, shape)
+                    })?;
+                    let Some(index) = self.find_field(&variant.data, k) else {
+                        return Err(in_variant(
+                            DeserializeError::new(
+                                ErrorCode::UnknownField,
+                                format!("Field '{k}' not found"),
+                            )
+                            .located(key.span, shape),
+                        ));
+                    };
+                    wip.begin_nth_field(index)
+                        .map_err(DeserializeError::reflect)?;
+                    self.deserialize_value(wip, v)
+                        .map_err(|e| in_variant(e.in_field(k)))?;
+                    wip.end().map_err(DeserializeError::reflect)?;
+                }
+
+                for (index, field) in fields.iter().enumerate() {
+                    if wip.is_field_set(index).map_err(DeserializeError::reflect)? {
+                        continue;
+                    }
+                    if field.flags.contains(FieldFlags::DEFAULT) || is_option(field.shape()) {
+                        wip.set_nth_field_to_default(index)
+                            .map_err(DeserializeError::reflect)?;
+                    } else {
+                        return Err(in_variant(
+                            DeserializeError::new(
+                                ErrorCode::MissingField,
+                                format!("Missing field '{}'", field.name),
+                            )
+                            .located(content.span, shape),
+                        ));
+                    }
+                }
+            }
+            _ => {
+                let expected = match variant.data.kind {
+                    StructKind::Struct => "hash",
+                    _ => "array",
+                };
+                return Err(DeserializeError::new(
+                    ErrorCode::TypeMismatch,
+                    format!(
+                        "Expected a YAML {expected} for variant '{name}', got: {}",
+                        yaml_type(content)
+                    ),
+                )
+                .located(content.span, shape));
+            }
         }
-        Def::Slice(_) => {
-            #[cfg(feature = "log")]
-            log::debug!("Processing slice type");
+        Ok(())
+    }
 
-            // Slices are deserialized like lists
-            deserialize_as_list(wip, value)?;
+    fn deserialize_as_list<'facet>(
+        &self,
+        wip: &mut Partial<'facet>,
+        value: &Node,
+    ) -> Result<(), DeserializeError> {
+        #[cfg(feature = "log")]
+        log::debug!("deserialize_as_list: shape={}", wip.shape());
+
+        // Byte buffers can be read from the base64 text of `!!binary` scalars
+        if let Some(text) = self.base64_text(value).filter(|_| is_bytes(wip.shape())) {
+            let bytes = decode_binary(text, value.binary().is_some())?;
+            wip.begin_list().map_err(DeserializeError::reflect)?;
+            for byte in bytes {
+                wip.begin_list_item().map_err(DeserializeError::reflect)?;
+                wip.set(byte).map_err(DeserializeError::reflect)?;
+                wip.end().map_err(DeserializeError::reflect)?;
+            }
+            return Ok(());
+        }
+
+        if let NodeValue::Array(array) = &value.value {
+            // Start the list
+            wip.begin_list().map_err(DeserializeError::reflect)?;
+
+            // Handle empty list - just return without adding items
+            if array.is_empty() {
+                return Ok(());
+            }
+
+            // Process each element
+            for (index, element) in array.iter().enumerate() {
+                #[cfg(feature = "log")]
+                log::debug!("Processing list element: {element:?}");
+
+                // Push element
+                wip.begin_list_item().map_err(DeserializeError::reflect)?;
+                let entry = single_entry(element)
+                    .filter(|_| self.options.ordered_maps && is_pair(wip.shape()));
+                let result = match entry {
+                    Some((key, v)) => self.deserialize_pair(wip, key, v),
+                    None => self.deserialize_value(wip, element),
+                };
+                result.map_err(|e| e.in_index(index))?;
+                wip.end().map_err(DeserializeError::reflect)?;
+            }
+
+            Ok(())
+        } else {
+            Err(DeserializeError::new(
+                ErrorCode::TypeMismatch,
+                format!("Expected a YAML array, got: {}", yaml_type(value)),
+            ))
         }
-        // Enum has been moved to Type system
-        _ => return Err(AnyErr(format!("Unsupported type: {shape:?}"))),
     }
-    Ok(())
-}
 
-fn deserialize_as_list<'facet>(wip: &mut Partial<'facet>, value: &Yaml) -> Result<(), AnyErr> {
-    #[cfg(feature = "log")]
-    log::debug!("deserialize_as_list: shape={}", wip.shape());
+    fn deserialize_as_map<'facet>(
+        &self,
+        wip: &mut Partial<'facet>,
+        value: &Node,
+    ) -> Result<(), DeserializeError> {
+        if let NodeValue::Hash(hash) = &value.value {
+            // Start the map
+            wip.begin_map().map_err(DeserializeError::reflect)?;
 
-    if let Yaml::Array(array) = value {
-        // Start the list
-        wip.begin_list().map_err(|e| AnyErr(e.to_string()))?;
+            // Handle empty map
+            if hash.is_empty() {
+                return Ok(());
+            }
 
-        // Handle empty list - just return without adding items
-        if array.is_empty() {
-            return Ok(());
+            // Process each key-value pair
+            for (k, v) in hash {
+                self.deserialize_entry(wip, k, v)?;
+            }
+
+            Ok(())
+        } else if let (NodeValue::Array(items), true) = (&value.value, self.options.ordered_maps) {
+            wip.begin_map().map_err(DeserializeError::reflect)?;
+
+            // Each item is a mapping with a single entry of the map
+            let shape = wip.shape();
+            for (index, item) in items.iter().enumerate() {
+                let Some((k, v)) = single_entry(item) else {
+                    let found = match &item.value {
+                        NodeValue::Hash(entries) => format!("one with {} keys", entries.len()),
+                        _ => yaml_type(item).to_string(),
+                    };
+                    return Err(DeserializeError::new(
+                        ErrorCode::TypeMismatch,
+                        format!("Expected a hash/map with a single key, got {found}"),
+                    )
+                    .located(item.span, shape)
+                    .in_index(index));
+                };
+                self.deserialize_entry(wip, k, v)
+                    .map_err(|e| e.in_index(index))?;
+            }
+
+            Ok(())
+        } else {
+            Err(DeserializeError::new(
+                ErrorCode::TypeMismatch,
+                format!("Expected a YAML hash/map, got: {}", yaml_type(value)),
+            ))
         }
+    }
 
-        // Process each element
-        for element in array.iter() {
-            #[cfg(feature = "log")]
-            log::debug!("Processing list element: {element:?}");
+    /// Deserialize an entry of a mapping into the map being built.
+    fn deserialize_entry<'facet>(
+        &self,
+        wip: &mut Partial<'facet>,
+        key: &Node,
+        value: &Node,
+    ) -> Result<(), DeserializeError> {
+        let key_name = node::key_segment(key);
 
-            // Push element
-            wip.begin_list_item().map_err(|e| AnyErr(e.to_string()))?;
-            deserialize_value(wip, element)?;
-            wip.end().map_err(|e| AnyErr(e.to_string()))?;
+        // Push map key, deserialized as the key type so keys don't have to be strings
+        wip.begin_key().map_err(DeserializeError::reflect)?;
+        if let Some(err) = complex_key_error(wip.shape(), key) {
+            return Err(err);
         }
+        self.deserialize_value(wip, key)
+            .map_err(|e| e.in_field(&key_name))?;
+        wip.end().map_err(DeserializeError::reflect)?;
 
+        // Push map value
+        wip.begin_value().map_err(DeserializeError::reflect)?;
+        self.deserialize_value(wip, value)
+            .map_err(|e| e.in_field(&key_name))?;
+        wip.end().map_err(DeserializeError::reflect)?;
+        Ok(())
+    }
+
+    /// Deserialize the entry of a mapping with a single key into a pair, the key into its first
+    /// field and the value into its second.
+    fn deserialize_pair<'facet>(
+        &self,
+        wip: &mut Partial<'facet>,
+        key: &Node,
+        value: &Node,
+    ) -> Result<(), DeserializeError> {
+        let key_name = node::key_segment(key);
+        for (index, node) in [key, value].into_iter().enumerate() {
+            wip.begin_nth_field(index)
+                .map_err(DeserializeError::reflect)?;
+            self.deserialize_value(wip, node)
+                .map_err(|e| e.in_field(&key_name))?;
+            wip.end().map_err(DeserializeError::reflect)?;
+        }
         Ok(())
-    } else {
-        Err(AnyErr(format!(
-            "Expected a YAML array, got: {}",
-            yaml_type(value)
-        )))
     }
-}
 
-fn deserialize_as_map<'facet>(wip: &mut Partial<'facet>, value: &Yaml) -> Result<(), AnyErr> {
-    if let Yaml::Hash(hash) = value {
-        // Start the map
-        wip.begin_map().map_err(|e| AnyErr(e.to_string()))?;
+    fn yaml_to_u64(&self, ty: &Node) -> Result<u64, DeserializeError> {
+        match &ty.value {
+            NodeValue::Real(r) => r.parse::<u64>().map_err(|_| {
+                DeserializeError::new(ErrorCode::InvalidScalar, "Failed to parse real as u64")
+            }),
+            NodeValue::Integer(i) => Ok(*i as u64),
+            NodeValue::String(s) => self
+                .number_text(s)
+                .and_then(|s| s.parse::<u64>().ok())
+                .ok_or_else(|| {
+                    DeserializeError::new(ErrorCode::InvalidScalar, "Failed to parse string as u64")
+                }),
+            NodeValue::Boolean(b) => Ok(if *b { 1 } else { 0 }),
+            _ => Err(DeserializeError::new(
+                ErrorCode::TypeMismatch,
+                format!("Cannot convert {} to u64", yaml_type(ty)),
+            )),
+        }
+    }
 
-        // Handle empty map
-        if hash.is_empty() {
-            return Ok(());
+    fn yaml_to_i64(&self, ty: &Node) -> Result<i64, DeserializeError> {
+        match &ty.value {
+            NodeValue::Integer(i) => Ok(*i),
+            NodeValue::Real(r) => r.parse::<i64>().map_err(|_| {
+                DeserializeError::new(ErrorCode::InvalidScalar, "Failed to parse real as i64")
+            }),
+            NodeValue::String(s) => self
+                .number_text(s)
+                .and_then(|s| s.parse::<i64>().ok())
+                .ok_or_else(|| {
+                    DeserializeError::new(ErrorCode::InvalidScalar, "Failed to parse string as i64")
+                }),
+            NodeValue::Boolean(b) => Ok(if *b { 1 } else { 0 }),
+            _ => Err(DeserializeError::new(
+                ErrorCode::TypeMismatch,
+                format!("Cannot convert {} to i64", yaml_type(ty)),
+            )),
         }
+    }
 
-        // Process each key-value pair
-        for (k, v) in hash {
-            // Get the key as a string
-            let key_str = k
-                .as_str()
-                .ok_or_else(|| AnyErr(format!("Expected string key, got: {}", yaml_type(k))))?;
+    /// Find the field of a struct that a key is read into, by its name, or by its name in the
+    /// current naming convention.
+    ///
+    /// Names set with the `rename` and `rename_all` attributes of facet are the names of the
+    /// fields, so they're always matched first. The convention is the one of the nearest
+    /// enclosing field with a `#[facet(yaml(rename_all = "..."))]` attribute, or else of the
+    /// [`rename_all`](DeserializeOptions::rename_all) option.
+    fn find_field(&self, sd: &StructType, key: &str) -> Option<usize> {
+        sd.fields
+            .iter()
+            .position(|field| field.name == key)
+            .or_else(|| {
+                let rule = self.rename_all.get()?;
+                sd.fields
+                    .iter()
+                    .position(|field| rule.apply(field.name) == key)
+            })
+    }
 
-            // Push map key
-            wip.begin_key().map_err(|e| AnyErr(e.to_string()))?;
-            wip.set(key_str.to_string())
-                .map_err(|e| AnyErr(e.to_string()))?;
-            wip.end().map_err(|e| AnyErr(e.to_string()))?;
+    /// Run `f` for the value of a field of a struct, with the naming convention the field sets
+    /// for the structs in it, if any.
+    fn in_field<R>(
+        &self,
+        fields: &StructFields,
+        index: usize,
+        f: impl FnOnce() -> Result<R, DeserializeError>,
+    ) -> Result<R, DeserializeError> {
+        let Some(rule) = fields.rename_all(index) else {
+            return f();
+        };
+        let outer = self.rename_all.replace(rule);
+        let result = f();
+        self.rename_all.set(outer);
+        result
+    }
 
-            // Push map value
-            wip.begin_value().map_err(|e| AnyErr(e.to_string()))?;
-            deserialize_value(wip, v)?;
-            wip.end().map_err(|e| AnyErr(e.to_string()))?;
+    /// Deserialize the value of a field of a struct, which is read from the YAML document in it
+    /// for fields with a `#[facet(yaml(embedded))]` attribute, and from a sequence of items for
+    /// fields with a `#[facet(yaml(key = "..."))]` attribute.
+    fn deserialize_field<'facet>(
+        &self,
+        wip: &mut Partial<'facet>,
+        fields: &StructFields,
+        sd: &StructType,
+        index: usize,
+        value: &Node,
+    ) -> Result<(), DeserializeError> {
+        self.in_field(fields, index, || {
+            if let Some(key) = fields.keyed(index) {
+                let value = keyed_mapping(key, sd.fields[index].shape(), value)?;
+                return self.deserialize_value(wip, &value);
+            }
+            if !fields.embedded(index) {
+                return self.deserialize_value(wip, value);
+            }
+            match self.embedded_document(value)? {
+                Some(doc) => self
+                    .deserialize_value(wip, &doc)
+                    .map_err(|e| e.at(value.span)),
+                None => set_empty_document(wip),
+            }
+        })
+    }
+
+    /// Load the YAML document embedded in a string, or `None` if it has no content.
+    ///
+    /// The errors in it are located at the string, since the document is only part of it.
+    fn embedded_document(&self, value: &Node) -> Result<Option<Node>, DeserializeError> {
+        let Some(text) = value.as_str() else {
+            return Err(DeserializeError::new(
+                ErrorCode::TypeMismatch,
+                format!(
+                    "Expected a string with a YAML document in it, got: {}",
+                    yaml_type(value)
+                ),
+            ));
+        };
+        let mut docs = node::load(text, self.options).map_err(|e| e.at(value.span))?;
+        match docs.len() {
+            0 => Ok(None),
+            1 => Ok(Some(docs.remove(0))),
+            _ => Err(DeserializeError::new(
+                ErrorCode::DocumentCount,
+                "Expected exactly one YAML document in the embedded string",
+            )),
         }
+    }
 
-        Ok(())
-    } else {
-        Err(AnyErr(format!(
-            "Expected a YAML hash/map, got: {}",
-            yaml_type(value)
-        )))
+    /// Convert a YAML value to a float.
+    ///
+    /// Only Rust's own float parsing is used, which never depends on the locale. Both the decimal
+    /// (`1.5`, `.5`) and the exponent (`1.5e3`) forms are accepted, as well as the YAML spellings
+    /// of the non-finite values. In lenient mode the digit separators of YAML 1.1, as in
+    /// `1_000.5`, and decimals without a digit before the dot, as in `.5`, are accepted too.
+    fn yaml_to_float<F: core::str::FromStr>(&self, ty: &Node) -> Result<F, DeserializeError> {
+        let type_name = core::any::type_name::<F>();
+        match &ty.value {
+            NodeValue::Real(text) | NodeValue::String(text)
+                if !self.options.lenient && has_leading_dot(text) =>
+            {
+                Err(DeserializeError::new(
+                    ErrorCode::InvalidScalar,
+                    format!(
+                        "Floats without a digit before the dot, like `{text}`, are only accepted \
+                         in lenient mode"
+                    ),
+                ))
+            }
+            NodeValue::Real(r) => parse_float(r).ok_or_else(|| float_error(r, type_name)),
+            NodeValue::Integer(i) => parse_float(&i.to_string()).ok_or_else(|| {
+                DeserializeError::new(
+                    ErrorCode::InvalidScalar,
+                    format!("Failed to parse integer as {type_name}"),
+                )
+            }),
+            NodeValue::String(s) => {
+                if let Some(f) = parse_float(s) {
+                    return Ok(f);
+                }
+                match without_separators(s).and_then(|s| parse_float(&s)) {
+                    Some(f) if self.options.lenient => Ok(f),
+                    Some(_) => Err(DeserializeError::new(
+                        ErrorCode::InvalidScalar,
+                        format!("Digit separators in `{s}` are only accepted in lenient mode"),
+                    )),
+                    None => Err(float_error(s, type_name)),
+                }
+            }
+            _ => Err(DeserializeError::new(
+                ErrorCode::TypeMismatch,
+                format!("Cannot convert {} to {type_name}", yaml_type(ty)),
+            )),
+        }
+    }
+
+    /// Get the text of a number that was written as a string.
+    ///
+    /// In lenient mode surrounding whitespace is ignored and an explicit `+` sign is accepted,
+    /// in strict mode both are rejected.
+    fn number_text<'s>(&self, s: &'s str) -> Option<&'s str> {
+        if self.options.lenient {
+            Some(s.trim())
+        } else if s.starts_with('+') || s.trim() != s {
+            None
+        } else {
+            Some(s)
+        }
     }
 }
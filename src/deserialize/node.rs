@@ -0,0 +1,791 @@
+//! Load YAML documents into a tree of nodes that remember where they came from.
+
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use facet::Facet;
+use yaml_rust2::{
+    Yaml,
+    parser::{Event, Parser, Tag},
+    scanner::{Marker, ScanError, TScalarStyle},
+};
+
+use super::{
+    DeserializeError,
+    options::{DeserializeOptions, PlainScalar, ScalarResolver},
+};
+use crate::ErrorCode;
+
+/// Tag handle the parser resolves `!!` to.
+pub(crate) const CORE_TAG_HANDLE: &str = "tag:yaml.org,2002:";
+
+/// A location in a YAML document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Facet)]
+pub struct Span {
+    /// Offset in characters from the start of the document.
+    pub offset: usize,
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Column number, starting at 1.
+    pub column: usize,
+}
+
+impl From<Marker> for Span {
+    fn from(marker: Marker) -> Self {
+        Self {
+            offset: marker.index(),
+            line: marker.line(),
+            column: marker.col() + 1,
+        }
+    }
+}
+
+impl core::fmt::Display for Span {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Span {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "line {=usize}, column {=usize}", self.line, self.column);
+    }
+}
+
+/// A YAML node along with its location in the source.
+#[derive(Debug, Clone)]
+pub(crate) struct Node {
+    /// The resolved value.
+    pub value: NodeValue,
+    /// Where the node starts.
+    pub span: Span,
+    /// Explicit tag, if the node had one.
+    pub tag: Option<Tag>,
+}
+
+/// Structural equality, ignoring where the nodes were found.
+impl PartialEq for Node {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl Node {
+    /// Get the node as a string slice, if it is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.value {
+            NodeValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Get the base64 text of a `!!binary` scalar, if the node is one.
+    pub fn binary(&self) -> Option<&str> {
+        match (&self.value, &self.tag) {
+            (NodeValue::String(text), Some(tag))
+                if tag.handle == CORE_TAG_HANDLE && tag.suffix == "binary" =>
+            {
+                Some(text)
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the explicit tag of the node, unless it's one of the core schema like `!!str`, e.g.
+    /// `!http` or `tag:example.com,2024:http`.
+    pub fn own_tag(&self) -> Option<String> {
+        match &self.tag {
+            Some(tag) if tag.handle != CORE_TAG_HANDLE => {
+                Some(format!("{}{}", tag.handle, tag.suffix))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The value of a YAML node, resolved with the core schema.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum NodeValue {
+    /// Floating point number, stored as its source text.
+    Real(String),
+    /// Integer.
+    Integer(i64),
+    /// String.
+    String(String),
+    /// Boolean.
+    Boolean(bool),
+    /// Sequence of nodes.
+    Array(Vec<Node>),
+    /// Mapping of nodes, in document order.
+    Hash(Vec<(Node, Node)>),
+    /// Null, e.g. `~` or `null`.
+    Null,
+    /// A scalar that doesn't match its explicit tag.
+    BadValue,
+    /// A node that couldn't be loaded, with the reason. Only produced by [`load_recovering`].
+    Invalid(String),
+}
+
+impl From<Yaml> for NodeValue {
+    fn from(yaml: Yaml) -> Self {
+        match yaml {
+            Yaml::Real(r) => Self::Real(r),
+            Yaml::Integer(i) => Self::Integer(i),
+            Yaml::String(s) => Self::String(s),
+            Yaml::Boolean(b) => Self::Boolean(b),
+            Yaml::Null => Self::Null,
+            // Collections and aliases are never produced when resolving a scalar
+            _ => Self::BadValue,
+        }
+    }
+}
+
+/// Load all documents in a YAML string.
+pub(crate) fn load(
+    yaml: &str,
+    options: &DeserializeOptions,
+) -> Result<Vec<Node>, DeserializeError> {
+    Loader::new(yaml, options, false).load_all()
+}
+
+/// Load all documents in a YAML string, taking the buffers of collections from `scratch`.
+///
+/// With a projection, which is a list of paths of mapping keys and sequence indices where `*`
+/// matches any of them, only the nodes on the paths are kept. Nodes that aren't on a path or
+/// below one are skipped without being built.
+pub(crate) fn load_reusing(
+    yaml: &str,
+    projection: Option<Vec<Vec<String>>>,
+    options: &DeserializeOptions,
+    scratch: &mut Scratch,
+) -> Result<Vec<Node>, DeserializeError> {
+    let mut loader = Loader::new(yaml, options, false);
+    loader.projection = projection;
+    loader.load_reusing(scratch)
+}
+
+/// Load all documents in a YAML string, replacing nodes that can't be loaded with
+/// [`NodeValue::Invalid`].
+///
+/// A duplicate key only invalidates its own value. A syntax error stops the parser, so it
+/// invalidates the node where it happened, and everything after it is missing.
+pub(crate) fn load_recovering(yaml: &str) -> Vec<Node> {
+    let options = DeserializeOptions::new();
+    match Loader::new(yaml, &options, true).load_documents() {
+        Ok(documents) => documents,
+        // Every error is turned into a node while recovering
+        Err(err) => vec![invalid(&err)],
+    }
+}
+
+/// Create a node for an error that was recovered from.
+fn invalid(err: &ScanError) -> Node {
+    Node {
+        value: NodeValue::Invalid(err.info().into()),
+        span: (*err.marker()).into(),
+        tag: None,
+    }
+}
+
+/// Buffers of collections that are no longer needed, to load the next documents into instead of
+/// allocating new ones.
+#[derive(Debug, Default)]
+pub(crate) struct Scratch {
+    /// Buffers for the items of sequences.
+    items: Vec<Vec<Node>>,
+    /// Buffers for the entries of mappings.
+    entries: Vec<Vec<(Node, Node)>>,
+}
+
+/// How many buffers of each kind are kept, so that a huge document doesn't hold on to its
+/// memory afterwards.
+const MAX_SCRATCH_BUFFERS: usize = 1024;
+
+impl Scratch {
+    /// Take back the buffers of the collections in a node that is no longer needed.
+    pub fn recycle(&mut self, node: Node) {
+        match node.value {
+            NodeValue::Array(mut items) => {
+                for item in items.drain(..) {
+                    self.recycle(item);
+                }
+                if self.items.len() < MAX_SCRATCH_BUFFERS {
+                    self.items.push(items);
+                }
+            }
+            NodeValue::Hash(mut entries) => {
+                for (key, value) in entries.drain(..) {
+                    self.recycle(key);
+                    self.recycle(value);
+                }
+                if self.entries.len() < MAX_SCRATCH_BUFFERS {
+                    self.entries.push(entries);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Builds nodes from the events of a parser.
+struct Loader<'input> {
+    /// Where the events come from.
+    parser: Parser<core::str::Chars<'input>>,
+    /// Nodes that have been anchored in the current document.
+    anchors: BTreeMap<usize, Anchored>,
+    /// How deeply aliases are nested in the node being loaded, so far.
+    alias_depth: usize,
+    /// How deeply aliases may be nested.
+    max_alias_depth: usize,
+    /// Number of nodes aliases expanded to in the current document.
+    expanded: usize,
+    /// How deeply collections are nested at the node being loaded.
+    depth: usize,
+    /// Whether errors are turned into invalid nodes instead of being returned.
+    recover: bool,
+    /// Whether the parser failed while recovering, so no more events can be read.
+    failed: bool,
+    /// Paths of the nodes to keep, or `None` to keep all of them.
+    projection: Option<Vec<Vec<String>>>,
+    /// Path of the node being loaded, only tracked when there's a projection.
+    path: Vec<String>,
+    /// Decides how plain scalars resolve before the core schema does.
+    resolver: Option<&'input ScalarResolver>,
+    /// Tells whether to stop loading.
+    options: &'input DeserializeOptions,
+    /// Whether loading was stopped by the options, which is the error of the parser then.
+    cancelled: bool,
+    /// The code of the limit that stopped loading, if one did, which is the error of the
+    /// parser then.
+    limit: Option<ErrorCode>,
+    /// Buffers to load collections into.
+    scratch: Scratch,
+    /// Whether the last scalar loaded was the empty one the parser reports for a node without
+    /// content, rather than one written in the document.
+    empty_scalar: bool,
+}
+
+/// A node with an anchor, which its aliases are copies of.
+struct Anchored {
+    /// The node, as it's copied for each alias.
+    node: Node,
+    /// How deeply aliases are nested in the node.
+    alias_depth: usize,
+    /// Number of nodes in the node, including itself.
+    size: usize,
+    /// How deeply collections are nested in the node, 0 for a scalar.
+    height: usize,
+}
+
+/// What comes next in a collection.
+enum Next {
+    /// A node, along with where it starts.
+    Node(Marker, Node),
+    /// A node that isn't selected by the projection, and was skipped.
+    Skipped,
+    /// The end of the collection.
+    End,
+}
+
+impl<'input> Loader<'input> {
+    /// Create a loader for a YAML string.
+    fn new(yaml: &'input str, options: &'input DeserializeOptions, recover: bool) -> Self {
+        Self {
+            parser: Parser::new_from_str(yaml),
+            anchors: BTreeMap::new(),
+            alias_depth: 0,
+            max_alias_depth: options.max_alias_depth,
+            expanded: 0,
+            depth: 0,
+            recover,
+            failed: false,
+            projection: None,
+            path: Vec::new(),
+            resolver: options.resolver.as_deref(),
+            options,
+            cancelled: false,
+            limit: None,
+            scratch: Scratch::default(),
+            empty_scalar: false,
+        }
+    }
+
+    /// Stop loading with an error when the options say so.
+    fn check_cancelled(&mut self, mark: Marker) -> Result<(), ScanError> {
+        if self.options.is_cancelled() {
+            self.cancelled = true;
+            return Err(ScanError::new(mark, "cancelled"));
+        }
+        Ok(())
+    }
+
+    /// Stop loading with an error for a limit of the options.
+    fn limit_error(&mut self, code: ErrorCode, mark: Marker, message: String) -> ScanError {
+        self.limit = Some(code);
+        ScanError::new_string(mark, message)
+    }
+
+    /// Convert an error of the parser, which is a cancellation if loading was stopped, or the
+    /// error of a limit.
+    fn error(&self, err: ScanError) -> DeserializeError {
+        if self.cancelled {
+            DeserializeError::cancelled()
+        } else if let Some(code) = self.limit {
+            DeserializeError::new(code, err.info()).at((*err.marker()).into())
+        } else {
+            err.into()
+        }
+    }
+
+    /// Load every document in the stream, converting the error.
+    fn load_all(mut self) -> Result<Vec<Node>, DeserializeError> {
+        self.load_documents().map_err(|err| self.error(err))
+    }
+
+    /// Load every document in the stream into the buffers of `scratch`, converting the error.
+    fn load_reusing(mut self, scratch: &mut Scratch) -> Result<Vec<Node>, DeserializeError> {
+        self.scratch = core::mem::take(scratch);
+        let result = self.load_documents().map_err(|err| self.error(err));
+        *scratch = self.scratch;
+        result
+    }
+
+    /// Load every document in the stream.
+    fn load_documents(&mut self) -> Result<Vec<Node>, ScanError> {
+        let mut documents = Vec::new();
+        match self.load_documents_into(&mut documents) {
+            Ok(()) => Ok(documents),
+            Err(err) if self.recover => {
+                documents.push(invalid(&err));
+                Ok(documents)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Load every document in the stream, keeping the ones loaded before an error.
+    fn load_documents_into(&mut self, documents: &mut Vec<Node>) -> Result<(), ScanError> {
+        let mut empty = 0;
+        loop {
+            let (event, mark) = self.parser.next_token()?;
+            match event {
+                Event::StreamStart | Event::Nothing => {}
+                Event::StreamEnd => {
+                    // A single document without content, such as one with only directives, is
+                    // the same as no document, while an explicit `~` is a null node
+                    if documents.len() == 1 && empty == 1 {
+                        documents.clear();
+                    }
+                    return Ok(());
+                }
+                Event::DocumentStart => {
+                    self.anchors.clear();
+                    self.expanded = 0;
+
+                    let Next::Node(_, root) = self.load_next(&Event::DocumentEnd)? else {
+                        // Empty document
+                        documents.push(Node {
+                            value: NodeValue::Null,
+                            span: mark.into(),
+                            tag: None,
+                        });
+                        empty += 1;
+                        continue;
+                    };
+                    if self.empty_scalar && root.value == NodeValue::Null {
+                        empty += 1;
+                    }
+                    documents.push(root);
+                    if self.failed {
+                        return Ok(());
+                    }
+
+                    let (event, mark) = self.parser.next_token()?;
+                    if event != Event::DocumentEnd {
+                        return Err(ScanError::new(mark, "expected the end of the document"));
+                    }
+                }
+                _ => return Err(ScanError::new(mark, "expected the start of a document")),
+            }
+        }
+    }
+
+    /// Load the next node in a collection, which ends with `end`.
+    fn load_next(&mut self, end: &Event) -> Result<Next, ScanError> {
+        let (event, mark) = match self.parser.next_token() {
+            Ok((event, _)) if event == *end => return Ok(Next::End),
+            Ok(token) => token,
+            Err(err) => {
+                let mark = *err.marker();
+                return self.recovered(Err(err)).map(|node| Next::Node(mark, node));
+            }
+        };
+        if !self.is_selected() {
+            self.skip_node(event, mark)?;
+            return Ok(Next::Skipped);
+        }
+        let result = self.load_node(event, mark);
+        self.recovered(result).map(|node| Next::Node(mark, node))
+    }
+
+    /// Load the next node, which must not be the end of a collection, or return `None` if it
+    /// was skipped.
+    fn load_value(&mut self) -> Result<Option<Node>, ScanError> {
+        let result = match self.parser.next_token() {
+            Ok((event, mark)) if !self.is_selected() => {
+                self.skip_node(event, mark)?;
+                return Ok(None);
+            }
+            Ok((event, mark)) => self.load_node(event, mark),
+            Err(err) => Err(err),
+        };
+        self.recovered(result).map(Some)
+    }
+
+    /// Whether the current path is selected by the projection, either because it leads to a
+    /// selected node or because it's below one.
+    fn is_selected(&self) -> bool {
+        let Some(projection) = &self.projection else {
+            return true;
+        };
+        projection.iter().any(|selected| {
+            self.path
+                .iter()
+                .zip(selected)
+                .all(|(segment, selected)| selected == "*" || segment == selected)
+        })
+    }
+
+    /// Add a mapping key or sequence index to the current path, when there's a projection.
+    fn enter(&mut self, segment: impl FnOnce() -> String) {
+        if self.projection.is_some() {
+            self.path.push(segment());
+        }
+    }
+
+    /// Remove the last segment of the current path, when there's a projection.
+    fn leave(&mut self) {
+        if self.projection.is_some() {
+            self.path.pop();
+        }
+    }
+
+    /// Read past a node without building it.
+    ///
+    /// Anchored nodes are still loaded, since they may be aliased from a selected node.
+    fn skip_node(&mut self, event: Event, mark: Marker) -> Result<(), ScanError> {
+        let end = match event {
+            Event::Scalar(_, _, anchor, _)
+            | Event::SequenceStart(anchor, _)
+            | Event::MappingStart(anchor, _)
+                if anchor > 0 =>
+            {
+                // Load it completely, whatever the projection
+                let projection = self.projection.take();
+                let result = self.load_node(event, mark);
+                self.projection = projection;
+                return result.map(|_| ());
+            }
+            Event::SequenceStart(..) => Event::SequenceEnd,
+            Event::MappingStart(..) => Event::MappingEnd,
+            _ => return Ok(()),
+        };
+
+        loop {
+            let (event, mark) = self.parser.next_token()?;
+            if event == end {
+                return Ok(());
+            }
+            self.check_cancelled(mark)?;
+            self.skip_node(event, mark)?;
+        }
+    }
+
+    /// While recovering, turn a parser error into an invalid node and stop reading events.
+    fn recovered(&mut self, result: Result<Node, ScanError>) -> Result<Node, ScanError> {
+        match result {
+            Err(err) if self.recover => {
+                self.failed = true;
+                Ok(invalid(&err))
+            }
+            result => result,
+        }
+    }
+
+    /// Load a node starting with the given event.
+    fn load_node(&mut self, event: Event, mark: Marker) -> Result<Node, ScanError> {
+        self.check_cancelled(mark)?;
+        // Nodes are loaded recursively, so deep nesting is rejected before it overflows the stack
+        let collection = matches!(event, Event::SequenceStart(..) | Event::MappingStart(..));
+        if collection {
+            if self.depth >= self.options.max_depth {
+                let message = format!(
+                    "collections are nested deeper than the limit of {}",
+                    self.options.max_depth
+                );
+                return Err(self.limit_error(ErrorCode::DepthLimit, mark, message));
+            }
+            self.depth += 1;
+        }
+        // Aliases in the node are counted from zero, and then added to the parent's count
+        let outer_alias_depth = core::mem::take(&mut self.alias_depth);
+        let result = self.load_node_contents(event, mark);
+        self.alias_depth = self.alias_depth.max(outer_alias_depth);
+        if collection {
+            self.depth -= 1;
+        }
+        result
+    }
+
+    /// Load a node starting with the given event, counting how deeply aliases are nested in it.
+    fn load_node_contents(&mut self, event: Event, mark: Marker) -> Result<Node, ScanError> {
+        let span = mark.into();
+        let (node, anchor) = match event {
+            Event::Scalar(value, style, anchor, tag) => {
+                self.empty_scalar = value.is_empty()
+                    && style == TScalarStyle::Plain
+                    && anchor == 0
+                    && tag.is_none();
+                let value = match self.resolver {
+                    Some(resolver) if style == TScalarStyle::Plain && tag.is_none() => {
+                        match resolver(&value) {
+                            Some(scalar) => scalar.into_node_value(value),
+                            None => resolve_scalar(value, style, None),
+                        }
+                    }
+                    _ => resolve_scalar(value, style, tag.as_ref()),
+                };
+                let value = match value {
+                    NodeValue::Real(text)
+                        if tag.is_none()
+                            && self.options.big_integers_as_strings
+                            && is_big_integer(&text) =>
+                    {
+                        NodeValue::String(text)
+                    }
+                    value => value,
+                };
+                (Node { value, span, tag }, anchor)
+            }
+            Event::SequenceStart(anchor, tag) => {
+                let mut items = self.scratch.items.pop().unwrap_or_default();
+                for index in 0usize.. {
+                    self.enter(|| index.to_string());
+                    let next = self.load_next(&Event::SequenceEnd);
+                    self.leave();
+                    match next? {
+                        Next::Node(_, item) => items.push(item),
+                        Next::Skipped => {}
+                        Next::End => break,
+                    }
+                    if self.failed {
+                        break;
+                    }
+                }
+                let value = NodeValue::Array(items);
+                (Node { value, span, tag }, anchor)
+            }
+            Event::MappingStart(anchor, tag) => {
+                let mut entries = self.scratch.entries.pop().unwrap_or_default();
+                loop {
+                    let (mark, key) = match self.load_next(&Event::MappingEnd)? {
+                        Next::Node(mark, key) => (mark, key),
+                        // Keys are always loaded, the current mapping is selected
+                        Next::Skipped => continue,
+                        Next::End => break,
+                    };
+                    if self.failed {
+                        // The key is invalid, and there's no value to go with it
+                        let span = key.span;
+                        let value = NodeValue::Null;
+                        entries.push((
+                            key,
+                            Node {
+                                value,
+                                span,
+                                tag: None,
+                            },
+                        ));
+                        break;
+                    }
+
+                    let duplicate = entries
+                        .iter()
+                        .any(|(k, _)| *k == key)
+                        .then(|| format!("{:?}: duplicated key in mapping", key.value));
+                    match &duplicate {
+                        Some(message) if !self.recover => {
+                            return Err(ScanError::new_string(mark, message.clone()));
+                        }
+                        _ => {}
+                    }
+
+                    self.enter(|| key_segment(&key));
+                    let value = self.load_value();
+                    self.leave();
+                    let Some(mut value) = value? else {
+                        // Not selected, so leave out the key as well
+                        continue;
+                    };
+                    if let Some(message) = duplicate {
+                        // Keep the first value, and mark the duplicate
+                        value.value = NodeValue::Invalid(message);
+                    }
+                    entries.push((key, value));
+                    if self.failed {
+                        break;
+                    }
+                }
+                let value = NodeValue::Hash(entries);
+                (Node { value, span, tag }, anchor)
+            }
+            Event::Alias(id) => {
+                let anchored = self
+                    .anchors
+                    .get(&id)
+                    .ok_or_else(|| ScanError::new(mark, "unknown anchor"))?;
+                let (depth, size, height) = (anchored.alias_depth, anchored.size, anchored.height);
+                // Each level of aliases multiplies the size of the document, and the depth of
+                // the recursion that reads it
+                if depth >= self.max_alias_depth {
+                    return Err(ScanError::new_string(
+                        mark,
+                        format!(
+                            "aliases are nested deeper than the limit of {}",
+                            self.max_alias_depth
+                        ),
+                    ));
+                }
+                // Aliases of a small node can still add up to a huge document, e.g. when each
+                // level has ten aliases of the level below, so the nodes they copy are counted
+                let expanded = self.expanded.saturating_add(size);
+                if expanded > self.options.max_alias_nodes {
+                    let message = format!(
+                        "aliases expand to more nodes than the limit of {}",
+                        self.options.max_alias_nodes
+                    );
+                    return Err(self.limit_error(ErrorCode::AliasLimit, mark, message));
+                }
+                // The copy is nested where the alias is
+                if self.depth + height > self.options.max_depth {
+                    let message = format!(
+                        "collections are nested deeper than the limit of {}",
+                        self.options.max_depth
+                    );
+                    return Err(self.limit_error(ErrorCode::DepthLimit, mark, message));
+                }
+                self.expanded = expanded;
+                self.alias_depth = depth + 1;
+                let mut node = self.anchors[&id].node.clone();
+                // Point at the alias rather than the anchor
+                node.span = span;
+                return Ok(node);
+            }
+            _ => return Err(ScanError::new(mark, "unexpected event")),
+        };
+
+        // Valid anchor IDs start at 1
+        if anchor > 0 {
+            let (size, height) = measure(&node);
+            let anchored = Anchored {
+                node: node.clone(),
+                alias_depth: self.alias_depth,
+                size,
+                height,
+            };
+            self.anchors.insert(anchor, anchored);
+        }
+
+        Ok(node)
+    }
+}
+
+/// Count the nodes in a node, including itself, and how deeply collections are nested in it.
+fn measure(node: &Node) -> (usize, usize) {
+    let mut size = 1;
+    let mut height = 0;
+    let mut add = |child: &Node| {
+        let (child_size, child_height) = measure(child);
+        size += child_size;
+        height = height.max(child_height);
+    };
+    match &node.value {
+        NodeValue::Array(items) => items.iter().for_each(&mut add),
+        NodeValue::Hash(entries) => entries.iter().for_each(|(key, value)| {
+            add(key);
+            add(value);
+        }),
+        _ => return (1, 0),
+    }
+    (size, height + 1)
+}
+
+/// The segment of a path that a mapping key stands for.
+pub(crate) fn key_segment(key: &Node) -> String {
+    match &key.value {
+        NodeValue::String(s) => s.clone(),
+        NodeValue::Integer(i) => format!("{i}"),
+        NodeValue::Boolean(b) => format!("{b}"),
+        // Complex keys can't be selected
+        _ => String::new(),
+    }
+}
+
+/// Whether the text of a real number is an integer too large for both `i64` and `u64`, which
+/// the parser resolves to a float.
+fn is_big_integer(text: &str) -> bool {
+    let digits = text.strip_prefix(['-', '+']).unwrap_or(text);
+    let fits =
+        text.parse::<i64>().is_ok() || (!text.starts_with('-') && digits.parse::<u64>().is_ok());
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) && !fits
+}
+
+/// Resolve the value of a scalar the same way `YamlLoader` does.
+pub(crate) fn resolve_scalar(value: String, style: TScalarStyle, tag: Option<&Tag>) -> NodeValue {
+    if style != TScalarStyle::Plain {
+        return NodeValue::String(value);
+    }
+
+    match tag {
+        Some(Tag { handle, suffix }) if handle == CORE_TAG_HANDLE => match suffix.as_str() {
+            "bool" => value
+                .parse::<bool>()
+                .map_or(NodeValue::BadValue, NodeValue::Boolean),
+            "int" => value
+                .parse::<i64>()
+                .map_or(NodeValue::BadValue, NodeValue::Integer),
+            "float" => match Yaml::from_str(&value) {
+                Yaml::Real(_) | Yaml::Integer(_) => NodeValue::Real(value),
+                _ => NodeValue::BadValue,
+            },
+            "null" => match value.as_str() {
+                "~" | "null" | "Null" | "NULL" | "" => NodeValue::Null,
+                _ => NodeValue::BadValue,
+            },
+            _ => NodeValue::String(value),
+        },
+        Some(_) => NodeValue::String(value),
+        // The parser only knows the lowercase spelling of the core schema's null
+        None if matches!(value.as_str(), "Null" | "NULL") => NodeValue::Null,
+        None => Yaml::from_str(&value).into(),
+    }
+}
+
+impl PlainScalar {
+    /// Get the value of a node with the given text that resolved to this.
+    fn into_node_value(self, text: String) -> NodeValue {
+        match self {
+            PlainScalar::Null => NodeValue::Null,
+            PlainScalar::Bool(b) => NodeValue::Boolean(b),
+            PlainScalar::Integer(i) => NodeValue::Integer(i),
+            // `Debug` is the shortest text that's read back as the same float
+            PlainScalar::Float(f) => NodeValue::Real(format!("{f:?}")),
+            PlainScalar::String => NodeValue::String(text),
+        }
+    }
+}
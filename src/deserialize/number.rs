@@ -0,0 +1,190 @@
+//! Numbers of dynamically typed YAML values.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use core::cmp::Ordering;
+use facet::Facet;
+
+use super::parse_float;
+
+/// A YAML number, which keeps whether it's an integer or a float, along with its text.
+///
+/// Like the numbers of `serde_json`, it can be read as any of the Rust types it fits in, so
+/// tools can tell whether converting it loses precision. Integers from 2⁶³ to 2⁶⁴ - 1 are read
+/// exactly as `u64`.
+///
+/// ```
+/// use facet_yaml::Value;
+///
+/// let value: Value = facet_yaml::from_str("[3, 18446744073709551615, 2.50]").unwrap();
+/// let numbers: Vec<_> = value.as_sequence().unwrap().iter().filter_map(Value::as_number).collect();
+///
+/// assert_eq!(numbers[0].as_i64(), Some(3));
+/// assert_eq!(numbers[1].as_i64(), None);
+/// assert_eq!(numbers[1].as_u64(), Some(u64::MAX));
+/// assert_eq!(numbers[2].as_f64(), Some(2.5));
+/// assert_eq!(numbers[2].raw(), "2.50");
+/// ```
+#[derive(Debug, Clone, Facet)]
+pub struct Number {
+    /// The value, in the type it was read as.
+    value: N,
+    /// The text of the number.
+    raw: String,
+}
+
+/// The value of a number.
+#[derive(Debug, Clone, Copy, Facet)]
+#[repr(u8)]
+enum N {
+    /// An integer that fits in `i64`.
+    Integer(i64),
+    /// An integer larger than `i64::MAX`.
+    Unsigned(u64),
+    /// A floating point number.
+    Float(f64),
+}
+
+impl Number {
+    /// Read the text of a real number, which is an integer when it's too large for `i64`.
+    pub(crate) fn from_real(text: &str) -> Option<Self> {
+        let value = match text.parse::<u64>() {
+            Ok(u) => N::Unsigned(u),
+            Err(_) => N::Float(parse_float(text)?),
+        };
+        let raw = text.into();
+        Some(Self { value, raw }.normalized())
+    }
+
+    /// Keep integers that fit in `i64` as such, so each integer has a single representation.
+    fn normalized(mut self) -> Self {
+        self.value = match self.value {
+            N::Unsigned(u) => i64::try_from(u).map_or(N::Unsigned(u), N::Integer),
+            value => value,
+        };
+        self
+    }
+
+    /// Whether the number is an integer that fits in `i64`.
+    pub fn is_i64(&self) -> bool {
+        matches!(self.value, N::Integer(_))
+    }
+
+    /// Whether the number is an integer that fits in `u64`.
+    pub fn is_u64(&self) -> bool {
+        self.as_u64().is_some()
+    }
+
+    /// Whether the number is a float, even one without a fractional part like `1.0`.
+    pub fn is_f64(&self) -> bool {
+        matches!(self.value, N::Float(_))
+    }
+
+    /// Get the number as an `i64`, if it's an integer that fits in it.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self.value {
+            N::Integer(i) => Some(i),
+            _ => None,
+        }
+    }
+
+    /// Get the number as a `u64`, if it's an integer that fits in it.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.value {
+            N::Integer(i) => u64::try_from(i).ok(),
+            N::Unsigned(u) => Some(u),
+            N::Float(_) => None,
+        }
+    }
+
+    /// Get the number as an `f64`, which loses precision for integers larger than 2⁵³.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self.value {
+            N::Integer(i) => Some(i as f64),
+            N::Unsigned(u) => Some(u as f64),
+            N::Float(f) => Some(f),
+        }
+    }
+
+    /// The text of the number as it's written in the document, e.g. `2.50` or `1e3`.
+    ///
+    /// Integers that fit in `i64` are written in decimal, since the document may spell them in
+    /// another base, e.g. `0x1F` is `31`. Numbers created from Rust values have the shortest text
+    /// that is read back as the same value.
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+
+    /// Compare numbers by value, integers exactly.
+    pub(crate) fn compare(&self, other: &Self) -> Ordering {
+        let integer = |n: &Self| match n.value {
+            N::Integer(i) => Some(i128::from(i)),
+            N::Unsigned(u) => Some(i128::from(u)),
+            N::Float(_) => None,
+        };
+        match (integer(self), integer(other)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            _ => {
+                let float = |n: &Self| n.as_f64().unwrap_or_default();
+                float(self).total_cmp(&float(other))
+            }
+        }
+    }
+}
+
+/// Numbers are equal when they have the same value and are both integers or both floats,
+/// however they're written.
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.value, other.value) {
+            (N::Integer(a), N::Integer(b)) => a == b,
+            (N::Unsigned(a), N::Unsigned(b)) => a == b,
+            (N::Float(a), N::Float(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl From<i64> for Number {
+    fn from(i: i64) -> Self {
+        Self {
+            value: N::Integer(i),
+            raw: i.to_string(),
+        }
+    }
+}
+
+impl From<u64> for Number {
+    fn from(u: u64) -> Self {
+        Self {
+            value: N::Unsigned(u),
+            raw: u.to_string(),
+        }
+        .normalized()
+    }
+}
+
+impl From<f64> for Number {
+    fn from(f: f64) -> Self {
+        let raw = if f.is_nan() {
+            ".nan".into()
+        } else if f.is_infinite() {
+            if f > 0.0 { ".inf" } else { "-.inf" }.into()
+        } else {
+            // `Debug` is the shortest text that's read back as the same float
+            format!("{f:?}")
+        };
+        Self {
+            value: N::Float(f),
+            raw,
+        }
+    }
+}
+
+impl core::fmt::Display for Number {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
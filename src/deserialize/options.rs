@@ -0,0 +1,633 @@
+//! Options to change how YAML documents are deserialized.
+
+use alloc::{string::String, sync::Arc, vec::Vec};
+use core::sync::atomic::{AtomicBool, Ordering};
+use facet_core::{Facet, Shape};
+
+use crate::{
+    YamlCustomize,
+    customize::{self, Customization},
+};
+
+/// Options for deserializing YAML documents.
+///
+/// ```
+/// let options = facet_yaml::DeserializeOptions::new().lenient(false);
+/// let port: u16 = facet_yaml::from_str_with_options("'8080'", &options).unwrap();
+/// assert_eq!(port, 8080);
+/// ```
+#[derive(Clone)]
+pub struct DeserializeOptions {
+    /// Whether to accept scalars that are only almost valid.
+    pub(crate) lenient: bool,
+    /// Decides how plain scalars resolve before the core schema does.
+    pub(crate) resolver: Option<Arc<ScalarResolver>>,
+    /// Convention of the keys of fields that aren't found by their name.
+    pub(crate) rename_all: Option<RenameRule>,
+    /// How deeply aliases may be nested in the nodes they refer to.
+    pub(crate) max_alias_depth: usize,
+    /// How many nodes the aliases of a document may expand to.
+    pub(crate) max_alias_nodes: usize,
+    /// How deeply collections may be nested.
+    pub(crate) max_depth: usize,
+    /// Tells whether to stop deserializing.
+    pub(crate) cancel: Option<Arc<CancelCheck>>,
+    /// Whether sequences of single-key mappings are read into maps and lists of pairs.
+    pub(crate) ordered_maps: bool,
+    /// Whether null is read into structs without fields.
+    pub(crate) unit_from_null: bool,
+    /// Whether integers too large for `i64` and `u64` are read as strings.
+    pub(crate) big_integers_as_strings: bool,
+    /// Whether byte buffers are read from strings of base64 text without a `!!binary` tag.
+    pub(crate) base64_strings: bool,
+    /// Types registered under the explicit tags of nodes, such as `!http`.
+    pub(crate) tags: Vec<(String, &'static Shape)>,
+    /// Types that are read with their [`YamlCustomize`] instead of their shape.
+    pub(crate) customized: Vec<Customization>,
+    /// Size in bytes under which documents take the path for small documents.
+    pub(crate) small_document_threshold: usize,
+}
+
+/// Decides how a plain scalar resolves, or returns `None` to resolve it with the core schema.
+pub(crate) type ScalarResolver = dyn Fn(&str) -> Option<PlainScalar> + Send + Sync;
+
+/// Returns whether to stop deserializing.
+pub(crate) type CancelCheck = dyn Fn() -> bool + Send + Sync;
+
+impl DeserializeOptions {
+    /// Create the default options.
+    pub fn new() -> Self {
+        Self {
+            lenient: true,
+            resolver: None,
+            rename_all: None,
+            max_alias_depth: 16,
+            max_alias_nodes: 1_000_000,
+            max_depth: 128,
+            cancel: None,
+            ordered_maps: false,
+            unit_from_null: false,
+            big_integers_as_strings: false,
+            base64_strings: false,
+            tags: Vec::new(),
+            customized: Vec::new(),
+            small_document_threshold: 1024,
+        }
+    }
+
+    /// Accept scalars that are only almost valid, as exported by some tools.
+    ///
+    /// When enabled, which is the default, numbers written as strings may have surrounding
+    /// whitespace and an explicit `+` sign, e.g. `" +42 "`, and floats may have the digit
+    /// separators of YAML 1.1, e.g. `1_000.5`, or no digit before the dot, e.g. `.5`. When
+    /// disabled such values are rejected.
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Decide how untagged plain scalars resolve, before they're bound to a type.
+    ///
+    /// The resolver is called with the text of every plain scalar that doesn't have a tag, and
+    /// returns what it resolves to, or `None` to resolve it with the YAML 1.2 core schema. This
+    /// allows other schemas, such as reading `on` and `off` as booleans, or keeping `1.0` a
+    /// string. Quoted and tagged scalars are never passed to it.
+    ///
+    /// ```
+    /// use facet_yaml::{DeserializeOptions, PlainScalar};
+    ///
+    /// let options = DeserializeOptions::new().resolve_plain_scalars(|text| match text {
+    ///     "on" => Some(PlainScalar::Bool(true)),
+    ///     "off" => Some(PlainScalar::Bool(false)),
+    ///     _ => None,
+    /// });
+    /// let flags: Vec<bool> = facet_yaml::from_str_with_options("[on, off, true]", &options).unwrap();
+    /// assert_eq!(flags, [true, false, true]);
+    /// ```
+    pub fn resolve_plain_scalars(
+        mut self,
+        resolver: impl Fn(&str) -> Option<PlainScalar> + Send + Sync + 'static,
+    ) -> Self {
+        self.resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Read the fields of structs from keys written with another naming convention, like the
+    /// `rename_all` attribute does for a single type.
+    ///
+    /// This is meant for types that can't be annotated, such as generated ones. Keys that match
+    /// the name of a field exactly, including names set with `rename` or `rename_all`, are still
+    /// read as they are, so types that already have attributes aren't affected.
+    ///
+    /// A field with a `#[facet(yaml(rename_all = "..."))]` attribute sets the convention for the
+    /// struct in it and the structs nested further, which takes precedence over this option until
+    /// a nested field sets another one. Keys are matched against, in order, the aliases of a
+    /// field, its name, and its name in the convention of the nearest field that sets one, or
+    /// else of this option.
+    ///
+    /// ```
+    /// use facet::Facet;
+    /// use facet_yaml::{DeserializeOptions, RenameRule};
+    ///
+    /// #[derive(Facet)]
+    /// struct Retry {
+    ///     max_attempts: u32,
+    ///     backoff_ms: u64,
+    /// }
+    ///
+    /// let options = DeserializeOptions::new().rename_all(RenameRule::CamelCase);
+    /// let yaml = "maxAttempts: 3\nbackoffMs: 250";
+    /// let retry: Retry = facet_yaml::from_str_with_options(yaml, &options).unwrap();
+    /// assert_eq!(retry.max_attempts, 3);
+    /// ```
+    pub fn rename_all(mut self, rule: RenameRule) -> Self {
+        self.rename_all = Some(rule);
+        self
+    }
+
+    /// Set how deeply aliases may be nested, 16 by default.
+    ///
+    /// An alias to a node that has no aliases is nested one level deep, an alias to a node with
+    /// such an alias two levels, and so on. Every level can multiply the size of the document,
+    /// so documents that nest them deeper are rejected before they're expanded. This doesn't
+    /// limit how deeply collections are nested in the document itself.
+    ///
+    /// ```
+    /// use facet_yaml::{DeserializeOptions, Value};
+    ///
+    /// let options = DeserializeOptions::new().max_alias_depth(1);
+    /// let yaml = "- &a [1]\n- &b [*a]\n- *b";
+    /// let err = facet_yaml::from_str_with_options::<Value>(yaml, &options).unwrap_err();
+    /// assert_eq!(err.message(), "aliases are nested deeper than the limit of 1");
+    /// ```
+    pub fn max_alias_depth(mut self, depth: usize) -> Self {
+        self.max_alias_depth = depth;
+        self
+    }
+
+    /// Set how many nodes the aliases of a document may expand to, a million by default.
+    ///
+    /// Every alias is a copy of the node it refers to, so each one adds the number of nodes in
+    /// it, and documents that would grow past the limit are rejected with an
+    /// [`ErrorCode::AliasLimit`](crate::ErrorCode::AliasLimit) error before they do. Unlike
+    /// [`max_alias_depth`](Self::max_alias_depth), this catches documents whose aliases aren't
+    /// deeply nested but are many at each level, like a billion laughs with ten aliases per
+    /// level.
+    ///
+    /// ```
+    /// use facet_yaml::{DeserializeOptions, ErrorCode, Value};
+    ///
+    /// let options = DeserializeOptions::new().max_alias_nodes(5);
+    /// let yaml = "- &a [1, 2]\n- [*a, *a]";
+    /// let err = facet_yaml::from_str_with_options::<Value>(yaml, &options).unwrap_err();
+    /// assert_eq!(err.code(), ErrorCode::AliasLimit);
+    /// ```
+    pub fn max_alias_nodes(mut self, nodes: usize) -> Self {
+        self.max_alias_nodes = nodes;
+        self
+    }
+
+    /// Set how deeply collections may be nested, 128 by default.
+    ///
+    /// Nodes are read recursively, so documents that nest sequences or mappings deeper, such as
+    /// `- - - - …`, are rejected with an [`ErrorCode::DepthLimit`](crate::ErrorCode::DepthLimit)
+    /// error instead of overflowing the stack. Collections copied by aliases count where the
+    /// alias is.
+    ///
+    /// ```
+    /// use facet_yaml::{DeserializeOptions, ErrorCode, Value};
+    ///
+    /// let options = DeserializeOptions::new().max_depth(2);
+    /// facet_yaml::from_str_with_options::<Value>("a: [1]", &options).unwrap();
+    /// let err = facet_yaml::from_str_with_options::<Value>("a: [[1]]", &options).unwrap_err();
+    /// assert_eq!(err.code(), ErrorCode::DepthLimit);
+    /// ```
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Stop deserializing with an [`ErrorCode::Cancelled`](crate::ErrorCode::Cancelled) error
+    /// once `cancel` returns `true`, such as when a deadline has passed.
+    ///
+    /// It's called before each node of the document is parsed and before each one is bound to
+    /// the type, so it should be cheap. This allows services to give up on huge or malicious
+    /// documents without stopping the thread that reads them.
+    ///
+    /// ```
+    /// use std::time::{Duration, Instant};
+    /// use facet_yaml::{DeserializeOptions, ErrorCode};
+    ///
+    /// let deadline = Instant::now() + Duration::from_secs(5);
+    /// let options = DeserializeOptions::new().cancel_if(move || Instant::now() > deadline);
+    /// let ports: Vec<u16> = facet_yaml::from_str_with_options("[80, 443]", &options).unwrap();
+    /// assert_eq!(ports, [80, 443]);
+    ///
+    /// let options = DeserializeOptions::new().cancel_if(|| true);
+    /// let err = facet_yaml::from_str_with_options::<Vec<u16>>("[80, 443]", &options).unwrap_err();
+    /// assert_eq!(err.code(), ErrorCode::Cancelled);
+    /// ```
+    pub fn cancel_if(mut self, cancel: impl Fn() -> bool + Send + Sync + 'static) -> Self {
+        self.cancel = Some(Arc::new(cancel));
+        self
+    }
+
+    /// Stop deserializing once `flag` is set, from any thread, like
+    /// [`cancel_if`](Self::cancel_if) does.
+    ///
+    /// ```
+    /// use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+    /// use facet_yaml::{DeserializeOptions, ErrorCode};
+    ///
+    /// let cancelled = Arc::new(AtomicBool::new(false));
+    /// let options = DeserializeOptions::new().cancel_flag(cancelled.clone());
+    ///
+    /// cancelled.store(true, Ordering::Relaxed);
+    /// let err = facet_yaml::from_str_with_options::<Vec<u16>>("[80]", &options).unwrap_err();
+    /// assert_eq!(err.code(), ErrorCode::Cancelled);
+    /// ```
+    pub fn cancel_flag(self, flag: Arc<AtomicBool>) -> Self {
+        self.cancel_if(move || flag.load(Ordering::Relaxed))
+    }
+
+    /// Read sequences of mappings with a single key each, like `- name: value`, into maps and
+    /// lists of pairs, keeping the order of the sequence.
+    ///
+    /// This is how tools like Ansible write ordered mappings, and the form of the `!!omap` type
+    /// of YAML 1.1. When enabled, a map can be read from such a sequence as well as from a
+    /// mapping, and each item of a list of pairs, like `Vec<(String, T)>`, from a mapping with a
+    /// single key. Maps that keep the order of their keys, such as `IndexMap`, keep the order of
+    /// the sequence.
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use facet_yaml::DeserializeOptions;
+    ///
+    /// let options = DeserializeOptions::new().ordered_maps(true);
+    /// let yaml = "- install: apt-get install -y nginx\n- start: systemctl start nginx";
+    /// let steps: Vec<(String, String)> = facet_yaml::from_str_with_options(yaml, &options).unwrap();
+    /// assert_eq!(steps[1], ("start".to_string(), "systemctl start nginx".to_string()));
+    ///
+    /// let steps: BTreeMap<String, String> = facet_yaml::from_str_with_options(yaml, &options).unwrap();
+    /// assert_eq!(steps["install"], "apt-get install -y nginx");
+    /// ```
+    pub fn ordered_maps(mut self, ordered_maps: bool) -> Self {
+        self.ordered_maps = ordered_maps;
+        self
+    }
+
+    /// Read null, like `~` or a key without a value, into structs without fields.
+    ///
+    /// This allows marker types as map values, e.g. `features: {fast_path: ~, telemetry: ~}`.
+    /// Unit structs such as `struct Enabled;` and `()` are always read from null.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use facet::Facet;
+    /// use facet_yaml::DeserializeOptions;
+    ///
+    /// #[derive(Debug, Facet, PartialEq)]
+    /// struct Enabled {}
+    ///
+    /// let options = DeserializeOptions::new().unit_from_null(true);
+    /// let yaml = "fast_path: ~\ntelemetry:";
+    /// let features: HashMap<String, Enabled> =
+    ///     facet_yaml::from_str_with_options(yaml, &options).unwrap();
+    /// assert_eq!(features["telemetry"], Enabled {});
+    /// ```
+    pub fn unit_from_null(mut self, unit_from_null: bool) -> Self {
+        self.unit_from_null = unit_from_null;
+        self
+    }
+
+    /// Read plain integers too large for `i64` and `u64` as strings, with the text they're
+    /// written with.
+    ///
+    /// YAML parsers read such integers as floats, so `99999999999999999999999` becomes
+    /// `1e23`, which is an error for strings and loses digits in a [`Value`](crate::Value).
+    /// With this option they're read as if they were quoted instead, so they can be kept as
+    /// text, like identifiers that happen to be made of digits. Integers that fit in `i64` or
+    /// `u64` and floats such as `1e400` are read as usual.
+    ///
+    /// ```
+    /// use facet_yaml::{DeserializeOptions, Value};
+    ///
+    /// let options = DeserializeOptions::new().big_integers_as_strings(true);
+    /// let id: String = facet_yaml::from_str_with_options("99999999999999999999999", &options).unwrap();
+    /// assert_eq!(id, "99999999999999999999999");
+    ///
+    /// let id: Value = facet_yaml::from_str_with_options("-99999999999999999999999", &options).unwrap();
+    /// assert_eq!(id.as_str(), Some("-99999999999999999999999"));
+    /// ```
+    pub fn big_integers_as_strings(mut self, big_integers_as_strings: bool) -> Self {
+        self.big_integers_as_strings = big_integers_as_strings;
+        self
+    }
+
+    /// Read byte buffers such as `Vec<u8>` from strings of base64 text, as if they had a
+    /// `!!binary` tag.
+    ///
+    /// Byte buffers are read from sequences of integers and from `!!binary` scalars anyway, but
+    /// secrets and certificates are often written as plain strings, leaving it to the reader to
+    /// know they're base64. Whitespace in the text is ignored, so it can be broken into lines
+    /// with a block scalar. Strings with another tag, such as `!!str`, are still rejected.
+    ///
+    /// ```
+    /// use facet::Facet;
+    /// use facet_yaml::DeserializeOptions;
+    ///
+    /// #[derive(Facet)]
+    /// struct Secret {
+    ///     key: Vec<u8>,
+    /// }
+    ///
+    /// let options = DeserializeOptions::new().base64_strings(true);
+    /// let secret: Secret = facet_yaml::from_str_with_options("key: aGkh", &options).unwrap();
+    /// assert_eq!(secret.key, b"hi!");
+    /// ```
+    pub fn base64_strings(mut self, base64_strings: bool) -> Self {
+        self.base64_strings = base64_strings;
+        self
+    }
+
+    /// Register a type under an explicit tag, to select the variant of an enum that holds it for
+    /// nodes with the tag.
+    ///
+    /// This allows dispatching on the tag of a node, like plugins whose configuration is
+    /// `!http {port: 80}`: a field whose type is an enum with a variant holding `T` as its
+    /// single unnamed field is read as that variant from nodes with the tag, whatever the
+    /// variant is named. Nodes with another tag are read as a variant holding a dynamic
+    /// [`Value`](crate::Value), if the enum has one, so a field that used to be a `Value` can
+    /// become such an enum and keep accepting the tags it doesn't know. Nodes without a tag are
+    /// read as usual.
+    ///
+    /// Local tags are matched as they're written, e.g. `!http`, and global ones in full, e.g.
+    /// `tag:example.com,2024:http` for `!e!http` with a `%TAG !e! tag:example.com,2024:`
+    /// directive.
+    ///
+    /// ```
+    /// use facet::Facet;
+    /// use facet_yaml::{DeserializeOptions, Value};
+    ///
+    /// #[derive(Debug, Facet, PartialEq)]
+    /// struct Http {
+    ///     port: u16,
+    /// }
+    ///
+    /// #[derive(Debug, Facet, PartialEq)]
+    /// #[repr(u8)]
+    /// enum Plugin {
+    ///     Http(Http),
+    ///     Other(Value),
+    /// }
+    ///
+    /// let options = DeserializeOptions::new().register_tag::<Http>("!http");
+    /// let yaml = "- !http {port: 80}\n- !grpc {port: 50051}";
+    /// let plugins: Vec<Plugin> = facet_yaml::from_str_with_options(yaml, &options).unwrap();
+    /// assert_eq!(plugins[0], Plugin::Http(Http { port: 80 }));
+    /// assert!(matches!(plugins[1], Plugin::Other(Value::Map(_))));
+    /// ```
+    pub fn register_tag<'a, T: Facet<'a>>(mut self, tag: impl Into<String>) -> Self {
+        let tag = tag.into();
+        self.tags.retain(|(registered, _)| *registered != tag);
+        self.tags.push((tag, T::SHAPE));
+        self
+    }
+
+    /// Read a type with its [`YamlCustomize`] implementation wherever it is in the document,
+    /// instead of by its shape.
+    ///
+    /// The node is converted to a [`Value`](crate::Value) and passed to
+    /// [`YamlCustomize::from_yaml`], whose error is reported where the node is. Nodes with the
+    /// [`TAG`](YamlCustomize::TAG) of the type have it removed first, and nodes with another tag
+    /// are rejected when the type has one.
+    ///
+    /// ```
+    /// use facet::Facet;
+    /// use facet_yaml::{DeserializeOptions, Value, YamlCustomize};
+    ///
+    /// #[derive(Debug, Facet, PartialEq)]
+    /// struct Version {
+    ///     major: u32,
+    ///     minor: u32,
+    /// }
+    ///
+    /// impl YamlCustomize for Version {
+    ///     fn to_yaml(&self) -> Value {
+    ///         Value::String(format!("{}.{}", self.major, self.minor))
+    ///     }
+    ///
+    ///     fn from_yaml(value: Value) -> Result<Self, String> {
+    ///         let text = value.as_str().ok_or("Expected a version string")?;
+    ///         let (major, minor) = text.split_once('.').ok_or("Expected `major.minor`")?;
+    ///         Ok(Version {
+    ///             major: major.parse().map_err(|_| "Invalid major version")?,
+    ///             minor: minor.parse().map_err(|_| "Invalid minor version")?,
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let options = DeserializeOptions::new().customize::<Version>();
+    /// let versions: Vec<Version> =
+    ///     facet_yaml::from_str_with_options("['1.2', '2.0']", &options).unwrap();
+    /// assert_eq!(versions[0], Version { major: 1, minor: 2 });
+    ///
+    /// let err = facet_yaml::from_str_with_options::<Vec<Version>>("['1.2', 'x']", &options)
+    ///     .unwrap_err();
+    /// assert_eq!(err.message(), "Expected `major.minor`");
+    /// ```
+    pub fn customize<T: YamlCustomize>(mut self) -> Self {
+        self.customized
+            .retain(|customization| customization.shape.id != T::SHAPE.id);
+        self.customized.push(Customization::of::<T>());
+        self
+    }
+
+    /// Set the size in bytes under which documents are deserialized without remembering the
+    /// field each key is for, which is 1024 by default.
+    ///
+    /// Remembering the field of a key only pays off when the key is seen again, which it
+    /// rarely is in a small document, so looking it up again is cheaper than storing it. This
+    /// matters to services that read many small snippets, each with
+    /// [`from_str_with_options`](crate::from_str_with_options). Values and errors are the same
+    /// whatever the threshold is. Set it to 0 to always remember the fields of keys, or use a
+    /// [`YamlDeserializer`](crate::YamlDeserializer) to remember them across documents.
+    ///
+    /// ```
+    /// use facet::Facet;
+    /// use facet_yaml::DeserializeOptions;
+    ///
+    /// #[derive(Facet)]
+    /// struct Event {
+    ///     kind: String,
+    ///     id: u64,
+    /// }
+    ///
+    /// let options = DeserializeOptions::new().small_document_threshold(256);
+    /// let event: Event = facet_yaml::from_str_with_options("{kind: push, id: 7}", &options).unwrap();
+    /// assert_eq!(event.id, 7);
+    /// ```
+    pub fn small_document_threshold(mut self, bytes: usize) -> Self {
+        self.small_document_threshold = bytes;
+        self
+    }
+
+    /// Get the type registered under a tag.
+    pub(crate) fn tagged_shape(&self, tag: &str) -> Option<&'static Shape> {
+        self.tags
+            .iter()
+            .find(|(registered, _)| registered == tag)
+            .map(|(_, shape)| *shape)
+    }
+
+    /// Get the customization registered for a type.
+    pub(crate) fn customization(&self, shape: &Shape) -> Option<Customization> {
+        customize::find(&self.customized, shape)
+    }
+
+    /// Whether the deserialization is to be stopped.
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(|cancel| cancel())
+    }
+}
+
+impl core::fmt::Debug for DeserializeOptions {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DeserializeOptions")
+            .field("lenient", &self.lenient)
+            .field("resolver", &self.resolver.is_some())
+            .field("rename_all", &self.rename_all)
+            .field("max_alias_depth", &self.max_alias_depth)
+            .field("max_alias_nodes", &self.max_alias_nodes)
+            .field("max_depth", &self.max_depth)
+            .field("cancel", &self.cancel.is_some())
+            .field("ordered_maps", &self.ordered_maps)
+            .field("unit_from_null", &self.unit_from_null)
+            .field("big_integers_as_strings", &self.big_integers_as_strings)
+            .field("base64_strings", &self.base64_strings)
+            .field(
+                "tags",
+                &self.tags.iter().map(|(tag, _)| tag).collect::<Vec<_>>(),
+            )
+            .field("customized", &self.customized)
+            .field("small_document_threshold", &self.small_document_threshold)
+            .finish()
+    }
+}
+
+impl Default for DeserializeOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What a plain scalar resolves to, as decided by a
+/// [resolver](DeserializeOptions::resolve_plain_scalars).
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlainScalar {
+    /// Null, like `~`.
+    Null,
+    /// Boolean, like `true`.
+    Bool(bool),
+    /// Integer, like `42`.
+    Integer(i64),
+    /// Floating point number, like `4.2`.
+    Float(f64),
+    /// The text of the scalar as a string.
+    String,
+}
+
+/// A naming convention for the keys of a document, see [`DeserializeOptions::rename_all`].
+///
+/// Field names are split into words at underscores, dashes and changes of case, so names in any
+/// of these conventions can be converted to any other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum RenameRule {
+    /// Like `maxAttempts`.
+    CamelCase,
+    /// Like `MaxAttempts`.
+    PascalCase,
+    /// Like `max_attempts`.
+    SnakeCase,
+    /// Like `MAX_ATTEMPTS`.
+    ScreamingSnakeCase,
+    /// Like `max-attempts`.
+    KebabCase,
+}
+
+impl RenameRule {
+    /// Get the convention with the name used by the `rename_all` attributes, like `camelCase`.
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "camelCase" => Some(Self::CamelCase),
+            "PascalCase" => Some(Self::PascalCase),
+            "snake_case" => Some(Self::SnakeCase),
+            "SCREAMING_SNAKE_CASE" => Some(Self::ScreamingSnakeCase),
+            "kebab-case" => Some(Self::KebabCase),
+            _ => None,
+        }
+    }
+
+    /// Convert the name of a field to this convention.
+    pub(crate) fn apply(self, name: &str) -> String {
+        let words = words(name);
+        let capitalized = || words.iter().map(|word| capitalize(word));
+        match self {
+            Self::CamelCase => {
+                let mut key = words.first().cloned().unwrap_or_default();
+                key.extend(capitalized().skip(1));
+                key
+            }
+            Self::PascalCase => capitalized().collect(),
+            Self::SnakeCase => words.join("_"),
+            Self::ScreamingSnakeCase => words.join("_").to_uppercase(),
+            Self::KebabCase => words.join("-"),
+        }
+    }
+}
+
+/// Split a name into lowercase words.
+///
+/// A new word starts after an underscore or a dash, at an uppercase letter that follows a
+/// lowercase letter or a digit, and at the last letter of an acronym, e.g. `HTTPServer` is `http`
+/// and `server`.
+fn words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (index, &c) in chars.iter().enumerate() {
+        if c == '_' || c == '-' {
+            if !word.is_empty() {
+                words.push(core::mem::take(&mut word));
+            }
+            continue;
+        }
+
+        let previous = index.checked_sub(1).map(|index| chars[index]);
+        let next = chars.get(index + 1);
+        let starts_word = c.is_uppercase()
+            && match previous {
+                Some(previous) if previous.is_lowercase() || previous.is_ascii_digit() => true,
+                Some(previous) if previous.is_uppercase() => next.is_some_and(|c| c.is_lowercase()),
+                _ => false,
+            };
+        if starts_word && !word.is_empty() {
+            words.push(core::mem::take(&mut word));
+        }
+        word.extend(c.to_lowercase());
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Make the first letter of a word uppercase.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
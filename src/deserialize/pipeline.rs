@@ -0,0 +1,903 @@
+//! Rewrite YAML documents as a stream of parse events, without loading them into a tree.
+
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, VecDeque},
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use facet_core::Facet;
+use facet_reflect::Partial;
+use yaml_rust2::{
+    Yaml,
+    parser::{Event, Parser, Tag},
+    scanner::TScalarStyle,
+};
+
+use super::{
+    DeserializeError, DeserializeOptions, Deserializer, FieldCache, Span,
+    node::{self, CORE_TAG_HANDLE, Node, NodeValue},
+    set_empty_document,
+};
+use crate::ErrorCode;
+
+/// A parse event flowing through a [`Pipeline`].
+///
+/// A mapping is a [`MappingStart`](Self::MappingStart), followed by alternating keys and values,
+/// and a [`MappingEnd`](Self::MappingEnd). Aliases are expanded, so an anchored node is seen
+/// again wherever it is referenced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PipelineEvent {
+    /// Start of a mapping.
+    MappingStart,
+    /// End of a mapping.
+    MappingEnd,
+    /// Start of a sequence.
+    SequenceStart,
+    /// End of a sequence.
+    SequenceEnd,
+    /// A scalar.
+    Scalar {
+        /// Text of the scalar, without quotes or escapes.
+        value: String,
+        /// Whether the scalar is resolved like an unquoted one, so `3` is an integer and `~` is
+        /// null. Otherwise it's always a string.
+        plain: bool,
+    },
+}
+
+impl PipelineEvent {
+    /// An unquoted scalar, resolved the way YAML would resolve it in a document.
+    pub fn plain(value: impl Into<String>) -> Self {
+        Self::Scalar {
+            value: value.into(),
+            plain: true,
+        }
+    }
+
+    /// A string scalar, which is quoted when emitted if it would otherwise be read as another
+    /// type.
+    pub fn string(value: impl Into<String>) -> Self {
+        Self::Scalar {
+            value: value.into(),
+            plain: false,
+        }
+    }
+}
+
+/// Where an event is in the document, as seen by a [`Transform`].
+#[derive(Debug)]
+pub struct TransformContext<'a> {
+    /// Path of the node the event belongs to.
+    path: &'a [String],
+    /// Whether the event is a mapping key.
+    is_key: bool,
+    /// Where the event comes from in the source document.
+    span: Span,
+}
+
+impl TransformContext<'_> {
+    /// Path of the node the event belongs to, as mapping keys and sequence indices.
+    ///
+    /// A mapping key has the path of the value it names, and the end of a collection has the
+    /// path of the collection.
+    pub fn path(&self) -> &[String] {
+        self.path
+    }
+
+    /// Whether the event is a mapping key.
+    pub fn is_key(&self) -> bool {
+        self.is_key
+    }
+
+    /// Where the event comes from in the source document. Events added by an earlier
+    /// transform have the location of the event they were added for.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Whether the path of the event is exactly `path`, a dotted list of keys and indices where
+    /// `*` matches any of them.
+    pub fn matches(&self, path: &str) -> bool {
+        let pattern: Vec<&str> = path.split('.').filter(|s| !s.is_empty()).collect();
+        pattern.len() == self.path.len()
+            && pattern
+                .iter()
+                .zip(self.path)
+                .all(|(pattern, segment)| *pattern == "*" || pattern == segment)
+    }
+}
+
+/// A step of a [`Pipeline`], which rewrites the events flowing through it.
+///
+/// Closures taking the same arguments as [`Transform::transform`] are transforms too.
+pub trait Transform {
+    /// Handle an event, pushing the events that replace it to `out`.
+    ///
+    /// Pushing nothing drops the event, and pushing it unchanged keeps it. The events that come
+    /// out of a pipeline must still form a document: a transform that drops a mapping key must
+    /// drop its value too.
+    fn transform(
+        &mut self,
+        event: PipelineEvent,
+        context: &TransformContext<'_>,
+        out: &mut Vec<PipelineEvent>,
+    );
+}
+
+impl<F> Transform for F
+where
+    F: FnMut(PipelineEvent, &TransformContext<'_>, &mut Vec<PipelineEvent>),
+{
+    fn transform(
+        &mut self,
+        event: PipelineEvent,
+        context: &TransformContext<'_>,
+        out: &mut Vec<PipelineEvent>,
+    ) {
+        self(event, context, out)
+    }
+}
+
+/// Renames the mapping keys at a path.
+#[derive(Debug, Clone)]
+pub struct RenameKey {
+    /// Path of the values whose keys are renamed.
+    path: Vec<String>,
+    /// The new name.
+    to: String,
+}
+
+impl RenameKey {
+    /// Rename the key of the values at `path`, a dotted list of keys and indices where `*`
+    /// matches any of them, to `to`.
+    pub fn new(path: &str, to: impl Into<String>) -> Result<Self, DeserializeError> {
+        Ok(Self {
+            path: split_path(path)?,
+            to: to.into(),
+        })
+    }
+}
+
+impl Transform for RenameKey {
+    fn transform(
+        &mut self,
+        event: PipelineEvent,
+        context: &TransformContext<'_>,
+        out: &mut Vec<PipelineEvent>,
+    ) {
+        if context.is_key && matches_path(&self.path, context.path) {
+            out.push(PipelineEvent::string(self.to.clone()));
+        } else {
+            out.push(event);
+        }
+    }
+}
+
+/// Drops the values at a path, along with their keys.
+#[derive(Debug, Clone)]
+pub struct DropPath {
+    /// Path of the dropped values.
+    path: Vec<String>,
+}
+
+impl DropPath {
+    /// Drop the values at `path`, a dotted list of keys and indices where `*` matches any of
+    /// them.
+    ///
+    /// Dropping sequence items leaves the indices of the following items unchanged for later
+    /// transforms, since they see the events that come out of this one.
+    pub fn new(path: &str) -> Result<Self, DeserializeError> {
+        Ok(Self {
+            path: split_path(path)?,
+        })
+    }
+}
+
+impl Transform for DropPath {
+    fn transform(
+        &mut self,
+        event: PipelineEvent,
+        context: &TransformContext<'_>,
+        out: &mut Vec<PipelineEvent>,
+    ) {
+        let dropped = context.path.len() >= self.path.len()
+            && matches_path(&self.path, &context.path[..self.path.len()]);
+        if !dropped {
+            out.push(event);
+        }
+    }
+}
+
+/// Adds a key with a default value to the mappings at a path that don't have it.
+#[derive(Debug, Clone)]
+pub struct InjectDefault {
+    /// Path of the mappings the key is added to.
+    parent: Vec<String>,
+    /// The added key.
+    key: String,
+    /// Events of the default value.
+    value: Vec<PipelineEvent>,
+    /// Whether each open mapping at the path has the key already, innermost last.
+    seen: Vec<bool>,
+}
+
+impl InjectDefault {
+    /// Add the value at `path`, a dotted list of keys and indices where `*` matches any of them,
+    /// when the mapping it would be in doesn't have it. The default is YAML text, such as `3`
+    /// or `{cpu: 100m}`.
+    pub fn new(path: &str, value: &str) -> Result<Self, DeserializeError> {
+        let mut parent = split_path(path)?;
+        let key = parent.pop().unwrap_or_default();
+
+        let mut source = Source::new(value);
+        let mut events = Vec::new();
+        while let Some((event, _)) = source.next_event()? {
+            events.push(event);
+        }
+        if events.is_empty() {
+            events.push(PipelineEvent::plain("~"));
+        }
+
+        Ok(Self {
+            parent,
+            key,
+            value: events,
+            seen: Vec::new(),
+        })
+    }
+}
+
+impl Transform for InjectDefault {
+    fn transform(
+        &mut self,
+        event: PipelineEvent,
+        context: &TransformContext<'_>,
+        out: &mut Vec<PipelineEvent>,
+    ) {
+        match &event {
+            PipelineEvent::MappingStart
+                if !context.is_key && matches_path(&self.parent, context.path) =>
+            {
+                self.seen.push(false);
+            }
+            PipelineEvent::MappingEnd if matches_path(&self.parent, context.path) => {
+                let seen = self.seen.pop();
+                if seen == Some(false) {
+                    out.push(PipelineEvent::string(self.key.clone()));
+                    out.extend(self.value.iter().cloned());
+                }
+            }
+            _ if context.is_key
+                && context.path.last() == Some(&self.key)
+                && matches_path(&self.parent, &context.path[..context.path.len() - 1]) =>
+            {
+                if let Some(seen) = self.seen.last_mut() {
+                    *seen = true;
+                }
+            }
+            _ => {}
+        }
+        out.push(event);
+    }
+}
+
+/// Streams the events of a YAML document through a chain of [`Transform`]s.
+///
+/// The transformed events can be written back as YAML with [`to_yaml`](Self::to_yaml), which
+/// never holds more than one event of each step in memory, deserialized into a value with
+/// [`deserialize`](Self::deserialize), or read one by one as an iterator.
+///
+/// ```
+/// use facet_yaml::{DropPath, InjectDefault, Pipeline, RenameKey};
+///
+/// let yaml = "
+/// metadata:
+///   name: web
+/// spec:
+///   replicas: 3
+/// status:
+///   ready: true
+/// ";
+/// let rewritten = Pipeline::new(yaml)
+///     .transform(DropPath::new("status")?)
+///     .transform(RenameKey::new("spec.replicas", "count")?)
+///     .transform(InjectDefault::new("spec.strategy", "{type: Recreate}")?)
+///     .to_yaml()?;
+/// assert_eq!(
+///     rewritten,
+///     "metadata:\n  name: web\nspec:\n  count: 3\n  strategy:\n    type: Recreate\n"
+/// );
+/// # Ok::<(), facet_yaml::DeserializeError>(())
+/// ```
+pub struct Pipeline<'input> {
+    /// Events of the document.
+    source: Source<'input>,
+    /// The transforms, in the order events flow through them.
+    stages: Vec<Stage<'input>>,
+    /// An event that was looked at but not consumed yet.
+    peeked: Option<(PipelineEvent, Span)>,
+}
+
+impl core::fmt::Debug for Pipeline<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Pipeline")
+            .field("transforms", &self.stages.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'input> Pipeline<'input> {
+    /// Create a pipeline over a YAML document, without any transforms.
+    ///
+    /// Nothing is parsed until the events are consumed.
+    pub fn new(yaml: &'input str) -> Self {
+        // The parser would otherwise treat a byte-order mark as part of the first scalar
+        let yaml = yaml.strip_prefix('\u{feff}').unwrap_or(yaml);
+        Self {
+            source: Source::new(yaml),
+            stages: Vec::new(),
+            peeked: None,
+        }
+    }
+
+    /// Add a transform, which sees the events coming out of the previous ones.
+    pub fn transform(mut self, transform: impl Transform + 'input) -> Self {
+        self.stages.push(Stage {
+            transform: Box::new(transform),
+            tracker: Tracker::default(),
+            pending: VecDeque::new(),
+        });
+        self
+    }
+
+    /// Write the transformed document as block-style YAML.
+    ///
+    /// Comments, anchors and the original formatting aren't kept. Scalars are written as they
+    /// were if they were unquoted, and quoted otherwise when they'd be read as something else.
+    pub fn to_yaml(mut self) -> Result<String, DeserializeError> {
+        let mut out = String::new();
+        if let Some((event, span)) = self.next_event()? {
+            self.emit_node(event, span, 0, Position::Root, &mut out)?;
+        }
+        self.expect_document_end()?;
+        Ok(out)
+    }
+
+    /// Deserialize the transformed document into a value of type `T`.
+    pub fn deserialize<T: for<'facet> Facet<'facet>>(mut self) -> Result<T, DeserializeError> {
+        let root = match self.next_event()? {
+            // The parser reports a document without content as an empty plain scalar
+            Some((PipelineEvent::Scalar { value, plain: true }, _)) if value.is_empty() => None,
+            Some((event, span)) => Some(self.build_node(event, span)?),
+            None => None,
+        };
+        self.expect_document_end()?;
+
+        let mut typed_partial = Partial::alloc::<T>()?;
+        {
+            let wip = typed_partial.inner_mut();
+            match root {
+                None => set_empty_document(wip)?,
+                Some(root) => {
+                    Deserializer::new(&DeserializeOptions::default(), &FieldCache::default())
+                        .deserialize_value(wip, &root)?
+                }
+            }
+        }
+        let boxed_value = typed_partial.build().map_err(DeserializeError::reflect)?;
+        Ok(*boxed_value)
+    }
+
+    /// Get the next transformed event, along with where it comes from.
+    fn next_event(&mut self) -> Result<Option<(PipelineEvent, Span)>, DeserializeError> {
+        if let Some(peeked) = self.peeked.take() {
+            return Ok(Some(peeked));
+        }
+        pull(&mut self.source, &mut self.stages)
+    }
+
+    /// Look at the next transformed event without consuming it.
+    fn peek_event(&mut self) -> Result<Option<&PipelineEvent>, DeserializeError> {
+        if self.peeked.is_none() {
+            self.peeked = pull(&mut self.source, &mut self.stages)?;
+        }
+        Ok(self.peeked.as_ref().map(|(event, _)| event))
+    }
+
+    /// Get the next event of a collection that was started.
+    fn expect_event(&mut self) -> Result<(PipelineEvent, Span), DeserializeError> {
+        self.next_event()?.ok_or_else(|| {
+            DeserializeError::new(
+                ErrorCode::Transform,
+                "Unexpected end of the transformed document",
+            )
+        })
+    }
+
+    /// Check that the root node was the whole document.
+    fn expect_document_end(&mut self) -> Result<(), DeserializeError> {
+        match self.next_event()? {
+            None => Ok(()),
+            Some((event, span)) => Err(DeserializeError::new(
+                ErrorCode::Transform,
+                format!("Unexpected {event:?} after the root node of the transformed document"),
+            )
+            .at(span)),
+        }
+    }
+
+    /// Build a node from its first event and the ones that follow it.
+    fn build_node(&mut self, event: PipelineEvent, span: Span) -> Result<Node, DeserializeError> {
+        let value = match event {
+            PipelineEvent::Scalar { value, plain } => {
+                let style = if plain {
+                    TScalarStyle::Plain
+                } else {
+                    TScalarStyle::DoubleQuoted
+                };
+                node::resolve_scalar(value, style, None)
+            }
+            PipelineEvent::SequenceStart => {
+                let mut items = Vec::new();
+                loop {
+                    match self.expect_event()? {
+                        (PipelineEvent::SequenceEnd, _) => break,
+                        (event, span) => items.push(self.build_node(event, span)?),
+                    }
+                }
+                NodeValue::Array(items)
+            }
+            PipelineEvent::MappingStart => {
+                let mut entries = Vec::new();
+                loop {
+                    match self.expect_event()? {
+                        (PipelineEvent::MappingEnd, _) => break,
+                        (event, span) => {
+                            let key = self.build_node(event, span)?;
+                            let (event, span) = self.expect_event()?;
+                            entries.push((key, self.build_node(event, span)?));
+                        }
+                    }
+                }
+                NodeValue::Hash(entries)
+            }
+            PipelineEvent::MappingEnd | PipelineEvent::SequenceEnd => {
+                return Err(unexpected_end(&event, span));
+            }
+        };
+
+        Ok(Node {
+            value,
+            span,
+            tag: None,
+        })
+    }
+
+    /// Write a node from its first event and the ones that follow it.
+    ///
+    /// Block collections have their entries at column `indent`.
+    fn emit_node(
+        &mut self,
+        event: PipelineEvent,
+        span: Span,
+        indent: usize,
+        position: Position,
+        out: &mut String,
+    ) -> Result<(), DeserializeError> {
+        let (end, empty) = match &event {
+            PipelineEvent::Scalar { value, plain } => {
+                if position == Position::Value {
+                    out.push(' ');
+                }
+                out.push_str(&scalar_text(value, *plain));
+                out.push('\n');
+                return Ok(());
+            }
+            PipelineEvent::MappingStart => (PipelineEvent::MappingEnd, "{}"),
+            PipelineEvent::SequenceStart => (PipelineEvent::SequenceEnd, "[]"),
+            PipelineEvent::MappingEnd | PipelineEvent::SequenceEnd => {
+                return Err(unexpected_end(&event, span));
+            }
+        };
+
+        if self.peek_event()? == Some(&end) {
+            self.next_event()?;
+            if position == Position::Value {
+                out.push(' ');
+            }
+            out.push_str(empty);
+            out.push('\n');
+            return Ok(());
+        }
+
+        // Below a key the entries start on the next line, after a dash they start on the same
+        let mut first = true;
+        if position == Position::Value {
+            out.push('\n');
+            first = false;
+        }
+        loop {
+            let (event, span) = self.expect_event()?;
+            if event == end {
+                return Ok(());
+            }
+            if !first {
+                out.extend(core::iter::repeat_n(' ', indent));
+            }
+            first = false;
+
+            if end == PipelineEvent::SequenceEnd {
+                out.push_str("- ");
+                self.emit_node(event, span, indent + 2, Position::Item, out)?;
+                continue;
+            }
+
+            match event {
+                PipelineEvent::Scalar { value, plain } => {
+                    out.push_str(&scalar_text(&value, plain));
+                    out.push(':');
+                }
+                event => {
+                    return Err(DeserializeError::new(
+                        ErrorCode::Transform,
+                        format!("Can't write {event:?} as a mapping key"),
+                    )
+                    .at(span));
+                }
+            }
+            let (value, span) = self.expect_event()?;
+            self.emit_node(value, span, indent + 2, Position::Value, out)?;
+        }
+    }
+}
+
+impl Iterator for Pipeline<'_> {
+    type Item = Result<PipelineEvent, DeserializeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_event()
+            .map(|event| event.map(|(event, _)| event))
+            .transpose()
+    }
+}
+
+/// Where a node is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Position {
+    /// The root of the document.
+    Root,
+    /// After a mapping key.
+    Value,
+    /// After the dash of a sequence item.
+    Item,
+}
+
+/// A transform, along with what it needs to see its input as a document.
+struct Stage<'input> {
+    /// The transform.
+    transform: Box<dyn Transform + 'input>,
+    /// Paths of the events going into the transform.
+    tracker: Tracker,
+    /// Events that came out of the transform, but weren't pulled yet.
+    pending: VecDeque<(PipelineEvent, Span)>,
+}
+
+/// Pull the next event out of the last stage, running the earlier ones as needed.
+fn pull(
+    source: &mut Source<'_>,
+    stages: &mut [Stage<'_>],
+) -> Result<Option<(PipelineEvent, Span)>, DeserializeError> {
+    let Some((stage, earlier)) = stages.split_last_mut() else {
+        return source.next_event();
+    };
+
+    let mut out = Vec::new();
+    loop {
+        if let Some(event) = stage.pending.pop_front() {
+            return Ok(Some(event));
+        }
+        let Some((event, span)) = pull(source, earlier)? else {
+            return Ok(None);
+        };
+
+        let (path, is_key) = stage.tracker.context(&event);
+        stage.tracker.advance(&event, &path);
+        let context = TransformContext {
+            path: &path,
+            is_key,
+            span,
+        };
+        stage.transform.transform(event, &context, &mut out);
+        stage
+            .pending
+            .extend(out.drain(..).map(|event| (event, span)));
+    }
+}
+
+/// Keeps track of the path of the events of a document.
+#[derive(Debug, Default)]
+struct Tracker {
+    /// The open collections, innermost last.
+    frames: Vec<Frame>,
+}
+
+/// A collection that was started and not ended yet.
+#[derive(Debug)]
+struct Frame {
+    /// Path of the collection.
+    path: Vec<String>,
+    /// What comes next in it.
+    kind: FrameKind,
+}
+
+/// What comes next in a collection.
+#[derive(Debug)]
+enum FrameKind {
+    /// A key if `None`, or the value of that key.
+    Mapping(Option<String>),
+    /// The item at that index.
+    Sequence(usize),
+}
+
+impl Tracker {
+    /// The path of an event, and whether it is a mapping key.
+    fn context(&self, event: &PipelineEvent) -> (Vec<String>, bool) {
+        let Some(frame) = self.frames.last() else {
+            return (Vec::new(), false);
+        };
+        let mut path = frame.path.clone();
+        if matches!(
+            event,
+            PipelineEvent::MappingEnd | PipelineEvent::SequenceEnd
+        ) {
+            return (path, false);
+        }
+
+        match &frame.kind {
+            FrameKind::Mapping(None) => {
+                path.push(match event {
+                    PipelineEvent::Scalar { value, .. } => value.clone(),
+                    // Complex keys can't be selected
+                    _ => String::new(),
+                });
+                (path, true)
+            }
+            FrameKind::Mapping(Some(key)) => {
+                path.push(key.clone());
+                (path, false)
+            }
+            FrameKind::Sequence(index) => {
+                path.push(index.to_string());
+                (path, false)
+            }
+        }
+    }
+
+    /// Move past an event, whose path is `path`.
+    fn advance(&mut self, event: &PipelineEvent, path: &[String]) {
+        match event {
+            PipelineEvent::MappingStart => self.frames.push(Frame {
+                path: path.to_vec(),
+                kind: FrameKind::Mapping(None),
+            }),
+            PipelineEvent::SequenceStart => self.frames.push(Frame {
+                path: path.to_vec(),
+                kind: FrameKind::Sequence(0),
+            }),
+            PipelineEvent::MappingEnd | PipelineEvent::SequenceEnd => {
+                self.frames.pop();
+                self.complete(String::new());
+            }
+            PipelineEvent::Scalar { value, .. } => self.complete(value.clone()),
+        }
+    }
+
+    /// Move past a whole node, which is named `key` if it is a mapping key.
+    fn complete(&mut self, key: String) {
+        let Some(frame) = self.frames.last_mut() else {
+            return;
+        };
+        match &mut frame.kind {
+            FrameKind::Mapping(next @ None) => *next = Some(key),
+            FrameKind::Mapping(next) => *next = None,
+            FrameKind::Sequence(index) => *index += 1,
+        }
+    }
+}
+
+/// Events of a node, with where they are in the input.
+type Events = Vec<(PipelineEvent, Span)>;
+
+/// Reads the events of a single YAML document, expanding aliases.
+struct Source<'input> {
+    /// Where the events come from.
+    parser: Parser<core::str::Chars<'input>>,
+    /// Events of the nodes that have been anchored.
+    anchors: BTreeMap<usize, Events>,
+    /// Anchored collections that are being read, with how deep in them the parser is.
+    recording: Vec<(usize, usize, Events)>,
+    /// Events of an alias that are being replayed.
+    replay: VecDeque<(PipelineEvent, Span)>,
+    /// Number of documents started so far.
+    documents: usize,
+    /// Whether the stream ended or failed.
+    done: bool,
+}
+
+impl<'input> Source<'input> {
+    /// Create a source for a YAML string.
+    fn new(yaml: &'input str) -> Self {
+        Self {
+            parser: Parser::new_from_str(yaml),
+            anchors: BTreeMap::new(),
+            recording: Vec::new(),
+            replay: VecDeque::new(),
+            documents: 0,
+            done: false,
+        }
+    }
+
+    /// Get the next event of the document.
+    fn next_event(&mut self) -> Result<Option<(PipelineEvent, Span)>, DeserializeError> {
+        let result = self.read();
+        if result.is_err() {
+            self.done = true;
+        }
+        result
+    }
+
+    /// Read the next event of the document from the parser or the alias being replayed.
+    fn read(&mut self) -> Result<Option<(PipelineEvent, Span)>, DeserializeError> {
+        loop {
+            if let Some((event, span)) = self.replay.pop_front() {
+                self.record(&event, span);
+                return Ok(Some((event, span)));
+            }
+            if self.done {
+                return Ok(None);
+            }
+
+            let (event, mark) = self.parser.next_token()?;
+            let span = Span::from(mark);
+            let (event, anchor) = match event {
+                Event::StreamStart | Event::Nothing | Event::DocumentEnd => continue,
+                Event::StreamEnd => {
+                    self.done = true;
+                    return Ok(None);
+                }
+                Event::DocumentStart => {
+                    self.documents += 1;
+                    if self.documents > 1 {
+                        return Err(DeserializeError::new(
+                            ErrorCode::DocumentCount,
+                            "Expected exactly one YAML document",
+                        )
+                        .at(span));
+                    }
+                    continue;
+                }
+                Event::Alias(id) => {
+                    let events = self.anchors.get(&id).ok_or_else(|| {
+                        DeserializeError::new(ErrorCode::UnknownAnchor, "Unknown anchor in alias")
+                            .at(span)
+                    })?;
+                    self.replay.extend(events.iter().cloned());
+                    continue;
+                }
+                Event::Scalar(value, style, anchor, tag) => {
+                    let plain = style == TScalarStyle::Plain
+                        && match &tag {
+                            None => true,
+                            Some(Tag { handle, suffix }) => {
+                                handle == CORE_TAG_HANDLE && suffix != "str"
+                            }
+                        };
+                    (PipelineEvent::Scalar { value, plain }, anchor)
+                }
+                Event::SequenceStart(anchor, _) => (PipelineEvent::SequenceStart, anchor),
+                Event::SequenceEnd => (PipelineEvent::SequenceEnd, 0),
+                Event::MappingStart(anchor, _) => (PipelineEvent::MappingStart, anchor),
+                Event::MappingEnd => (PipelineEvent::MappingEnd, 0),
+            };
+
+            // Valid anchor IDs start at 1
+            if anchor > 0 {
+                if matches!(event, PipelineEvent::Scalar { .. }) {
+                    self.anchors.insert(anchor, vec![(event.clone(), span)]);
+                } else {
+                    self.recording.push((anchor, 0, Vec::new()));
+                }
+            }
+            self.record(&event, span);
+            return Ok(Some((event, span)));
+        }
+    }
+
+    /// Add an event to the anchored collections being read, keeping the ones that ended.
+    fn record(&mut self, event: &PipelineEvent, span: Span) {
+        for (_, depth, events) in &mut self.recording {
+            events.push((event.clone(), span));
+            match event {
+                PipelineEvent::MappingStart | PipelineEvent::SequenceStart => *depth += 1,
+                PipelineEvent::MappingEnd | PipelineEvent::SequenceEnd => *depth -= 1,
+                PipelineEvent::Scalar { .. } => {}
+            }
+        }
+        while let Some((anchor, 0, _)) = self.recording.last() {
+            let anchor = *anchor;
+            if let Some((_, _, events)) = self.recording.pop() {
+                self.anchors.insert(anchor, events);
+            }
+        }
+    }
+}
+
+/// Error for the end of a collection where a node was expected.
+fn unexpected_end(event: &PipelineEvent, span: Span) -> DeserializeError {
+    DeserializeError::new(
+        ErrorCode::Transform,
+        format!("Unexpected {event:?} in the transformed document, expected a node"),
+    )
+    .at(span)
+}
+
+/// Split a dotted path into its segments.
+fn split_path(path: &str) -> Result<Vec<String>, DeserializeError> {
+    if path.is_empty() || path.split('.').any(str::is_empty) {
+        return Err(DeserializeError::new(
+            ErrorCode::InvalidPath,
+            format!("Invalid pipeline path `{path}`"),
+        ));
+    }
+    Ok(path.split('.').map(ToString::to_string).collect())
+}
+
+/// Whether a path matches a pattern of the same length, where `*` matches any segment.
+fn matches_path(pattern: &[String], path: &[String]) -> bool {
+    pattern.len() == path.len()
+        && pattern
+            .iter()
+            .zip(path)
+            .all(|(pattern, segment)| pattern == "*" || pattern == segment)
+}
+
+/// Text of a scalar in block-style YAML.
+fn scalar_text(value: &str, plain: bool) -> String {
+    if (plain && !value.is_empty()) || !needs_quotes(value) {
+        return value.to_string();
+    }
+
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Whether a string has to be quoted to be read back as the same string.
+fn needs_quotes(value: &str) -> bool {
+    let Some(first) = value.chars().next() else {
+        return true;
+    };
+    !matches!(Yaml::from_str(value), Yaml::String(_))
+        || "-?:,[]{}#&*!|>'\"%@`".contains(first)
+        || value.starts_with(char::is_whitespace)
+        || value.ends_with(char::is_whitespace)
+        || value.ends_with(':')
+        || value.contains(": ")
+        || value.contains(" #")
+        || value.chars().any(char::is_control)
+}
@@ -0,0 +1,266 @@
+//! Use YAML documents as a configuration source for `figment`.
+
+// `figment::Error` is large, but it's what `Provider::data` has to return
+#![allow(clippy::result_large_err)]
+
+#[cfg(not(feature = "std"))]
+compile_error!("feature `std` is required, since `figment` needs it");
+
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use figment::{
+    Error, Metadata, Profile, Provider, Source,
+    value::{Dict, Empty, Map, Num, Tag, Value},
+};
+
+use super::{
+    DeserializeError, DeserializeOptions, Span,
+    node::{self, Node, NodeValue, key_segment},
+    parse_float,
+};
+use crate::ErrorCode;
+
+/// A [`figment`] provider that reads a YAML document, so it can be merged with other
+/// configuration sources such as environment variables.
+///
+/// The document must be a mapping, whose keys are the keys of the configuration. Keys in the
+/// errors reported by figment are followed by where they are in the document, e.g.
+/// `servers.port (line 4, column 5)`, so a value with the wrong type can be found in the file.
+///
+/// A file that doesn't exist provides no values, unless it's [`required`](Self::required).
+///
+/// ```no_run
+/// use figment::Figment;
+///
+/// let port: u16 = Figment::new()
+///     .merge(facet_yaml::YamlProvider::file("config.yaml"))
+///     .extract_inner("server.port")?;
+/// # Ok::<(), figment::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct YamlProvider {
+    /// Where the document is read from.
+    source: YamlSource,
+    /// Whether a missing file is an error.
+    required: bool,
+    /// The profile the values are provided for.
+    profile: Profile,
+}
+
+/// Where a [`YamlProvider`] reads its document from.
+#[derive(Debug, Clone)]
+enum YamlSource {
+    /// A file, read every time the values are requested.
+    File(PathBuf),
+    /// A document in memory.
+    String(String),
+}
+
+impl YamlProvider {
+    /// Provide the values of a YAML file.
+    pub fn file(path: impl AsRef<Path>) -> Self {
+        Self::with_source(YamlSource::File(path.as_ref().to_path_buf()))
+    }
+
+    /// Provide the values of a YAML document.
+    pub fn string(yaml: impl Into<String>) -> Self {
+        Self::with_source(YamlSource::String(yaml.into()))
+    }
+
+    fn with_source(source: YamlSource) -> Self {
+        Self {
+            source,
+            required: false,
+            profile: Profile::Default,
+        }
+    }
+
+    /// Set whether a file that doesn't exist is an error, instead of providing no values.
+    pub fn required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// Provide the values for `profile` instead of the default profile.
+    pub fn profile(mut self, profile: impl Into<Profile>) -> Self {
+        self.profile = profile.into();
+        self
+    }
+
+    /// Read the document, or nothing if it's an optional file that doesn't exist.
+    fn read(&self) -> Result<Option<String>, Error> {
+        match &self.source {
+            YamlSource::String(yaml) => Ok(Some(yaml.clone())),
+            YamlSource::File(path) => match fs::read_to_string(path) {
+                Ok(yaml) => Ok(Some(yaml)),
+                Err(err) if err.kind() == io::ErrorKind::NotFound && !self.required => Ok(None),
+                Err(err) => Err(Error::from(format!(
+                    "Failed to read {}: {err}",
+                    path.display()
+                ))),
+            },
+        }
+    }
+
+    /// Read and convert the document, remembering where each key is.
+    fn load(&self) -> Result<Loaded, Error> {
+        let mut loaded = Loaded {
+            dict: Dict::new(),
+            spans: BTreeMap::new(),
+        };
+        let Some(yaml) = self.read()? else {
+            return Ok(loaded);
+        };
+        let yaml = yaml.strip_prefix('\u{feff}').unwrap_or(&yaml);
+
+        let docs = node::load(yaml, &DeserializeOptions::default()).map_err(figment_error)?;
+        match docs.as_slice() {
+            [] => {}
+            [doc] => match &doc.value {
+                NodeValue::Null => {}
+                NodeValue::Hash(entries) => loaded.dict = loaded.convert_map(entries, "")?,
+                _ => {
+                    return Err(figment_error(
+                        DeserializeError::new(
+                            ErrorCode::TypeMismatch,
+                            "Expected a YAML hash at the root of the document",
+                        )
+                        .at(doc.span),
+                    ));
+                }
+            },
+            _ => {
+                return Err(figment_error(DeserializeError::new(
+                    ErrorCode::DocumentCount,
+                    "Expected exactly one YAML document",
+                )));
+            }
+        }
+        Ok(loaded)
+    }
+}
+
+impl Provider for YamlProvider {
+    fn metadata(&self) -> Metadata {
+        let metadata = match &self.source {
+            YamlSource::File(path) => Metadata::from("YAML file", Source::File(path.clone())),
+            YamlSource::String(_) => Metadata::named("YAML document"),
+        };
+
+        // Errors in the document itself are reported by `data`
+        let spans = Arc::new(self.load().map(|loaded| loaded.spans).unwrap_or_default());
+        metadata.interpolater(move |profile: &Profile, keys: &[&str]| {
+            let key = keys.join(".");
+            match spans.get(&key) {
+                Some(span) => format!("{profile}.{key} ({span})"),
+                None => format!("{profile}.{key}"),
+            }
+        })
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let mut data = Map::new();
+        data.insert(self.profile.clone(), self.load()?.dict);
+        Ok(data)
+    }
+}
+
+/// A document converted to figment values.
+struct Loaded {
+    /// The values of the root mapping.
+    dict: Dict,
+    /// Where each key is, by its dotted path. Sequence indices aren't part of the path, since
+    /// figment doesn't report them, so the first key on a path is kept.
+    spans: BTreeMap<String, Span>,
+}
+
+impl Loaded {
+    fn convert_map(&mut self, entries: &[(Node, Node)], path: &str) -> Result<Dict, Error> {
+        let mut dict = Dict::new();
+        for (key, value) in entries {
+            let name = key_segment(key);
+            if name.is_empty() {
+                return Err(figment_error(
+                    DeserializeError::new(
+                        ErrorCode::InvalidKey,
+                        "Keys of the configuration must be scalars",
+                    )
+                    .at(key.span),
+                ));
+            }
+
+            let path = if path.is_empty() {
+                name.clone()
+            } else {
+                format!("{path}.{name}")
+            };
+            self.spans.entry(path.clone()).or_insert(key.span);
+
+            let value = self.convert(value, &path)?;
+            dict.insert(name, value);
+        }
+        Ok(dict)
+    }
+
+    fn convert(&mut self, node: &Node, path: &str) -> Result<Value, Error> {
+        let tag = Tag::Default;
+        Ok(match &node.value {
+            NodeValue::Null => Value::Empty(tag, Empty::None),
+            NodeValue::Boolean(b) => Value::Bool(tag, *b),
+            NodeValue::Integer(i) => Value::Num(tag, Num::I64(*i)),
+            NodeValue::Real(r) => match parse_float(r) {
+                Some(f) => Value::Num(tag, Num::F64(f)),
+                None => {
+                    return Err(invalid_node(
+                        node,
+                        format!("Invalid real number `{r}`"),
+                        path,
+                    ));
+                }
+            },
+            NodeValue::String(s) => Value::String(tag, s.clone()),
+            NodeValue::Array(items) => Value::Array(
+                tag,
+                items
+                    .iter()
+                    .map(|item| self.convert(item, path))
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            NodeValue::Hash(entries) => Value::Dict(tag, self.convert_map(entries, path)?),
+            NodeValue::BadValue => {
+                return Err(invalid_node(
+                    node,
+                    "Value doesn't match its tag".into(),
+                    path,
+                ));
+            }
+            NodeValue::Invalid(message) => {
+                return Err(invalid_node(node, message.clone(), path));
+            }
+        })
+    }
+}
+
+/// Create the error for a node that can't be converted.
+fn invalid_node(node: &Node, message: String, path: &str) -> Error {
+    let mut err = DeserializeError::new(ErrorCode::InvalidScalar, message).at(node.span);
+    if !path.is_empty() {
+        err = err.in_field(path);
+    }
+    figment_error(err)
+}
+
+/// Report an error of the document to figment, with its location in the message.
+fn figment_error(err: DeserializeError) -> Error {
+    Error::from(err.to_string())
+}
@@ -0,0 +1,312 @@
+//! Compare the shapes of types, to find out which documents one accepts that the other doesn't.
+
+use alloc::{collections::BTreeSet, format, string::String, vec::Vec};
+use facet_core::{
+    Def, Facet, FieldFlags, NumericType, PrimitiveType, Shape, StructType, Type, UserType,
+};
+
+use super::{Value, validate::has_default};
+use crate::attributes::FieldAttributes;
+
+/// Checks that every document that deserializes into `Old` also deserializes into `New`.
+///
+/// This is meant to be asserted in tests, so changes to a configuration type don't break the
+/// documents that were written for its previous version. The shapes are compared the same way
+/// the deserializer reads documents:
+///
+/// - every key of `Old` must be read by a field of `New`, by its name, an alias or the same
+///   `yaml(path = "...")`, since unknown keys are rejected;
+/// - fields of `New` that aren't required by `Old` must have a default, and not be
+///   `yaml(required)`;
+/// - types may only widen, e.g. `u16` to `u32`, integers to floats, `T` to `Option<T>` or
+///   anything to [`Value`].
+///
+/// Types that are neither structs, collections, options nor numbers, booleans or strings have to
+/// stay the same.
+///
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Old {
+///     port: u16,
+///     host: String,
+/// }
+///
+/// #[derive(Facet)]
+/// struct New {
+///     port: u32,
+///     #[facet(yaml(alias = "host"))]
+///     hostname: String,
+///     #[facet(default)]
+///     tls: bool,
+/// }
+///
+/// assert!(facet_yaml::schema::compatible::<Old, New>().is_ok());
+///
+/// let incompatibilities = facet_yaml::schema::compatible::<New, Old>().unwrap_err();
+/// assert_eq!(incompatibilities[0].path(), "port");
+/// ```
+pub fn compatible<'a, 'b, Old: Facet<'a>, New: Facet<'b>>() -> Result<(), Vec<Incompatibility>> {
+    let mut checker = Checker {
+        incompatibilities: Vec::new(),
+        visited: BTreeSet::new(),
+    };
+    checker.check(Old::SHAPE, New::SHAPE, "");
+
+    if checker.incompatibilities.is_empty() {
+        Ok(())
+    } else {
+        Err(checker.incompatibilities)
+    }
+}
+
+/// A difference between two types that makes a document of the old one invalid for the new one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Incompatibility {
+    /// Path to the value, empty for the root.
+    path: String,
+    /// What changed.
+    message: String,
+}
+
+impl Incompatibility {
+    /// Path to the value that changed, such as `servers[].port`, or an empty string for the
+    /// root.
+    ///
+    /// Items of lists are written as `[]` and values of maps as `{}`, since they apply to all of
+    /// them.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Description of what changed, without its path.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl core::fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{} at `{}`", self.message, self.path)
+        }
+    }
+}
+
+impl core::error::Error for Incompatibility {}
+
+/// Compares pairs of shapes, collecting what's incompatible.
+struct Checker {
+    /// What has been found so far.
+    incompatibilities: Vec<Incompatibility>,
+    /// Pairs of shapes that are checked already, so recursive types end.
+    visited: BTreeSet<(usize, usize)>,
+}
+
+impl Checker {
+    fn report(&mut self, path: &str, message: String) {
+        self.incompatibilities.push(Incompatibility {
+            path: path.into(),
+            message,
+        });
+    }
+
+    fn check(&mut self, old: &'static Shape, new: &'static Shape, path: &str) {
+        // Dynamic values take any node
+        if new.is_type::<Value>() || old.id == new.id {
+            return;
+        }
+        if !self.visited.insert((
+            core::ptr::from_ref(old) as usize,
+            core::ptr::from_ref(new) as usize,
+        )) {
+            return;
+        }
+
+        match (old.def, new.def) {
+            (Def::Option(old), Def::Option(new)) => return self.check(old.t(), new.t(), path),
+            (_, Def::Option(new)) => return self.check(old, new.t(), path),
+            (Def::Option(_), _) => {
+                return self.report(path, format!("Null is no longer accepted by {new}"));
+            }
+            (Def::List(old), Def::List(new)) => {
+                return self.check(old.t(), new.t(), &join(path, "[]"));
+            }
+            (Def::Map(old), Def::Map(new)) => {
+                self.check(old.k(), new.k(), path);
+                return self.check(old.v(), new.v(), &join(path, "{}"));
+            }
+            _ => {}
+        }
+
+        if let (Type::User(UserType::Struct(old_sd)), Type::User(UserType::Struct(new_sd))) =
+            (&old.ty, &new.ty)
+        {
+            return self.check_struct(old, old_sd, new, new_sd, path);
+        }
+
+        if let (Def::Scalar, Def::Scalar) = (old.def, new.def) {
+            if let Some(message) = scalar_change(old, new) {
+                self.report(path, message);
+            }
+            return;
+        }
+
+        self.report(path, format!("Type changed from {old} to {new}"));
+    }
+
+    fn check_struct(
+        &mut self,
+        old: &'static Shape,
+        old_sd: &StructType,
+        new: &'static Shape,
+        new_sd: &StructType,
+        path: &str,
+    ) {
+        let (old_fields, new_fields) = match (fields_of(old_sd), fields_of(new_sd)) {
+            (Ok(old_fields), Ok(new_fields)) => (old_fields, new_fields),
+            (Err(message), _) | (_, Err(message)) => return self.report(path, message),
+        };
+        let old_has_default = has_default(old);
+        let new_has_default = has_default(new);
+
+        // Whether each field of the new type is always set by documents of the old type
+        let mut required = alloc::vec![false; new_sd.fields.len()];
+
+        for (old_index, old_field) in old_fields.iter().enumerate() {
+            let old_optional = !old_field.required
+                && (old_has_default
+                    || old_sd.fields[old_index].flags.contains(FieldFlags::DEFAULT));
+
+            let mut matched = None;
+            for key in &old_field.keys {
+                let new_index = new_fields
+                    .iter()
+                    .position(|new_field| new_field.reads(key, old_field.path.is_some()));
+                match new_index {
+                    Some(new_index) => matched = Some(new_index),
+                    None => self.report(
+                        &join(path, key),
+                        format!(
+                            "Field '{key}' was removed, so documents that have it are rejected"
+                        ),
+                    ),
+                }
+            }
+
+            if let Some(new_index) = matched {
+                required[new_index] |= !old_optional;
+                let name = &old_field.keys[0];
+                self.check(
+                    old_sd.fields[old_index].shape(),
+                    new_sd.fields[new_index].shape(),
+                    &join(path, name),
+                );
+            }
+        }
+
+        for (new_index, field) in new_sd.fields.iter().enumerate() {
+            let new_optional = !new_fields[new_index].required
+                && (new_has_default || field.flags.contains(FieldFlags::DEFAULT));
+            if !required[new_index] && !new_optional {
+                self.report(
+                    &join(path, &new_fields[new_index].keys[0]),
+                    format!(
+                        "Field '{}' is required, but documents of the old type may not have it",
+                        field.name
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Where a struct field is read from.
+struct FieldKeys {
+    /// The keys the field is read from, its name or path first and then its aliases.
+    keys: Vec<String>,
+    /// Dotted path of the field, if it's read from a nested path instead of its own key.
+    path: Option<String>,
+    /// Whether documents must have the field, even if it has a default.
+    required: bool,
+}
+
+impl FieldKeys {
+    /// Whether the field reads a key, or a path when `is_path` is set.
+    fn reads(&self, key: &str, is_path: bool) -> bool {
+        match &self.path {
+            Some(path) => is_path && path == key,
+            None => !is_path && self.keys.iter().any(|k| k == key),
+        }
+    }
+}
+
+/// Read where the fields of a struct are read from.
+fn fields_of(sd: &StructType) -> Result<Vec<FieldKeys>, String> {
+    sd.fields
+        .iter()
+        .map(|field| {
+            let attributes = FieldAttributes::of(field)?;
+            Ok(match attributes.path {
+                Some(path) => {
+                    let path = path.join(".");
+                    FieldKeys {
+                        keys: alloc::vec![path.clone()],
+                        path: Some(path),
+                        required: attributes.required,
+                    }
+                }
+                None => FieldKeys {
+                    keys: core::iter::once(field.name.into())
+                        .chain(attributes.aliases)
+                        .collect(),
+                    path: None,
+                    required: attributes.required,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Describe why scalars of the old type may not deserialize into the new one, if they may not.
+fn scalar_change(old: &'static Shape, new: &'static Shape) -> Option<String> {
+    let changed = || Some(format!("Type changed from {old} to {new}"));
+
+    let (
+        Type::Primitive(PrimitiveType::Numeric(old_numeric)),
+        Type::Primitive(PrimitiveType::Numeric(new_numeric)),
+    ) = (old.ty, new.ty)
+    else {
+        return changed();
+    };
+
+    let old_bits = old.layout.sized_layout().unwrap().size() * 8;
+    let new_bits = new.layout.sized_layout().unwrap().size() * 8;
+    let narrowed = || Some(format!("Values of {old} may be out of range for {new}"));
+    match (old_numeric, new_numeric) {
+        (NumericType::Integer { signed: false }, NumericType::Integer { signed: false })
+        | (NumericType::Integer { signed: true }, NumericType::Integer { signed: true }) => {
+            (new_bits < old_bits).then(narrowed).flatten()
+        }
+        (NumericType::Integer { signed: false }, NumericType::Integer { signed: true }) => {
+            (new_bits <= old_bits).then(narrowed).flatten()
+        }
+        (NumericType::Integer { signed: true }, NumericType::Integer { signed: false }) => {
+            narrowed()
+        }
+        (NumericType::Integer { .. } | NumericType::Float, NumericType::Float) => None,
+        (NumericType::Float, NumericType::Integer { .. }) => changed(),
+    }
+}
+
+/// Append a segment to a path, separating fields with dots.
+fn join(path: &str, segment: &str) -> String {
+    if path.is_empty() || segment.starts_with('[') || segment.starts_with('{') {
+        format!("{path}{segment}")
+    } else {
+        format!("{path}.{segment}")
+    }
+}
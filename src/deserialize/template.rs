@@ -0,0 +1,331 @@
+//! Write skeleton documents of types, for users to fill in by hand.
+
+use alloc::{format, string::String, vec::Vec};
+use facet_core::{Def, Facet, FieldFlags, Shape, StructKind, StructType, Type, UserType};
+
+use super::{DeserializeError, validate::has_default};
+use crate::{ErrorCode, attributes::FieldAttributes};
+
+/// Writes a skeleton YAML document of `T`, with every key that documents of it may have, such
+/// as the starting point of a configuration file in onboarding documentation.
+///
+/// The doc comment of each field is written above its key. Fields that documents must have are
+/// left empty, with a `# <REQUIRED: type>` comment, and fields that may be left out are
+/// commented out. Structs are expanded into their fields, the same way for lists of structs
+/// with a single item, and fields with a `#[facet(yaml(path = "..."))]` attribute under the
+/// keys of their path, where sequences also have a single item whatever the index. Other values
+/// are described by their type, along with the variants of enums without data.
+///
+/// Unlike a serialized default value, the document needs no value of `T`, and shows which keys
+/// have to be filled in.
+///
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Server {
+///     /// Address to listen on.
+///     host: String,
+///     #[facet(default)]
+///     port: u16,
+/// }
+///
+/// #[derive(Facet)]
+/// struct Config {
+///     /// Name of the cluster.
+///     name: String,
+///     server: Server,
+///     #[facet(default)]
+///     replicas: Option<u32>,
+/// }
+///
+/// assert_eq!(
+///     facet_yaml::template::<Config>().unwrap(),
+///     "# Name of the cluster.\nname: # <REQUIRED: String>\nserver:\n  # Address to listen on.\n  \
+///      host: # <REQUIRED: String>\n  # port: <u16>\n# replicas: <u32>\n"
+/// );
+/// ```
+pub fn template<'a, T: Facet<'a>>() -> Result<String, DeserializeError> {
+    let mut builder = Builder {
+        expanding: Vec::new(),
+    };
+    let lines = match builder.content(T::SHAPE)? {
+        Content::Placeholder(ty) => alloc::vec![Line::new(format!("# <REQUIRED: {ty}>"))],
+        Content::Mapping(entries) => mapping_lines(&entries),
+        Content::Sequence(entries) => item_lines(&entries),
+    };
+
+    let mut yaml = String::new();
+    for line in lines {
+        for _ in 0..line.indent {
+            yaml.push(' ');
+        }
+        yaml.push_str(&line.text);
+        yaml.push('\n');
+    }
+    Ok(yaml)
+}
+
+/// A key of the template, with what's under it.
+struct Entry {
+    key: String,
+    /// Lines of the doc comment of the field.
+    doc: &'static [&'static str],
+    /// Whether documents may leave the key out.
+    optional: bool,
+    content: Content,
+}
+
+/// What's under a key of the template.
+enum Content {
+    /// A value that isn't expanded, described by its type.
+    Placeholder(String),
+    /// A mapping with the given keys.
+    Mapping(Vec<Entry>),
+    /// A sequence with one item, a mapping with the given keys.
+    Sequence(Vec<Entry>),
+}
+
+/// Builds the entries of a template from the shapes of the types.
+struct Builder {
+    /// Structs that are being expanded, so recursive types end.
+    expanding: Vec<&'static Shape>,
+}
+
+impl Builder {
+    fn content(&mut self, shape: &'static Shape) -> Result<Content, DeserializeError> {
+        let shape = unwrap_shape(shape);
+        if self
+            .expanding
+            .iter()
+            .any(|expanding| expanding.id == shape.id)
+        {
+            return Ok(Content::Placeholder(describe(shape)));
+        }
+
+        if let Type::User(UserType::Struct(sd)) = &shape.ty {
+            if matches!(sd.kind, StructKind::Struct) && !sd.fields.is_empty() {
+                self.expanding.push(shape);
+                let entries = self.fields(shape, sd);
+                self.expanding.pop();
+                return Ok(Content::Mapping(entries?));
+            }
+        }
+
+        if let Def::List(ld) = shape.def {
+            // An item is only shown when it has keys that aren't commented out
+            if let Content::Mapping(entries) = self.content(ld.t())? {
+                if entries.iter().any(|entry| !entry.optional) {
+                    return Ok(Content::Sequence(entries));
+                }
+            }
+        }
+
+        Ok(Content::Placeholder(describe(shape)))
+    }
+
+    /// The content of a map field read from a sequence, whose item has the key of the map
+    /// before the keys of its value, unless the value has a field of that name.
+    fn keyed_content(
+        &mut self,
+        key: &str,
+        shape: &'static Shape,
+    ) -> Result<Content, DeserializeError> {
+        let Def::Map(md) = unwrap_shape(shape).def else {
+            return self.content(shape);
+        };
+        let mut entries = match self.content(md.v())? {
+            Content::Mapping(entries) => entries,
+            _ => Vec::new(),
+        };
+        if !entries.iter().any(|entry| entry.key == key) {
+            let entry = Entry {
+                key: key.into(),
+                doc: &[],
+                optional: false,
+                content: Content::Placeholder(describe(md.k())),
+            };
+            entries.insert(0, entry);
+        }
+        Ok(Content::Sequence(entries))
+    }
+
+    fn fields(
+        &mut self,
+        shape: &'static Shape,
+        sd: &StructType,
+    ) -> Result<Vec<Entry>, DeserializeError> {
+        let has_default = has_default(shape);
+        let mut entries = Vec::new();
+        for field in sd.fields {
+            let attributes = FieldAttributes::of(field)
+                .map_err(|message| DeserializeError::new(ErrorCode::InvalidAttribute, message))?;
+            if attributes.skip_deserializing {
+                continue;
+            }
+
+            let optional =
+                !attributes.required && (has_default || field.flags.contains(FieldFlags::DEFAULT));
+            let (parents, key) = match &attributes.path {
+                Some(path) => (&path[..path.len() - 1], path[path.len() - 1].clone()),
+                None => (&[][..], String::from(field.name)),
+            };
+            // Embedded documents are strings, which aren't expanded
+            let content = if attributes.embedded {
+                let shape = unwrap_shape(field.shape());
+                Content::Placeholder(format!("YAML document of {shape}"))
+            } else if let Some(item_key) = &attributes.key {
+                self.keyed_content(item_key, field.shape())?
+            } else {
+                self.content(field.shape())?
+            };
+            let entry = Entry {
+                key,
+                doc: field.doc,
+                optional,
+                content,
+            };
+
+            // Fields read from a path are under collections that are only required along with
+            // them
+            let mut siblings: &mut Vec<Entry> = &mut entries;
+            let mut parents = parents.iter().peekable();
+            while let Some(parent) = parents.next() {
+                // An index after a key makes its value a sequence, shown with a single item
+                let sequence = parents
+                    .next_if(|segment| segment.parse::<usize>().is_ok())
+                    .is_some();
+                let index = match siblings.iter().position(|entry| {
+                    entry.key == *parent
+                        && match entry.content {
+                            Content::Mapping(_) => !sequence,
+                            Content::Sequence(_) => sequence,
+                            Content::Placeholder(_) => false,
+                        }
+                }) {
+                    Some(index) => index,
+                    None => {
+                        siblings.push(Entry {
+                            key: parent.clone(),
+                            doc: &[],
+                            optional: true,
+                            content: if sequence {
+                                Content::Sequence(Vec::new())
+                            } else {
+                                Content::Mapping(Vec::new())
+                            },
+                        });
+                        siblings.len() - 1
+                    }
+                };
+                let parent = &mut siblings[index];
+                parent.optional &= optional;
+                let (Content::Mapping(children) | Content::Sequence(children)) =
+                    &mut parent.content
+                else {
+                    unreachable!("Parents of paths are collections");
+                };
+                siblings = children;
+            }
+            siblings.push(entry);
+        }
+        Ok(entries)
+    }
+}
+
+/// A line of a template, indented relative to the mapping or sequence it's in.
+struct Line {
+    indent: usize,
+    text: String,
+}
+
+impl Line {
+    fn new(text: String) -> Self {
+        Self { indent: 0, text }
+    }
+}
+
+fn mapping_lines(entries: &[Entry]) -> Vec<Line> {
+    let mut lines = Vec::new();
+    for entry in entries {
+        let mut entry_lines: Vec<Line> = entry
+            .doc
+            .iter()
+            .map(|doc| Line::new(format!("#{doc}")))
+            .collect();
+        let key = &entry.key;
+        let nested = match &entry.content {
+            Content::Placeholder(ty) if entry.optional => {
+                entry_lines.push(Line::new(format!("{key}: <{ty}>")));
+                Vec::new()
+            }
+            Content::Placeholder(ty) => {
+                entry_lines.push(Line::new(format!("{key}: # <REQUIRED: {ty}>")));
+                Vec::new()
+            }
+            Content::Mapping(entries) => {
+                entry_lines.push(Line::new(format!("{key}:")));
+                mapping_lines(entries)
+            }
+            Content::Sequence(entries) => {
+                entry_lines.push(Line::new(format!("{key}:")));
+                item_lines(entries)
+            }
+        };
+        entry_lines.extend(nested.into_iter().map(|line| Line {
+            indent: line.indent + 2,
+            text: line.text,
+        }));
+
+        if entry.optional {
+            // The entry is commented out below its doc comment, keeping the indentation of
+            // what's in it
+            for line in &mut entry_lines[entry.doc.len()..] {
+                line.text = format!("# {:indent$}{}", "", line.text, indent = line.indent);
+                line.indent = 0;
+            }
+        }
+        lines.extend(entry_lines);
+    }
+    lines
+}
+
+/// The lines of a sequence with a single mapping item, whose first required key is after the
+/// `- `.
+fn item_lines(entries: &[Entry]) -> Vec<Line> {
+    let mut lines = mapping_lines(entries);
+    // Comments before the key stay in line with the `- `
+    let first = lines
+        .iter()
+        .position(|line| line.indent == 0 && !line.text.starts_with('#'))
+        .unwrap_or(0);
+    lines[first].text.insert_str(0, "- ");
+    for line in &mut lines[first + 1..] {
+        line.indent += 2;
+    }
+    lines
+}
+
+/// Describe the values of a type that isn't expanded.
+fn describe(shape: &'static Shape) -> String {
+    if let Type::User(UserType::Enum(ed)) = &shape.ty {
+        if ed
+            .variants
+            .iter()
+            .all(|variant| matches!(variant.data.kind, StructKind::Unit))
+        {
+            let names: Vec<_> = ed.variants.iter().map(|variant| variant.name).collect();
+            return format!("{shape}, one of {}", names.join(", "));
+        }
+    }
+    format!("{shape}")
+}
+
+/// Look through options and pointers, since documents have the value itself.
+fn unwrap_shape(shape: &'static Shape) -> &'static Shape {
+    match shape.def {
+        Def::Option(od) => unwrap_shape(od.t()),
+        Def::Pointer(pd) => pd.pointee().map_or(shape, unwrap_shape),
+        _ => shape,
+    }
+}
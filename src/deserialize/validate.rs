@@ -0,0 +1,392 @@
+//! Check that YAML documents bind to a type, without building values.
+
+use alloc::{format, string::String, vec, vec::Vec};
+use facet_core::{
+    Def, Facet, FieldFlags, NumericType, PrimitiveType, Shape, StructKind, StructType, Type,
+    UserType,
+};
+use facet_reflect::Partial;
+
+use super::{
+    DeserializeError, DeserializeOptions, Deserializer, FieldCache, StructFields, Value,
+    array_items, complex_key_error, decode_binary, exclusive_conflicts, find_path, is_bytes,
+    is_newtype, is_option, keyed_mapping,
+    node::{self, Node, NodeValue},
+    set_empty_document, yaml_type,
+};
+use crate::ErrorCode;
+
+/// Checks that a YAML string would deserialize into a value of type `T`, reporting every
+/// problem instead of only the first one.
+///
+/// The document is compared with the shape of `T` without building the value, so strings,
+/// lists and maps aren't allocated. Scalars that are parsed from strings and other types that
+/// can only be checked by building them are deserialized on their own and dropped. This is meant
+/// for linting many files quickly, for example in CI.
+///
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Server {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// let errors = facet_yaml::validate::<Vec<Server>>(
+///     "- {host: a, port: 70000}\n- {host: b, port: 80, tls: true}\n",
+/// )
+/// .unwrap_err();
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(errors[0].path(), "[0].port");
+/// assert_eq!(errors[1].path(), "[1]");
+/// ```
+pub fn validate<'facet, T: Facet<'facet>>(yaml: &str) -> Result<(), Vec<DeserializeError>> {
+    // The parser would otherwise treat a byte-order mark as part of the first scalar
+    let yaml = yaml.strip_prefix('\u{feff}').unwrap_or(yaml);
+
+    let options = DeserializeOptions::default();
+    let docs = node::load(yaml, &options).map_err(|e| vec![e.with_source(yaml)])?;
+    let errors = match docs.as_slice() {
+        [doc] => {
+            let fields = FieldCache::default();
+            let validator = Validator {
+                deserializer: Deserializer::new(&options, &fields),
+            };
+            validator.check_value(T::SHAPE, doc)
+        }
+        // Only comments, directives or whitespace
+        [] => Partial::alloc_shape(T::SHAPE)
+            .map_err(DeserializeError::from)
+            .and_then(|mut partial| set_empty_document(&mut partial))
+            .err()
+            .into_iter()
+            .collect(),
+        _ => vec![DeserializeError::new(
+            ErrorCode::DocumentCount,
+            "Expected exactly one YAML document",
+        )],
+    };
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.into_iter().map(|e| e.with_source(yaml)).collect())
+    }
+}
+
+/// Compares YAML nodes with shapes.
+struct Validator<'opts> {
+    /// Used for the types that can't be checked without building them.
+    deserializer: Deserializer<'opts>,
+}
+
+impl Validator<'_> {
+    /// Check a node, locating the errors at the innermost node that caused them.
+    fn check_value(&self, shape: &'static Shape, value: &Node) -> Vec<DeserializeError> {
+        self.check_node(shape, value)
+            .into_iter()
+            .map(|e| e.located(value.span, shape))
+            .collect()
+    }
+
+    /// Check the value of a field of a struct, with the naming convention the field sets for the
+    /// structs in it, if any, the document in it for `#[facet(yaml(embedded))]` fields, or the
+    /// items of the sequence for `#[facet(yaml(key = "..."))]` fields.
+    fn check_field(
+        &self,
+        fields: &StructFields,
+        sd: &StructType,
+        index: usize,
+        value: &Node,
+    ) -> Vec<DeserializeError> {
+        let field_shape = sd.fields[index].shape();
+        self.deserializer
+            .in_field(fields, index, || {
+                if let Some(key) = fields.keyed(index) {
+                    let value = keyed_mapping(key, field_shape, value)?;
+                    return Ok(self.check_value(field_shape, &value));
+                }
+                if !fields.embedded(index) {
+                    return Ok(self.check_value(field_shape, value));
+                }
+                // Documents embedded in strings are checked like the documents they're in
+                let errors = match self.deserializer.embedded_document(value)? {
+                    Some(doc) => self.check_value(field_shape, &doc),
+                    None => Partial::alloc_shape(field_shape)
+                        .map_err(DeserializeError::from)
+                        .and_then(|mut partial| set_empty_document(&mut partial))
+                        .err()
+                        .into_iter()
+                        .collect(),
+                };
+                Ok(errors.into_iter().map(|e| e.at(value.span)).collect())
+            })
+            .unwrap_or_else(|err| vec![err])
+    }
+
+    fn check_node(&self, shape: &'static Shape, value: &Node) -> Vec<DeserializeError> {
+        // Cron expressions and transparent types only make sense once they're built
+        #[cfg(feature = "cron")]
+        if shape.is_type::<crate::CronExpr>() {
+            return self.check_by_building(shape, value);
+        }
+        if shape
+            .attributes
+            .contains(&facet_core::ShapeAttribute::Transparent)
+        {
+            return self.check_by_building(shape, value);
+        }
+        // Dynamic values take any node
+        if shape.is_type::<Value>() {
+            return Vec::new();
+        }
+
+        if let Type::User(UserType::Struct(sd)) = &shape.ty {
+            // Tuples are sequences, or their only field
+            let is_tuple = matches!(sd.kind, StructKind::Tuple | StructKind::TupleStruct);
+            if is_newtype(sd) || (is_tuple && !matches!(value.value, NodeValue::Hash(_))) {
+                return self.check_by_building(shape, value);
+            }
+
+            // Unit structs are written as null
+            if value.value == NodeValue::Null && matches!(sd.kind, StructKind::Unit) {
+                return Vec::new();
+            }
+            let NodeValue::Hash(hash) = &value.value else {
+                return vec![DeserializeError::new(
+                    ErrorCode::TypeMismatch,
+                    format!("Expected a YAML hash, got: {}", yaml_type(value)),
+                )];
+            };
+
+            let cache = self.deserializer.fields;
+            let fields = match cache.fields(shape, sd) {
+                Ok(fields) => fields,
+                Err(err) => return vec![err],
+            };
+            let paths = fields.paths();
+            let skipped = fields.skipped();
+            let required = fields.required();
+            let exclusive = fields.exclusive();
+
+            let mut errors = Vec::new();
+            let mut set = vec![false; sd.fields.len()];
+            let mut keys = vec![None; sd.fields.len()];
+            for (key, v) in hash {
+                let Some(k) = key.as_str() else {
+                    errors.push(
+                        DeserializeError::new(
+                            ErrorCode::InvalidKey,
+                            format!("Expected string key, got: {}", yaml_type(key)),
+                        )
+                        .located(key.span, shape),
+                    );
+                    continue;
+                };
+                let index = cache
+                    .field_index(shape, &fields, k, self.deserializer.rename_all.get(), |k| {
+                        self.deserializer.find_field(sd, k)
+                    })
+                    .filter(|index| !paths.iter().any(|(i, _)| i == index));
+                match index {
+                    Some(index) if skipped.contains(&index) => {}
+                    Some(index) => {
+                        set[index] = true;
+                        keys[index] = Some((String::from(k), key.span));
+                        errors.extend(
+                            self.check_field(&fields, sd, index, v)
+                                .into_iter()
+                                .map(|e| e.in_field(k)),
+                        );
+                    }
+                    // Checked below, along with the other paths
+                    None if paths.iter().any(|(_, path)| path[0] == k) => {}
+                    None => errors.push(
+                        DeserializeError::new(
+                            ErrorCode::UnknownField,
+                            format!("Field '{k}' not found"),
+                        )
+                        .located(key.span, shape),
+                    ),
+                }
+            }
+
+            for (index, path) in paths {
+                let Some(v) = find_path(value, path) else {
+                    continue;
+                };
+                set[*index] = true;
+                let path = path.join(".");
+                errors.extend(
+                    self.check_field(&fields, sd, *index, v)
+                        .into_iter()
+                        .map(|e| e.in_field(&path)),
+                );
+                keys[*index] = Some((path, v.span));
+            }
+            errors.extend(exclusive_conflicts(exclusive, &keys, shape));
+
+            // Missing fields are taken from the default of the struct, if it has one, or are
+            // `None`, unless they're required
+            let struct_default = has_default(shape);
+            let missing = sd.fields.iter().enumerate().filter(|(index, field)| {
+                !set[*index]
+                    && (required.contains(index)
+                        || !(struct_default
+                            || field.flags.contains(FieldFlags::DEFAULT)
+                            || is_option(field.shape())))
+            });
+            errors.extend(missing.map(|(_, field)| {
+                DeserializeError::new(
+                    ErrorCode::MissingField,
+                    format!("Missing field '{}'", field.name),
+                )
+            }));
+
+            return errors;
+        }
+
+        match shape.def {
+            Def::Scalar => match shape.ty {
+                Type::Primitive(PrimitiveType::Numeric(numeric_type)) => self
+                    .check_number(shape, numeric_type, value)
+                    .err()
+                    .into_iter()
+                    .collect(),
+                _ if shape.is_type::<bool>() => match value.value {
+                    NodeValue::Boolean(_) | NodeValue::Integer(_) | NodeValue::String(_) => {
+                        Vec::new()
+                    }
+                    _ => vec![DeserializeError::new(
+                        ErrorCode::TypeMismatch,
+                        format!("Cannot convert {} to bool", yaml_type(value)),
+                    )],
+                },
+                _ if shape.is_type::<String>() => match value.as_str() {
+                    Some(_) => Vec::new(),
+                    None => vec![DeserializeError::new(
+                        ErrorCode::TypeMismatch,
+                        format!("Expected string, got: {}", yaml_type(value)),
+                    )],
+                },
+                _ => self.check_by_building(shape, value),
+            },
+            Def::List(ld) => {
+                if let Some(text) = self
+                    .deserializer
+                    .base64_text(value)
+                    .filter(|_| is_bytes(shape))
+                {
+                    return decode_binary(text, value.binary().is_some())
+                        .err()
+                        .into_iter()
+                        .collect();
+                }
+                let NodeValue::Array(items) = &value.value else {
+                    return vec![DeserializeError::new(
+                        ErrorCode::TypeMismatch,
+                        format!("Expected a YAML array, got: {}", yaml_type(value)),
+                    )];
+                };
+                items
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(index, item)| {
+                        self.check_value(ld.t(), item)
+                            .into_iter()
+                            .map(move |e| e.in_index(index))
+                    })
+                    .collect()
+            }
+            Def::Array(ad) => match array_items(shape, ad.n, value) {
+                Ok(items) => items
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(index, item)| {
+                        self.check_value(ad.t(), item)
+                            .into_iter()
+                            .map(move |e| e.in_index(index))
+                    })
+                    .collect(),
+                Err(err) => vec![err],
+            },
+            Def::Map(md) => {
+                let NodeValue::Hash(hash) = &value.value else {
+                    return vec![DeserializeError::new(
+                        ErrorCode::TypeMismatch,
+                        format!("Expected a YAML hash/map, got: {}", yaml_type(value)),
+                    )];
+                };
+                hash.iter()
+                    .flat_map(|(k, v)| {
+                        let key_name = node::key_segment(k);
+                        let key_errors = match complex_key_error(md.k(), k) {
+                            Some(err) => vec![err],
+                            None => self.check_value(md.k(), k),
+                        };
+                        key_errors
+                            .into_iter()
+                            .chain(self.check_value(md.v(), v))
+                            .map(move |e| e.in_field(&key_name))
+                    })
+                    .collect()
+            }
+            Def::Option(od) => match value.value {
+                NodeValue::Null => Vec::new(),
+                _ => self.check_value(od.t(), value),
+            },
+            _ => self.check_by_building(shape, value),
+        }
+    }
+
+    /// Check that a number fits its type, the same way it's converted when deserializing.
+    fn check_number(
+        &self,
+        shape: &'static Shape,
+        numeric_type: NumericType,
+        value: &Node,
+    ) -> Result<(), DeserializeError> {
+        let bits = shape.layout.sized_layout().unwrap().size() * 8;
+        match numeric_type {
+            NumericType::Integer { signed: false } => {
+                let u = self.deserializer.yaml_to_u64(value)?;
+                if bits < 64 && u >> bits != 0 {
+                    return Err(DeserializeError::new(
+                        ErrorCode::OutOfRange,
+                        format!("Value {u} out of range for {shape}"),
+                    ));
+                }
+            }
+            NumericType::Integer { signed: true } => {
+                let i = self.deserializer.yaml_to_i64(value)?;
+                if bits < 64 && (i < -(1 << (bits - 1)) || i >= 1 << (bits - 1)) {
+                    return Err(DeserializeError::new(
+                        ErrorCode::OutOfRange,
+                        format!("Value {i} out of range for {shape}"),
+                    ));
+                }
+            }
+            NumericType::Float if bits == 32 => {
+                self.deserializer.yaml_to_float::<f32>(value)?;
+            }
+            NumericType::Float => {
+                self.deserializer.yaml_to_float::<f64>(value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check a node by deserializing it into a value that is dropped right away.
+    fn check_by_building(&self, shape: &'static Shape, value: &Node) -> Vec<DeserializeError> {
+        let result = Partial::alloc_shape(shape)
+            .map_err(DeserializeError::from)
+            .and_then(|mut partial| self.deserializer.deserialize_node(&mut partial, value));
+        result.err().into_iter().collect()
+    }
+}
+
+/// Whether a type has a default value, which missing struct fields can be taken from.
+pub(crate) fn has_default(shape: &'static Shape) -> bool {
+    Partial::alloc_shape(shape).is_ok_and(|mut partial| partial.set_default().is_ok())
+}
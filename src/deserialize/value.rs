@@ -0,0 +1,550 @@
+//! Dynamically typed YAML values.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::cmp::Ordering;
+use facet::Facet;
+#[cfg(feature = "serialize")]
+use yaml_rust2::Yaml;
+
+use super::{
+    DeserializeError, Number, Span,
+    node::{self, CORE_TAG_HANDLE, Node, NodeValue},
+};
+use crate::ErrorCode;
+
+/// Load a YAML document into a [`Value`], keeping as much of it as possible when it has errors.
+///
+/// This is meant for tools like linters, which want to report every problem in a document
+/// instead of stopping at the first one. Nodes that couldn't be loaded are replaced with
+/// [`Value::Invalid`], and [`Value::errors`] lists all of them:
+///
+/// - a duplicate key keeps the first value, and the value of the duplicate is invalid;
+/// - a scalar that doesn't match its tag, such as `!!int ten`, is invalid;
+/// - a syntax error stops the parser, so the node where it happened is invalid and everything
+///   after it is missing.
+///
+/// ```
+/// use facet_yaml::Value;
+///
+/// let value = facet_yaml::from_str_recovering("name: server\nport: !!int eighty\n");
+/// assert_eq!(value.get("name").and_then(Value::as_str), Some("server"));
+///
+/// let errors = value.errors();
+/// assert_eq!(errors.len(), 1);
+/// assert_eq!(errors[0].path(), "port");
+/// ```
+pub fn from_str_recovering(yaml: &str) -> Value {
+    let yaml = yaml.strip_prefix('\u{feff}').unwrap_or(yaml);
+
+    let mut documents = node::load_recovering(yaml).into_iter();
+    match (documents.next(), documents.next()) {
+        (None, _) => Value::Null,
+        (Some(document), None) => Value::from_node(document),
+        // The second document is the error when the first one couldn't be ended
+        (
+            Some(_),
+            Some(Node {
+                value: NodeValue::Invalid(message),
+                span,
+                ..
+            }),
+        ) => Value::Invalid(span, message),
+        (Some(_), Some(extra)) => {
+            Value::Invalid(extra.span, "Expected exactly one YAML document".into())
+        }
+    }
+}
+
+/// Any YAML value.
+///
+/// A field of this type accepts any node, so it can hold the parts of a document that don't
+/// have a fixed structure.
+#[derive(Debug, Clone, PartialEq, Facet)]
+#[repr(u8)]
+pub enum Value {
+    /// Null, e.g. `~`, `null` or a missing value.
+    Null,
+    /// Boolean.
+    Bool(bool),
+    /// Integer or floating point number.
+    Number(Number),
+    /// String.
+    String(String),
+    /// Sequence of values.
+    Sequence(#[facet(recursive_type)] Vec<Value>),
+    /// Mapping, in document order.
+    Map(#[facet(recursive_type)] Map),
+    /// A node that couldn't be loaded, along with where it is and why.
+    ///
+    /// Only produced by [`from_str_recovering`].
+    Invalid(Span, String),
+}
+
+impl Value {
+    /// Whether the value is null.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    /// Get the value as a boolean, if it is one.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Get the value as a number, if it is one.
+    pub fn as_number(&self) -> Option<&Number> {
+        match self {
+            Value::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// Get the value as an `i64`, if it's an integer that fits in it.
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number()?.as_i64()
+    }
+
+    /// Get the value as a `u64`, if it's an integer that fits in it.
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number()?.as_u64()
+    }
+
+    /// Get the value as a float, if it is a number.
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number()?.as_f64()
+    }
+
+    /// Get the value as a string slice, if it is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Get the value as a sequence, if it is one.
+    pub fn as_sequence(&self) -> Option<&[Value]> {
+        match self {
+            Value::Sequence(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Get the value as a mapping, if it is one.
+    pub fn as_map(&self) -> Option<&Map> {
+        match self {
+            Value::Map(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Get the value of a string key, if this is a mapping that contains it.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_map()?.get(key)
+    }
+
+    /// Whether the value is invalid.
+    pub fn is_invalid(&self) -> bool {
+        matches!(self, Value::Invalid(..))
+    }
+
+    /// All the invalid nodes in this value, in document order, with their paths.
+    pub fn errors(&self) -> Vec<DeserializeError> {
+        match self {
+            Value::Invalid(span, message) => {
+                vec![DeserializeError::new(ErrorCode::InvalidNode, message.as_str()).at(*span)]
+            }
+            Value::Sequence(values) => values
+                .iter()
+                .enumerate()
+                .flat_map(|(index, value)| {
+                    value
+                        .errors()
+                        .into_iter()
+                        .map(move |err| err.in_index(index))
+                })
+                .collect(),
+            Value::Map(map) => map
+                .iter()
+                .flat_map(|(key, value)| {
+                    let name = key_name(key);
+                    key.errors().into_iter().chain(
+                        value
+                            .errors()
+                            .into_iter()
+                            .map(move |err| err.in_field(&name)),
+                    )
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Convert a loaded node.
+    pub(crate) fn from_node(node: Node) -> Self {
+        match node.value {
+            NodeValue::Null => Value::Null,
+            NodeValue::Boolean(b) => Value::Bool(b),
+            NodeValue::Integer(i) => Value::Number(i.into()),
+            NodeValue::Real(r) => match Number::from_real(&r) {
+                Some(n) => Value::Number(n),
+                None => Value::Invalid(node.span, format!("Invalid real number `{r}`")),
+            },
+            NodeValue::String(s) => Value::String(s),
+            NodeValue::Array(items) => {
+                Value::Sequence(items.into_iter().map(Value::from_node).collect())
+            }
+            NodeValue::Hash(entries) => Value::Map(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (Value::from_node(k), Value::from_node(v)))
+                    .collect(),
+            ),
+            NodeValue::BadValue => {
+                let tag = match &node.tag {
+                    Some(tag) if tag.handle == CORE_TAG_HANDLE => format!("!!{}", tag.suffix),
+                    Some(tag) => format!("{}{}", tag.handle, tag.suffix),
+                    None => "its tag".to_string(),
+                };
+                Value::Invalid(node.span, format!("Value doesn't match {tag}"))
+            }
+            NodeValue::Invalid(message) => Value::Invalid(node.span, message),
+        }
+    }
+
+    /// Copy into a node without a location, to deserialize it the way a document with the same
+    /// content is.
+    pub(crate) fn to_node(&self) -> Node {
+        let mut span = Span {
+            offset: 0,
+            line: 0,
+            column: 0,
+        };
+        let value = match self {
+            Value::Null => NodeValue::Null,
+            Value::Bool(b) => NodeValue::Boolean(*b),
+            Value::Number(n) => match n.as_i64() {
+                Some(i) => NodeValue::Integer(i),
+                None => NodeValue::Real(n.raw().into()),
+            },
+            Value::String(s) => NodeValue::String(s.clone()),
+            Value::Sequence(values) => {
+                NodeValue::Array(values.iter().map(Value::to_node).collect())
+            }
+            Value::Map(map) => NodeValue::Hash(
+                map.iter()
+                    .map(|(k, v)| (k.to_node(), v.to_node()))
+                    .collect(),
+            ),
+            Value::Invalid(at, message) => {
+                span = *at;
+                NodeValue::Invalid(message.clone())
+            }
+        };
+        Node {
+            value,
+            span,
+            tag: None,
+        }
+    }
+
+    /// Convert to a node without a location, to deserialize it the way a document with the
+    /// same content is.
+    #[cfg(feature = "serde-bridge")]
+    pub(crate) fn into_node(self) -> Node {
+        let mut span = Span {
+            offset: 0,
+            line: 0,
+            column: 0,
+        };
+        let value = match self {
+            Value::Null => NodeValue::Null,
+            Value::Bool(b) => NodeValue::Boolean(b),
+            Value::Number(n) => match n.as_i64() {
+                Some(i) => NodeValue::Integer(i),
+                None => NodeValue::Real(n.raw().into()),
+            },
+            Value::String(s) => NodeValue::String(s),
+            Value::Sequence(values) => {
+                NodeValue::Array(values.into_iter().map(Value::into_node).collect())
+            }
+            Value::Map(map) => NodeValue::Hash(
+                map.into_iter()
+                    .map(|(k, v)| (k.into_node(), v.into_node()))
+                    .collect(),
+            ),
+            Value::Invalid(at, message) => {
+                span = at;
+                NodeValue::Invalid(message)
+            }
+        };
+        Node {
+            value,
+            span,
+            tag: None,
+        }
+    }
+
+    /// Convert a node built by the serializer, which has no aliases.
+    #[cfg(feature = "serde-bridge")]
+    pub(crate) fn from_yaml(yaml: Yaml) -> Self {
+        match yaml {
+            Yaml::Real(r) => match Number::from_real(&r) {
+                Some(n) => Value::Number(n),
+                None => Value::String(r),
+            },
+            Yaml::Integer(i) => Value::Number(i.into()),
+            Yaml::String(s) => Value::String(s),
+            Yaml::Boolean(b) => Value::Bool(b),
+            Yaml::Array(items) => {
+                Value::Sequence(items.into_iter().map(Value::from_yaml).collect())
+            }
+            Yaml::Hash(entries) => Value::Map(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (Value::from_yaml(k), Value::from_yaml(v)))
+                    .collect(),
+            ),
+            Yaml::Null | Yaml::Alias(_) | Yaml::BadValue => Value::Null,
+        }
+    }
+
+    /// Convert to a node to serialize it, which fails for invalid nodes.
+    #[cfg(feature = "serialize")]
+    pub(crate) fn to_yaml(&self, non_finite_floats: bool) -> Result<Yaml, crate::YamlSerError> {
+        Ok(match self {
+            Value::Null => Yaml::Null,
+            Value::Bool(b) => Yaml::Boolean(*b),
+            Value::Number(n) => {
+                let float = n.as_f64().unwrap_or_default();
+                if let Some(i) = n.as_i64() {
+                    Yaml::Integer(i)
+                } else if float.is_finite() {
+                    // Written as it was read, so that no precision is lost
+                    Yaml::Real(n.raw().into())
+                } else {
+                    crate::serialize::tree::real(float, non_finite_floats)?
+                }
+            }
+            Value::String(s) => Yaml::String(s.clone()),
+            Value::Sequence(values) => Yaml::Array(
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(index, value)| {
+                        value
+                            .to_yaml(non_finite_floats)
+                            .map_err(|e| e.in_index(index))
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            Value::Map(map) => Yaml::Hash(
+                map.iter()
+                    .map(|(k, v)| {
+                        let value = v
+                            .to_yaml(non_finite_floats)
+                            .map_err(|e| e.in_field(k.as_str().unwrap_or("?")))?;
+                        Ok((k.to_yaml(non_finite_floats)?, value))
+                    })
+                    .collect::<Result<_, crate::YamlSerError>>()?,
+            ),
+            Value::Invalid(..) => {
+                return Err(crate::YamlSerError::UnsupportedType {
+                    type_name: "Value::Invalid".into(),
+                });
+            }
+        })
+    }
+}
+
+/// Total order of values, used to sort keys.
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    /// Rank of each kind of value, numbers sharing one.
+    fn rank(value: &Value) -> u8 {
+        match value {
+            Value::Null => 0,
+            Value::Bool(_) => 1,
+            Value::Number(_) => 2,
+            Value::String(_) => 3,
+            Value::Sequence(_) => 4,
+            Value::Map(_) => 5,
+            Value::Invalid(..) => 6,
+        }
+    }
+
+    match (a, b) {
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (Value::Number(a), Value::Number(b)) => a.compare(b),
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Sequence(a), Value::Sequence(b)) => a
+            .iter()
+            .zip(b)
+            .map(|(a, b)| compare_values(a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len())),
+        (Value::Map(a), Value::Map(b)) => a
+            .iter()
+            .zip(b.iter())
+            .map(|((ak, av), (bk, bv))| compare_values(ak, bk).then_with(|| compare_values(av, bv)))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len())),
+        (Value::Invalid(a, _), Value::Invalid(b, _)) => a.offset.cmp(&b.offset),
+        (a, b) => rank(a).cmp(&rank(b)),
+    }
+}
+
+/// Name of a key in the path of an error.
+fn key_name(key: &Value) -> String {
+    match key {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.raw().into(),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => "~".into(),
+        key => format!("{key:?}"),
+    }
+}
+
+/// A YAML mapping, which keeps its entries in document order.
+///
+/// Entries stay in the order they were loaded or inserted until they're explicitly sorted, so
+/// a document can be read, edited and written back without reordering it. Keys are compared
+/// structurally, since any value can be a key, so looking up a key is linear in the number of
+/// entries.
+///
+/// ```
+/// use facet_yaml::Value;
+///
+/// let mut value: Value = facet_yaml::from_str("zeta: 1\nalpha: 2\nmid: 3").unwrap();
+/// let Value::Map(map) = &mut value else { unreachable!() };
+///
+/// assert_eq!(map.get_index(0).and_then(|(key, _)| key.as_str()), Some("zeta"));
+/// map.sort_keys();
+/// let keys: Vec<_> = map.keys().filter_map(Value::as_str).collect();
+/// assert_eq!(keys, ["alpha", "mid", "zeta"]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Facet)]
+pub struct Map {
+    /// Entries in document order.
+    #[facet(recursive_type)]
+    entries: Vec<(Value, Value)>,
+}
+
+impl Map {
+    /// Create an empty mapping.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the value of a string key.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k.as_str() == Some(key))
+            .map(|(_, v)| v)
+    }
+
+    /// Get the value of a string key for modification.
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.entries
+            .iter_mut()
+            .find(|(k, _)| k.as_str() == Some(key))
+            .map(|(_, v)| v)
+    }
+
+    /// Get the entry at a position, in document order.
+    pub fn get_index(&self, index: usize) -> Option<(&Value, &Value)> {
+        self.entries.get(index).map(|(k, v)| (k, v))
+    }
+
+    /// Get the position of a string key, in document order.
+    pub fn index_of(&self, key: &str) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|(k, _)| k.as_str() == Some(key))
+    }
+
+    /// Insert an entry, returning the previous value of the key if it was already present.
+    ///
+    /// A new key is added at the end, an existing key keeps its position.
+    pub fn insert(&mut self, key: Value, value: Value) -> Option<Value> {
+        match self.entries.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => Some(core::mem::replace(existing, value)),
+            None => {
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// Remove a string key, returning its value. The other entries keep their order.
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let index = self.index_of(key)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    /// Sort the entries by key.
+    ///
+    /// Keys of different kinds are ordered null, booleans, numbers, strings, sequences,
+    /// mappings, then invalid nodes, and keys of the same kind by their value. Numbers are
+    /// compared by value whether they're integers or floats. The sort is stable, so duplicate
+    /// keys keep their order.
+    pub fn sort_keys(&mut self) {
+        self.sort_keys_by(compare_values);
+    }
+
+    /// Sort the entries by key with a comparison function. The sort is stable.
+    pub fn sort_keys_by(&mut self, mut compare: impl FnMut(&Value, &Value) -> Ordering) {
+        self.entries.sort_by(|(a, _), (b, _)| compare(a, b));
+    }
+
+    /// Iterate over the entries in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Value, &Value)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    /// Iterate over the keys in order.
+    pub fn keys(&self) -> impl Iterator<Item = &Value> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    /// Iterate over the values in order.
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+}
+
+impl FromIterator<(Value, Value)> for Map {
+    /// Collect entries in order. Duplicate keys are kept, since invalid documents can have them.
+    fn from_iter<I: IntoIterator<Item = (Value, Value)>>(iter: I) -> Self {
+        Self {
+            entries: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl IntoIterator for Map {
+    type Item = (Value, Value);
+    type IntoIter = alloc::vec::IntoIter<(Value, Value)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
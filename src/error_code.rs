@@ -0,0 +1,137 @@
+//! Stable identifiers of the ways deserializing and serializing can fail.
+
+/// Identifier of a kind of error, which stays the same across versions of the crate even when
+/// the message changes.
+///
+/// Tests can match on it instead of on the message, and its string form, such as `E001`, can be
+/// looked up in the documentation below. Codes are never reused for another kind of error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// `E000`: an error from a custom message, e.g. one returned by a pipeline transform.
+    Custom,
+    /// `E001`: a mapping has a key that isn't a field of the struct.
+    UnknownField,
+    /// `E002`: a required field of a struct isn't in the document.
+    MissingField,
+    /// `E003`: a node has the wrong kind for the type, e.g. a sequence for a string.
+    TypeMismatch,
+    /// `E004`: a number doesn't fit the integer type it's read into.
+    OutOfRange,
+    /// `E005`: a scalar can't be parsed as the type it's read into, e.g. a cron expression with
+    /// a typo, or has a tag that doesn't match its content.
+    InvalidScalar,
+    /// `E006`: the document isn't valid YAML.
+    Syntax,
+    /// `E007`: the stream has more than one document where one is expected.
+    DocumentCount,
+    /// `E008`: the document is empty and the type has no default value.
+    NoDefault,
+    /// `E009`: a mapping key isn't a string where struct fields are read.
+    InvalidKey,
+    /// `E010`: a `#[facet(yaml(...))]` attribute is invalid.
+    InvalidAttribute,
+    /// `E011`: an alias refers to an anchor that isn't defined before it.
+    UnknownAnchor,
+    /// `E012`: a projection path is invalid.
+    InvalidPath,
+    /// `E013`: the input isn't valid UTF-8.
+    InvalidUtf8,
+    /// `E014`: collections are nested deeper than
+    /// [`max_depth`](crate::DeserializeOptions::max_depth) allows.
+    DepthLimit,
+    /// `E015`: the type can't be deserialized from YAML.
+    Unsupported,
+    /// `E016`: the value couldn't be built, as reported by `facet-reflect`.
+    Reflect,
+    /// `E017`: a node of a recovered document couldn't be read, see
+    /// [`Value::errors`](crate::Value::errors).
+    InvalidNode,
+    /// `E018`: fields that exclude each other, such as a password and a password file, are both
+    /// in the document.
+    ConflictingFields,
+    /// `E019`: a string or mapping key isn't the name of a variant of the enum it's read into.
+    UnknownVariant,
+    /// `E020`: an item of a keyed list has the same key as an earlier item.
+    DuplicateItem,
+    /// `E021`: the aliases of a document expand to more nodes than
+    /// [`max_alias_nodes`](crate::DeserializeOptions::max_alias_nodes) allows.
+    AliasLimit,
+    /// `E022`: a pipeline transform produced events that don't form a valid document.
+    Transform,
+    /// `E101`: a number can't be written as a YAML integer.
+    NumberConversion,
+    /// `E102`: a value can't be written as a YAML mapping key.
+    KeyConversion,
+    /// `E103`: byte arrays can't be written as YAML.
+    UnsupportedByteArray,
+    /// `E104`: the type can't be written as YAML.
+    UnsupportedType,
+    /// `E105`: the destination of the document returned an error.
+    Write,
+    /// `E106`: the value contains itself, through shared pointers such as `Rc`.
+    Cycle,
+    /// `E107`: a float is infinite or NaN, and
+    /// [`non_finite_floats`](crate::SerializeOptions::non_finite_floats) is disabled.
+    NonFiniteFloat,
+    /// `E108`: the caller stopped the serialization, with the progress callback of
+    /// [`to_writer_with_progress`](crate::to_writer_with_progress), or the deserialization, with
+    /// [`cancel_if`](crate::DeserializeOptions::cancel_if).
+    Cancelled,
+    /// `E109`: the previous version of a document given to
+    /// [`to_string_over`](crate::to_string_over) isn't valid YAML.
+    InvalidPrevious,
+}
+
+impl ErrorCode {
+    /// The identifier of the code, such as `E001`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Custom => "E000",
+            Self::UnknownField => "E001",
+            Self::MissingField => "E002",
+            Self::TypeMismatch => "E003",
+            Self::OutOfRange => "E004",
+            Self::InvalidScalar => "E005",
+            Self::Syntax => "E006",
+            Self::DocumentCount => "E007",
+            Self::NoDefault => "E008",
+            Self::InvalidKey => "E009",
+            Self::InvalidAttribute => "E010",
+            Self::UnknownAnchor => "E011",
+            Self::InvalidPath => "E012",
+            Self::InvalidUtf8 => "E013",
+            Self::DepthLimit => "E014",
+            Self::Unsupported => "E015",
+            Self::Reflect => "E016",
+            Self::InvalidNode => "E017",
+            Self::ConflictingFields => "E018",
+            Self::UnknownVariant => "E019",
+            Self::DuplicateItem => "E020",
+            Self::AliasLimit => "E021",
+            Self::Transform => "E022",
+            Self::NumberConversion => "E101",
+            Self::KeyConversion => "E102",
+            Self::UnsupportedByteArray => "E103",
+            Self::UnsupportedType => "E104",
+            Self::Write => "E105",
+            Self::Cycle => "E106",
+            Self::NonFiniteFloat => "E107",
+            Self::Cancelled => "E108",
+            Self::InvalidPrevious => "E109",
+        }
+    }
+}
+
+impl core::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ErrorCode {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "{=str}", self.as_str());
+    }
+}
@@ -7,12 +7,44 @@
 
 extern crate alloc;
 
+#[cfg(any(feature = "deserialize", feature = "serialize"))]
+mod attributes;
+#[cfg(any(feature = "deserialize", feature = "serialize"))]
+mod base64;
+#[cfg(any(feature = "deserialize", feature = "serialize"))]
+mod error_code;
+#[cfg(any(feature = "deserialize", feature = "serialize"))]
+pub use error_code::ErrorCode;
+
+#[cfg(any(feature = "deserialize", feature = "serialize"))]
+mod stream;
+#[cfg(any(feature = "deserialize", feature = "serialize"))]
+pub use stream::{DocumentStream, StreamDocument};
+
 #[cfg(feature = "deserialize")]
 mod deserialize;
 #[cfg(feature = "deserialize")]
 pub use deserialize::*;
 
+#[cfg(feature = "deserialize")]
+mod customize;
+#[cfg(feature = "deserialize")]
+pub use customize::YamlCustomize;
+
 #[cfg(feature = "serialize")]
 mod serialize;
 #[cfg(feature = "serialize")]
 pub use serialize::*;
+
+#[cfg(feature = "serde-bridge")]
+pub mod serde_bridge;
+
+#[cfg(feature = "cron")]
+mod cron;
+#[cfg(feature = "cron")]
+pub use cron::*;
+
+#[cfg(feature = "notify")]
+mod watch;
+#[cfg(feature = "notify")]
+pub use watch::*;
@@ -0,0 +1,233 @@
+//! Adapters between `Facet` types and serde, through [`Value`].
+//!
+//! Projects that are moving from serde to facet, or that must feed both, can use these to
+//! deserialize a `Facet` type from any serde `Deserializer`, such as `serde_json`, or serialize
+//! it into any serde `Serializer`. The data goes through a [`Value`], so the type is read and
+//! written the same way as a YAML document with the same content: the `#[facet(yaml(...))]`
+//! attributes, defaults and enums all apply.
+//!
+//! The functions fit serde's `with` attribute, for `Facet` fields of serde types:
+//!
+//! ```
+//! use facet::Facet;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Facet, PartialEq)]
+//! struct Limits {
+//!     cpu: f64,
+//!     #[facet(default)]
+//!     memory: Option<String>,
+//! }
+//!
+//! #[derive(Debug, Deserialize, Serialize, PartialEq)]
+//! struct Container {
+//!     name: String,
+//!     #[serde(with = "facet_yaml::serde_bridge")]
+//!     limits: Limits,
+//! }
+//!
+//! let json = r#"{"name":"web","limits":{"cpu":0.5}}"#;
+//! let container: Container = serde_json::from_str(json).unwrap();
+//! assert_eq!(container.limits, Limits { cpu: 0.5, memory: None });
+//! assert_eq!(serde_json::to_string(&container).unwrap(), json);
+//! ```
+
+use alloc::{format, string::String, vec::Vec};
+
+use facet_core::Facet;
+use facet_reflect::Peek;
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{self, MapAccess, SeqAccess, Visitor},
+    ser::{self, SerializeMap as _},
+};
+
+use crate::{Map, SerializeOptions, Value, deserialize::from_value, serialize::tree};
+
+/// Serialize a `Facet` value with a serde `Serializer`.
+///
+/// Fields whose value is `None` are left out, like in YAML documents. Byte buffers are written
+/// as sequences of integers.
+pub fn serialize<'a, T: Facet<'a>, S: Serializer>(
+    value: &'a T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    to_value(value)
+        .map_err(ser::Error::custom)?
+        .serialize(serializer)
+}
+
+/// Deserialize a `Facet` value from a serde `Deserializer`.
+pub fn deserialize<'de, T: for<'facet> Facet<'facet>, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    let value = Value::deserialize(deserializer)?;
+    from_value(value).map_err(de::Error::custom)
+}
+
+/// Convert a `Facet` value to the [`Value`] of its YAML document.
+fn to_value<'a, T: Facet<'a>>(value: &'a T) -> Result<Value, crate::YamlSerError> {
+    // Byte buffers as base64 text would need their `!!binary` tag to be read back
+    let options = SerializeOptions::new().binary(false);
+    Ok(Value::from_yaml(
+        tree::to_yaml(Peek::new(value), &options)?.root,
+    ))
+}
+
+/// A `Facet` value that implements serde's `Serialize` and `Deserialize`, for where a type is
+/// needed rather than functions, such as the items of a collection.
+///
+/// ```
+/// use facet::Facet;
+/// use facet_yaml::serde_bridge::Serde;
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Port {
+///     number: u16,
+/// }
+///
+/// let ports: Vec<Serde<Port>> = serde_json::from_str(r#"[{"number":80}]"#).unwrap();
+/// assert_eq!(ports[0].0, Port { number: 80 });
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Serde<T>(pub T);
+
+impl<T: for<'facet> Facet<'facet>> Serialize for Serde<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, T: for<'facet> Facet<'facet>> Deserialize<'de> for Serde<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer).map(Serde)
+    }
+}
+
+/// Invalid nodes can't be serialized, since they're only known by their error.
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Null => serializer.serialize_unit(),
+            Value::Bool(b) => serializer.serialize_bool(*b),
+            Value::Number(n) => {
+                if let Some(i) = n.as_i64() {
+                    serializer.serialize_i64(i)
+                } else if let Some(u) = n.as_u64() {
+                    serializer.serialize_u64(u)
+                } else {
+                    serializer.serialize_f64(n.as_f64().unwrap_or_default())
+                }
+            }
+            Value::String(s) => serializer.serialize_str(s),
+            Value::Sequence(values) => serializer.collect_seq(values),
+            Value::Map(map) => map.serialize(serializer),
+            Value::Invalid(span, message) => Err(ser::Error::custom(format!(
+                "Invalid node at {span} can't be serialized: {message}"
+            ))),
+        }
+    }
+}
+
+impl Serialize for Map {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (key, value) in self.iter() {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for Map {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::Map(map) => Ok(map),
+            _ => Err(de::Error::custom("Expected a YAML hash")),
+        }
+    }
+}
+
+/// Builds a [`Value`] from whatever the deserializer has.
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("any YAML value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Value, E> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.into()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Value, E> {
+        Ok(Value::Sequence(
+            v.iter()
+                .map(|byte| Value::Number(i64::from(*byte).into()))
+                .collect(),
+        ))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(Value::Sequence(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Value, A::Error> {
+        let mut map = Map::new();
+        while let Some((key, value)) = access.next_entry()? {
+            map.insert(key, value);
+        }
+        Ok(Value::Map(map))
+    }
+}
@@ -0,0 +1,1362 @@
+//! Write YAML documents in block style, with flow style for the collections that ask for it.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+use facet_core::{Def, Field, Shape, StructKind, Type, UserType, Variant};
+use yaml_rust2::{
+    Yaml,
+    scanner::TScalarStyle,
+    yaml::{Array, Hash},
+};
+
+use super::{
+    AmbiguousStrings, MultilineStrings, NullStyle, QuoteStyle, SerializeOptions, SortKeys,
+    YamlSerError,
+    previous::{Entry, Layout},
+    progress::{Reporter, Segment},
+    tree::{self, Document, Shared},
+};
+use crate::attributes::{self, FieldAttributes};
+
+/// Writes a YAML document the same way `yaml_rust2::YamlEmitter` does, except for how strings
+/// are protected and the layout options.
+///
+/// When the shape of the serialized value is known, it's followed along with the document to find
+/// the attributes of the fields.
+pub(crate) struct Emitter<'a, W: Write> {
+    /// Where the document is written to.
+    out: Columns<'a, W>,
+    /// How to write the scalars.
+    options: &'a SerializeOptions,
+    /// Current nesting level, `-1` for the root.
+    level: isize,
+    /// Whether the last node written is a block scalar whose final line break is part of its
+    /// content, so the document can't end right after it.
+    ends_in_block: bool,
+    /// The values behind shared pointers of the current document.
+    shared: &'a [Shared],
+    /// The number of the anchor of each shared value, once it's written.
+    anchors: Vec<Option<usize>>,
+    /// Reports how far the document is, when a callback is registered.
+    progress: Option<Reporter<'a>>,
+    /// The previous version of the first document, whose layout and comments are followed
+    /// where they can be.
+    previous: Option<&'a Entry>,
+    /// The nodes the current node is in, with their previous version, when there is one.
+    layouts: Vec<Level<'a>>,
+}
+
+impl<'a, W: Write> Emitter<'a, W> {
+    /// Create an emitter writing into `out`.
+    pub fn new(out: &'a mut W, options: &'a SerializeOptions) -> Self {
+        Self {
+            out: Columns {
+                out,
+                column: 0,
+                written: 0,
+                comment: String::new(),
+            },
+            options,
+            level: -1,
+            ends_in_block: false,
+            shared: &[],
+            anchors: Vec::new(),
+            progress: None,
+            previous: None,
+            layouts: Vec::new(),
+        }
+    }
+
+    /// Write the first document with the layout of its previous version where it's the same,
+    /// such as the order of keys and how strings are quoted, and with its comments.
+    pub fn with_previous(mut self, previous: Option<&'a Entry>) -> Self {
+        self.previous = previous;
+        self
+    }
+
+    /// Report how far the document is with `progress` while it's written.
+    #[cfg(feature = "std")]
+    pub fn with_progress(mut self, progress: Option<Reporter<'a>>) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Write a complete document, starting with its `---` marker unless disabled.
+    pub fn dump(
+        &mut self,
+        doc: &'a Document,
+        shape: Option<&'static Shape>,
+    ) -> Result<(), YamlSerError> {
+        self.dump_stream(core::slice::from_ref(doc), shape)
+    }
+
+    /// Write a stream of documents of the given shape.
+    ///
+    /// Documents after the first always start with a `---` marker on its own line, since it's
+    /// what separates them, and so do documents with a `%YAML` directive, which must be
+    /// followed by one.
+    pub fn dump_stream(
+        &mut self,
+        docs: &'a [Document],
+        shape: Option<&'static Shape>,
+    ) -> Result<(), YamlSerError> {
+        let mut ends_in_newline = false;
+        for (index, doc) in docs.iter().enumerate() {
+            if index > 0 {
+                if !ends_in_newline {
+                    self.out.write_char('\n')?;
+                }
+                // Directives are only allowed after the end of the previous document
+                if self.options.yaml_directive && !self.options.document_end {
+                    self.out.write_str("...\n")?;
+                }
+            }
+            if self.options.yaml_directive {
+                self.out.write_str("%YAML 1.2\n")?;
+            }
+            if index > 0 || self.options.document_start || self.options.yaml_directive {
+                self.out.write_str("---\n")?;
+            }
+            self.level = -1;
+            self.ends_in_block = false;
+            // Anchors are numbered from the start of each document
+            self.shared = &doc.shared;
+            self.anchors = alloc::vec![None; doc.shared.len()];
+            // Only the first document has a previous version
+            self.layouts.clear();
+            if index == 0 && self.previous.is_some() {
+                self.layouts.push(Level {
+                    entry: self.previous,
+                    items: Vec::new(),
+                });
+            }
+            let shape = shape.map(NodeShape::Shape);
+            let root_flow = self.layout().and_then(Layout::flow);
+            let tagged = self.customized(shape).1.is_some();
+            if tagged || root_flow.unwrap_or(self.options.flow_depth == Some(0)) {
+                self.emit_flow(&doc.root, shape, false)?;
+            } else {
+                self.emit_node(&doc.root, shape, FieldStyle::default())?;
+            }
+            self.out.write_comment()?;
+            if self.options.document_end {
+                self.out.write_str("\n...")?;
+            }
+            ends_in_newline =
+                self.options.trailing_newline || (self.ends_in_block && !self.options.document_end);
+            if ends_in_newline {
+                self.out.write_char('\n')?;
+            }
+        }
+        if let Some(progress) = &mut self.progress {
+            progress.report(self.out.written, true)?;
+        }
+        Ok(())
+    }
+
+    /// Enter the value of a mapping key or sequence item, reporting the progress when it's
+    /// due.
+    fn enter(&mut self, step: Step<'_>) -> Result<(), YamlSerError> {
+        if let Some(level) = self.layouts.last() {
+            let entry = match step {
+                Step::Key(key) => level.entry.and_then(|entry| entry.value.entry(key)),
+                Step::Index(index) => level.items.get(index).copied().flatten(),
+            };
+            self.layouts.push(Level {
+                entry,
+                items: Vec::new(),
+            });
+        }
+        if let Some(progress) = &mut self.progress {
+            progress.enter(match step {
+                Step::Key(key) => Segment::key(key),
+                Step::Index(index) => Segment::Index(index),
+            });
+            progress.report(self.out.written, false)?;
+        }
+        Ok(())
+    }
+
+    /// Leave the value entered last.
+    fn leave(&mut self) {
+        self.layouts.pop();
+        if let Some(progress) = &mut self.progress {
+            progress.leave();
+        }
+    }
+
+    /// Get the layout of the current node in the previous version of the document, if it was
+    /// there.
+    fn layout(&self) -> Option<&'a Layout> {
+        self.entry().map(|entry| &entry.value)
+    }
+
+    /// Get the layout of the key of the current node in the previous version of the document,
+    /// if it was there.
+    fn key_layout(&self) -> Option<&'a Layout> {
+        self.entry().and_then(|entry| entry.key.as_ref())
+    }
+
+    /// Get the entry or item of the current node in the previous version of the document, if
+    /// it was there.
+    fn entry(&self) -> Option<&'a Entry> {
+        self.layouts.last().and_then(|level| level.entry)
+    }
+
+    /// Find the previous version of each item of the current sequence, for [`Self::enter`].
+    fn match_items(&mut self, array: &Array) {
+        if let Some(level) = self.layouts.last_mut() {
+            level.items = level
+                .entry
+                .map_or_else(Vec::new, |entry| entry.value.match_items(array));
+        }
+    }
+
+    /// Write the comments that were before the current entry or item in the previous version
+    /// of the document, at the column it starts at, and the comment that was at the end of its
+    /// line once the line is written.
+    fn emit_comments(&mut self) -> Result<(), YamlSerError> {
+        let Some(entry) = self.entry() else {
+            return Ok(());
+        };
+        let column = self.out.column;
+        for comment in &entry.comments {
+            writeln!(self.out, "{comment}")?;
+            self.write_spaces(column)?;
+        }
+        if let Some(comment) = &entry.trailing {
+            self.out.comment(comment);
+        }
+        Ok(())
+    }
+
+    fn write_indent(&mut self) -> Result<(), YamlSerError> {
+        if self.level > 0 {
+            self.write_spaces(self.level as usize * self.options.indent)?;
+        }
+        Ok(())
+    }
+
+    fn write_spaces(&mut self, count: usize) -> Result<(), YamlSerError> {
+        for _ in 0..count {
+            self.out.write_char(' ')?;
+        }
+        Ok(())
+    }
+
+    /// Write a node of the given shape, if it's known.
+    ///
+    /// The `style` is inherited from the field the node belongs to.
+    fn emit_node(
+        &mut self,
+        node: &Yaml,
+        shape: Option<NodeShape>,
+        style: FieldStyle,
+    ) -> Result<(), YamlSerError> {
+        self.ends_in_block = false;
+        // Other scalars than strings that are the same as before are written as they were, e.g.
+        // `0x1F`
+        let previous = self.previous_text(node).filter(|text| {
+            !text.is_empty()
+                && matches!(
+                    node,
+                    Yaml::Integer(_) | Yaml::Real(_) | Yaml::Boolean(_) | Yaml::Null
+                )
+        });
+        if let Some(text) = previous {
+            self.out.write_str(text)?;
+            return Ok(());
+        }
+        match node {
+            Yaml::Array(array) if self.is_packed(array) => return self.emit_packed(array),
+            Yaml::Array(array) => return self.emit_array(array, shape, style),
+            Yaml::Hash(hash) => return self.emit_hash(hash, shape),
+            // Byte buffers are base64 text, which needs its tag to be read back as bytes
+            Yaml::String(value) if shape.is_some_and(NodeShape::is_bytes) => {
+                // An empty buffer is quoted, so the tag isn't left at the end of the line
+                let value = if value.is_empty() { "\"\"" } else { value };
+                write!(self.out, "!!binary {value}")?;
+            }
+            Yaml::String(value) => self.emit_string_value(value, style)?,
+            Yaml::Boolean(value) => write!(self.out, "{value}")?,
+            Yaml::Integer(value) => write!(self.out, "{value}")?,
+            Yaml::Real(value) => self.out.write_str(value)?,
+            Yaml::Null | Yaml::BadValue => self.out.write_str(match self.options.null_style {
+                NullStyle::Tilde => "~",
+                NullStyle::Null => "null",
+                // Items of flow collections can't be empty
+                NullStyle::Empty if style.flow => "null",
+                NullStyle::Empty => "",
+            })?,
+            Yaml::Alias(index) => match self.reference(*index) {
+                Reference::Inline(node) => return self.emit_node(node, shape, style),
+                Reference::Anchor(anchor, node) => {
+                    write!(self.out, "&id{anchor:03}")?;
+                    return self.emit_val(false, node, shape, style);
+                }
+                Reference::Alias(anchor) => write!(self.out, "*id{anchor:03}")?,
+            },
+        }
+        Ok(())
+    }
+
+    /// Find out how a shared value is written where the alias at `index` is: in full when
+    /// it's only written once, with an anchor the first time it's written, and as an alias of
+    /// that anchor after it.
+    fn reference(&mut self, index: usize) -> Reference<'a> {
+        let shared = &self.shared;
+        let shared = &shared[index];
+        if shared.uses == 1 {
+            return Reference::Inline(&shared.node);
+        }
+        match self.anchors[index] {
+            Some(anchor) => Reference::Alias(anchor),
+            None => {
+                let anchor = self.anchors.iter().flatten().count() + 1;
+                self.anchors[index] = Some(anchor);
+                Reference::Anchor(anchor, &shared.node)
+            }
+        }
+    }
+
+    /// Write a key of a hash entry, which is only quoted when it has to be, or when it was in the
+    /// previous version of the document.
+    fn emit_key(&mut self, key: &Yaml) -> Result<(), YamlSerError> {
+        match key {
+            Yaml::String(value) => {
+                let style = self
+                    .key_layout()
+                    .and_then(|layout| layout.string_style(value));
+                if !self.write_in_style(value, style, false)? {
+                    self.emit_string(value, false)?;
+                }
+                Ok(())
+            }
+            // Null keys can't be empty, like the items of flow collections
+            _ => self.emit_node(
+                key,
+                None,
+                FieldStyle {
+                    flow: true,
+                    ..FieldStyle::default()
+                },
+            ),
+        }
+    }
+
+    /// Write a string, always quoted when `quote` is set.
+    fn emit_string(&mut self, value: &str, quote: bool) -> Result<(), YamlSerError> {
+        if quote || needs_quotes(value) {
+            self.write_quoted(value)?;
+        } else if !is_ambiguous(value) {
+            self.out.write_str(value)?;
+        } else {
+            match self.options.ambiguous_strings {
+                AmbiguousStrings::Plain => self.out.write_str(value)?,
+                AmbiguousStrings::Quote => self.write_quoted(value)?,
+                AmbiguousStrings::Tag => write!(self.out, "!!str {value}")?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a string value, the way it was written in the previous version of the document when
+    /// it can be read back the same way.
+    fn emit_string_value(&mut self, value: &str, style: FieldStyle) -> Result<(), YamlSerError> {
+        if self.emit_previous_string(value, style)? {
+            return Ok(());
+        }
+        // Block scalars can't be inside flow collections
+        if !style.flow && self.is_block(value, style) {
+            let folded =
+                self.options.multiline_strings == MultilineStrings::Folded && !style.embedded;
+            self.emit_block(value, folded)
+        } else {
+            let quote = style.quote || self.options.quote_style != QuoteStyle::Plain;
+            self.emit_string(value, quote)
+        }
+    }
+
+    /// Write a string value the way it was written in the previous version of the document, and
+    /// tell whether it could be.
+    ///
+    /// Embedded documents are written the way they always are.
+    fn emit_previous_string(
+        &mut self,
+        value: &str,
+        style: FieldStyle,
+    ) -> Result<bool, YamlSerError> {
+        let previous = match self.layout() {
+            Some(layout) if !style.embedded => layout.string_style(value),
+            _ => None,
+        };
+        match previous {
+            // Strings that must be quoted stay quoted
+            Some(TScalarStyle::Plain) if style.quote => Ok(false),
+            Some(TScalarStyle::Literal | TScalarStyle::Folded)
+                if style.flow || !self.is_block(value, style) =>
+            {
+                Ok(false)
+            }
+            previous => self.write_in_style(value, previous, true),
+        }
+    }
+
+    /// Write a string with the style of a scalar of the previous version of the document, and
+    /// tell whether it could be, which isn't the case for block scalars unless `block` is set,
+    /// nor for single quotes around line breaks and control characters.
+    fn write_in_style(
+        &mut self,
+        value: &str,
+        style: Option<TScalarStyle>,
+        block: bool,
+    ) -> Result<bool, YamlSerError> {
+        match style {
+            // The string was read back from the same text, where it's still used
+            Some(TScalarStyle::Plain) => self.out.write_str(value)?,
+            Some(TScalarStyle::SingleQuoted) if !value.contains(char::is_control) => {
+                write_single_quoted(&mut self.out, value)?;
+            }
+            Some(TScalarStyle::DoubleQuoted) => write_double_quoted(&mut self.out, value)?,
+            Some(TScalarStyle::Literal) if block => self.emit_block(value, false)?,
+            Some(TScalarStyle::Folded) if block => self.emit_block(value, true)?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Get the text of the current node in the previous version of the document, if it was a
+    /// plain scalar with the same value.
+    fn previous_text(&self, node: &Yaml) -> Option<&'a str> {
+        self.layout().and_then(|layout| layout.same_text(node))
+    }
+
+    /// Whether a string is written as a block scalar, which is the case for strings with line
+    /// breaks unless they can't be written as one.
+    ///
+    /// Embedded documents are always written as literal block scalars when they can be, so they
+    /// can be read as they are.
+    fn is_block(&self, value: &str, style: FieldStyle) -> bool {
+        let first = value.lines().find(|line| !line.is_empty());
+        (style.embedded || self.options.multiline_strings != MultilineStrings::Quoted)
+            && value.contains('\n')
+            // The indentation of the block is taken from its first line
+            && !first.is_some_and(|line| line.starts_with([' ', '\t']))
+            // Lines with only spaces may be read as empty lines
+            && !value.split('\n').any(|line| !line.is_empty() && line.trim().is_empty())
+            && !value.contains(|c: char| {
+                (c.is_control() && c != '\n' && c != '\t')
+                    || ['\u{85}', '\u{2028}', '\u{2029}', '\u{feff}'].contains(&c)
+            })
+    }
+
+    /// Write a string with line breaks as a literal (`|`) or `folded` (`>`) block scalar,
+    /// indented one level deeper than the node it belongs to.
+    fn emit_block(&mut self, value: &str, folded: bool) -> Result<(), YamlSerError> {
+        // The chomping indicator tells how many line breaks the content ends with
+        let (body, chomping) = match value.strip_suffix('\n') {
+            None => (value, "-"),
+            Some(body) if body.ends_with('\n') || body.is_empty() => (body, "+"),
+            Some(body) => (body, ""),
+        };
+        let lines: Vec<&str> = body.split('\n').collect();
+        let indent = (self.level + 1).max(1) as usize * self.options.indent;
+
+        if folded {
+            write!(self.out, ">{chomping}")?;
+            for (index, line) in lines.iter().enumerate() {
+                for segment in wrap(line, self.options.line_width.saturating_sub(indent)) {
+                    self.write_block_line(indent, segment)?;
+                }
+                // A single line break between two lines of text would be folded into a space,
+                // so it's written as an empty line, which is folded into a line break
+                let next_text = lines[index + 1..].iter().find(|line| !line.is_empty());
+                if is_foldable(line) && next_text.is_some_and(|next| is_foldable(next)) {
+                    self.out.write_char('\n')?;
+                }
+            }
+        } else {
+            write!(self.out, "|{chomping}")?;
+            for line in &lines {
+                self.write_block_line(indent, line)?;
+            }
+        }
+
+        self.ends_in_block = chomping != "-";
+        Ok(())
+    }
+
+    /// Write a line of a block scalar, on its own line.
+    fn write_block_line(&mut self, indent: usize, line: &str) -> Result<(), YamlSerError> {
+        self.out.write_char('\n')?;
+        if !line.is_empty() {
+            self.write_spaces(indent)?;
+            self.out.write_str(line)?;
+        }
+        Ok(())
+    }
+
+    /// Write a string as a quoted scalar, with the quotes of the style.
+    fn write_quoted(&mut self, value: &str) -> Result<(), YamlSerError> {
+        if self.options.quote_style == QuoteStyle::Single && !value.contains(char::is_control) {
+            write_single_quoted(&mut self.out, value)
+        } else {
+            write_double_quoted(&mut self.out, value)
+        }
+    }
+
+    fn emit_array(
+        &mut self,
+        array: &Array,
+        shape: Option<NodeShape>,
+        style: FieldStyle,
+    ) -> Result<(), YamlSerError> {
+        self.ends_in_block = false;
+        if array.is_empty() {
+            self.out.write_str("[]")?;
+            return Ok(());
+        }
+
+        self.match_items(array);
+        self.level += 1;
+        for (index, item) in array.iter().enumerate() {
+            self.enter(Step::Index(index))?;
+            if index > 0 {
+                self.out.write_char('\n')?;
+                self.write_indent()?;
+            }
+            self.emit_comments()?;
+            self.out.write_char('-')?;
+            let item_shape = shape.and_then(|shape| shape.item(index));
+            self.emit_val(true, item, item_shape, style)?;
+            self.leave();
+        }
+        self.level -= 1;
+        Ok(())
+    }
+
+    fn emit_hash(&mut self, hash: &Hash, shape: Option<NodeShape>) -> Result<(), YamlSerError> {
+        self.ends_in_block = false;
+        // Hashes whose entries are all commented out are still read back as hashes
+        let hollow = is_hollow(hash);
+        if hollow {
+            self.out.write_str("{}")?;
+        }
+        if hash.is_empty() {
+            return Ok(());
+        }
+
+        let shape = shape.map(|shape| shape.unwrapped().variant_in(hash));
+
+        self.level += 1;
+        for (index, (key, value)) in self.entries(hash, shape).into_iter().enumerate() {
+            self.enter(Step::Key(key))?;
+            if index > 0 || hollow {
+                self.out.write_char('\n')?;
+                self.write_indent()?;
+            }
+            self.emit_comments()?;
+
+            let (value_shape, style) = match shape {
+                Some(shape) => shape.value(key)?,
+                None => (None, FieldStyle::default()),
+            };
+
+            // Entries of the previous version keep their own comments instead
+            if self.options.doc_comments && self.entry().is_none() {
+                // Comments are at the column of the key, which the first entry is already at
+                for line in shape.map_or(&[][..], |shape| shape.doc(key)) {
+                    // The space after `///` is kept after `#`, and lines of text are wrapped to
+                    // fit in the line width with it
+                    let (space, text) = match line.strip_prefix(' ') {
+                        Some(text) => (" ", text),
+                        None => ("", *line),
+                    };
+                    let width = self
+                        .options
+                        .line_width
+                        .saturating_sub(self.out.column + 1 + space.len());
+                    for segment in wrap(text, width) {
+                        writeln!(self.out, "#{space}{segment}")?;
+                        self.write_indent()?;
+                    }
+                }
+            }
+
+            if let Some(value) = tree::commented(value) {
+                self.emit_commented(key, value, value_shape, style)?;
+            } else if matches!(key, Yaml::Hash(_) | Yaml::Array(_)) {
+                self.out.write_char('?')?;
+                self.emit_val(true, key, None, FieldStyle::default())?;
+                self.out.write_char('\n')?;
+                self.write_indent()?;
+                self.out.write_char(':')?;
+                self.emit_val(true, value, value_shape, style)?;
+            } else {
+                self.emit_key(key)?;
+                self.out.write_char(':')?;
+                self.emit_val(false, value, value_shape, style)?;
+            }
+            self.leave();
+        }
+        self.level -= 1;
+        Ok(())
+    }
+
+    /// Write a hash entry as comments, with the `#` of every line at the column of the key, so
+    /// removing them gives back the entry.
+    fn emit_commented(
+        &mut self,
+        key: &Yaml,
+        value: &Yaml,
+        shape: Option<NodeShape>,
+        style: FieldStyle,
+    ) -> Result<(), YamlSerError> {
+        let column = self.out.column;
+        // Lines are wrapped to fit in the line width with the `# ` before them
+        let options = SerializeOptions {
+            line_width: self.options.line_width.saturating_sub(2),
+            ..self.options.clone()
+        };
+        let mut text = String::new();
+        {
+            let mut entry = Emitter::new(&mut text, &options);
+            entry.level = self.level;
+            entry.out.column = column;
+            entry.shared = self.shared;
+            entry.anchors = alloc::vec![None; self.shared.len()];
+            entry.emit_key(key)?;
+            entry.out.write_char(':')?;
+            entry.emit_val(false, value, shape, style)?;
+        }
+
+        for (index, line) in text.split('\n').enumerate() {
+            if index > 0 {
+                self.out.write_char('\n')?;
+                self.write_spaces(column)?;
+            }
+            // Every line but the first is indented at least as much as the key
+            let indent = line.len() - line.trim_start_matches(' ').len();
+            match &line[indent.min(column)..] {
+                "" => self.out.write_char('#')?,
+                line => write!(self.out, "# {line}")?,
+            }
+        }
+        self.ends_in_block = false;
+        Ok(())
+    }
+
+    /// Get the entries of a hash of the given shape, in the order they're written.
+    fn entries<'h>(&self, hash: &'h Hash, shape: Option<NodeShape>) -> Vec<(&'h Yaml, &'h Yaml)> {
+        let mut entries: Vec<_> = hash.iter().collect();
+        let is_struct = shape.is_some_and(NodeShape::is_struct);
+        // Sorted maps are already in the order of their keys, which may differ from the order
+        // of the nodes they're written as, e.g. enum variants are in their declaration order
+        let is_sorted_map = shape.is_some_and(NodeShape::is_sorted_map);
+        let sorted = match self.options.sort_keys {
+            SortKeys::Never => false,
+            SortKeys::Maps => !is_struct && !is_sorted_map,
+            SortKeys::MapsAndStructs => !is_sorted_map,
+        };
+        if sorted {
+            entries.sort_by_key(|(key, _)| *key);
+        }
+
+        // Keys of the previous version of the document keep their order there, and new keys
+        // stay after the key they follow
+        if let Some(previous) = self
+            .layout()
+            .filter(|layout| matches!(layout, Layout::Mapping { .. }))
+        {
+            let mut last = None;
+            let mut added = 0;
+            let mut ranked: Vec<_> = entries
+                .into_iter()
+                .map(|entry| match previous.position(entry.0) {
+                    Some(position) => {
+                        last = Some(position);
+                        ((last, 0), entry)
+                    }
+                    None => {
+                        added += 1;
+                        ((last, added), entry)
+                    }
+                })
+                .collect();
+            ranked.sort_by_key(|(rank, _)| *rank);
+            entries = ranked.into_iter().map(|(_, entry)| entry).collect();
+        }
+        entries
+    }
+
+    /// Write the value of a hash entry or array item, after a space or on the next line.
+    ///
+    /// Collections that are `inline` start on the same line as the `-` or `?` before them,
+    /// padded so their entries line up with the next nesting level. Collections in flow style
+    /// always do.
+    fn emit_val(
+        &mut self,
+        inline: bool,
+        value: &Yaml,
+        shape: Option<NodeShape>,
+        style: FieldStyle,
+    ) -> Result<(), YamlSerError> {
+        // Shared values are written in full after their anchor the first time, with
+        // collections starting on the next line so the anchor isn't read as one of their keys
+        if let Yaml::Alias(index) = value {
+            return match self.reference(*index) {
+                Reference::Inline(value) => self.emit_val(inline, value, shape, style),
+                Reference::Anchor(anchor, value) => {
+                    write!(self.out, " &id{anchor:03}")?;
+                    match value {
+                        // Only sequences that are values of a mapping may be indentless
+                        Yaml::Array(array)
+                            if inline
+                                && !array.is_empty()
+                                && !self.is_flow(style)
+                                && !self.is_packed(array) =>
+                        {
+                            self.out.write_char('\n')?;
+                            self.level += 1;
+                            self.write_indent()?;
+                            self.level -= 1;
+                            self.emit_array(array, shape, style)
+                        }
+                        _ => self.emit_val(false, value, shape, style),
+                    }
+                }
+                Reference::Alias(anchor) => Ok(write!(self.out, " *id{anchor:03}")?),
+            };
+        }
+
+        // Tagged collections are written in flow style, so the tag isn't taken for a key
+        let (shape, tag) = self.customized(shape);
+        if let Some(tag) = tag {
+            write!(self.out, " {tag} ")?;
+            return match value {
+                Yaml::Array(_) | Yaml::Hash(_) => self.emit_flow(value, None, style.quote),
+                _ => self.emit_node(value, None, style),
+            };
+        }
+
+        match value {
+            Yaml::Array(_) | Yaml::Hash(_) if self.is_flow(style) => {
+                self.out.write_char(' ')?;
+                self.emit_flow(value, shape, style.quote)
+            }
+            Yaml::Array(array) if self.is_packed(array) => {
+                self.out.write_char(' ')?;
+                self.emit_packed(array)
+            }
+            Yaml::Array(array) if array.is_empty() => {
+                self.out.write_char(' ')?;
+                self.emit_array(array, shape, style)
+            }
+            Yaml::Hash(hash) if is_hollow(hash) => {
+                self.out.write_char(' ')?;
+                self.emit_hash(hash, shape)
+            }
+            Yaml::Array(array) if inline => {
+                self.write_spaces(self.options.indent - 1)?;
+                self.emit_array(array, shape, style)
+            }
+            Yaml::Hash(hash) if inline => {
+                self.write_spaces(self.options.indent - 1)?;
+                self.emit_hash(hash, shape)
+            }
+            Yaml::Array(array) if !self.options.indent_sequences => {
+                // The items start at the same column as the key
+                self.out.write_char('\n')?;
+                self.write_indent()?;
+                self.level -= 1;
+                let result = self.emit_array(array, shape, style);
+                self.level += 1;
+                result
+            }
+            Yaml::Array(_) | Yaml::Hash(_) => {
+                self.out.write_char('\n')?;
+                self.level += 1;
+                self.write_indent()?;
+                self.level -= 1;
+                self.emit_node(value, shape, style)
+            }
+            // Empty nulls are left after the `-` or `:`, without a trailing space
+            Yaml::Null | Yaml::BadValue
+                if self.options.null_style == NullStyle::Empty
+                    || self.previous_text(value) == Some("") =>
+            {
+                self.ends_in_block = false;
+                Ok(())
+            }
+            _ => {
+                self.out.write_char(' ')?;
+                self.emit_node(value, shape, style)
+            }
+        }
+    }
+
+    /// Look past the shape of a type with a [`YamlCustomize`](crate::YamlCustomize), since its
+    /// node is the one it converts to, and get the tag it's written with.
+    fn customized(&self, shape: Option<NodeShape>) -> (Option<NodeShape>, Option<&'static str>) {
+        #[cfg(feature = "deserialize")]
+        if let Some(NodeShape::Shape(unwrapped)) = shape.map(NodeShape::unwrapped) {
+            if let Some(customization) = self.options.customization(unwrapped) {
+                return (None, customization.tag);
+            }
+        }
+        (shape, None)
+    }
+
+    /// Whether a value nested one level below the current collection is written in flow style,
+    /// which is the style it had in the previous version of the document if it was a collection.
+    fn is_flow(&self, style: FieldStyle) -> bool {
+        if let Some(flow) = self.layout().and_then(Layout::flow) {
+            return style.flow || flow;
+        }
+        style.flow
+            || self
+                .options
+                .flow_depth
+                .is_some_and(|depth| self.level + 1 >= depth as isize)
+    }
+
+    /// Whether a sequence is written with as many numbers on each line as fit.
+    fn is_packed(&self, array: &Array) -> bool {
+        self.options.pack_numbers
+            && !array.is_empty()
+            && array
+                .iter()
+                .all(|item| matches!(item, Yaml::Integer(_) | Yaml::Real(_)))
+    }
+
+    /// Write a sequence of numbers in flow style, starting a new line before a number that
+    /// wouldn't fit in the line width. The lines after the first are indented one level deeper
+    /// than the node the sequence belongs to.
+    fn emit_packed(&mut self, array: &Array) -> Result<(), YamlSerError> {
+        let indent = (self.level + 1).max(1) as usize * self.options.indent;
+        self.match_items(array);
+        self.out.write_char('[')?;
+        for (index, item) in array.iter().enumerate() {
+            let number = match item {
+                Yaml::Integer(value) => alloc::format!("{value}"),
+                Yaml::Real(value) => value.clone(),
+                _ => unreachable!("Packed sequences only have numbers"),
+            };
+            self.enter(Step::Index(index))?;
+            if index > 0 {
+                self.out.write_char(',')?;
+                // The number is followed by a comma or the closing bracket
+                if self.out.column + number.len() + 2 > self.options.line_width {
+                    self.out.write_char('\n')?;
+                    self.write_spaces(indent)?;
+                } else {
+                    self.out.write_char(' ')?;
+                }
+            }
+            self.out.write_str(&number)?;
+            self.leave();
+        }
+        self.out.write_char(']')?;
+        Ok(())
+    }
+
+    /// Write a node and everything in it in flow style, e.g. `[1, 2]` or `{name: web}`.
+    ///
+    /// Strings with flow indicators such as `,` or `]` are already quoted by [`needs_quotes`].
+    fn emit_flow(
+        &mut self,
+        node: &Yaml,
+        shape: Option<NodeShape>,
+        quote: bool,
+    ) -> Result<(), YamlSerError> {
+        let (shape, tag) = self.customized(shape);
+        if let Some(tag) = tag {
+            write!(self.out, "{tag} ")?;
+        }
+        match node {
+            Yaml::Array(array) => {
+                self.match_items(array);
+                self.out.write_char('[')?;
+                for (index, item) in array.iter().enumerate() {
+                    self.enter(Step::Index(index))?;
+                    if index > 0 {
+                        self.out.write_str(", ")?;
+                    }
+                    let item_shape = shape.and_then(|shape| shape.item(index));
+                    self.emit_flow(item, item_shape, quote)?;
+                    self.leave();
+                }
+                self.out.write_char(']')?;
+            }
+            Yaml::Hash(hash) => {
+                let shape = shape.map(|shape| shape.unwrapped().variant_in(hash));
+                self.out.write_char('{')?;
+                // Flow collections can't have comments in them
+                let entries = self.entries(hash, shape).into_iter();
+                let entries = entries.filter(|(_, value)| tree::commented(value).is_none());
+                for (index, (key, value)) in entries.enumerate() {
+                    self.enter(Step::Key(key))?;
+                    if index > 0 {
+                        self.out.write_str(", ")?;
+                    }
+                    let (value_shape, style) = match shape {
+                        Some(shape) => shape.value(key)?,
+                        None => (None, FieldStyle::default()),
+                    };
+                    if matches!(key, Yaml::Hash(_) | Yaml::Array(_)) {
+                        self.out.write_str("? ")?;
+                        self.emit_flow(key, None, false)?;
+                    } else {
+                        self.emit_key(key)?;
+                    }
+                    self.out.write_str(": ")?;
+                    self.emit_flow(value, value_shape, style.quote)?;
+                    self.leave();
+                }
+                self.out.write_char('}')?;
+            }
+            Yaml::Alias(index) => match self.reference(*index) {
+                Reference::Inline(node) => self.emit_flow(node, shape, quote)?,
+                Reference::Anchor(anchor, node) => {
+                    write!(self.out, "&id{anchor:03} ")?;
+                    self.emit_flow(node, shape, quote)?;
+                }
+                Reference::Alias(anchor) => write!(self.out, "*id{anchor:03}")?,
+            },
+            _ => {
+                let style = FieldStyle {
+                    quote,
+                    flow: true,
+                    ..FieldStyle::default()
+                };
+                self.emit_node(node, shape, style)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A writer that keeps track of the column the next character is written at, counted in
+/// characters, and of the number of bytes written.
+///
+/// A comment can be left for the end of the current line, which it's written at before the next
+/// line break.
+struct Columns<'a, W: Write> {
+    out: &'a mut W,
+    column: usize,
+    written: u64,
+    /// The comment to write at the end of the current line, or an empty string.
+    comment: String,
+}
+
+impl<W: Write> Columns<'_, W> {
+    /// Write a comment at the end of the current line, after what's still to be written on it.
+    fn comment(&mut self, comment: &str) {
+        if !self.comment.is_empty() {
+            self.comment.push(' ');
+        }
+        self.comment.push_str(comment);
+    }
+
+    /// Write the comment left for the end of the current line, if there is one.
+    fn write_comment(&mut self) -> core::fmt::Result {
+        if self.comment.is_empty() {
+            return Ok(());
+        }
+        let comment = core::mem::take(&mut self.comment);
+        write!(self, " {comment}")
+    }
+}
+
+impl<W: Write> Write for Columns<'_, W> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        if let Some(at) = s.find('\n').filter(|_| !self.comment.is_empty()) {
+            self.write_str(&s[..at])?;
+            self.write_comment()?;
+            return self.write_str(&s[at..]);
+        }
+        match s.rfind('\n') {
+            Some(at) => self.column = s[at + 1..].chars().count(),
+            None => self.column += s.chars().count(),
+        }
+        self.written += s.len() as u64;
+        self.out.write_str(s)
+    }
+}
+
+/// A node the current node is in, with its previous version.
+struct Level<'a> {
+    /// The entry or item of the node in the previous version of the document, if it was there.
+    entry: Option<&'a Entry>,
+    /// The previous version of each item, when the node is a sequence whose items are written.
+    items: Vec<Option<&'a Entry>>,
+}
+
+/// A step from a collection to a node in it.
+#[derive(Clone, Copy)]
+enum Step<'h> {
+    /// The value of a mapping key.
+    Key(&'h Yaml),
+    /// The item of a sequence at the index.
+    Index(usize),
+}
+
+/// How a shared value is written where one of its aliases is.
+enum Reference<'a> {
+    /// In full, since it's the only place it's written.
+    Inline(&'a Yaml),
+    /// In full, after the anchor with the number, since it's the first place it's written.
+    Anchor(usize, &'a Yaml),
+    /// As an alias of the anchor with the number.
+    Alias(usize),
+}
+
+/// How the value of a field is written, from its attributes.
+#[derive(Debug, Clone, Copy, Default)]
+struct FieldStyle {
+    /// Whether strings are always quoted.
+    quote: bool,
+    /// Whether collections are written in flow style.
+    flow: bool,
+    /// Whether the value is a YAML document embedded in a string.
+    embedded: bool,
+}
+
+/// What is known about the type of a node, to find the attributes of the fields in it.
+#[derive(Debug, Clone, Copy)]
+enum NodeShape {
+    /// A value of the shape.
+    Shape(&'static Shape),
+    /// The content of an enum variant with named fields or more than one field, which has no
+    /// shape of its own.
+    Variant(&'static Variant),
+}
+
+impl NodeShape {
+    /// Look through options and pointers, since they're written as the value itself.
+    fn unwrapped(self) -> Self {
+        match self {
+            Self::Shape(shape) => Self::Shape(unwrap_shape(shape)),
+            Self::Variant(_) => self,
+        }
+    }
+
+    /// Look through internally tagged enums to the variant named by the tag in their mapping,
+    /// since the fields of the variant are next to it.
+    fn variant_in(self, hash: &Hash) -> Self {
+        let Self::Shape(shape) = self else {
+            return self;
+        };
+        let (Type::User(UserType::Enum(ed)), Some(tag)) = (&shape.ty, attributes::enum_tag(shape))
+        else {
+            return self;
+        };
+        let name = hash.get(&Yaml::String(tag.into())).and_then(Yaml::as_str);
+        match ed
+            .variants
+            .iter()
+            .find(|variant| Some(variant.name) == name)
+        {
+            Some(variant) => match (variant.data.kind, variant.data.fields) {
+                (StructKind::Tuple | StructKind::TupleStruct, [field]) => {
+                    Self::Shape(field.shape()).unwrapped()
+                }
+                _ => Self::Variant(variant),
+            },
+            None => self,
+        }
+    }
+
+    /// Whether the node is a mapping of fields, whose order is the order they're declared in.
+    fn is_struct(self) -> bool {
+        match self {
+            Self::Shape(shape) => matches!(shape.ty, Type::User(UserType::Struct(_))),
+            Self::Variant(_) => true,
+        }
+    }
+
+    /// Whether the node is a map that iterates in the order of its keys, such as `BTreeMap`.
+    fn is_sorted_map(self) -> bool {
+        match self {
+            Self::Shape(shape) => {
+                let shape = unwrap_shape(shape);
+                matches!(shape.def, Def::Map(_)) && shape.type_identifier == "BTreeMap"
+            }
+            Self::Variant(_) => false,
+        }
+    }
+
+    /// Whether the node is a byte buffer, such as `Vec<u8>`, which is written as a `!!binary`
+    /// scalar.
+    fn is_bytes(self) -> bool {
+        match self {
+            Self::Shape(shape) => {
+                matches!(unwrap_shape(shape).def, Def::List(ld) if ld.t().is_type::<u8>())
+            }
+            Self::Variant(_) => false,
+        }
+    }
+
+    /// Get the shape of the value of a hash entry, and how it's written.
+    fn value(self, key: &Yaml) -> Result<(Option<Self>, FieldStyle), YamlSerError> {
+        let shape = match self {
+            Self::Shape(shape) => shape,
+            Self::Variant(variant) => return field_shape(variant.data.fields, key),
+        };
+        // Dynamic values are written as the nodes they hold, whose keys aren't variants
+        #[cfg(feature = "deserialize")]
+        if shape.is_type::<crate::Value>() {
+            return Ok((None, FieldStyle::default()));
+        }
+
+        match &shape.ty {
+            Type::User(UserType::Struct(sd)) => return field_shape(sd.fields, key),
+            // The content of untagged enums doesn't tell which variant it is
+            Type::User(UserType::Enum(_)) if attributes::is_untagged(shape) => {
+                return Ok((None, FieldStyle::default()));
+            }
+            // Enums with fields are a mapping from the name of the variant to its content
+            Type::User(UserType::Enum(ed)) => {
+                let Some(variant) = ed
+                    .variants
+                    .iter()
+                    .find(|variant| key.as_str() == Some(variant.name))
+                else {
+                    return Ok((None, FieldStyle::default()));
+                };
+                return match (variant.data.kind, variant.data.fields) {
+                    (StructKind::Unit, _) => Ok((None, FieldStyle::default())),
+                    (StructKind::Tuple | StructKind::TupleStruct, [field]) => {
+                        Ok((Some(Self::Shape(field.shape())), field_style(field)?))
+                    }
+                    _ => Ok((Some(Self::Variant(variant)), FieldStyle::default())),
+                };
+            }
+            _ => {}
+        }
+
+        match shape.def {
+            Def::Map(md) => Ok((Some(Self::Shape(md.v())), FieldStyle::default())),
+            _ => Ok((None, FieldStyle::default())),
+        }
+    }
+
+    /// Get the doc comment of the field a hash entry is for, one item for each line.
+    fn doc(self, key: &Yaml) -> &'static [&'static str] {
+        let fields = match self {
+            Self::Shape(shape) => match &shape.ty {
+                Type::User(UserType::Struct(sd)) => sd.fields,
+                _ => return &[],
+            },
+            Self::Variant(variant) => variant.data.fields,
+        };
+        fields
+            .iter()
+            .find(|field| key.as_str() == Some(field.name))
+            .map_or(&[], |field| field.doc)
+    }
+
+    /// Get the shape of the item of a sequence at `index`.
+    fn item(self, index: usize) -> Option<Self> {
+        match self {
+            Self::Shape(shape) => match unwrap_shape(shape).def {
+                Def::List(ld) => Some(Self::Shape(ld.t())),
+                Def::Array(ad) => Some(Self::Shape(ad.t())),
+                Def::Slice(sd) => Some(Self::Shape(sd.t())),
+                Def::Set(sd) => Some(Self::Shape(sd.t())),
+                // Maps written as keyed lists, whose items are their values
+                Def::Map(md) => Some(Self::Shape(md.v())),
+                _ => None,
+            },
+            Self::Variant(variant) => variant
+                .data
+                .fields
+                .get(index)
+                .map(|field| Self::Shape(field.shape())),
+        }
+    }
+}
+
+/// Get the shape of the value of a field, by its key, and how it's written.
+fn field_shape(
+    fields: &'static [Field],
+    key: &Yaml,
+) -> Result<(Option<NodeShape>, FieldStyle), YamlSerError> {
+    match fields.iter().find(|field| key.as_str() == Some(field.name)) {
+        Some(field) => {
+            // Embedded documents are strings rather than values of the field
+            let style = field_style(field)?;
+            let shape = (!style.embedded).then(|| NodeShape::Shape(field.shape()));
+            Ok((shape, style))
+        }
+        None => Ok((None, FieldStyle::default())),
+    }
+}
+
+/// Get how the value of a field is written, from its attributes.
+fn field_style(field: &Field) -> Result<FieldStyle, YamlSerError> {
+    let attributes =
+        FieldAttributes::of(field).map_err(|message| YamlSerError::InvalidAttribute { message })?;
+    Ok(FieldStyle {
+        quote: attributes.quote,
+        flow: attributes.flow,
+        embedded: attributes.embedded,
+    })
+}
+
+/// Get the shape of the value inside options, pointers and newtypes, since they're written as the
+/// value itself.
+fn unwrap_shape(shape: &'static Shape) -> &'static Shape {
+    match (shape.def, shape.ty) {
+        (Def::Option(od), _) => unwrap_shape(od.t()),
+        (Def::Pointer(pd), _) => pd.pointee().map_or(shape, unwrap_shape),
+        // Newtypes are written as their field
+        (_, Type::User(UserType::Struct(sd)))
+            if matches!(sd.kind, StructKind::TupleStruct) && sd.fields.len() == 1 =>
+        {
+            unwrap_shape(sd.fields[0].shape())
+        }
+        _ => shape,
+    }
+}
+
+/// Whether all the entries of a hash, if any, are written as comments, so it's written as `{}`.
+fn is_hollow(hash: &Hash) -> bool {
+    hash.iter()
+        .all(|(_, value)| tree::commented(value).is_some())
+}
+
+/// Whether a line of a folded block scalar is folded with the line after it, which is the case
+/// for lines of text that aren't more indented than the block.
+fn is_foldable(line: &str) -> bool {
+    !line.is_empty() && !line.starts_with([' ', '\t'])
+}
+
+/// Split a line of a folded block scalar into segments of at most `width` characters where
+/// possible, at single spaces between words, so folding joins them back with the same space.
+fn wrap(line: &str, width: usize) -> Vec<&str> {
+    if !is_foldable(line) {
+        return alloc::vec![line];
+    }
+
+    let is_white = |b: Option<&u8>| matches!(b, Some(b' ' | b'\t'));
+    let bytes = line.as_bytes();
+    let mut segments = Vec::new();
+    let mut start = 0;
+    // Offset of the last space the current segment can be split at
+    let mut split = None;
+    for (offset, c) in line.char_indices() {
+        let splittable = c == ' '
+            && !is_white(bytes.get(offset - 1))
+            && !is_white(bytes.get(offset + 1))
+            && offset + 1 < bytes.len();
+        if !splittable {
+            continue;
+        }
+        if line[start..offset].chars().count() > width {
+            if let Some(at) = split.take() {
+                segments.push(&line[start..at]);
+                start = at + 1;
+            }
+        }
+        split = Some(offset);
+    }
+    if line[start..].chars().count() > width {
+        if let Some(at) = split {
+            segments.push(&line[start..at]);
+            start = at + 1;
+        }
+    }
+    segments.push(&line[start..]);
+    segments
+}
+
+/// Write a string as a single-quoted scalar, which can't have control characters.
+fn write_single_quoted(out: &mut impl Write, value: &str) -> Result<(), YamlSerError> {
+    out.write_char('\'')?;
+    for c in value.chars() {
+        if c == '\'' {
+            out.write_str("''")?;
+        } else {
+            out.write_char(c)?;
+        }
+    }
+    out.write_char('\'')?;
+    Ok(())
+}
+
+/// Write a string as a double-quoted scalar.
+fn write_double_quoted(out: &mut impl Write, value: &str) -> Result<(), YamlSerError> {
+    out.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\x08' => out.write_str("\\b")?,
+            '\t' => out.write_str("\\t")?,
+            '\n' => out.write_str("\\n")?,
+            '\x0c' => out.write_str("\\f")?,
+            '\r' => out.write_str("\\r")?,
+            '\0'..='\x1f' | '\x7f' => write!(out, "\\u{:04x}", c as u32)?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')?;
+    Ok(())
+}
+
+/// Whether a string has to be quoted to be read back as the same string.
+fn needs_quotes(value: &str) -> bool {
+    value.is_empty()
+        || value.starts_with(' ')
+        || value.ends_with(' ')
+        || value.starts_with(['&', '*', '?', '|', '-', '<', '>', '=', '!', '%', '@', '.'])
+        || value.contains([
+            ':', '{', '}', '[', ']', ',', '#', '`', '"', '\'', '\\', '\t', '\n', '\r',
+        ])
+        || value.contains(|c: char| c.is_ascii_control())
+        || [
+            // Other spellings of booleans and null in the core schema
+            "True", "TRUE", "False", "FALSE", "Null", "NULL",
+            // Read as booleans by YAML 1.1 consumers, so quoted even though they're strings in
+            // YAML 1.2
+            "y", "Y", "n", "N", "yes", "Yes", "YES", "no", "No", "NO", "on", "On", "ON", "off",
+            "Off", "OFF",
+        ]
+        .contains(&value)
+        || value.starts_with("0x")
+        || value.parse::<i64>().is_ok()
+        || value.parse::<f64>().is_ok()
+        || !matches!(Yaml::from_str(value), Yaml::String(_))
+}
+
+/// Whether a string that is valid as a plain scalar would be read as another type by YAML 1.1
+/// consumers, e.g. `2024-01-01` as a timestamp or `1_000` as an integer.
+fn is_ambiguous(value: &str) -> bool {
+    let unsigned = value.strip_prefix(['-', '+']).unwrap_or(value);
+    let Some(first) = unsigned.chars().next() else {
+        return false;
+    };
+
+    // Timestamps start with a date
+    let mut date = value.splitn(3, '-');
+    let is_date = matches!(
+        (date.next(), date.next(), date.next()),
+        (Some(year), Some(month), Some(day))
+            if year.len() == 4
+                && year.bytes().all(|b| b.is_ascii_digit())
+                && (1..=2).contains(&month.len())
+                && month.bytes().all(|b| b.is_ascii_digit())
+                && day.bytes().take_while(u8::is_ascii_digit).count() >= 1
+    );
+
+    is_date
+        // Binary, octal and numbers with digit separators
+        || unsigned.starts_with("0b")
+        || unsigned.starts_with("0o")
+        || (first.is_ascii_digit()
+            && unsigned.contains('_')
+            && unsigned.replace('_', "").parse::<f64>().is_ok())
+        || [".inf", ".Inf", ".INF"].contains(&unsigned)
+}
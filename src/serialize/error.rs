@@ -1,5 +1,9 @@
 //! Errors from parsing into YAML documents.
 
+use alloc::{format, string::String};
+
+use crate::ErrorCode;
+
 /// Any error from serializing YAML.
 pub enum YamlSerError {
     /// Could not convert number to i64 representation.
@@ -14,6 +18,88 @@ pub enum YamlSerError {
     },
     /// YAML doesn't support byte arrays.
     UnsupportedByteArray,
+    /// The type can't be written as YAML.
+    UnsupportedType {
+        /// Name of the type.
+        type_name: String,
+    },
+    /// The destination of the document returned an error.
+    Write,
+    /// The writer of the document returned an I/O error.
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+    /// A `#[facet(yaml(...))]` attribute of a field is invalid.
+    InvalidAttribute {
+        /// Why the attribute is invalid.
+        message: String,
+    },
+    /// The value contains itself, through shared pointers such as `Rc`, so it can't be written
+    /// as a tree.
+    Cycle {
+        /// Path to where the value is reached again, such as `children[0].parent`.
+        path: String,
+    },
+    /// A float is infinite or NaN, which the options don't allow.
+    NonFiniteFloat {
+        /// The float.
+        value: f64,
+        /// Path to the float, such as `limits.ratio`, or an empty string for the root.
+        path: String,
+    },
+    /// The previous version of the document, whose layout is kept, isn't valid YAML.
+    InvalidPrevious {
+        /// Why the document is invalid.
+        message: String,
+    },
+    /// The progress callback stopped the serialization.
+    Cancelled {
+        /// Path of the node that was to be written next, or an empty string when the document
+        /// was complete.
+        path: String,
+    },
+}
+
+impl YamlSerError {
+    /// Stable identifier of the kind of error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::InvalidNumberToI64Conversion { .. } => ErrorCode::NumberConversion,
+            Self::InvalidKeyConversion { .. } => ErrorCode::KeyConversion,
+            Self::UnsupportedByteArray => ErrorCode::UnsupportedByteArray,
+            Self::UnsupportedType { .. } => ErrorCode::UnsupportedType,
+            Self::Write => ErrorCode::Write,
+            #[cfg(feature = "std")]
+            Self::Io(_) => ErrorCode::Write,
+            Self::InvalidAttribute { .. } => ErrorCode::InvalidAttribute,
+            Self::Cycle { .. } => ErrorCode::Cycle,
+            Self::NonFiniteFloat { .. } => ErrorCode::NonFiniteFloat,
+            Self::InvalidPrevious { .. } => ErrorCode::InvalidPrevious,
+            Self::Cancelled { .. } => ErrorCode::Cancelled,
+        }
+    }
+
+    /// Prefix the path of a cycle or a non-finite float with a struct field or map key.
+    pub(crate) fn in_field(self, name: &str) -> Self {
+        self.prefixed(name)
+    }
+
+    /// Prefix the path of a cycle or a non-finite float with a list index.
+    pub(crate) fn in_index(self, index: usize) -> Self {
+        self.prefixed(&format!("[{index}]"))
+    }
+
+    /// Prefix the path of a cycle or a non-finite float with a segment, separating it from a
+    /// following field with a dot. Other errors don't have a path.
+    fn prefixed(mut self, segment: &str) -> Self {
+        if let Self::Cycle { path } | Self::NonFiniteFloat { path, .. } = &mut self {
+            *path = if path.is_empty() || path.starts_with('[') {
+                format!("{segment}{path}")
+            } else {
+                format!("{segment}.{path}")
+            };
+        }
+        self
+    }
 }
 
 impl core::fmt::Display for YamlSerError {
@@ -28,11 +114,123 @@ impl core::fmt::Display for YamlSerError {
             Self::UnsupportedByteArray => {
                 write!(f, "YAML doesn't support byte arrays")
             }
+            Self::UnsupportedType { type_name } => {
+                write!(f, "Type {type_name} can't be serialized to YAML")
+            }
+            Self::Write => write!(f, "Failed to write the YAML document"),
+            #[cfg(feature = "std")]
+            Self::Io(err) => write!(f, "Failed to write the YAML document: {err}"),
+            Self::InvalidAttribute { message } => write!(f, "{message}"),
+            Self::Cycle { path } if path.is_empty() => {
+                write!(
+                    f,
+                    "The value contains itself, so it can't be serialized to YAML"
+                )
+            }
+            Self::Cycle { path } => write!(
+                f,
+                "The value contains itself at `{path}`, so it can't be serialized to YAML"
+            ),
+            Self::NonFiniteFloat { value, path } if path.is_empty() => {
+                write!(
+                    f,
+                    "Float {value} isn't finite, and non-finite floats are disabled"
+                )
+            }
+            Self::NonFiniteFloat { value, path } => write!(
+                f,
+                "Float {value} at `{path}` isn't finite, and non-finite floats are disabled"
+            ),
+            Self::InvalidPrevious { message } => {
+                write!(
+                    f,
+                    "The previous version of the document is invalid: {message}"
+                )
+            }
+            Self::Cancelled { path } if path.is_empty() => {
+                write!(
+                    f,
+                    "Serialization was cancelled after the document was written"
+                )
+            }
+            Self::Cancelled { path } => {
+                write!(f, "Serialization was cancelled before `{path}` was written")
+            }
         }
     }
 }
 
-impl core::error::Error for YamlSerError {}
+impl core::error::Error for YamlSerError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<core::fmt::Error> for YamlSerError {
+    fn from(_: core::fmt::Error) -> Self {
+        Self::Write
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for YamlSerError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for YamlSerError {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        match self {
+            Self::InvalidNumberToI64Conversion { source_type } => {
+                defmt::write!(
+                    f,
+                    "Error converting {=str} to i64, out of range",
+                    source_type
+                )
+            }
+            Self::InvalidKeyConversion { yaml_type } => {
+                defmt::write!(f, "Error converting type {=str} to YAML key", yaml_type)
+            }
+            Self::UnsupportedByteArray => defmt::write!(f, "YAML doesn't support byte arrays"),
+            Self::UnsupportedType { type_name } => defmt::write!(
+                f,
+                "Type {=str} can't be serialized to YAML",
+                type_name.as_str()
+            ),
+            Self::Write => defmt::write!(f, "Failed to write the YAML document"),
+            #[cfg(feature = "std")]
+            Self::Io(_) => defmt::write!(f, "Failed to write the YAML document"),
+            Self::InvalidAttribute { message } => defmt::write!(f, "{=str}", message.as_str()),
+            Self::Cycle { path } => defmt::write!(
+                f,
+                "The value contains itself at `{=str}`, so it can't be serialized to YAML",
+                path.as_str()
+            ),
+            Self::NonFiniteFloat { value, path } => defmt::write!(
+                f,
+                "Float {=f64} at `{=str}` isn't finite, and non-finite floats are disabled",
+                value,
+                path.as_str()
+            ),
+            Self::InvalidPrevious { message } => defmt::write!(
+                f,
+                "The previous version of the document is invalid: {=str}",
+                message.as_str()
+            ),
+            Self::Cancelled { path } => defmt::write!(
+                f,
+                "Serialization was cancelled before `{=str}` was written",
+                path.as_str()
+            ),
+        }
+    }
+}
 
 impl core::fmt::Debug for YamlSerError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -3,24 +3,43 @@
 #[cfg(not(feature = "alloc"))]
 compile_error!("feature `alloc` is required");
 
+mod emitter;
 mod error;
+mod options;
+mod previous;
+mod progress;
+pub(crate) mod tree;
 
 use core::borrow::Borrow as _;
+#[cfg(feature = "std")]
+use std::io::Write as _;
 
 use alloc::{
     borrow::Cow,
+    format,
     string::{String, ToString as _},
     vec::Vec,
 };
 
+use emitter::Emitter;
 pub use error::YamlSerError;
-use facet_serialize::{Serialize, Serializer};
+use facet_reflect::Peek;
+use facet_serialize::Serializer;
+pub use options::{
+    AmbiguousStrings, MultilineStrings, NullStyle, QuoteStyle, SerializeOptions, SortKeys,
+};
+pub use progress::Progress;
+#[cfg(feature = "std")]
+use progress::{ProgressCallback, Reporter};
+use tree::Document;
 use yaml_rust2::{
-    Yaml, YamlEmitter,
+    Yaml,
     yaml::{Array, Hash},
 };
 
-/// Serializer for YAML values.
+/// Serializer for YAML values, for use with `facet-serialize`.
+///
+/// [`to_string`] doesn't use it, since it reads the values directly.
 pub struct YamlSerializer {
     /// Current stack of where we are in the tree.
     key_stack: Vec<Cow<'static, str>>,
@@ -28,15 +47,23 @@ pub struct YamlSerializer {
     yaml: Yaml,
     /// What type the current item is.
     current: KeyOrValue,
+    /// How the document is written.
+    options: SerializeOptions,
 }
 
 impl YamlSerializer {
     /// Create a new serialzer.
     pub fn new() -> Self {
+        Self::with_options(SerializeOptions::default())
+    }
+
+    /// Create a new serializer that writes the document with the given options.
+    pub fn with_options(options: SerializeOptions) -> Self {
         Self {
             key_stack: Vec::new(),
             yaml: Yaml::BadValue,
             current: KeyOrValue::Value,
+            options,
         }
     }
 
@@ -46,10 +73,18 @@ impl YamlSerializer {
     }
 
     /// Get the output serialized YAML string.
-    pub fn into_string(self) -> String {
+    ///
+    /// The `#[facet(yaml(...))]` attributes of the fields are only used by [`to_string`], since
+    /// the serializer doesn't know the type of the value otherwise.
+    pub fn into_string(mut self) -> String {
+        if self.options.skip_empty {
+            remove_empty(&mut self.yaml);
+        }
+
+        let doc = Document::from(self.yaml);
         let mut output = String::new();
-        let mut emitter = YamlEmitter::new(&mut output);
-        emitter.dump(&self.yaml).unwrap();
+        // Without a shape no attributes are read, which is the only way it can fail
+        let _ = Emitter::new(&mut output, &self.options).dump(&doc, None);
 
         output
     }
@@ -62,8 +97,9 @@ impl YamlSerializer {
                 if let Some(array) = self.current_mut().as_mut_vec() {
                     // Push it when it's an array, so we don't have to keep track of its keys
                     array.push(value);
-                } else if value == Yaml::Null {
-                    // Remove the last item if it's none and a hash value
+                } else if value == Yaml::Null && !self.key_stack.is_empty() {
+                    // Remove the last item if it's none and a hash value, a null document is
+                    // still written
                     self.remove_current();
                 } else {
                     // Convert the pushed value to the yaml type
@@ -197,7 +233,7 @@ impl Serializer for YamlSerializer {
     }
 
     fn serialize_f64(&mut self, value: f64) -> Result<(), Self::Error> {
-        self.write_value(Yaml::Real(value.to_string()))
+        self.write_value(tree::real(value, self.options.non_finite_floats)?)
     }
 
     fn serialize_bool(&mut self, value: bool) -> Result<(), Self::Error> {
@@ -227,9 +263,9 @@ impl Serializer for YamlSerializer {
     fn serialize_unit_variant(
         &mut self,
         _variant_index: usize,
-        _variant_name: &'static str,
+        variant_name: &'static str,
     ) -> Result<(), Self::Error> {
-        todo!()
+        self.write_value(Yaml::String(variant_name.to_string()))
     }
 
     fn start_object(&mut self, _len: Option<usize>) -> Result<(), Self::Error> {
@@ -291,12 +327,461 @@ enum KeyOrValue {
 }
 
 /// Serialize any `Facet` type to a YAML string.
+///
+/// The strings of fields with a `#[facet(yaml(quote))]` attribute are always quoted, for values
+/// such as version numbers that other tools might read as another type. Collections of fields
+/// with a `#[facet(yaml(flow))]` attribute are written in flow style, e.g. `[1, 2]`.
+///
+/// Fields whose value is `None` are left out, as are fields with a `#[facet(yaml(skip_empty))]`
+/// attribute whose value is an empty collection.
+///
+/// Fields with a `#[facet(yaml(embedded))]` attribute are written as a string with their own
+/// YAML document, in a literal block scalar, the way tools such as Helm and CI systems embed
+/// configuration in their own. The deserializer reads them back from it.
+///
+/// Map fields with a `#[facet(yaml(key = "name"))]` attribute are written as a sequence of their
+/// values, each with its key under `name` first. The items of a `HashMap` are sorted by their
+/// key.
+///
+/// The keys of maps may be of any type. Scalars such as integers, booleans and enums without
+/// data are written as they are, e.g. `80: http`, and other keys, such as structs, are written
+/// as complex keys after a `?`.
 #[cfg(feature = "alloc")]
 pub fn to_string<'a, T: facet_core::Facet<'a>>(value: &'a T) -> Result<String, YamlSerError> {
-    let mut serializer = YamlSerializer::new();
-    value.serialize(&mut serializer)?;
+    to_string_with_options(value, &SerializeOptions::default())
+}
+
+/// Serialize any `Facet` type to a YAML string, with custom options.
+#[cfg(feature = "alloc")]
+pub fn to_string_with_options<'a, T: facet_core::Facet<'a>>(
+    value: &'a T,
+    options: &SerializeOptions,
+) -> Result<String, YamlSerError> {
+    let doc = tree::to_yaml(Peek::new(value), options)?;
+
+    let mut output = String::new();
+    Emitter::new(&mut output, options).dump(&doc, Some(T::SHAPE))?;
+    Ok(output)
+}
+
+/// Serialize any `Facet` type to a YAML string that replaces a previous version of the
+/// document, such as a configuration file, written the way the previous version was where it can
+/// be, so a diff between them only shows what changed.
+///
+/// Keys keep their order, and new keys follow the key they follow in the value. Strings keep
+/// their quotes or block style, other scalars that are the same keep their spelling, such as
+/// `0x1F`, and collections stay in block or flow style. Sequence items are matched with their
+/// previous version by their content, or for mappings by the value of their first scalar field
+/// such as a `name`, so inserting or removing an item doesn't change how the others are written.
+/// The indentation and whether sequences are indented under their key are taken from the
+/// previous version as well.
+///
+/// The text around the document is kept as it is, as in a [`DocumentStream`](crate::DocumentStream):
+/// directives, markers, the final line break and the comments before and after the content.
+/// Comments in the content stay with the entry or item they're before or at the end of the
+/// line of, and [doc comments](SerializeOptions::doc_comments) are only written for new entries.
+/// Comments in flow collections or between the lines of a scalar aren't kept.
+///
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Config {
+///     name: String,
+///     port: u16,
+///     tags: Vec<String>,
+/// }
+///
+/// let previous = "# Edited by hand\nport: 0x1F90 # 8080\nname: 'web'\ntags: [a, b]\n";
+/// let config = Config {
+///     name: "web".into(),
+///     port: 8080,
+///     tags: vec!["a".into(), "c".into()],
+/// };
+/// let yaml = facet_yaml::to_string_over(&config, previous).unwrap();
+/// assert_eq!(
+///     yaml,
+///     "# Edited by hand\nport: 0x1F90 # 8080\nname: 'web'\ntags: [a, c]\n"
+/// );
+/// ```
+///
+/// An empty previous version, or one with only comments, is like no previous version. A previous
+/// version that isn't valid YAML, or that has more than one document, is an
+/// [`ErrorCode::InvalidPrevious`](crate::ErrorCode::InvalidPrevious) error; streams are written
+/// over with [`to_string_multi_over`].
+#[cfg(feature = "alloc")]
+pub fn to_string_over<'a, T: facet_core::Facet<'a>>(
+    value: &'a T,
+    previous: &str,
+) -> Result<String, YamlSerError> {
+    to_string_over_with_options(value, previous, &SerializeOptions::default())
+}
+
+/// Serialize any `Facet` type to a YAML string that replaces a previous version of the
+/// document, with custom options for what the previous version doesn't tell.
+#[cfg(feature = "alloc")]
+pub fn to_string_over_with_options<'a, T: facet_core::Facet<'a>>(
+    value: &'a T,
+    previous: &str,
+    options: &SerializeOptions,
+) -> Result<String, YamlSerError> {
+    let previous = previous::Previous::parse(previous)?;
+    if previous.len() > 1 {
+        return Err(YamlSerError::InvalidPrevious {
+            message: format!(
+                "it has {} documents, which `to_string_multi_over` writes over",
+                previous.len()
+            ),
+        });
+    }
+    let options = previous.options(options);
+    let doc = tree::to_yaml(Peek::new(value), &options)?;
+
+    previous.write(core::slice::from_ref(&doc), Some(T::SHAPE), &options)
+}
+
+/// Serialize any `Facet` type to the bytes of a YAML document, encoded as UTF-8.
+///
+/// This is [`to_string`] for APIs that take bytes, and is available without `std`.
+#[cfg(feature = "alloc")]
+pub fn to_vec<'a, T: facet_core::Facet<'a>>(value: &'a T) -> Result<Vec<u8>, YamlSerError> {
+    to_vec_with_options(value, &SerializeOptions::default())
+}
+
+/// Serialize any `Facet` type to the bytes of a YAML document, with custom options.
+#[cfg(feature = "alloc")]
+pub fn to_vec_with_options<'a, T: facet_core::Facet<'a>>(
+    value: &'a T,
+    options: &SerializeOptions,
+) -> Result<Vec<u8>, YamlSerError> {
+    to_string_with_options(value, options).map(String::into_bytes)
+}
+
+/// Serialize any `Facet` type as YAML into a [`core::fmt::Write`], such as a formatter or a
+/// string that already has content.
+///
+/// This is available without `std`. An error of the writer is returned as
+/// [`YamlSerError::Write`], and what was written before is left in it.
+///
+/// ```
+/// use core::fmt;
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Config {
+///     workers: u16,
+/// }
+///
+/// struct Report(Config);
+///
+/// impl fmt::Display for Report {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         writeln!(f, "# Effective configuration")?;
+///         facet_yaml::write_yaml(f, &self.0).map_err(|_| fmt::Error)
+///     }
+/// }
+///
+/// let report = Report(Config { workers: 4 }).to_string();
+/// assert_eq!(report, "# Effective configuration\n---\nworkers: 4");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn write_yaml<'a, W: core::fmt::Write, T: facet_core::Facet<'a>>(
+    writer: &mut W,
+    value: &'a T,
+) -> Result<(), YamlSerError> {
+    write_yaml_with_options(writer, value, &SerializeOptions::default())
+}
+
+/// Serialize any `Facet` type as YAML into a [`core::fmt::Write`], with custom options.
+#[cfg(feature = "alloc")]
+pub fn write_yaml_with_options<'a, W: core::fmt::Write, T: facet_core::Facet<'a>>(
+    writer: &mut W,
+    value: &'a T,
+    options: &SerializeOptions,
+) -> Result<(), YamlSerError> {
+    let doc = tree::to_yaml(Peek::new(value), options)?;
+
+    Emitter::new(writer, options).dump(&doc, Some(T::SHAPE))?;
+    Ok(())
+}
+
+/// Serialize a list of values to a YAML stream, with one document for each value.
+///
+/// Every document starts with a `---` marker, so the stream can be read back document by
+/// document, e.g. as a file of Kubernetes manifests. An empty list gives an empty stream.
+///
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Deployment {
+///     name: String,
+///     replicas: u32,
+/// }
+///
+/// let deployments = [
+///     Deployment { name: "web".into(), replicas: 2 },
+///     Deployment { name: "worker".into(), replicas: 1 },
+/// ];
+/// assert_eq!(
+///     facet_yaml::to_string_multi(&deployments).unwrap(),
+///     "---\nname: web\nreplicas: 2\n---\nname: worker\nreplicas: 1"
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+pub fn to_string_multi<'a, T: facet_core::Facet<'a>>(
+    values: &'a [T],
+) -> Result<String, YamlSerError> {
+    to_string_multi_with_options(values, &SerializeOptions::default())
+}
 
-    Ok(serializer.into_string())
+/// Serialize a list of values to a YAML stream, with custom options.
+///
+/// [`document_start`](SerializeOptions::document_start) only applies to the first document,
+/// since the others need their marker to be separated.
+#[cfg(feature = "alloc")]
+pub fn to_string_multi_with_options<'a, T: facet_core::Facet<'a>>(
+    values: &'a [T],
+    options: &SerializeOptions,
+) -> Result<String, YamlSerError> {
+    let docs = documents(values, options)?;
+
+    let mut output = String::new();
+    Emitter::new(&mut output, options).dump_stream(&docs, Some(T::SHAPE))?;
+    Ok(output)
+}
+
+/// Serialize a list of values to a YAML stream that replaces a previous version of the stream,
+/// such as a file of Kubernetes manifests, written the way the previous version was.
+///
+/// Each value is written over the document at its position, as [`to_string_over`] does, and the
+/// text between documents is kept. Values without a document are added after the last one, with
+/// a `---` marker, and documents without a value are removed along with the comments after them.
+///
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Deployment {
+///     name: String,
+///     replicas: u32,
+/// }
+///
+/// let previous = "# web\nname: web\nreplicas: 2\n---\n# worker\nname: worker\nreplicas: 1\n";
+/// let deployments = [
+///     Deployment { name: "web".into(), replicas: 4 },
+///     Deployment { name: "worker".into(), replicas: 1 },
+/// ];
+/// assert_eq!(
+///     facet_yaml::to_string_multi_over(&deployments, previous).unwrap(),
+///     "# web\nname: web\nreplicas: 4\n---\n# worker\nname: worker\nreplicas: 1\n"
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+pub fn to_string_multi_over<'a, T: facet_core::Facet<'a>>(
+    values: &'a [T],
+    previous: &str,
+) -> Result<String, YamlSerError> {
+    to_string_multi_over_with_options(values, previous, &SerializeOptions::default())
+}
+
+/// Serialize a list of values to a YAML stream that replaces a previous version of the stream,
+/// with custom options for what the previous version doesn't tell.
+#[cfg(feature = "alloc")]
+pub fn to_string_multi_over_with_options<'a, T: facet_core::Facet<'a>>(
+    values: &'a [T],
+    previous: &str,
+    options: &SerializeOptions,
+) -> Result<String, YamlSerError> {
+    let previous = previous::Previous::parse(previous)?;
+    let options = previous.options(options);
+    let docs = documents(values, &options)?;
+
+    previous.write(&docs, Some(T::SHAPE), &options)
+}
+
+/// Convert each value of a stream to a YAML node.
+fn documents<'a, T: facet_core::Facet<'a>>(
+    values: &'a [T],
+    options: &SerializeOptions,
+) -> Result<Vec<Document>, YamlSerError> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            tree::to_yaml(Peek::new(value), options).map_err(|e| e.in_index(index))
+        })
+        .collect()
+}
+
+/// Serialize any `Facet` type as YAML into a writer, such as a file or a socket.
+///
+/// The document is written while it's generated instead of being built as a string first. The
+/// writer is buffered, so it doesn't need to be a `BufWriter`.
+///
+/// ```no_run
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Config {
+///     workers: u16,
+/// }
+///
+/// let mut file = std::fs::File::create("config.yaml")?;
+/// facet_yaml::to_writer(&mut file, &Config { workers: 4 })?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "std")]
+pub fn to_writer<'a, W: std::io::Write, T: facet_core::Facet<'a>>(
+    writer: &mut W,
+    value: &'a T,
+) -> Result<(), YamlSerError> {
+    to_writer_with_options(writer, value, &SerializeOptions::default())
+}
+
+/// Serialize any `Facet` type as YAML into a writer, with custom options.
+#[cfg(feature = "std")]
+pub fn to_writer_with_options<'a, W: std::io::Write, T: facet_core::Facet<'a>>(
+    writer: &mut W,
+    value: &'a T,
+    options: &SerializeOptions,
+) -> Result<(), YamlSerError> {
+    let doc = tree::to_yaml(Peek::new(value), options)?;
+
+    write_io(
+        writer,
+        core::slice::from_ref(&doc),
+        Some(T::SHAPE),
+        options,
+        None,
+    )
+}
+
+/// Serialize any `Facet` type as YAML into a writer, calling `progress` as the document is
+/// written, such as to show a progress bar for a large export.
+///
+/// The callback is called with the number of bytes written so far and the path of the node
+/// written next, each time at least [`progress_interval`](SerializeOptions::progress_interval)
+/// bytes were written since the last call, and once more when the document is complete.
+/// Returning [`ControlFlow::Break`](core::ops::ControlFlow::Break) stops the serialization
+/// with a [`YamlSerError::Cancelled`] error, such as when a timeout is reached. What was
+/// written before is left in the writer.
+///
+/// The value is converted before it's written, so the callback is only called while the
+/// document is written.
+///
+/// ```
+/// use std::ops::ControlFlow;
+///
+/// let values: Vec<u32> = (0..1000).collect();
+/// let options = facet_yaml::SerializeOptions::new().progress_interval(1024);
+///
+/// let mut output = Vec::new();
+/// let mut reports = Vec::new();
+/// facet_yaml::to_writer_with_progress(&mut output, &values, &options, |progress| {
+///     reports.push(progress.bytes_written());
+///     ControlFlow::Continue(())
+/// })?;
+/// assert_eq!(reports.len(), 6);
+/// assert_eq!(reports.last(), Some(&(output.len() as u64)));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "std")]
+pub fn to_writer_with_progress<'a, W: std::io::Write, T: facet_core::Facet<'a>>(
+    writer: &mut W,
+    value: &'a T,
+    options: &SerializeOptions,
+    mut progress: impl FnMut(&Progress<'_>) -> core::ops::ControlFlow<()>,
+) -> Result<(), YamlSerError> {
+    let doc = tree::to_yaml(Peek::new(value), options)?;
+
+    write_io(
+        writer,
+        core::slice::from_ref(&doc),
+        Some(T::SHAPE),
+        options,
+        Some(&mut progress),
+    )
+}
+
+/// Serialize a list of values as a YAML stream into a writer, with one document for each value.
+///
+/// See [`to_string_multi`] for how the documents are separated.
+#[cfg(feature = "std")]
+pub fn to_writer_multi<'a, W: std::io::Write, T: facet_core::Facet<'a>>(
+    writer: &mut W,
+    values: &'a [T],
+) -> Result<(), YamlSerError> {
+    to_writer_multi_with_options(writer, values, &SerializeOptions::default())
+}
+
+/// Serialize a list of values as a YAML stream into a writer, with custom options.
+#[cfg(feature = "std")]
+pub fn to_writer_multi_with_options<'a, W: std::io::Write, T: facet_core::Facet<'a>>(
+    writer: &mut W,
+    values: &'a [T],
+    options: &SerializeOptions,
+) -> Result<(), YamlSerError> {
+    let docs = documents(values, options)?;
+
+    write_io(writer, &docs, Some(T::SHAPE), options, None)
+}
+
+/// Write a stream of documents of the given shape into a writer, through a buffer, reporting
+/// how far it is with `progress` if given.
+#[cfg(feature = "std")]
+fn write_io<W: std::io::Write>(
+    writer: &mut W,
+    docs: &[Document],
+    shape: Option<&'static facet_core::Shape>,
+    options: &SerializeOptions,
+    progress: Option<&mut ProgressCallback<'_>>,
+) -> Result<(), YamlSerError> {
+    let mut out = IoWriter {
+        inner: std::io::BufWriter::new(writer),
+        error: None,
+    };
+    let result = Emitter::new(&mut out, options)
+        .with_progress(progress.map(|callback| Reporter::new(callback, options.progress_interval)))
+        .dump_stream(docs, shape);
+    if let Some(err) = out.error {
+        return Err(YamlSerError::Io(err));
+    }
+    result?;
+    out.inner.flush()?;
+    Ok(())
+}
+
+/// Writes text into an I/O writer, keeping the error it returned since `fmt::Error` can't hold
+/// it.
+#[cfg(feature = "std")]
+struct IoWriter<W: std::io::Write> {
+    /// Where the text is written to.
+    inner: W,
+    /// The first error of the writer.
+    error: Option<std::io::Error>,
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> core::fmt::Write for IoWriter<W> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|err| {
+            self.error = Some(err);
+            core::fmt::Error
+        })
+    }
+}
+
+/// Remove the entries of mappings whose value is an empty collection, at any depth.
+fn remove_empty(yaml: &mut Yaml) {
+    match yaml {
+        Yaml::Array(array) => array.iter_mut().for_each(remove_empty),
+        Yaml::Hash(hash) => hash.retain(|_, value| {
+            remove_empty(value);
+            !tree::is_empty(value)
+        }),
+        _ => {}
+    }
 }
 
 /// Static type name for a YAML type.
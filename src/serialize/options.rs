@@ -0,0 +1,651 @@
+//! Options to change how YAML documents are serialized.
+
+#[cfg(feature = "deserialize")]
+use crate::{
+    YamlCustomize,
+    customize::{self, Customization},
+};
+
+/// Options for serializing YAML documents.
+///
+/// ```
+/// use facet_yaml::{AmbiguousStrings, SerializeOptions};
+///
+/// let options = SerializeOptions::new().ambiguous_strings(AmbiguousStrings::Quote);
+/// let yaml = facet_yaml::to_string_with_options(&"2024-01-01".to_string(), &options).unwrap();
+/// assert_eq!(yaml, "---\n\"2024-01-01\"");
+/// ```
+///
+/// The layout can be changed to match an existing style guide:
+///
+/// ```
+/// use facet::Facet;
+/// use facet_yaml::SerializeOptions;
+///
+/// #[derive(Facet)]
+/// struct Deployment {
+///     name: String,
+///     ports: Vec<u16>,
+/// }
+///
+/// let options = SerializeOptions::new()
+///     .indent(4)
+///     .indent_sequences(false)
+///     .document_start(false)
+///     .trailing_newline(true);
+/// let deployment = Deployment {
+///     name: "web".into(),
+///     ports: vec![80, 443],
+/// };
+/// let yaml = facet_yaml::to_string_with_options(&deployment, &options).unwrap();
+/// assert_eq!(yaml, "name: web\nports:\n- 80\n- 443\n");
+/// ```
+#[derive(Debug, Clone)]
+pub struct SerializeOptions {
+    /// How to write strings that YAML 1.1 consumers would read as another type.
+    pub(crate) ambiguous_strings: AmbiguousStrings,
+    /// How string values are quoted.
+    pub(crate) quote_style: QuoteStyle,
+    /// How strings with line breaks are written.
+    pub(crate) multiline_strings: MultilineStrings,
+    /// Which mappings have their keys sorted.
+    pub(crate) sort_keys: SortKeys,
+    /// How null values are written.
+    pub(crate) null_style: NullStyle,
+    /// Whether the doc comments of fields are written above their keys.
+    pub(crate) doc_comments: bool,
+    /// Number of spaces for each nesting level.
+    pub(crate) indent: usize,
+    /// Whether sequences that are values of a mapping are indented under their key.
+    pub(crate) indent_sequences: bool,
+    /// Whether the document starts with a `---` marker.
+    pub(crate) document_start: bool,
+    /// Whether the document ends with a `...` marker.
+    pub(crate) document_end: bool,
+    /// Whether the document starts with a `%YAML 1.2` directive.
+    pub(crate) yaml_directive: bool,
+    /// Whether the document ends with a line break.
+    pub(crate) trailing_newline: bool,
+    /// Nesting level from which collections are written in flow style.
+    pub(crate) flow_depth: Option<usize>,
+    /// Whether fields and map entries whose value is an empty collection are left out.
+    pub(crate) skip_empty: bool,
+    /// Whether fields that are the same as their default are written as comments.
+    pub(crate) comment_defaults: bool,
+    /// Whether byte buffers are written as `!!binary` scalars.
+    pub(crate) binary: bool,
+    /// Whether values behind shared pointers are written once, with aliases to them.
+    pub(crate) anchors: bool,
+    /// Whether infinite and NaN floats are written, instead of being an error.
+    pub(crate) non_finite_floats: bool,
+    /// Whether sequences of numbers are written in flow style, with as many on each line as fit.
+    pub(crate) pack_numbers: bool,
+    /// Width that lines are wrapped at, including their indentation.
+    pub(crate) line_width: usize,
+    /// Number of bytes written between two calls of a progress callback.
+    pub(crate) progress_interval: usize,
+    /// Types that are written with their [`YamlCustomize`] instead of their shape.
+    #[cfg(feature = "deserialize")]
+    pub(crate) customized: alloc::vec::Vec<Customization>,
+}
+
+impl SerializeOptions {
+    /// Create the default options.
+    pub fn new() -> Self {
+        Self {
+            ambiguous_strings: AmbiguousStrings::Plain,
+            quote_style: QuoteStyle::Plain,
+            multiline_strings: MultilineStrings::Literal,
+            sort_keys: SortKeys::Never,
+            null_style: NullStyle::Tilde,
+            doc_comments: false,
+            indent: 2,
+            indent_sequences: true,
+            document_start: true,
+            document_end: false,
+            yaml_directive: false,
+            trailing_newline: false,
+            flow_depth: None,
+            skip_empty: false,
+            comment_defaults: false,
+            binary: true,
+            anchors: false,
+            non_finite_floats: true,
+            pack_numbers: false,
+            line_width: 80,
+            progress_interval: 64 * 1024,
+            #[cfg(feature = "deserialize")]
+            customized: alloc::vec::Vec::new(),
+        }
+    }
+
+    /// Set the number of spaces for each nesting level, 2 by default.
+    ///
+    /// Values below 2 are raised to 2, since the `- ` before the items of a sequence is as wide.
+    pub fn indent(mut self, indent: usize) -> Self {
+        self.indent = indent.max(2);
+        self
+    }
+
+    /// Set whether sequences that are values of a mapping are indented under their key, which
+    /// is the default. When disabled, the `-` of their items is aligned with the key, as in
+    /// Kubernetes manifests.
+    pub fn indent_sequences(mut self, indent_sequences: bool) -> Self {
+        self.indent_sequences = indent_sequences;
+        self
+    }
+
+    /// Set whether the document starts with a `---` marker, which is the default.
+    ///
+    /// The marker is written anyway when the document has a
+    /// [`%YAML` directive](Self::yaml_directive).
+    pub fn document_start(mut self, document_start: bool) -> Self {
+        self.document_start = document_start;
+        self
+    }
+
+    /// Set whether the document ends with a `...` marker on its own line, which isn't the
+    /// default.
+    ///
+    /// ```
+    /// use facet_yaml::SerializeOptions;
+    ///
+    /// let options = SerializeOptions::new().document_end(true);
+    /// let yaml = facet_yaml::to_string_with_options(&vec![80u16, 443], &options).unwrap();
+    /// assert_eq!(yaml, "---\n- 80\n- 443\n...");
+    /// ```
+    pub fn document_end(mut self, document_end: bool) -> Self {
+        self.document_end = document_end;
+        self
+    }
+
+    /// Set whether the document starts with a `%YAML 1.2` directive, which isn't the default,
+    /// for consumers that would otherwise read it as YAML 1.1.
+    ///
+    /// The directive is always followed by a `---` marker, even when
+    /// [`document_start`](Self::document_start) is disabled. In a stream every document has the
+    /// directive, and the ones before it end with `...`, as YAML requires.
+    ///
+    /// ```
+    /// use facet_yaml::SerializeOptions;
+    ///
+    /// let options = SerializeOptions::new().yaml_directive(true).document_start(false);
+    /// let yaml = facet_yaml::to_string_with_options(&"web".to_string(), &options).unwrap();
+    /// assert_eq!(yaml, "%YAML 1.2\n---\nweb");
+    /// ```
+    pub fn yaml_directive(mut self, yaml_directive: bool) -> Self {
+        self.yaml_directive = yaml_directive;
+        self
+    }
+
+    /// Set whether the document ends with a line break, which isn't the default.
+    pub fn trailing_newline(mut self, trailing_newline: bool) -> Self {
+        self.trailing_newline = trailing_newline;
+        self
+    }
+
+    /// Set whether fields and map entries whose value is an empty sequence or mapping are left
+    /// out, which isn't the default. Those whose value is `None` are always left out.
+    ///
+    /// Reading the document back needs `#[facet(default)]` on the fields that may be left out.
+    /// Single fields can be left out when empty with `#[facet(yaml(skip_empty))]` instead.
+    pub fn skip_empty(mut self, skip_empty: bool) -> Self {
+        self.skip_empty = skip_empty;
+        self
+    }
+
+    /// Set whether fields that are the same as their default are written as comments, which
+    /// isn't the default, so a generated configuration file lists every setting without
+    /// changing any.
+    ///
+    /// The default of a field is the value it's read back as when it's missing: the one of its
+    /// `#[facet(default)]` attribute, or else the one of its struct. Fields without a default
+    /// are always written. Like the attributes of fields, this is only used by
+    /// [`to_string`](crate::to_string) and the functions like it, and comments are left out of
+    /// collections in flow style.
+    ///
+    /// ```
+    /// use facet::Facet;
+    /// use facet_yaml::SerializeOptions;
+    ///
+    /// #[derive(Facet)]
+    /// struct Client {
+    ///     url: String,
+    ///     #[facet(default = 3)]
+    ///     retries: u32,
+    ///     #[facet(default = 30)]
+    ///     timeout: u64,
+    /// }
+    ///
+    /// let client = Client {
+    ///     url: "https://example.com".into(),
+    ///     retries: 3,
+    ///     timeout: 10,
+    /// };
+    /// let options = SerializeOptions::new().comment_defaults(true);
+    /// assert_eq!(
+    ///     facet_yaml::to_string_with_options(&client, &options).unwrap(),
+    ///     "---\nurl: \"https://example.com\"\n# retries: 3\ntimeout: 10"
+    /// );
+    /// ```
+    pub fn comment_defaults(mut self, comment_defaults: bool) -> Self {
+        self.comment_defaults = comment_defaults;
+        self
+    }
+
+    /// Set whether byte buffers such as `Vec<u8>` are written as `!!binary` scalars with their
+    /// base64 text, which is the default, instead of sequences of integers.
+    ///
+    /// ```
+    /// use facet::Facet;
+    /// use facet_yaml::SerializeOptions;
+    ///
+    /// #[derive(Facet)]
+    /// struct Secret {
+    ///     key: Vec<u8>,
+    /// }
+    ///
+    /// let secret = Secret { key: b"hi!".to_vec() };
+    /// assert_eq!(facet_yaml::to_string(&secret).unwrap(), "---\nkey: !!binary aGkh");
+    ///
+    /// let options = SerializeOptions::new().binary(false);
+    /// assert_eq!(
+    ///     facet_yaml::to_string_with_options(&secret, &options).unwrap(),
+    ///     "---\nkey:\n  - 104\n  - 105\n  - 33"
+    /// );
+    /// ```
+    pub fn binary(mut self, binary: bool) -> Self {
+        self.binary = binary;
+        self
+    }
+
+    /// Set whether values that are shared through pointers such as `Arc` or `Rc` are written in
+    /// full only the first time, with an anchor, and as an alias of it after that. This isn't
+    /// the default, since some tools don't support aliases.
+    ///
+    /// Pointers are shared when they point to the same value, e.g. clones of an `Arc`. Values
+    /// that are `None` or empty collections are never aliased. When the document is read back,
+    /// each alias is a copy of the anchored value.
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use facet::Facet;
+    /// use facet_yaml::SerializeOptions;
+    ///
+    /// #[derive(Facet)]
+    /// struct Upstream {
+    ///     host: String,
+    /// }
+    ///
+    /// #[derive(Facet)]
+    /// struct Routes {
+    ///     api: Arc<Upstream>,
+    ///     web: Arc<Upstream>,
+    /// }
+    ///
+    /// let upstream = Arc::new(Upstream { host: "10.0.0.1".into() });
+    /// let routes = Routes { api: upstream.clone(), web: upstream };
+    ///
+    /// let options = SerializeOptions::new().anchors(true);
+    /// assert_eq!(
+    ///     facet_yaml::to_string_with_options(&routes, &options).unwrap(),
+    ///     "---\napi: &id001\n  host: 10.0.0.1\nweb: *id001"
+    /// );
+    /// ```
+    pub fn anchors(mut self, anchors: bool) -> Self {
+        self.anchors = anchors;
+        self
+    }
+
+    /// Set whether the doc comments of struct fields are written as `#` comments above their
+    /// keys, which isn't the default.
+    ///
+    /// This makes a serialized default value a self-documenting configuration file. Collections
+    /// in flow style have no comments, since they're on a single line. Lines of text are wrapped
+    /// at the [line width](Self::line_width), with the comment continuing at the column of the
+    /// key, while indented lines such as code examples are kept as they are.
+    ///
+    /// ```
+    /// use facet::Facet;
+    /// use facet_yaml::SerializeOptions;
+    ///
+    /// #[derive(Facet)]
+    /// struct Config {
+    ///     /// Name of the cluster.
+    ///     name: String,
+    ///     /// Number of workers.
+    ///     ///
+    ///     /// Each one runs on its own thread.
+    ///     workers: u16,
+    /// }
+    ///
+    /// let config = Config {
+    ///     name: "prod".into(),
+    ///     workers: 4,
+    /// };
+    /// let options = SerializeOptions::new().doc_comments(true);
+    /// assert_eq!(
+    ///     facet_yaml::to_string_with_options(&config, &options).unwrap(),
+    ///     "---\n# Name of the cluster.\nname: prod\n# Number of workers.\n#\n# Each one runs \
+    ///      on its own thread.\nworkers: 4"
+    /// );
+    /// ```
+    pub fn doc_comments(mut self, doc_comments: bool) -> Self {
+        self.doc_comments = doc_comments;
+        self
+    }
+
+    /// Set whether infinite and NaN floats are written as `.inf`, `-.inf` and `.nan`, which is
+    /// the default, instead of being an error.
+    ///
+    /// Disabling them is meant for documents that are converted to JSON later, which has no
+    /// way to write them.
+    ///
+    /// ```
+    /// use facet_yaml::{ErrorCode, SerializeOptions};
+    ///
+    /// let values = vec![1.5, f64::INFINITY, f64::NAN];
+    /// assert_eq!(
+    ///     facet_yaml::to_string(&values).unwrap(),
+    ///     "---\n- 1.5\n- .inf\n- .nan"
+    /// );
+    ///
+    /// let options = SerializeOptions::new().non_finite_floats(false);
+    /// let err = facet_yaml::to_string_with_options(&values, &options).unwrap_err();
+    /// assert_eq!(err.code(), ErrorCode::NonFiniteFloat);
+    /// ```
+    pub fn non_finite_floats(mut self, non_finite_floats: bool) -> Self {
+        self.non_finite_floats = non_finite_floats;
+        self
+    }
+
+    /// Set whether sequences of numbers are written in flow style, with as many numbers on each
+    /// line as fit in the [`line_width`](Self::line_width), which isn't the default.
+    ///
+    /// This keeps large numeric data, such as embeddings or the rows of a matrix, short, instead
+    /// of writing each number on its own line. Sequences with anything other than integers and
+    /// floats are written as usual.
+    ///
+    /// ```
+    /// use facet::Facet;
+    /// use facet_yaml::SerializeOptions;
+    ///
+    /// #[derive(Facet)]
+    /// struct Model {
+    ///     weights: Vec<f32>,
+    /// }
+    ///
+    /// let model = Model {
+    ///     weights: vec![0.25, -1.5, 3.0, 0.125, 8.0, -0.5],
+    /// };
+    /// let options = SerializeOptions::new().pack_numbers(true).line_width(32);
+    /// assert_eq!(
+    ///     facet_yaml::to_string_with_options(&model, &options).unwrap(),
+    ///     "---\nweights: [0.25, -1.5, 3.0,\n  0.125, 8.0, -0.5]"
+    /// );
+    /// ```
+    pub fn pack_numbers(mut self, pack_numbers: bool) -> Self {
+        self.pack_numbers = pack_numbers;
+        self
+    }
+
+    /// Set the width that lines are wrapped at, including their indentation, 80 by default.
+    ///
+    /// It applies to [folded](MultilineStrings::Folded) block scalars,
+    /// [packed numbers](Self::pack_numbers) and [doc comments](Self::doc_comments). Words and
+    /// numbers that are wider are written on a line of their own.
+    pub fn line_width(mut self, line_width: usize) -> Self {
+        self.line_width = line_width;
+        self
+    }
+
+    /// Set how many bytes are written between two calls of the progress callback of
+    /// [`to_writer_with_progress`](crate::to_writer_with_progress), 64 KiB by default.
+    ///
+    /// The callback is called between nodes, so more bytes may be written before it's called,
+    /// such as for a long string.
+    pub fn progress_interval(mut self, bytes: usize) -> Self {
+        self.progress_interval = bytes;
+        self
+    }
+
+    /// Write collections nested at least `depth` levels below the root in flow style, e.g.
+    /// `[1, 2]` or `{name: web}`, while the outer ones stay in block style.
+    ///
+    /// With a depth of 1 the root mapping is written in block style and everything in it is
+    /// written on one line per entry. A depth of 0 writes the whole document on one line. Fields
+    /// can be written in flow style regardless of their depth with `#[facet(yaml(flow))]`.
+    pub fn flow_depth(mut self, depth: usize) -> Self {
+        self.flow_depth = Some(depth);
+        self
+    }
+
+    /// How to write strings that are plain strings in YAML 1.2, but that YAML 1.1 consumers
+    /// would read as another type, such as `2024-01-01` (a timestamp) or `1_000` (an integer).
+    ///
+    /// Strings that YAML 1.2 would read as another type, such as `0x1F` or `true`, are always
+    /// quoted.
+    pub fn ambiguous_strings(mut self, ambiguous_strings: AmbiguousStrings) -> Self {
+        self.ambiguous_strings = ambiguous_strings;
+        self
+    }
+
+    /// Set how string values are quoted. By default they're only quoted when they have to be,
+    /// with double quotes.
+    ///
+    /// Keys of mappings are only quoted when they have to be, but with the quotes of the style.
+    ///
+    /// ```
+    /// use facet_yaml::{QuoteStyle, SerializeOptions};
+    ///
+    /// let options = SerializeOptions::new().quote_style(QuoteStyle::Single);
+    /// let tags = vec!["web".to_string(), "it's".to_string()];
+    /// let yaml = facet_yaml::to_string_with_options(&tags, &options).unwrap();
+    /// assert_eq!(yaml, "---\n- 'web'\n- 'it''s'");
+    /// ```
+    pub fn quote_style(mut self, quote_style: QuoteStyle) -> Self {
+        self.quote_style = quote_style;
+        self
+    }
+
+    /// Set how strings with line breaks are written, as literal block scalars by default.
+    ///
+    /// ```
+    /// let script = "set -e\nmake install\n".to_string();
+    /// let yaml = facet_yaml::to_string(&script).unwrap();
+    /// assert_eq!(yaml, "---\n|\n  set -e\n  make install\n");
+    /// ```
+    pub fn multiline_strings(mut self, multiline_strings: MultilineStrings) -> Self {
+        self.multiline_strings = multiline_strings;
+        self
+    }
+
+    /// Set which mappings have their keys sorted, so the output doesn't depend on the iteration
+    /// order of maps such as `HashMap`. Keys aren't sorted by default, and a `BTreeMap` is always
+    /// written in the order of its keys.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use facet_yaml::{SerializeOptions, SortKeys};
+    ///
+    /// let labels = HashMap::from([("tier", "web"), ("app", "shop"), ("env", "prod")]);
+    /// let options = SerializeOptions::new().sort_keys(SortKeys::Maps);
+    /// let yaml = facet_yaml::to_string_with_options(&labels, &options).unwrap();
+    /// assert_eq!(yaml, "---\napp: shop\nenv: prod\ntier: web");
+    /// ```
+    pub fn sort_keys(mut self, sort_keys: SortKeys) -> Self {
+        self.sort_keys = sort_keys;
+        self
+    }
+
+    /// Set how null values, such as `None` items of lists and unit values, are written, `~` by
+    /// default.
+    ///
+    /// Fields whose value is `None` are left out whatever the style.
+    ///
+    /// ```
+    /// use facet_yaml::{NullStyle, SerializeOptions};
+    ///
+    /// let values = vec![Some(1), None];
+    /// let options = SerializeOptions::new().null_style(NullStyle::Null);
+    /// let yaml = facet_yaml::to_string_with_options(&values, &options).unwrap();
+    /// assert_eq!(yaml, "---\n- 1\n- null");
+    /// ```
+    pub fn null_style(mut self, null_style: NullStyle) -> Self {
+        self.null_style = null_style;
+        self
+    }
+
+    /// Write a type with its [`YamlCustomize`] implementation wherever it is in the document,
+    /// instead of by its shape.
+    ///
+    /// The [`Value`](crate::Value) returned by [`YamlCustomize::to_yaml`] is written in place of
+    /// the value, after the [`TAG`](YamlCustomize::TAG) of the type if it has one. Collections
+    /// with a tag are written in flow style.
+    ///
+    /// ```
+    /// use facet::Facet;
+    /// use facet_yaml::{SerializeOptions, Value, YamlCustomize};
+    ///
+    /// #[derive(Debug, Facet, PartialEq)]
+    /// struct Range {
+    ///     start: u32,
+    ///     end: u32,
+    /// }
+    ///
+    /// impl YamlCustomize for Range {
+    ///     const TAG: Option<&'static str> = Some("!range");
+    ///
+    ///     fn to_yaml(&self) -> Value {
+    ///         Value::Sequence(vec![
+    ///             Value::Number(u64::from(self.start).into()),
+    ///             Value::Number(u64::from(self.end).into()),
+    ///         ])
+    ///     }
+    ///
+    ///     fn from_yaml(value: Value) -> Result<Self, String> {
+    ///         match value.as_sequence() {
+    ///             Some([start, end]) => Ok(Range {
+    ///                 start: start.as_u64().ok_or("Invalid start")? as u32,
+    ///                 end: end.as_u64().ok_or("Invalid end")? as u32,
+    ///             }),
+    ///             _ => Err("Expected `[start, end]`".to_string()),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let options = SerializeOptions::new().customize::<Range>();
+    /// let ranges = vec![Range { start: 1, end: 5 }];
+    /// let yaml = facet_yaml::to_string_with_options(&ranges, &options).unwrap();
+    /// assert_eq!(yaml, "---\n- !range [1, 5]");
+    /// ```
+    #[cfg(feature = "deserialize")]
+    pub fn customize<T: YamlCustomize>(mut self) -> Self {
+        self.customized
+            .retain(|customization| customization.shape.id != T::SHAPE.id);
+        self.customized.push(Customization::of::<T>());
+        self
+    }
+
+    /// Get the customization registered for a type.
+    #[cfg(feature = "deserialize")]
+    pub(crate) fn customization(&self, shape: &facet_core::Shape) -> Option<Customization> {
+        customize::find(&self.customized, shape)
+    }
+}
+
+impl Default for SerializeOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How to write strings that YAML 1.1 consumers would read as another type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AmbiguousStrings {
+    /// Write them as plain scalars, which is enough for YAML 1.2 consumers such as this crate.
+    #[default]
+    Plain,
+    /// Write them as double-quoted scalars, e.g. `"2024-01-01"`.
+    Quote,
+    /// Write them with an explicit `!!str` tag, e.g. `!!str 2024-01-01`.
+    Tag,
+}
+
+/// How string values are quoted.
+///
+/// Strings are only written as plain scalars when they're read back as the same string. To also
+/// quote the ones that YAML 1.1 consumers would read as another type, such as `yes` or
+/// `2024-01-01`, use [`AmbiguousStrings::Quote`] with the plain style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuoteStyle {
+    /// Write strings as plain scalars when it's safe, and double-quote them otherwise.
+    #[default]
+    Plain,
+    /// Always single-quote strings, e.g. `'web'`. Strings with control characters are
+    /// double-quoted instead, since single-quoted scalars can't escape them.
+    Single,
+    /// Always double-quote strings, e.g. `"web"`.
+    Double,
+}
+
+/// How strings with line breaks are written.
+///
+/// Block scalars are used whatever the [`QuoteStyle`], since they're always read as strings. They
+/// can't be used inside flow collections, or for strings whose first line starts with a space,
+/// that have lines with only spaces, or control characters other than tabs, which are quoted
+/// instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MultilineStrings {
+    /// Write them as literal block scalars, which keep every line as it is:
+    ///
+    /// ```yaml
+    /// script: |
+    ///   set -e
+    ///   make install
+    /// ```
+    #[default]
+    Literal,
+    /// Write them as folded block scalars, where long lines are wrapped at the
+    /// [line width](SerializeOptions::line_width):
+    ///
+    /// ```yaml
+    /// description: >-
+    ///   A long paragraph that continues
+    ///   on the next line.
+    /// ```
+    Folded,
+    /// Write them as double-quoted scalars, with escaped line breaks, e.g. `"a\nb"`.
+    Quoted,
+}
+
+/// Which mappings have their keys sorted when serializing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortKeys {
+    /// Write keys in the order of the value: the iteration order of maps and the declaration
+    /// order of struct fields.
+    #[default]
+    Never,
+    /// Sort the keys of maps, and keep struct fields in their declaration order.
+    ///
+    /// A `BTreeMap` keeps the order of its keys, which is already sorted, even when it differs
+    /// from the order of the keys as written, e.g. enum variants in their declaration order.
+    Maps,
+    /// Sort the keys of maps and the fields of structs, keeping the order of a `BTreeMap` as
+    /// with [`SortKeys::Maps`].
+    MapsAndStructs,
+}
+
+/// How null values are written when serializing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NullStyle {
+    /// Write them as `~`.
+    #[default]
+    Tilde,
+    /// Write them as `null`, for consumers that don't read `~`.
+    Null,
+    /// Write nothing after the `-` or `:` before them, e.g. `key:`. Nulls in flow collections
+    /// are written as `null`, since they can't be empty there.
+    Empty,
+}
@@ -0,0 +1,548 @@
+//! Read how the previous version of a document was written, to write the new version the same
+//! way where it hasn't changed.
+
+use alloc::{
+    string::{String, ToString as _},
+    vec::Vec,
+};
+use core::str::Chars;
+
+use facet_core::Shape;
+use yaml_rust2::{
+    Yaml,
+    parser::{Event, Parser},
+    scanner::{Marker, ScanError, TScalarStyle},
+    yaml::Array,
+};
+
+use super::{SerializeOptions, YamlSerError, emitter::Emitter, tree::Document};
+use crate::DocumentStream;
+
+/// The layout of the previous version of a stream of documents.
+#[derive(Debug)]
+pub(crate) struct Previous {
+    /// The documents, with the comments, directives and markers around them.
+    stream: DocumentStream,
+    /// The root of each document, or `None` for the empty ones.
+    roots: Vec<Option<Entry>>,
+    /// Whether the stream ended with a line break.
+    trailing_newline: bool,
+    /// Number of spaces for each nesting level, if a mapping was nested in another.
+    indent: Option<usize>,
+    /// Whether sequences that are values of a mapping were indented under their key, if there
+    /// was one.
+    indent_sequences: Option<bool>,
+}
+
+/// A mapping entry or a sequence item of the previous version of a document, with the comments
+/// around it.
+#[derive(Debug)]
+pub(crate) struct Entry {
+    /// The layout of the key, for a mapping entry.
+    pub key: Option<Layout>,
+    /// The layout of the value, or of the item.
+    pub value: Layout,
+    /// The comments on their own lines right before the entry, from their `#`.
+    pub comments: Vec<String>,
+    /// The comment at the end of the line the entry starts on, from its `#`.
+    pub trailing: Option<String>,
+}
+
+/// How a node of the previous version of a document was written.
+#[derive(Debug)]
+pub(crate) enum Layout {
+    /// A scalar, with its text before it was resolved.
+    Scalar {
+        /// The value the scalar was read as, or a bad value for scalars with a tag.
+        value: Yaml,
+        /// The text of the scalar, without its quotes.
+        text: String,
+        /// How the scalar was quoted, or the kind of block scalar it was.
+        style: TScalarStyle,
+    },
+    /// A sequence, in block or flow style.
+    Sequence { items: Vec<Entry>, flow: bool },
+    /// A mapping, in block or flow style, with its entries in the order they were written.
+    Mapping { entries: Vec<Entry>, flow: bool },
+    /// An alias of a node written elsewhere.
+    Alias,
+}
+
+/// A comment of the previous version of a document.
+#[derive(Debug)]
+struct Comment {
+    /// The line it's on, counted from 1 like the marks of the parser.
+    line: usize,
+    /// The text of the comment, from its `#`.
+    text: String,
+    /// Whether nothing is before it on its line.
+    own_line: bool,
+}
+
+impl Previous {
+    /// Read the layout of each document of a YAML stream.
+    pub fn parse(yaml: &str) -> Result<Self, YamlSerError> {
+        let invalid = |err: ScanError| YamlSerError::InvalidPrevious {
+            message: err.to_string(),
+        };
+        let stream = DocumentStream::new(yaml);
+        let mut roots = Vec::new();
+        let mut indent = None;
+        let mut indent_sequences = None;
+        for document in stream.documents() {
+            let text = document.to_string();
+            // The content starts on the last line of the header
+            let first_line = document.header().matches('\n').count() + 1;
+            let (mut comments, trailing): (Vec<_>, Vec<_>) = comments(document.body(), first_line)
+                .into_iter()
+                .partition(|comment| comment.own_line);
+            comments.reverse();
+            let mut reader = Reader {
+                parser: Parser::new_from_str(&text),
+                chars: text.chars().collect(),
+                comments,
+                trailing,
+                indent,
+                indent_sequences,
+            };
+            roots.push(reader.root().map_err(invalid)?);
+            indent = reader.indent;
+            indent_sequences = reader.indent_sequences;
+        }
+
+        Ok(Self {
+            stream,
+            roots,
+            trailing_newline: yaml.ends_with('\n'),
+            indent,
+            indent_sequences,
+        })
+    }
+
+    /// The number of documents.
+    pub fn len(&self) -> usize {
+        self.stream.len()
+    }
+
+    /// Get the options of a new version of the documents, with the layout of this one.
+    pub fn options(&self, options: &SerializeOptions) -> SerializeOptions {
+        let mut options = options.clone();
+        if let Some(indent) = self.indent {
+            options.indent = indent.max(2);
+        }
+        if let Some(indent_sequences) = self.indent_sequences {
+            options.indent_sequences = indent_sequences;
+        }
+        options
+    }
+
+    /// Write the new version of each document over the previous document at its position,
+    /// keeping the text around it.
+    ///
+    /// Documents without a previous version are added after the last one, and previous
+    /// documents without a new version are removed.
+    pub fn write(
+        mut self,
+        docs: &[Document],
+        shape: Option<&'static Shape>,
+        options: &SerializeOptions,
+    ) -> Result<String, YamlSerError> {
+        if self.stream.is_empty() {
+            // The new documents follow the comments, if there are any
+            let mut output = self.stream.to_string();
+            if !output.is_empty() && !output.ends_with('\n') {
+                output.push('\n');
+            }
+            Emitter::new(&mut output, options).dump_stream(docs, shape)?;
+            return Ok(output);
+        }
+
+        // The markers and directives are those of the previous version
+        let options = SerializeOptions {
+            document_start: false,
+            document_end: false,
+            yaml_directive: false,
+            trailing_newline: true,
+            ..options.clone()
+        };
+        for (index, doc) in docs.iter().enumerate() {
+            let mut body = String::new();
+            Emitter::new(&mut body, &options)
+                .with_previous(self.roots.get(index).and_then(Option::as_ref))
+                .dump(doc, shape)?;
+            match self.stream.documents_mut().get_mut(index) {
+                Some(document) => document.set_body(body),
+                None => self.stream.push(body),
+            }
+        }
+        self.stream.truncate(docs.len());
+
+        let mut output = self.stream.to_string();
+        if !self.trailing_newline && output.ends_with('\n') {
+            output.pop();
+        }
+        Ok(output)
+    }
+}
+
+impl Layout {
+    /// Get the entry of a mapping key.
+    pub fn entry(&self, key: &Yaml) -> Option<&Entry> {
+        match self {
+            Self::Mapping { entries, .. } => entries.iter().find(|entry| entry.has_key(key)),
+            _ => None,
+        }
+    }
+
+    /// Get the position of a mapping key in the mapping.
+    pub fn position(&self, key: &Yaml) -> Option<usize> {
+        match self {
+            Self::Mapping { entries, .. } => entries.iter().position(|entry| entry.has_key(key)),
+            _ => None,
+        }
+    }
+
+    /// Find the previous version of each item of a new version of a sequence.
+    ///
+    /// Items are matched with the first item left that has the same content, or for mappings,
+    /// the same value for the key of their first scalar entry, such as a `name`. Other items take
+    /// the place of the item at their position, if it wasn't matched with another item, so an
+    /// item that's inserted or removed doesn't shift the layout of the items after it.
+    pub fn match_items<'a>(&'a self, array: &Array) -> Vec<Option<&'a Entry>> {
+        let Self::Sequence { items, .. } = self else {
+            return Vec::new();
+        };
+        let mut used = alloc::vec![false; items.len()];
+        let mut matched: Vec<Option<&Entry>> = array
+            .iter()
+            .map(|node| {
+                let position = (0..items.len())
+                    .find(|&position| !used[position] && items[position].value.is_same(node))?;
+                used[position] = true;
+                Some(&items[position])
+            })
+            .collect();
+        for (position, item) in matched.iter_mut().enumerate() {
+            if item.is_none() && used.get(position) == Some(&false) {
+                used[position] = true;
+                *item = Some(&items[position]);
+            }
+        }
+        matched
+    }
+
+    /// Whether the node was a collection in flow style, or `None` if it wasn't a collection.
+    pub fn flow(&self) -> Option<bool> {
+        match self {
+            Self::Sequence { flow, .. } | Self::Mapping { flow, .. } => Some(*flow),
+            _ => None,
+        }
+    }
+
+    /// Get the text of a plain scalar that was read as the same value as a new node, which can
+    /// be written the same way again, e.g. `0x1F` for 31 or an empty node for null.
+    pub fn same_text(&self, node: &Yaml) -> Option<&str> {
+        match self {
+            Self::Scalar {
+                text,
+                style: TScalarStyle::Plain,
+                ..
+            } if self.is_scalar(node) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Get the style a string was written with, if it can be written with it again.
+    ///
+    /// Quoted and block scalars keep their style even when their content changes, but plain
+    /// scalars only when it doesn't, since other content might not be read back as a string.
+    pub fn string_style(&self, value: &str) -> Option<TScalarStyle> {
+        match self {
+            Self::Scalar {
+                value: Yaml::String(previous),
+                style: TScalarStyle::Plain,
+                ..
+            } => (previous == value).then_some(TScalarStyle::Plain),
+            Self::Scalar {
+                value: Yaml::String(_),
+                style,
+                ..
+            } => Some(*style),
+            _ => None,
+        }
+    }
+
+    /// Whether the node is the previous version of a sequence item: a scalar read as the same
+    /// value, or a mapping with the same value for the key of the item's first scalar entry.
+    fn is_same(&self, node: &Yaml) -> bool {
+        match (self, node) {
+            (Self::Scalar { .. }, _) => self.is_scalar(node),
+            (Self::Mapping { .. }, Yaml::Hash(hash)) => hash
+                .iter()
+                .find(|(_, value)| !matches!(value, Yaml::Array(_) | Yaml::Hash(_)))
+                .and_then(|(key, value)| Some(self.entry(key)?.value.is_scalar(value)))
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+
+    /// Whether the node was a scalar read as the same value as a new node.
+    fn is_scalar(&self, node: &Yaml) -> bool {
+        let Self::Scalar { value, .. } = self else {
+            return false;
+        };
+        match (value, node) {
+            // Floats are compared by value, since they're stored as their text
+            (Yaml::Real(previous), Yaml::Real(new)) => {
+                previous == new
+                    || matches!(
+                        (previous.parse::<f64>(), new.parse::<f64>()),
+                        (Ok(previous), Ok(new)) if previous == new
+                    )
+            }
+            (Yaml::BadValue, _) => false,
+            (previous, new) => previous == new,
+        }
+    }
+}
+
+impl Entry {
+    /// Whether the entry is the entry of a mapping key.
+    fn has_key(&self, key: &Yaml) -> bool {
+        self.key
+            .as_ref()
+            .is_some_and(|layout| layout.is_scalar(key))
+    }
+}
+
+/// Find the comments in the content of a document, which starts at line `first_line`.
+///
+/// The parser skips comments, so they're found in the text, outside of quoted and block scalars.
+fn comments(body: &str, first_line: usize) -> Vec<Comment> {
+    let mut comments = Vec::new();
+    // The indentation of the line a block scalar starts on, and of its content once it's known
+    let mut block: Option<(usize, Option<usize>)> = None;
+    // The quote of a scalar that goes on to the next line
+    let mut quote = None;
+    for (line, text) in (first_line..).zip(body.lines()) {
+        let indent = text.len() - text.trim_start_matches(' ').len();
+        if let Some((start, content)) = block {
+            match content {
+                _ if text.trim().is_empty() => continue,
+                Some(content) if indent >= content => continue,
+                None if indent > start => {
+                    block = Some((start, Some(indent)));
+                    continue;
+                }
+                _ => block = None,
+            }
+        }
+
+        // The character before the current one that isn't a space, to tell quotes that start a
+        // scalar from quotes in a plain scalar, e.g. `it's`
+        let mut before = None;
+        let mut end = text.len();
+        let mut chars = text.char_indices().peekable();
+        while let Some((at, c)) = chars.next() {
+            match (quote, c) {
+                (Some('\''), '\'') => {
+                    // Single quotes are escaped by doubling them
+                    if chars.next_if(|&(_, next)| next == '\'').is_none() {
+                        quote = None;
+                    }
+                }
+                (Some(_), '\\') => {
+                    chars.next();
+                }
+                (Some('"'), '"') => quote = None,
+                (Some(_), _) => {}
+                (None, '#') if text[..at].ends_with([' ', '\t']) || at == 0 => {
+                    comments.push(Comment {
+                        line,
+                        text: text[at..].trim_end().to_string(),
+                        own_line: text[..at].trim().is_empty(),
+                    });
+                    end = at;
+                    break;
+                }
+                (None, '\'' | '"')
+                    if matches!(before, None | Some(':' | '-' | '?' | ',' | '[' | '{')) =>
+                {
+                    quote = Some(c);
+                }
+                (None, _) => {}
+            }
+            if !c.is_whitespace() {
+                before = Some(c);
+            }
+        }
+
+        // A block scalar starts after a header such as `|` or `>-` at the end of the line
+        let content = text[..end].trim_end();
+        let header = content.rsplit([' ', '\t']).next().unwrap_or_default();
+        if quote.is_none()
+            && header.starts_with(['|', '>'])
+            && header[1..]
+                .chars()
+                .all(|c| matches!(c, '-' | '+' | '0'..='9'))
+        {
+            block = Some((indent, None));
+        }
+    }
+    comments
+}
+
+/// Reads the layout of the nodes of a document from the events of the parser.
+struct Reader<'a> {
+    parser: Parser<Chars<'a>>,
+    /// The characters of the document, to find which collections are in flow style.
+    chars: Vec<char>,
+    /// The comments on their own lines that aren't attached to an entry yet, the first one last.
+    comments: Vec<Comment>,
+    /// The comments at the end of a line that aren't attached to an entry yet.
+    trailing: Vec<Comment>,
+    /// Number of spaces for each nesting level, from the first mapping nested in another.
+    indent: Option<usize>,
+    /// Whether sequences are indented under their key, from the first sequence that is the
+    /// value of a mapping.
+    indent_sequences: Option<bool>,
+}
+
+impl Reader<'_> {
+    /// Read the layout of the root node of the document, if it has one.
+    fn root(&mut self) -> Result<Option<Entry>, ScanError> {
+        loop {
+            let (event, mark) = self.parser.next_token()?;
+            match event {
+                Event::StreamStart | Event::DocumentStart | Event::Nothing => {}
+                Event::DocumentEnd | Event::StreamEnd => return Ok(None),
+                event => {
+                    return Ok(Some(Entry {
+                        key: None,
+                        value: self.node(event, mark, None)?,
+                        comments: Vec::new(),
+                        trailing: None,
+                    }));
+                }
+            }
+        }
+    }
+
+    /// Read the layout of a node starting with the given event, given the column of the block
+    /// mapping it's the value of, if any.
+    fn node(
+        &mut self,
+        event: Event,
+        mark: Marker,
+        parent_column: Option<usize>,
+    ) -> Result<Layout, ScanError> {
+        Ok(match event {
+            Event::Scalar(text, style, _, tag) => {
+                let value = match (style, tag) {
+                    (_, Some(_)) => Yaml::BadValue,
+                    (TScalarStyle::Plain, None) => Yaml::from_str(&text),
+                    (_, None) => Yaml::String(text.clone()),
+                };
+                Layout::Scalar { value, text, style }
+            }
+            Event::SequenceStart(..) => {
+                let flow = self.chars.get(mark.index()) == Some(&'[');
+                if let (Some(parent), false) = (parent_column, flow) {
+                    // The mark of a block sequence is at the content of its first item, after
+                    // the `-`
+                    let dash = self.chars[..mark.index()].iter().rposition(|&c| c == '-');
+                    if let Some(dash) = dash {
+                        self.indent_sequences
+                            .get_or_insert(self.column(dash) > parent);
+                    }
+                }
+                let mut items = Vec::new();
+                loop {
+                    let (event, start) = self.parser.next_token()?;
+                    if event == Event::SequenceEnd {
+                        break;
+                    }
+                    let comments = self.comments_before(start.line(), flow);
+                    let value = self.node(event, start, None)?;
+                    let trailing = self.trailing_on(start.line(), flow);
+                    items.push(Entry {
+                        key: None,
+                        value,
+                        comments,
+                        trailing,
+                    });
+                }
+                Layout::Sequence { items, flow }
+            }
+            Event::MappingStart(..) => {
+                let flow = self.chars.get(mark.index()) == Some(&'{');
+                // The column of the keys, from the first one since the mark of a block mapping
+                // is after it
+                let mut column = None;
+                let mut entries = Vec::new();
+                loop {
+                    let (event, start) = self.parser.next_token()?;
+                    if event == Event::MappingEnd {
+                        break;
+                    }
+                    if !flow && column.is_none() {
+                        column = Some(start.col());
+                        if let Some(parent) = parent_column.filter(|&parent| start.col() > parent) {
+                            self.indent.get_or_insert(start.col() - parent);
+                        }
+                    }
+                    let comments = self.comments_before(start.line(), flow);
+                    let key = self.node(event, start, None)?;
+                    let (event, mark) = self.parser.next_token()?;
+                    let value = self.node(event, mark, column)?;
+                    let trailing = self.trailing_on(start.line(), flow);
+                    entries.push(Entry {
+                        key: Some(key),
+                        value,
+                        comments,
+                        trailing,
+                    });
+                }
+                Layout::Mapping { entries, flow }
+            }
+            _ => Layout::Alias,
+        })
+    }
+
+    /// Take the comments on their own lines before the entry starting on `line`, unless it's in
+    /// a flow collection, which is written without comments.
+    fn comments_before(&mut self, line: usize, flow: bool) -> Vec<String> {
+        let mut comments = Vec::new();
+        while !flow
+            && self
+                .comments
+                .last()
+                .is_some_and(|comment| comment.line < line)
+        {
+            comments.extend(self.comments.pop().map(|comment| comment.text));
+        }
+        comments
+    }
+
+    /// Take the comment at the end of `line`, once the nodes in the entry starting on it had
+    /// the chance to take it, unless the entry is in a flow collection.
+    fn trailing_on(&mut self, line: usize, flow: bool) -> Option<String> {
+        if flow {
+            return None;
+        }
+        let position = self
+            .trailing
+            .iter()
+            .position(|comment| comment.line == line)?;
+        Some(self.trailing.remove(position).text)
+    }
+
+    /// Get the column of the character at `index`.
+    fn column(&self, index: usize) -> usize {
+        let line_start = self.chars[..index]
+            .iter()
+            .rposition(|&c| c == '\n')
+            .map_or(0, |newline| newline + 1);
+        index - line_start
+    }
+}
@@ -0,0 +1,133 @@
+//! Report how far the writing of a document is, for long-running exports.
+
+use alloc::{
+    string::{String, ToString as _},
+    vec::Vec,
+};
+use core::ops::ControlFlow;
+
+use yaml_rust2::Yaml;
+
+use super::{YamlSerError, tree};
+
+/// How far the writing of a document is, passed to the callback of
+/// [`to_writer_with_progress`](crate::to_writer_with_progress).
+#[derive(Debug, Clone, Copy)]
+pub struct Progress<'a> {
+    /// Number of bytes of the document written so far.
+    bytes_written: u64,
+    /// Path of the node that is written next.
+    path: &'a str,
+}
+
+impl Progress<'_> {
+    /// Number of bytes of the document written so far, including those still in the buffer of
+    /// the writer.
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    /// Path of the node that is written next, such as `items[3].name`, or an empty string once
+    /// the document is complete.
+    pub fn path(&self) -> &str {
+        self.path
+    }
+}
+
+/// A callback of [`to_writer_with_progress`](crate::to_writer_with_progress).
+pub(crate) type ProgressCallback<'a> = dyn FnMut(&Progress<'_>) -> ControlFlow<()> + 'a;
+
+/// Calls the progress callback as the document is written, keeping track of the path of the
+/// node being written.
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+pub(crate) struct Reporter<'a> {
+    callback: &'a mut ProgressCallback<'a>,
+    /// Number of bytes between two calls of the callback.
+    interval: u64,
+    /// Number of bytes from which the callback is called next.
+    next: u64,
+    /// Keys and indices of the nodes the current node is in.
+    path: Vec<Segment>,
+}
+
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+impl<'a> Reporter<'a> {
+    /// Create a reporter calling `callback` every `interval` bytes.
+    pub fn new(callback: &'a mut ProgressCallback<'a>, interval: usize) -> Self {
+        let interval = interval.max(1) as u64;
+        Self {
+            callback,
+            interval,
+            next: interval,
+            path: Vec::new(),
+        }
+    }
+
+    /// Enter the value of a mapping key or sequence item.
+    pub fn enter(&mut self, segment: Segment) {
+        self.path.push(segment);
+    }
+
+    /// Leave the node entered last.
+    pub fn leave(&mut self) {
+        self.path.pop();
+    }
+
+    /// Call the callback when at least the interval was written since the last call, or
+    /// always when the document is `complete`.
+    pub fn report(&mut self, bytes_written: u64, complete: bool) -> Result<(), YamlSerError> {
+        if !complete && bytes_written < self.next {
+            return Ok(());
+        }
+        self.next = bytes_written + self.interval;
+
+        let path = if complete {
+            String::new()
+        } else {
+            self.path_string()
+        };
+        let progress = Progress {
+            bytes_written,
+            path: &path,
+        };
+        match (self.callback)(&progress) {
+            ControlFlow::Continue(()) => Ok(()),
+            ControlFlow::Break(()) => Err(YamlSerError::Cancelled { path }),
+        }
+    }
+
+    /// Write the path in the same form as the paths of errors.
+    fn path_string(&self) -> String {
+        let mut path = String::new();
+        for segment in &self.path {
+            match segment {
+                Segment::Key(key) if path.is_empty() => path.push_str(key),
+                Segment::Key(key) => {
+                    path.push('.');
+                    path.push_str(key);
+                }
+                Segment::Index(index) => {
+                    path.push('[');
+                    path.push_str(&index.to_string());
+                    path.push(']');
+                }
+            }
+        }
+        path
+    }
+}
+
+/// A step of the path to a node.
+pub(crate) enum Segment {
+    /// The value of a mapping key.
+    Key(String),
+    /// The item of a sequence at the index.
+    Index(usize),
+}
+
+impl Segment {
+    /// The value of a mapping key.
+    pub fn key(key: &Yaml) -> Self {
+        Self::Key(tree::key_segment(key))
+    }
+}
@@ -0,0 +1,702 @@
+//! Build YAML document trees by walking values with `Peek`.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use facet_core::{Def, Field, FieldFlags, Shape, StructKind, StructType, Type, UserType};
+use facet_reflect::{HasFields, Partial, Peek, ScalarType, ValueId};
+use yaml_rust2::{
+    Yaml,
+    yaml::{Array, Hash},
+};
+
+use super::{SerializeOptions, YamlSerError, emitter::Emitter};
+use crate::{
+    attributes::{self, FieldAttributes},
+    base64,
+};
+
+/// A document converted from a value.
+pub(crate) struct Document {
+    /// The root node, where each value behind a shared pointer is an alias, indexing `shared`.
+    pub root: Yaml,
+    /// The values behind shared pointers, when they're written with anchors.
+    pub shared: Vec<Shared>,
+}
+
+/// A value behind a pointer, which may be written more than once.
+pub(crate) struct Shared {
+    /// The node of the value.
+    pub node: Yaml,
+    /// The number of aliases of the value in the document. Values that are only written once
+    /// don't need an anchor.
+    pub uses: usize,
+}
+
+impl From<Yaml> for Document {
+    fn from(root: Yaml) -> Self {
+        Self {
+            root,
+            shared: Vec::new(),
+        }
+    }
+}
+
+/// Convert a value to a YAML document.
+///
+/// Borrowed data, such as `&str`, `&[T]` or `Cow<'_, str>`, is written the same way as its
+/// owned counterpart. Values that are shared through pointers such as `Rc` are written in full
+/// every time unless [`anchors`](SerializeOptions::anchors) is set, and values that contain
+/// themselves are an error.
+pub(crate) fn to_yaml(
+    peek: Peek<'_, '_>,
+    options: &SerializeOptions,
+) -> Result<Document, YamlSerError> {
+    let mut walk = Walk {
+        options,
+        visiting: Vec::new(),
+        skip_empty: options.skip_empty,
+        comment_defaults: options.comment_defaults,
+        binary: options.binary,
+        anchors: options.anchors,
+        non_finite_floats: options.non_finite_floats,
+        seen: Vec::new(),
+        shared: Vec::new(),
+        defaults: Vec::new(),
+    };
+    let root = node(peek, &mut walk)?;
+    Ok(Document {
+        root,
+        shared: walk.shared,
+    })
+}
+
+/// What is known while walking a value.
+struct Walk<'a> {
+    /// The options of the document, for the documents embedded in it.
+    options: &'a SerializeOptions,
+    /// The values behind the pointers that lead to the current one, to find cycles.
+    visiting: Vec<ValueId>,
+    /// Whether entries whose value is an empty collection are left out.
+    skip_empty: bool,
+    /// Whether fields that are the same as their default are commented out.
+    comment_defaults: bool,
+    /// Whether byte buffers are written as base64 text instead of sequences of integers.
+    binary: bool,
+    /// Whether values behind pointers are aliases, so they're written once.
+    anchors: bool,
+    /// Whether infinite and NaN floats are written, instead of being an error.
+    non_finite_floats: bool,
+    /// The values behind pointers that are written already, in the order of `shared`.
+    seen: Vec<ValueId>,
+    /// The nodes of the values behind pointers.
+    shared: Vec<Shared>,
+    /// The defaults of the fields of each struct found so far, from [`default_fields`].
+    defaults: Vec<(&'static Shape, Vec<(&'static str, Yaml)>)>,
+}
+
+/// Convert a value to a YAML node.
+fn node(peek: Peek<'_, '_>, walk: &mut Walk<'_>) -> Result<Yaml, YamlSerError> {
+    let shape = peek.shape();
+    let unsupported = |_| YamlSerError::UnsupportedType {
+        type_name: shape.to_string(),
+    };
+
+    // Customized types are written as the node they convert to
+    #[cfg(feature = "deserialize")]
+    if let Some(customization) = walk.options.customization(shape) {
+        let value = (customization.write)(peek).ok_or_else(|| YamlSerError::UnsupportedType {
+            type_name: shape.to_string(),
+        })?;
+        return value.to_yaml(walk.non_finite_floats);
+    }
+
+    // Dynamic values are written as the nodes they hold
+    #[cfg(feature = "deserialize")]
+    if shape.is_type::<crate::Value>() {
+        return peek
+            .get::<crate::Value>()
+            .map_err(unsupported)?
+            .to_yaml(walk.non_finite_floats);
+    }
+
+    match (shape.def, shape.ty) {
+        (Def::Scalar, _) => scalar(peek, walk),
+        (Def::Option(_), _) => match peek.into_option().map_err(unsupported)?.value() {
+            Some(value) => node(value, walk),
+            None => Ok(Yaml::Null),
+        },
+        (Def::List(ld), _) if ld.t().is_type::<u8>() && walk.binary => {
+            let list = peek.into_list_like().map_err(unsupported)?;
+            let bytes = list
+                .iter()
+                .map(|item| item.get::<u8>().copied().map_err(unsupported))
+                .collect::<Result<Vec<u8>, _>>()?;
+            // Tagged as `!!binary` by the emitter, which knows the shape
+            Ok(Yaml::String(base64::encode(&bytes)))
+        }
+        (Def::List(_) | Def::Array(_) | Def::Slice(_), _) => {
+            let list = peek.into_list_like().map_err(unsupported)?;
+            Ok(Yaml::Array(
+                list.iter()
+                    .enumerate()
+                    .map(|(index, item)| node(item, walk).map_err(|e| e.in_index(index)))
+                    .collect::<Result<Array, _>>()?,
+            ))
+        }
+        // Sets are written as sequences, in the order they iterate in
+        (Def::Set(_), _) => {
+            let set = peek.into_set().map_err(unsupported)?;
+            Ok(Yaml::Array(
+                set.iter()
+                    .enumerate()
+                    .map(|(index, item)| node(item, walk).map_err(|e| e.in_index(index)))
+                    .collect::<Result<Array, _>>()?,
+            ))
+        }
+        (Def::Map(md), _) => {
+            let map = peek.into_map().map_err(unsupported)?;
+            let mut hash = Hash::new();
+            for (key, value) in map.iter() {
+                // Keys are always written in full, so they can be compared by whoever reads
+                // them. Keys that aren't scalars, such as structs, are written as complex keys
+                let anchors = core::mem::replace(&mut walk.anchors, false);
+                let key = node(key, walk);
+                walk.anchors = anchors;
+                let key = key?;
+                let value = node(value, walk).map_err(|e| e.in_field(&key_segment(&key)))?;
+                // Entries without a value are left out, like the fields of structs
+                if (value == Yaml::Null && !is_unit(md.v()))
+                    || (walk.skip_empty && is_empty(&value))
+                {
+                    continue;
+                }
+                hash.insert(key, value);
+            }
+            Ok(Yaml::Hash(hash))
+        }
+        // Smart pointers, and borrowed data such as `Cow`
+        (Def::Pointer(_), _) => match peek.into_pointer().map_err(unsupported)?.borrow_inner() {
+            Some(inner) => {
+                // The value is its own ancestor, so writing it would never end
+                let id = inner.id();
+                if walk.visiting.contains(&id) {
+                    return Err(YamlSerError::Cycle {
+                        path: String::new(),
+                    });
+                }
+                // The value is written already, so it's an alias of it
+                let seen = walk
+                    .anchors
+                    .then(|| walk.seen.iter().position(|seen| *seen == id))
+                    .flatten();
+                if let Some(index) = seen {
+                    walk.shared[index].uses += 1;
+                    return Ok(Yaml::Alias(index));
+                }
+                walk.visiting.push(id);
+                let result = node(inner, walk);
+                walk.visiting.pop();
+
+                // Missing values and empty collections stay as they are, so they can be left out
+                let node = result?;
+                if !walk.anchors || node == Yaml::Null || is_empty(&node) {
+                    return Ok(node);
+                }
+                walk.seen.push(id);
+                walk.shared.push(Shared { node, uses: 1 });
+                Ok(Yaml::Alias(walk.shared.len() - 1))
+            }
+            // A weak pointer whose value is gone
+            None => Ok(Yaml::Null),
+        },
+        (_, Type::User(UserType::Struct(sd))) => {
+            let fields = peek.into_struct().map_err(unsupported)?;
+            match sd.kind {
+                // Unit structs and `()` are written as null, e.g. `marker: ~`
+                StructKind::Unit => Ok(Yaml::Null),
+                StructKind::Tuple if sd.fields.is_empty() => Ok(Yaml::Null),
+                // Newtypes are written as their field, e.g. `struct Meters(f64)` as `3.5`
+                StructKind::TupleStruct if sd.fields.len() == 1 => {
+                    match fields.fields_for_serialize().next() {
+                        Some((_, value)) => node(value, walk),
+                        None => Ok(Yaml::Null),
+                    }
+                }
+                StructKind::Tuple | StructKind::TupleStruct => Ok(Yaml::Array(
+                    fields
+                        .fields_for_serialize()
+                        .enumerate()
+                        .map(|(index, (_, value))| node(value, walk).map_err(|e| e.in_index(index)))
+                        .collect::<Result<Array, _>>()?,
+                )),
+                StructKind::Struct => {
+                    let defaults = if walk.comment_defaults {
+                        struct_defaults(shape, &sd, walk)
+                    } else {
+                        Vec::new()
+                    };
+                    let mut hash = Hash::new();
+                    for (field, value) in fields.fields_for_serialize() {
+                        let attributes = FieldAttributes::of(&field)
+                            .map_err(|message| YamlSerError::InvalidAttribute { message })?;
+                        let default = defaults.iter().find(|(name, _)| *name == field.name);
+                        let value = match default {
+                            _ if attributes.embedded => embedded(value, walk),
+                            Some((_, default)) => field_node(value, default, walk),
+                            None => node(value, walk),
+                        }
+                        .map_err(|e| e.in_field(field.name))?;
+                        let value = match &attributes.key {
+                            Some(key) => keyed_items(value, key, &field)?,
+                            None => value,
+                        };
+                        insert_field(&mut hash, &field, value, walk)?;
+                    }
+                    Ok(Yaml::Hash(hash))
+                }
+            }
+        }
+        (_, Type::User(UserType::Enum(_))) => {
+            let value = peek.into_enum().map_err(unsupported)?;
+            let variant = value
+                .active_variant()
+                .map_err(|_| YamlSerError::UnsupportedType {
+                    type_name: shape.to_string(),
+                })?;
+            let name = variant.name;
+            let tag = attributes::enum_tag(shape);
+            // Variants with fields are a mapping from their name to their content, the way they
+            // are read back
+            let content = match variant.data.kind {
+                StructKind::Unit if tag.is_none() => return Ok(Yaml::String(name.into())),
+                StructKind::Unit => Yaml::Null,
+                StructKind::Tuple | StructKind::TupleStruct if variant.data.fields.len() == 1 => {
+                    match value.fields_for_serialize().next() {
+                        Some((_, value)) => node(value, walk).map_err(|e| e.in_field(name))?,
+                        None => Yaml::Null,
+                    }
+                }
+                StructKind::Tuple | StructKind::TupleStruct => Yaml::Array(
+                    value
+                        .fields_for_serialize()
+                        .enumerate()
+                        .map(|(index, (_, value))| {
+                            node(value, walk).map_err(|e| e.in_index(index).in_field(name))
+                        })
+                        .collect::<Result<Array, _>>()?,
+                ),
+                StructKind::Struct => {
+                    let mut hash = Hash::new();
+                    for (field, value) in value.fields_for_serialize() {
+                        let value =
+                            node(value, walk).map_err(|e| e.in_field(field.name).in_field(name))?;
+                        insert_field(&mut hash, &field, value, walk)?;
+                    }
+                    Yaml::Hash(hash)
+                }
+            };
+            // Untagged variants are their content, and unit ones their name
+            if attributes::is_untagged(shape) {
+                return Ok(content);
+            }
+            let mut hash = Hash::new();
+            match tag {
+                // Internally tagged variants are the mapping of their fields, after the tag
+                Some(tag) => {
+                    hash.insert(Yaml::String(tag.into()), Yaml::String(name.into()));
+                    match content {
+                        Yaml::Hash(fields) => hash.extend(fields),
+                        Yaml::Null => {}
+                        _ => {
+                            return Err(YamlSerError::UnsupportedType {
+                                type_name: shape.to_string(),
+                            });
+                        }
+                    }
+                }
+                None => {
+                    hash.insert(Yaml::String(name.into()), content);
+                }
+            }
+            Ok(Yaml::Hash(hash))
+        }
+        // References such as `&str` and `&[T]`
+        (_, Type::Pointer(_)) => match peek.as_str() {
+            Some(s) => Ok(Yaml::String(s.into())),
+            None => node(peek.innermost_peek(), walk),
+        },
+        _ if shape.inner.is_some() => node(peek.innermost_peek(), walk),
+        _ => Err(YamlSerError::UnsupportedType {
+            type_name: shape.to_string(),
+        }),
+    }
+}
+
+/// Add the value of a struct field to its mapping, unless it's left out.
+fn insert_field(
+    hash: &mut Hash,
+    field: &Field,
+    value: Yaml,
+    walk: &Walk<'_>,
+) -> Result<(), YamlSerError> {
+    // Missing values are read back as `None`, but unit values must be there
+    if value == Yaml::Null && !is_unit(field.shape()) {
+        return Ok(());
+    }
+    // Empty collections can be left out too, for fields with a default
+    if is_empty(commented(&value).unwrap_or(&value))
+        && (walk.skip_empty
+            || FieldAttributes::of(field)
+                .map_err(|message| YamlSerError::InvalidAttribute { message })?
+                .skip_empty)
+    {
+        return Ok(());
+    }
+    hash.insert(Yaml::String(field.name.into()), value);
+    Ok(())
+}
+
+/// Convert a value to a string with its own YAML document, for fields with a
+/// `#[facet(yaml(embedded))]` attribute.
+///
+/// The document is written with the options of the one it's in, without its markers and with a
+/// final line break, so it's written as a literal block scalar. Missing values stay missing.
+fn embedded(value: Peek<'_, '_>, walk: &mut Walk<'_>) -> Result<Yaml, YamlSerError> {
+    let options = SerializeOptions {
+        document_start: false,
+        document_end: false,
+        yaml_directive: false,
+        trailing_newline: true,
+        ..walk.options.clone()
+    };
+    let mut inner = Walk {
+        options: &options,
+        visiting: core::mem::take(&mut walk.visiting),
+        skip_empty: walk.skip_empty,
+        comment_defaults: walk.comment_defaults,
+        binary: walk.binary,
+        anchors: walk.anchors,
+        non_finite_floats: walk.non_finite_floats,
+        seen: Vec::new(),
+        shared: Vec::new(),
+        defaults: core::mem::take(&mut walk.defaults),
+    };
+    let root = node(value, &mut inner);
+    // Cycles through the embedded document are still found
+    walk.visiting = inner.visiting;
+    walk.defaults = inner.defaults;
+    let root = root?;
+    if root == Yaml::Null {
+        return Ok(Yaml::Null);
+    }
+
+    let doc = Document {
+        root,
+        shared: inner.shared,
+    };
+    let mut text = String::new();
+    Emitter::new(&mut text, &options).dump(&doc, Some(value.shape()))?;
+    Ok(Yaml::String(text))
+}
+
+/// Convert the mapping of a map field to a sequence of its values, each with its key under
+/// `key`, for fields with a `#[facet(yaml(key = "..."))]` attribute.
+///
+/// Values that have the key already, such as structs with a field of that name, are written as
+/// they are. The items of maps that don't keep an order, such as `HashMap`, are sorted by their
+/// key, so the document is the same every time.
+fn keyed_items(value: Yaml, key: &str, field: &Field) -> Result<Yaml, YamlSerError> {
+    let hash = match value {
+        Yaml::Hash(hash) => hash,
+        // Values commented out as defaults are still written as a sequence
+        Yaml::Array(mut array) if matches!(array.as_slice(), [Yaml::BadValue, Yaml::Hash(_)]) => {
+            if let Some(value) = array.pop() {
+                array.push(keyed_items(value, key, field)?);
+            }
+            return Ok(Yaml::Array(array));
+        }
+        value => return Ok(value),
+    };
+
+    let mut entries: Vec<(Yaml, Yaml)> = hash.into_iter().collect();
+    if field.shape().type_identifier == "HashMap" {
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    }
+    let key = Yaml::String(key.into());
+    entries
+        .into_iter()
+        .map(|(name, value)| {
+            let fields = match value {
+                Yaml::Hash(fields) if fields.contains_key(&key) => return Ok(Yaml::Hash(fields)),
+                Yaml::Hash(fields) => fields,
+                // Unit values have no fields
+                Yaml::Null => Hash::new(),
+                _ => {
+                    return Err(YamlSerError::InvalidAttribute {
+                        message: format!(
+                            "The values of field '{}' are written as items of a keyed list, so \
+                             they need to be mappings",
+                            field.name
+                        ),
+                    });
+                }
+            };
+            let mut item = Hash::new();
+            item.insert(key.clone(), name);
+            item.extend(fields);
+            Ok(Yaml::Hash(item))
+        })
+        .collect::<Result<Array, _>>()
+        .map(Yaml::Array)
+}
+
+/// Convert the value of a field with a default to a YAML node, which is commented out when it's
+/// the same as the default.
+fn field_node(
+    value: Peek<'_, '_>,
+    default: &Yaml,
+    walk: &mut Walk<'_>,
+) -> Result<Yaml, YamlSerError> {
+    // Compared in full the way defaults are written, so shared values only get anchors where
+    // they're actually written, and the fields in it aren't commented out yet
+    let anchors = core::mem::replace(&mut walk.anchors, false);
+    walk.comment_defaults = false;
+    let full = node(value, walk);
+    walk.anchors = anchors;
+    walk.comment_defaults = true;
+    let full = full?;
+    if full == *default && full != Yaml::Null {
+        return Ok(Yaml::Array(alloc::vec![Yaml::BadValue, full]));
+    }
+    node(value, walk)
+}
+
+/// The value of a hash entry that is written as a comment, if it is one.
+///
+/// Commented out values are wrapped in a sequence starting with a bad value, which is never
+/// the node of a value otherwise.
+pub(crate) fn commented(value: &Yaml) -> Option<&Yaml> {
+    match value {
+        Yaml::Array(array) => match array.as_slice() {
+            [Yaml::BadValue, value] => Some(value),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Get the defaults of the fields of a struct from [`default_fields`], building them only the
+/// first time a struct of the shape is found.
+fn struct_defaults(
+    shape: &'static Shape,
+    sd: &StructType,
+    walk: &mut Walk<'_>,
+) -> Vec<(&'static str, Yaml)> {
+    if let Some((_, defaults)) = walk
+        .defaults
+        .iter()
+        .find(|(seen, _)| core::ptr::eq(*seen, shape))
+    {
+        return defaults.clone();
+    }
+    let defaults = default_fields(shape, sd, walk);
+    walk.defaults.push((shape, defaults.clone()));
+    defaults
+}
+
+/// The nodes of the fields of a struct that have a default, as they're written when they're set
+/// to it.
+///
+/// The default of a field is the one it's read back as when it's missing, which is the one of
+/// the field if it has one, or else the one of the struct. The other fields are set to the
+/// default of their type to build the struct, which fails for some types, in which case no
+/// field of the struct is known to have a default.
+fn default_fields(
+    shape: &'static Shape,
+    sd: &StructType,
+    walk: &Walk<'_>,
+) -> Vec<(&'static str, Yaml)> {
+    let has_default = |field: &Field| field.flags.contains(FieldFlags::DEFAULT);
+    let mut struct_default = Partial::alloc_shape(shape)
+        .ok()
+        .and_then(|mut partial| partial.set_default().is_ok().then_some(partial));
+    if struct_default.is_none() && !sd.fields.iter().any(has_default) {
+        return Vec::new();
+    }
+
+    let is_struct_default = struct_default.is_some();
+    let built = Partial::alloc_shape(shape).and_then(|mut partial| {
+        for (index, field) in sd.fields.iter().enumerate() {
+            match &mut struct_default {
+                Some(default) if !has_default(field) => {
+                    partial.steal_nth_field(default, index)?;
+                }
+                _ => {
+                    partial.set_nth_field_to_default(index)?;
+                }
+            }
+        }
+        partial.build()
+    });
+    let Ok(value) = built else {
+        return Vec::new();
+    };
+    let Ok(fields) = value.peek().into_struct() else {
+        return Vec::new();
+    };
+
+    // The defaults are written in full, without commenting out what's in them
+    let mut walk = Walk {
+        options: walk.options,
+        visiting: Vec::new(),
+        skip_empty: walk.skip_empty,
+        comment_defaults: false,
+        binary: walk.binary,
+        anchors: false,
+        non_finite_floats: walk.non_finite_floats,
+        seen: Vec::new(),
+        shared: Vec::new(),
+        defaults: Vec::new(),
+    };
+    fields
+        .fields_for_serialize()
+        .filter(|(field, _)| is_struct_default || has_default(field))
+        .filter_map(|(field, value)| Some((field.name, node(value, &mut walk).ok()?)))
+        .collect()
+}
+
+/// The segment of a path for the value of a mapping key, which is `?` for keys that aren't
+/// scalars.
+pub(crate) fn key_segment(key: &Yaml) -> String {
+    match key {
+        Yaml::String(key) | Yaml::Real(key) => key.clone(),
+        Yaml::Integer(key) => key.to_string(),
+        Yaml::Boolean(key) => key.to_string(),
+        Yaml::Null => String::from("~"),
+        _ => String::from("?"),
+    }
+}
+
+/// Whether a type has a single value, like `()` and unit structs, which is written as null
+/// rather than left out.
+fn is_unit(shape: &Shape) -> bool {
+    match shape.ty {
+        Type::User(UserType::Struct(sd)) => {
+            matches!(sd.kind, StructKind::Unit)
+                || (matches!(sd.kind, StructKind::Tuple) && sd.fields.is_empty())
+        }
+        _ => shape.is_type::<()>(),
+    }
+}
+
+/// Whether a node is an empty sequence or mapping.
+pub(crate) fn is_empty(yaml: &Yaml) -> bool {
+    match yaml {
+        Yaml::Array(array) => array.is_empty(),
+        Yaml::Hash(hash) => hash.is_empty(),
+        _ => false,
+    }
+}
+
+/// Convert a float to a YAML node, writing infinite and NaN values the way YAML's core schema
+/// reads them back, or failing when they aren't allowed.
+pub(crate) fn real<F: Copy + Into<f64> + core::fmt::Debug>(
+    value: F,
+    non_finite_floats: bool,
+) -> Result<Yaml, YamlSerError> {
+    let float: f64 = value.into();
+    if float.is_finite() {
+        // `Debug` always includes a decimal point or an exponent, so the value can't be read back
+        // as an integer, and like `Display` it's the shortest text that round-trips exactly
+        return Ok(Yaml::Real(format!("{value:?}")));
+    }
+    if !non_finite_floats {
+        return Err(YamlSerError::NonFiniteFloat {
+            value: float,
+            path: String::new(),
+        });
+    }
+    let text = if float.is_nan() {
+        ".nan"
+    } else if float > 0.0 {
+        ".inf"
+    } else {
+        "-.inf"
+    };
+    Ok(Yaml::Real(text.into()))
+}
+
+/// Convert a scalar to a YAML node.
+fn scalar(peek: Peek<'_, '_>, walk: &Walk<'_>) -> Result<Yaml, YamlSerError> {
+    let peek = peek.innermost_peek();
+    // The `str` pointee of a `Box`, `Arc` or `Rc`, which `as_str` would read as a `&str`
+    if let Ok(s) = peek.get::<str>() {
+        return Ok(Yaml::String(s.into()));
+    }
+    if let Some(s) = peek.as_str() {
+        return Ok(Yaml::String(s.into()));
+    }
+
+    let shape = peek.shape();
+    let unsupported = |_| YamlSerError::UnsupportedType {
+        type_name: shape.to_string(),
+    };
+    let integer = |value: Result<i64, _>, source_type| {
+        value
+            .map(Yaml::Integer)
+            .map_err(|_| YamlSerError::InvalidNumberToI64Conversion { source_type })
+    };
+
+    Ok(match peek.scalar_type() {
+        Some(ScalarType::Unit) => Yaml::Null,
+        Some(ScalarType::Bool) => Yaml::Boolean(*peek.get::<bool>().map_err(unsupported)?),
+        Some(ScalarType::Char) => {
+            Yaml::String(peek.get::<char>().map_err(unsupported)?.to_string())
+        }
+        Some(ScalarType::F32) => real(
+            *peek.get::<f32>().map_err(unsupported)?,
+            walk.non_finite_floats,
+        )?,
+        Some(ScalarType::F64) => real(
+            *peek.get::<f64>().map_err(unsupported)?,
+            walk.non_finite_floats,
+        )?,
+        Some(ScalarType::U8) => Yaml::Integer((*peek.get::<u8>().map_err(unsupported)?).into()),
+        Some(ScalarType::U16) => Yaml::Integer((*peek.get::<u16>().map_err(unsupported)?).into()),
+        Some(ScalarType::U32) => Yaml::Integer((*peek.get::<u32>().map_err(unsupported)?).into()),
+        Some(ScalarType::U64) => {
+            integer((*peek.get::<u64>().map_err(unsupported)?).try_into(), "u64")?
+        }
+        Some(ScalarType::U128) => integer(
+            (*peek.get::<u128>().map_err(unsupported)?).try_into(),
+            "u128",
+        )?,
+        Some(ScalarType::USize) => integer(
+            (*peek.get::<usize>().map_err(unsupported)?).try_into(),
+            "usize",
+        )?,
+        Some(ScalarType::I8) => Yaml::Integer((*peek.get::<i8>().map_err(unsupported)?).into()),
+        Some(ScalarType::I16) => Yaml::Integer((*peek.get::<i16>().map_err(unsupported)?).into()),
+        Some(ScalarType::I32) => Yaml::Integer((*peek.get::<i32>().map_err(unsupported)?).into()),
+        Some(ScalarType::I64) => Yaml::Integer(*peek.get::<i64>().map_err(unsupported)?),
+        Some(ScalarType::I128) => integer(
+            (*peek.get::<i128>().map_err(unsupported)?).try_into(),
+            "i128",
+        )?,
+        Some(ScalarType::ISize) => integer(
+            (*peek.get::<isize>().map_err(unsupported)?).try_into(),
+            "isize",
+        )?,
+        // Addresses, dates and other types that are written as text
+        _ if shape.is_display() => Yaml::String(peek.to_string()),
+        _ => {
+            return Err(YamlSerError::UnsupportedType {
+                type_name: shape.to_string(),
+            });
+        }
+    })
+}
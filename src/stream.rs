@@ -0,0 +1,295 @@
+//! Split a stream of YAML documents so each can be edited without touching the others.
+
+use alloc::{
+    string::{String, ToString as _},
+    vec::Vec,
+};
+
+use facet_core::Facet;
+
+#[cfg(feature = "deserialize")]
+use crate::{DeserializeError, from_str};
+
+/// The documents of a YAML stream, each with the text around it.
+///
+/// Every document keeps its framing verbatim: the comments and directives before it, its `---`
+/// and `...` markers, and the comments after it. Writing the stream back with
+/// [`Display`](core::fmt::Display) gives the exact input, except for the bodies of documents
+/// that were replaced, so a tool that edits one document of a multi-document file leaves the
+/// others byte-identical.
+///
+/// Documents are split at lines starting with a `---` or `...` marker, which YAML doesn't allow
+/// inside content. The stream isn't parsed, so errors in a document are only reported when it's
+/// deserialized.
+///
+/// ```
+/// # #[cfg(feature = "deserialize")] {
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Deployment {
+///     replicas: u32,
+/// }
+///
+/// let yaml = "# web\n---\nreplicas: 2\n...\n# worker\n---\nreplicas: 1 # scaled down\n";
+/// let mut stream = facet_yaml::DocumentStream::new(yaml);
+/// assert_eq!(stream.len(), 2);
+///
+/// let web: Deployment = stream.documents()[0].deserialize().unwrap();
+/// stream.documents_mut()[0].set_body(format!("replicas: {}\n", web.replicas * 2));
+/// assert_eq!(
+///     stream.to_string(),
+///     "# web\n---\nreplicas: 4\n...\n# worker\n---\nreplicas: 1 # scaled down\n"
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentStream {
+    /// The documents, in order.
+    documents: Vec<StreamDocument>,
+    /// Comments and whitespace after the last document.
+    trailer: String,
+}
+
+/// One document of a [`DocumentStream`], split into its body and the text around it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamDocument {
+    /// Directives, comments and whitespace before the content, along with the `---` marker.
+    header: String,
+    /// The content.
+    body: String,
+    /// The `...` marker, and the comments and whitespace after the content.
+    footer: String,
+}
+
+impl DocumentStream {
+    /// Split a YAML stream into its documents.
+    pub fn new(yaml: &str) -> Self {
+        let mut documents = Vec::new();
+        let mut current = StreamDocument::empty();
+        // Whether the content of the current document has started, i.e. it can't get any more
+        // header lines
+        let mut in_body = false;
+
+        for line in yaml.split_inclusive('\n') {
+            if let Some(rest) = marker(line, "---") {
+                if in_body || current.has_start_marker() {
+                    documents.push(current.finished());
+                    current = StreamDocument::empty();
+                }
+                in_body = true;
+                if is_blank_or_comment(rest) {
+                    current.header.push_str(line);
+                } else {
+                    // Content may start on the same line as the marker, e.g. `--- |`
+                    let start = line.len() - rest.trim_start().len();
+                    current.header.push_str(&line[..start]);
+                    current.body.push_str(&line[start..]);
+                }
+            } else if marker(line, "...").is_some() {
+                current.footer.push_str(line);
+                documents.push(current.finished());
+                current = StreamDocument::empty();
+                in_body = false;
+            } else if in_body {
+                current.body.push_str(line);
+            } else if is_blank_or_comment(line) || line.starts_with('%') {
+                current.header.push_str(line);
+            } else {
+                in_body = true;
+                current.body.push_str(line);
+            }
+        }
+
+        let trailer = if in_body || current.has_start_marker() {
+            documents.push(current.finished());
+            String::new()
+        } else {
+            current.header
+        };
+
+        Self { documents, trailer }
+    }
+
+    /// The number of documents.
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    /// Whether there are no documents, only comments or whitespace.
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// The documents, in order.
+    pub fn documents(&self) -> &[StreamDocument] {
+        &self.documents
+    }
+
+    /// The documents, in order, to edit them.
+    pub fn documents_mut(&mut self) -> &mut [StreamDocument] {
+        &mut self.documents
+    }
+
+    /// Add a document with the given content after the last one, starting with a `---` marker.
+    #[cfg(feature = "serialize")]
+    pub(crate) fn push(&mut self, body: impl Into<String>) {
+        let mut document = StreamDocument::empty();
+        // The marker needs its own line, even if the text before it doesn't end with a line break
+        if self
+            .documents
+            .last()
+            .is_some_and(|last| !last.to_string().ends_with('\n'))
+        {
+            document.header.push('\n');
+        }
+        document.header.push_str("---\n");
+        document.set_body(body);
+        self.documents.push(document);
+    }
+
+    /// Remove the documents after the first `len`, along with the text around them.
+    #[cfg(feature = "serialize")]
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.documents.truncate(len);
+    }
+}
+
+impl core::fmt::Display for DocumentStream {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for document in &self.documents {
+            write!(f, "{document}")?;
+        }
+        f.write_str(&self.trailer)
+    }
+}
+
+impl StreamDocument {
+    /// Create a document without any text yet.
+    fn empty() -> Self {
+        Self {
+            header: String::new(),
+            body: String::new(),
+            footer: String::new(),
+        }
+    }
+
+    /// Whether the header has the `---` marker of the document.
+    fn has_start_marker(&self) -> bool {
+        self.header
+            .split_inclusive('\n')
+            .any(|line| marker(line, "---").is_some())
+    }
+
+    /// Move the comments at the end of the body to the footer, so they're kept when the body is
+    /// replaced.
+    fn finished(mut self) -> Self {
+        let lines: Vec<&str> = self.body.split_inclusive('\n').collect();
+        let mut start = lines.len();
+        while start > 0 && (is_blank(lines[start - 1]) || lines[start - 1].starts_with('#')) {
+            start -= 1;
+        }
+        // Blank lines right after the content belong to it if it has a block scalar that keeps
+        // them, like `|+`
+        if lines[..start].iter().any(|line| keeps_trailing_lines(line)) {
+            while start < lines.len() && is_blank(lines[start]) {
+                start += 1;
+            }
+        }
+
+        let split = lines[..start].iter().map(|line| line.len()).sum();
+        let trailing = self.body.split_off(split);
+        self.footer.insert_str(0, &trailing);
+        self
+    }
+
+    /// The directives, comments and whitespace before the content, along with the `---` marker.
+    pub fn header(&self) -> &str {
+        &self.header
+    }
+
+    /// The content of the document.
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// The comments and whitespace after the content, along with the `...` marker.
+    pub fn footer(&self) -> &str {
+        &self.footer
+    }
+
+    /// Replace the content of the document, keeping its header and footer.
+    ///
+    /// A `---` marker at the start of `yaml`, as written by the serializer, is removed since the
+    /// header already has the document's own marker. A line break is added at the end if needed,
+    /// so the footer and the next document still start on their own line.
+    pub fn set_body(&mut self, yaml: impl Into<String>) {
+        let mut body = yaml.into();
+        let starts_with_marker = body
+            .lines()
+            .next()
+            .and_then(|line| marker(line, "---"))
+            .is_some_and(is_blank_or_comment);
+        if starts_with_marker {
+            body.drain(..body.find('\n').map_or(body.len(), |end| end + 1));
+        }
+        if !body.is_empty() && !body.ends_with('\n') {
+            body.push('\n');
+        }
+        self.body = body;
+    }
+
+    /// Replace the content of the document with a serialized value, keeping its header and
+    /// footer.
+    #[cfg(feature = "serialize")]
+    pub fn set_value<'a, T: Facet<'a>>(&mut self, value: &'a T) -> Result<(), crate::YamlSerError> {
+        self.set_body(crate::to_string(value)?);
+        Ok(())
+    }
+
+    /// Deserialize the document into a value of type `T`.
+    ///
+    /// Its directives are included, so tags with custom handles are resolved.
+    #[cfg(feature = "deserialize")]
+    pub fn deserialize<T: for<'facet> Facet<'facet>>(&self) -> Result<T, DeserializeError> {
+        from_str(&self.to_string())
+    }
+}
+
+impl core::fmt::Display for StreamDocument {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.header)?;
+        f.write_str(&self.body)?;
+        f.write_str(&self.footer)
+    }
+}
+
+/// If a line starts with a document marker, get the rest of the line after it.
+fn marker<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    let rest = line.strip_prefix(marker)?;
+    (rest.is_empty() || rest.starts_with([' ', '\t', '\r', '\n'])).then_some(rest)
+}
+
+/// Whether a line only has whitespace.
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+/// Whether a line only has whitespace or a comment.
+fn is_blank_or_comment(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with('#')
+}
+
+/// Whether a line starts a block scalar with the keep chomping indicator, e.g. `text: |+`, which
+/// keeps the blank lines at its end.
+fn keeps_trailing_lines(line: &str) -> bool {
+    let header = line
+        .trim_end()
+        .trim_end_matches(|c: char| c.is_ascii_digit());
+    header.strip_suffix('+').is_some_and(|header| {
+        header
+            .trim_end_matches(|c: char| c.is_ascii_digit())
+            .ends_with(['|', '>'])
+    })
+}
@@ -0,0 +1,181 @@
+//! Reload a YAML file whenever it changes.
+
+#[cfg(not(feature = "std"))]
+compile_error!("feature `std` is required, since watching files needs the file system");
+
+use alloc::string::String;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use facet_core::Facet;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher as _};
+
+use crate::DeserializeError;
+
+/// Watch a YAML file, and call `callback` with its deserialized contents whenever it changes.
+///
+/// The callback is called once with the current contents before this returns, and then from a
+/// background thread every time the file is written, created or replaced. Since the whole
+/// document is deserialized into `T`, anything checked while loading (missing fields, values out
+/// of range, invalid cron expressions, ...) is reported to the callback as an error, and the
+/// previous value can be kept.
+///
+/// The parent directory is watched rather than the file itself, so editors that save by
+/// replacing the file are picked up too. Saves that don't change the contents are skipped.
+///
+/// Watching stops when the returned [`Watcher`] is dropped.
+///
+/// ```no_run
+/// use facet::Facet;
+///
+/// #[derive(Debug, Facet)]
+/// struct Config {
+///     workers: u16,
+/// }
+///
+/// let _watcher = facet_yaml::watch::<Config, _>("config.yaml", |config| match config {
+///     Ok(config) => println!("Reloaded: {config:?}"),
+///     Err(err) => eprintln!("Keeping the previous configuration: {err}"),
+/// })?;
+/// # Ok::<(), facet_yaml::WatchError>(())
+/// ```
+pub fn watch<T, F>(path: impl AsRef<Path>, mut callback: F) -> Result<Watcher, WatchError>
+where
+    T: for<'facet> Facet<'facet>,
+    F: FnMut(Result<T, WatchError>) + Send + 'static,
+{
+    let path = path.as_ref().to_path_buf();
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| WatchError::NotAFile(path.clone()))?
+        .to_owned();
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    let mut reloader = Reloader {
+        path,
+        previous: None,
+    };
+    callback(reloader.load());
+
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let event = match event {
+            Ok(event) => event,
+            Err(err) => return callback(Err(WatchError::Notify(err))),
+        };
+
+        #[cfg(feature = "log")]
+        log::debug!("File event: {event:?}");
+
+        if !matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Any
+        ) || !event
+            .paths
+            .iter()
+            .any(|path| path.file_name() == Some(file_name.as_os_str()))
+        {
+            return;
+        }
+
+        if let Some(result) = reloader.reload() {
+            callback(result);
+        }
+    })
+    .map_err(WatchError::Notify)?;
+
+    watcher
+        .watch(&directory, RecursiveMode::NonRecursive)
+        .map_err(WatchError::Notify)?;
+
+    Ok(Watcher { _inner: watcher })
+}
+
+/// Watches a file for as long as it is alive, see [`watch`].
+pub struct Watcher {
+    /// Stops watching when dropped.
+    _inner: RecommendedWatcher,
+}
+
+impl core::fmt::Debug for Watcher {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Watcher").finish_non_exhaustive()
+    }
+}
+
+/// Error from watching or reloading a YAML file.
+#[derive(Debug)]
+pub enum WatchError {
+    /// The watched path doesn't name a file.
+    NotAFile(PathBuf),
+    /// The file couldn't be read.
+    Io(std::io::Error),
+    /// The contents of the file couldn't be deserialized.
+    Deserialize(DeserializeError),
+    /// The file system couldn't be watched.
+    Notify(notify::Error),
+}
+
+impl core::fmt::Display for WatchError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            WatchError::NotAFile(path) => write!(f, "{} is not a file", path.display()),
+            WatchError::Io(err) => write!(f, "Failed to read file: {err}"),
+            WatchError::Deserialize(err) => write!(f, "Failed to deserialize file: {err}"),
+            WatchError::Notify(err) => write!(f, "Failed to watch file: {err}"),
+        }
+    }
+}
+
+impl core::error::Error for WatchError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            WatchError::NotAFile(_) => None,
+            WatchError::Io(err) => Some(err),
+            WatchError::Deserialize(err) => Some(err),
+            WatchError::Notify(err) => Some(err),
+        }
+    }
+}
+
+/// Reads and deserializes the watched file.
+struct Reloader {
+    /// The watched file.
+    path: PathBuf,
+    /// Contents of the last read, to skip events that didn't change anything.
+    previous: Option<String>,
+}
+
+impl Reloader {
+    /// Read and deserialize the file.
+    fn load<T: for<'facet> Facet<'facet>>(&mut self) -> Result<T, WatchError> {
+        let contents = fs::read_to_string(&self.path).map_err(WatchError::Io)?;
+        self.deserialize(contents)
+    }
+
+    /// Read and deserialize the file if it changed since the last read.
+    fn reload<T: for<'facet> Facet<'facet>>(&mut self) -> Option<Result<T, WatchError>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) if self.previous.as_ref() == Some(&contents) => return None,
+            Ok(contents) => contents,
+            // Replaced by a rename, the file will be created again right away
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(err) => return Some(Err(WatchError::Io(err))),
+        };
+        Some(self.deserialize(contents))
+    }
+
+    /// Deserialize the contents, remembering them for the next change.
+    fn deserialize<T: for<'facet> Facet<'facet>>(
+        &mut self,
+        contents: String,
+    ) -> Result<T, WatchError> {
+        let value = crate::from_str(&contents).map_err(WatchError::Deserialize);
+        self.previous = Some(contents);
+        value
+    }
+}
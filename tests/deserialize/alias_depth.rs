@@ -0,0 +1,107 @@
+use facet_testhelpers::test;
+use facet_yaml::{DeserializeOptions, ErrorCode, Value};
+
+/// A document where every anchor refers to the previous one, `depth` times.
+fn alias_chain(depth: usize) -> String {
+    let mut yaml = String::from("a0: &a0 [leaf]\n");
+    for level in 1..=depth {
+        yaml.push_str(&format!("a{level}: &a{level} [*a{}]\n", level - 1));
+    }
+    yaml
+}
+
+#[test]
+fn test_alias_depth_within_limit() {
+    let options = DeserializeOptions::new().max_alias_depth(3);
+    let value: Value = facet_yaml::from_str_with_options(&alias_chain(3), &options).unwrap();
+    assert_eq!(value.as_map().unwrap().len(), 4);
+}
+
+#[test]
+fn test_alias_depth_over_limit() {
+    let options = DeserializeOptions::new().max_alias_depth(3);
+    let err = facet_yaml::from_str_with_options::<Value>(&alias_chain(4), &options).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Syntax);
+    assert_eq!(
+        err.message(),
+        "aliases are nested deeper than the limit of 3"
+    );
+    assert_eq!(err.span().map(|span| span.line), Some(5));
+}
+
+#[test]
+fn test_alias_depth_default_limit() {
+    facet_yaml::from_str::<Value>(&alias_chain(16)).unwrap();
+    facet_yaml::from_str::<Value>(&alias_chain(1000)).unwrap_err();
+}
+
+#[test]
+fn test_alias_depth_of_repeated_aliases() {
+    // Exponential expansion: every level doubles the size of the document
+    let mut yaml = String::from("l0: &l0 [lol, lol]\n");
+    for level in 1..40 {
+        let previous = level - 1;
+        yaml.push_str(&format!(
+            "l{level}: &l{level} [*l{previous}, *l{previous}]\n"
+        ));
+    }
+    let err = facet_yaml::from_str::<Value>(&yaml).unwrap_err();
+    assert_eq!(err.span().map(|span| span.line), Some(18));
+}
+
+#[test]
+fn test_alias_depth_counts_aliases_in_mappings() {
+    let yaml = "base: &base {x: 1}\nmid: &mid {inner: *base}\ntop: *mid\n";
+    let options = DeserializeOptions::new().max_alias_depth(1);
+    let err = facet_yaml::from_str_with_options::<Value>(yaml, &options).unwrap_err();
+    assert_eq!(err.span().map(|span| span.line), Some(3));
+
+    let options = DeserializeOptions::new().max_alias_depth(2);
+    facet_yaml::from_str_with_options::<Value>(yaml, &options).unwrap();
+}
+
+/// A billion laughs with `fan_out` aliases of the level below on each of `levels` levels.
+fn billion_laughs(fan_out: usize, levels: usize) -> String {
+    let mut yaml = format!("l0: &l0 [{}]\n", vec!["lol"; fan_out].join(", "));
+    for level in 1..levels {
+        let aliases = vec![format!("*l{}", level - 1); fan_out].join(", ");
+        yaml.push_str(&format!("l{level}: &l{level} [{aliases}]\n"));
+    }
+    yaml
+}
+
+#[test]
+fn test_alias_nodes_of_wide_fan_out() {
+    // Aliases are only nested 8 deep, but the last level alone expands to 10^9 nodes
+    let err = facet_yaml::from_str::<Value>(&billion_laughs(10, 9)).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::AliasLimit);
+    assert_eq!(
+        err.message(),
+        "aliases expand to more nodes than the limit of 1000000"
+    );
+    // The fifth level copies 111111 nodes for each alias, which reaches the limit at its eighth
+    assert_eq!(err.span().map(|span| span.line), Some(6));
+
+    facet_yaml::from_str::<Value>(&billion_laughs(10, 5)).unwrap();
+}
+
+#[test]
+fn test_alias_nodes_limit() {
+    let yaml = "base: &base {x: 1, y: 2}\ncopies: [*base, *base]\n";
+    let options = DeserializeOptions::new().max_alias_nodes(10);
+    facet_yaml::from_str_with_options::<Value>(yaml, &options).unwrap();
+
+    let options = DeserializeOptions::new().max_alias_nodes(9);
+    let err = facet_yaml::from_str_with_options::<Value>(yaml, &options).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::AliasLimit);
+    assert_eq!(err.span().map(|span| span.line), Some(2));
+}
+
+#[test]
+fn test_alias_nodes_are_counted_for_each_document() {
+    let yaml = "- &a [1, 2]\n- *a\n---\n- &a [1, 2]\n- *a\n";
+    let options = DeserializeOptions::new().max_alias_nodes(3);
+    // Both documents are loaded before their number is checked
+    let err = facet_yaml::from_str_with_options::<Value>(yaml, &options).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::DocumentCount);
+}
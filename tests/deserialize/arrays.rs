@@ -0,0 +1,60 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::ErrorCode;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Theme {
+    background: [f32; 3],
+    checksum: [u8; 4],
+    #[facet(default)]
+    corners: [[u16; 2]; 2],
+}
+
+#[test]
+fn test_fixed_size_arrays() {
+    let theme: Theme = facet_yaml::from_str(
+        "background: [0.5, 1, 0.25]\nchecksum:\n  - 222\n  - 173\n  - 190\n  - 239\ncorners: [[0, 0], [640, 480]]\n",
+    )
+    .unwrap();
+    assert_eq!(
+        theme,
+        Theme {
+            background: [0.5, 1.0, 0.25],
+            checksum: [222, 173, 190, 239],
+            corners: [[0, 0], [640, 480]],
+        }
+    );
+}
+
+#[test]
+fn test_fixed_size_array_length_mismatch() {
+    let err = facet_yaml::from_str::<Theme>("background: [0.5, 1]\nchecksum: [1, 2, 3, 4]\n")
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+    assert_eq!(err.path(), "background");
+    assert_eq!(err.message(), "Expected 3 items for [f32; 3], got 2");
+
+    let err = facet_yaml::from_str::<Theme>(
+        "background: [0, 0, 0]\nchecksum: [1, 2, 3, 4]\ncorners: [[0, 0], [1, 2, 3]]\n",
+    )
+    .unwrap_err();
+    assert_eq!(err.path(), "corners[1]");
+    assert_eq!(err.message(), "Expected 2 items for [u16; 2], got 3");
+}
+
+#[test]
+fn test_fixed_size_array_items() {
+    let err = facet_yaml::from_str::<Theme>("background: [0, 0, 0]\nchecksum: [1, 2, 300, 4]\n")
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::OutOfRange);
+    assert_eq!(err.path(), "checksum[2]");
+
+    let errors = facet_yaml::validate::<Theme>("background: [0, 0]\nchecksum: [1, 2, 300, 4]\n")
+        .unwrap_err();
+    let paths: Vec<_> = errors.iter().map(|e| e.path()).collect();
+    assert_eq!(paths, ["background", "checksum[2]"]);
+
+    let err =
+        facet_yaml::from_str::<Theme>("background: red\nchecksum: [1, 2, 3, 4]\n").unwrap_err();
+    assert_eq!(err.message(), "Expected a YAML array, got: string");
+}
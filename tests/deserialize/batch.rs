@@ -0,0 +1,124 @@
+use std::{fs, path::PathBuf};
+
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::{DeserializeOptions, FileError};
+
+#[derive(Debug, Default, Facet, PartialEq)]
+struct Manifest {
+    name: String,
+    #[facet(default)]
+    replicas: u16,
+}
+
+/// Write the files to a fresh directory, returning their paths.
+fn write_files(test: &str, files: &[(&str, &str)]) -> Vec<PathBuf> {
+    let directory =
+        std::env::temp_dir().join(format!("facet-yaml-batch-{test}-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    files
+        .iter()
+        .map(|(name, contents)| {
+            let path = directory.join(name);
+            fs::write(&path, contents).unwrap();
+            path
+        })
+        .collect()
+}
+
+#[test]
+fn test_from_paths_all_ok() {
+    let paths = write_files(
+        "ok",
+        &[
+            ("web.yaml", "name: web\nreplicas: 3\n"),
+            ("worker.yml", "name: worker\n"),
+        ],
+    );
+    let report = facet_yaml::from_paths::<Manifest, _>(&paths, &DeserializeOptions::default());
+
+    assert!(report.is_ok());
+    assert_eq!(report.errors().count(), 0);
+    assert_eq!(report.warnings().count(), 0);
+    assert_eq!(report.to_string(), "");
+
+    let values = report.into_values().unwrap();
+    assert_eq!(
+        values,
+        vec![
+            (
+                paths[0].clone(),
+                Manifest {
+                    name: "web".into(),
+                    replicas: 3
+                }
+            ),
+            (
+                paths[1].clone(),
+                Manifest {
+                    name: "worker".into(),
+                    replicas: 0
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_from_paths_aggregates_errors() {
+    let mut paths = write_files(
+        "errors",
+        &[
+            ("web.yaml", "name: web\n"),
+            ("bad.yaml", "name: bad\nreplicas: 70000\n"),
+            ("empty.yaml", "# nothing\n"),
+            ("notes.txt", "name: notes\n"),
+        ],
+    );
+    paths.push(paths[0].with_file_name("missing.yaml"));
+    let report = facet_yaml::from_paths::<Manifest, _>(&paths, &DeserializeOptions::default());
+
+    assert!(!report.is_ok());
+    assert_eq!(report.files.len(), 5);
+    assert_eq!(
+        report
+            .values()
+            .map(|(_, manifest)| manifest.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["web", "", "notes"]
+    );
+
+    let errors: Vec<_> = report.errors().collect();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].0, paths[1]);
+    match errors[0].1 {
+        FileError::Deserialize(err) => assert_eq!(err.path(), "replicas"),
+        err => panic!("unexpected error: {err}"),
+    }
+    assert_eq!(errors[1].0, paths[4]);
+    assert!(matches!(errors[1].1, FileError::Io(_)));
+
+    let warnings: Vec<_> = report.warnings().collect();
+    assert_eq!(
+        warnings,
+        vec![
+            (
+                paths[2].as_path(),
+                "File is empty, the default value is used"
+            ),
+            (
+                paths[3].as_path(),
+                "File doesn't have a .yaml or .yml extension"
+            ),
+        ]
+    );
+
+    let text = report.to_string();
+    assert_eq!(text.lines().count(), 4);
+    assert!(text.starts_with(&format!(
+        "{}: Value 70000 out of range for u16 at `replicas` (line 2, column 11)\n",
+        paths[1].display()
+    )));
+
+    assert!(report.into_values().is_err());
+}
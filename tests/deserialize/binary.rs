@@ -0,0 +1,76 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::{DeserializeOptions, ErrorCode};
+
+#[derive(Debug, Facet, PartialEq)]
+struct Secret {
+    data: Vec<u8>,
+}
+
+#[test]
+fn test_binary_scalar() {
+    let secret: Secret = facet_yaml::from_str("data: !!binary aGVsbG8=").unwrap();
+    assert_eq!(secret.data, b"hello");
+}
+
+#[test]
+fn test_binary_block_scalar() {
+    let yaml = "data: !!binary |\n  R0lGODlhDAAMAIQAAP//9/X1\n  7unp5WZmZgAAAOfn515eXg==\n";
+    let secret: Secret = facet_yaml::from_str(yaml).unwrap();
+    assert_eq!(&secret.data[..6], b"GIF89a");
+    assert_eq!(secret.data.len(), 34);
+}
+
+#[test]
+fn test_binary_sequence_still_accepted() {
+    let secret: Secret = facet_yaml::from_str("data: [104, 105]").unwrap();
+    assert_eq!(secret.data, b"hi");
+}
+
+#[test]
+fn test_binary_invalid_base64() {
+    let err = facet_yaml::from_str::<Secret>("data: !!binary not*base64").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::InvalidScalar);
+    assert_eq!(err.message(), "Invalid base64 text in `!!binary` scalar");
+    assert_eq!(err.path(), "data");
+}
+
+#[test]
+fn test_binary_untagged_string_is_rejected() {
+    let err = facet_yaml::from_str::<Secret>("data: aGVsbG8=").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+}
+
+#[test]
+fn test_binary_validate() {
+    facet_yaml::validate::<Secret>("data: !!binary aGVsbG8=").unwrap();
+    let errors = facet_yaml::validate::<Secret>("data: !!binary aGVsbG8").unwrap_err();
+    assert_eq!(errors[0].code(), ErrorCode::InvalidScalar);
+}
+
+#[test]
+fn test_binary_from_base64_strings() {
+    let options = DeserializeOptions::new().base64_strings(true);
+    let secret: Secret = facet_yaml::from_str_with_options("data: aGVsbG8=", &options).unwrap();
+    assert_eq!(secret.data, b"hello");
+
+    // Quoted and block strings too, and `!!binary` scalars and sequences as always
+    let secret: Secret =
+        facet_yaml::from_str_with_options("data: |\n  aGVs\n  bG8=\n", &options).unwrap();
+    assert_eq!(secret.data, b"hello");
+    let secret: Secret =
+        facet_yaml::from_str_with_options("data: !!binary 'aGk='", &options).unwrap();
+    assert_eq!(secret.data, b"hi");
+    let secret: Secret = facet_yaml::from_str_with_options("data: [104, 105]", &options).unwrap();
+    assert_eq!(secret.data, b"hi");
+
+    let err = facet_yaml::from_str_with_options::<Secret>("data: hello!", &options).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::InvalidScalar);
+    assert_eq!(err.message(), "Invalid base64 text in string read as bytes");
+    assert_eq!(err.path(), "data");
+
+    // Strings with another tag aren't base64
+    let err =
+        facet_yaml::from_str_with_options::<Secret>("data: !!str aGk=", &options).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+}
@@ -0,0 +1,60 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::{DeserializeOptions, ErrorCode, YamlDeserializer};
+
+#[derive(Debug, Facet, PartialEq)]
+struct Job {
+    name: String,
+    steps: Vec<String>,
+}
+
+const YAML: &str = "name: build\nsteps: [fetch, compile, test]";
+
+#[test]
+fn test_cancel_flag() {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let options = DeserializeOptions::new().cancel_flag(cancelled.clone());
+
+    let job: Job = facet_yaml::from_str_with_options(YAML, &options).unwrap();
+    assert_eq!(job.steps, ["fetch", "compile", "test"]);
+
+    cancelled.store(true, Ordering::Relaxed);
+    let err = facet_yaml::from_str_with_options::<Job>(YAML, &options).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Cancelled);
+    assert_eq!(err.message(), "Deserialization was cancelled");
+}
+
+/// Cancel once the check has been called `calls` times.
+fn cancel_after(calls: usize) -> DeserializeOptions {
+    let count = AtomicUsize::new(0);
+    DeserializeOptions::new().cancel_if(move || count.fetch_add(1, Ordering::Relaxed) >= calls)
+}
+
+#[test]
+fn test_cancel_while_parsing() {
+    // The document has 8 nodes, which are parsed before any of them is bound
+    let err = facet_yaml::from_str_with_options::<Job>(YAML, &cancel_after(4)).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Cancelled);
+}
+
+#[test]
+fn test_cancel_while_binding() {
+    let err = facet_yaml::from_str_with_options::<Job>(YAML, &cancel_after(10)).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Cancelled);
+    assert_eq!(err.message(), "Deserialization was cancelled");
+
+    let job: Job = facet_yaml::from_str_with_options(YAML, &cancel_after(100)).unwrap();
+    assert_eq!(job.name, "build");
+}
+
+#[test]
+fn test_cancel_with_yaml_deserializer() {
+    let deserializer = YamlDeserializer::new(DeserializeOptions::new().cancel_if(|| true));
+    let err = deserializer.from_str::<Job>(YAML).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Cancelled);
+}
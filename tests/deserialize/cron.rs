@@ -0,0 +1,57 @@
+#![cfg(feature = "cron")]
+
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::CronExpr;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Job {
+    name: String,
+    schedule: CronExpr,
+}
+
+#[test]
+fn test_deserialize_cron_expression() {
+    let yaml = r#"
+        name: backup
+        schedule: "*/15 9-17 * * MON-FRI"
+    "#;
+
+    let job: Job = facet_yaml::from_str(yaml).unwrap();
+    assert_eq!(job.name, "backup");
+    assert_eq!(job.schedule.as_str(), "*/15 9-17 * * MON-FRI");
+}
+
+#[test]
+fn test_deserialize_cron_shorthand_and_seconds() {
+    let jobs: Vec<CronExpr> = facet_yaml::from_str(
+        r#"
+        - "@daily"
+        - 30 0 0 1 JAN *
+    "#,
+    )
+    .unwrap();
+    assert_eq!(jobs[0].as_str(), "@daily");
+    assert_eq!(jobs[1].as_str(), "30 0 0 1 JAN *");
+}
+
+#[test]
+fn test_deserialize_invalid_cron_expression() {
+    let yaml = "name: backup\nschedule: 61 * * * *";
+
+    let err = facet_yaml::from_str::<Job>(yaml).unwrap_err().to_string();
+    assert!(
+        err.contains("invalid minute field `61`"),
+        "Unexpected error: {err}"
+    );
+    assert!(err.contains("line 2, column 11"), "Missing location: {err}");
+}
+
+#[test]
+fn test_parse_cron_expression() {
+    assert!(CronExpr::parse("0 0 * * *").is_ok());
+    assert!(CronExpr::parse("0 0 * *").is_err());
+    assert!(CronExpr::parse("0 24 * * *").is_err());
+    assert!(CronExpr::parse("*/0 * * * *").is_err());
+    assert!(CronExpr::parse("@reboot").is_err());
+}
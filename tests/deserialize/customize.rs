@@ -0,0 +1,124 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::{DeserializeOptions, ErrorCode, Value, YamlCustomize};
+
+#[derive(Debug, Facet, PartialEq)]
+struct Duration {
+    seconds: i64,
+    nanos: i32,
+}
+
+impl YamlCustomize for Duration {
+    fn to_yaml(&self) -> Value {
+        Value::String(format!("{}s", self.seconds))
+    }
+
+    fn from_yaml(value: Value) -> Result<Self, String> {
+        let seconds = value
+            .as_str()
+            .and_then(|text| text.strip_suffix('s'))
+            .and_then(|seconds| seconds.parse().ok())
+            .ok_or("Expected a duration such as `90s`")?;
+        Ok(Duration { seconds, nanos: 0 })
+    }
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Range {
+    start: u32,
+    end: u32,
+}
+
+impl YamlCustomize for Range {
+    const TAG: Option<&'static str> = Some("!range");
+
+    fn to_yaml(&self) -> Value {
+        Value::Sequence(vec![
+            Value::Number(u64::from(self.start).into()),
+            Value::Number(u64::from(self.end).into()),
+        ])
+    }
+
+    fn from_yaml(value: Value) -> Result<Self, String> {
+        match value.as_sequence() {
+            Some([start, end]) => Ok(Range {
+                start: start.as_u64().ok_or("Invalid start")? as u32,
+                end: end.as_u64().ok_or("Invalid end")? as u32,
+            }),
+            _ => Err("Expected `[start, end]`".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Job {
+    timeout: Duration,
+    #[facet(default)]
+    retry_after: Option<Duration>,
+    #[facet(default)]
+    ports: Vec<Range>,
+}
+
+fn options() -> DeserializeOptions {
+    DeserializeOptions::new()
+        .customize::<Duration>()
+        .customize::<Range>()
+}
+
+#[test]
+fn test_customized_types() {
+    let job: Job = facet_yaml::from_str_with_options(
+        "timeout: 90s\nretry_after: 5s\nports:\n  - !range [80, 89]\n  - [443, 443]\n",
+        &options(),
+    )
+    .unwrap();
+    assert_eq!(
+        job,
+        Job {
+            timeout: Duration {
+                seconds: 90,
+                nanos: 0,
+            },
+            retry_after: Some(Duration {
+                seconds: 5,
+                nanos: 0,
+            }),
+            ports: vec![
+                Range { start: 80, end: 89 },
+                Range {
+                    start: 443,
+                    end: 443,
+                }
+            ],
+        }
+    );
+
+    // Without the customization the type is read by its shape
+    let duration: Duration = facet_yaml::from_str("seconds: 90\nnanos: 5").unwrap();
+    assert_eq!(
+        duration,
+        Duration {
+            seconds: 90,
+            nanos: 5,
+        }
+    );
+}
+
+#[test]
+fn test_customized_type_errors() {
+    // The message of the type is reported where the node is
+    let err = facet_yaml::from_str_with_options::<Job>("timeout: soon\n", &options()).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Custom);
+    assert_eq!(err.message(), "Expected a duration such as `90s`");
+    assert_eq!(err.path(), "timeout");
+    assert_eq!(err.span().unwrap().line, 1);
+
+    // Nodes can't have another tag than the one of the type
+    let err = facet_yaml::from_str_with_options::<Job>(
+        "timeout: 1s\nports:\n  - !span [1, 2]\n",
+        &options(),
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+    assert_eq!(err.span().unwrap().line, 3);
+}
@@ -171,3 +171,103 @@ fn test_default_with_complex_expression() {
     assert_eq!(config2.timeout_seconds, 60);
     assert_eq!(config2.default_roles, vec!["guest".to_string()]);
 }
+
+#[test]
+fn test_struct_level_default_fills_partial_sections() {
+    #[derive(Facet, Debug, PartialEq)]
+    #[facet(default)]
+    struct Pool {
+        size: u32,
+        timeout: u64,
+        name: String,
+    }
+
+    impl Default for Pool {
+        fn default() -> Self {
+            Self {
+                size: 8,
+                timeout: 30,
+                name: "main".into(),
+            }
+        }
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Config {
+        pool: Pool,
+    }
+
+    // Each missing field comes from `Default::default()`, not the default of its type
+    let config: Config = facet_yaml::from_str("pool:\n  timeout: 5\n").unwrap();
+    assert_eq!(
+        config.pool,
+        Pool {
+            size: 8,
+            timeout: 5,
+            name: "main".into(),
+        }
+    );
+
+    let config: Config = facet_yaml::from_str("pool: {}").unwrap();
+    assert_eq!(config.pool, Pool::default());
+}
+
+#[test]
+fn test_missing_field_without_struct_default() {
+    #[derive(Facet, Debug)]
+    #[allow(dead_code)]
+    struct Pool {
+        size: u32,
+        timeout: u64,
+    }
+
+    let err = facet_yaml::from_str::<Pool>("size: 4").unwrap_err();
+    assert_eq!(err.code(), facet_yaml::ErrorCode::MissingField);
+    assert_eq!(err.message(), "Missing field 'timeout'");
+}
+
+#[test]
+fn test_field_default_functions_everywhere() {
+    fn default_port() -> u16 {
+        8080
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Listener {
+        #[facet(default = "0.0.0.0".to_string())]
+        host: String,
+        #[facet(default = default_port())]
+        port: u16,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    enum Upstream {
+        Http {
+            url: String,
+            #[facet(default = default_port())]
+            port: u16,
+        },
+    }
+
+    let expected = Listener {
+        host: "0.0.0.0".into(),
+        port: 8080,
+    };
+
+    // An empty document is missing every field, rather than the whole struct
+    let listener: Listener = facet_yaml::from_str("# defaults only\n").unwrap();
+    assert_eq!(listener, expected);
+    let listener: Listener = facet_yaml::from_str("{}").unwrap();
+    assert_eq!(listener, expected);
+    assert!(facet_yaml::validate::<Listener>("host: 0.0.0.0\n").is_ok());
+
+    let upstream: Upstream = facet_yaml::from_str("Http:\n  url: http://backend\n").unwrap();
+    assert_eq!(
+        upstream,
+        Upstream::Http {
+            url: "http://backend".into(),
+            port: 8080,
+        }
+    );
+}
@@ -0,0 +1,76 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::ErrorCode;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Release {
+    name: String,
+    #[facet(yaml(embedded))]
+    values: Values,
+    #[facet(yaml(embedded))]
+    overrides: Option<Values>,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Values {
+    replicas: u32,
+    #[facet(default)]
+    image: String,
+}
+
+#[test]
+fn test_embedded_documents() {
+    let release: Release = facet_yaml::from_str(
+        "name: web\nvalues: |\n  replicas: 3\n  image: nginx\noverrides: '{replicas: 5}'\n",
+    )
+    .unwrap();
+    assert_eq!(
+        release,
+        Release {
+            name: "web".into(),
+            values: Values {
+                replicas: 3,
+                image: "nginx".into(),
+            },
+            overrides: Some(Values {
+                replicas: 5,
+                image: String::new(),
+            }),
+        }
+    );
+
+    // An empty document is the default, like a missing key
+    let release: Release =
+        facet_yaml::from_str("name: web\nvalues: 'replicas: 1'\noverrides: ''\n").unwrap();
+    assert_eq!(release.overrides, None);
+}
+
+#[test]
+fn test_embedded_documents_must_be_strings() {
+    let err = facet_yaml::from_str::<Release>("name: web\nvalues:\n  replicas: 3\n").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+    assert_eq!(err.path(), "values");
+    assert_eq!(
+        err.message(),
+        "Expected a string with a YAML document in it, got: hash/map"
+    );
+}
+
+#[test]
+fn test_errors_in_embedded_documents_point_at_the_string() {
+    let yaml = "name: web\nvalues: |\n  replicas: many\n";
+    let err = facet_yaml::from_str::<Release>(yaml).unwrap_err();
+    assert_eq!(err.path(), "values.replicas");
+    // Block scalars start on the line after their indicator, where the embedded document does
+    assert_eq!(err.span().unwrap().line, 3);
+
+    let errors = facet_yaml::validate::<Release>(yaml).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path(), "values.replicas");
+    assert_eq!(errors[0].span().unwrap().line, 3);
+
+    let err =
+        facet_yaml::from_str::<Release>("name: web\nvalues: \"a: 1\\n---\\nb: 2\"\n").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::DocumentCount);
+    assert_eq!(err.path(), "values");
+}
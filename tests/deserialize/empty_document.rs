@@ -0,0 +1,82 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use std::collections::HashMap;
+
+#[derive(Debug, Default, Facet, PartialEq)]
+struct Config {
+    name: String,
+    port: u16,
+}
+
+#[test]
+fn test_deserialize_directive_only_document() {
+    let config: Config = facet_yaml::from_str("%YAML 1.2\n---\n").unwrap();
+    assert_eq!(config, Config::default());
+}
+
+#[test]
+fn test_deserialize_comment_only_document() {
+    let config: Config = facet_yaml::from_str("# Nothing configured yet\n").unwrap();
+    assert_eq!(config, Config::default());
+
+    let config: Config = facet_yaml::from_str("").unwrap();
+    assert_eq!(config, Config::default());
+}
+
+#[test]
+fn test_deserialize_explicitly_ended_empty_document() {
+    let values: Vec<u32> = facet_yaml::from_str("---\n# empty\n...\n").unwrap();
+    assert!(values.is_empty());
+
+    let map: HashMap<String, u32> = facet_yaml::from_str("---\n").unwrap();
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_deserialize_null_document_into_option() {
+    let value: Option<u32> = facet_yaml::from_str("~").unwrap();
+    assert_eq!(value, None);
+
+    let value: Option<u32> = facet_yaml::from_str("# no value\n").unwrap();
+    assert_eq!(value, None);
+}
+
+#[test]
+fn test_deserialize_explicit_null_document() {
+    // An explicit null is a value, which only an Option can hold, unlike a missing one
+    let err = facet_yaml::from_str::<String>("~").unwrap_err();
+    assert_eq!(err.code(), facet_yaml::ErrorCode::TypeMismatch);
+    facet_yaml::from_str::<u32>("null").unwrap_err();
+    facet_yaml::from_str::<Config>("--- ~\n").unwrap_err();
+    assert!(facet_yaml::validate::<u32>("~").is_err());
+
+    assert_eq!(facet_yaml::from_str::<u32>("---\n").unwrap(), 0);
+    assert!(facet_yaml::validate::<u32>("---\n").is_ok());
+}
+
+#[test]
+fn test_deserialize_empty_document_without_default() {
+    #[derive(Debug, Facet)]
+    struct Required {
+        #[allow(dead_code)]
+        name: String,
+    }
+
+    let err = facet_yaml::from_str::<Required>("%YAML 1.2\n---\n").unwrap_err();
+    assert!(err.to_string().contains("document is empty"), "{err}");
+}
+
+#[test]
+fn test_deserialize_with_byte_order_mark() {
+    let config: Config = facet_yaml::from_str("\u{feff}name: server\nport: 8080\n").unwrap();
+    assert_eq!(
+        config,
+        Config {
+            name: "server".to_string(),
+            port: 8080
+        }
+    );
+
+    let config: Config = facet_yaml::from_str("\u{feff}%YAML 1.2\n---\n").unwrap();
+    assert_eq!(config, Config::default());
+}
@@ -0,0 +1,195 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::ErrorCode;
+
+#[derive(Debug, Facet, PartialEq)]
+#[repr(u8)]
+enum Backend {
+    Memory,
+    Redis(String),
+    Pair(String, u16),
+    Postgres {
+        host: String,
+        #[facet(default)]
+        port: u16,
+    },
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Cache {
+    backend: Backend,
+}
+
+#[test]
+fn test_unit_variant() {
+    let cache: Cache = facet_yaml::from_str("backend: Memory").unwrap();
+    assert_eq!(cache.backend, Backend::Memory);
+
+    let cache: Cache = facet_yaml::from_str("backend: {Memory: ~}").unwrap();
+    assert_eq!(cache.backend, Backend::Memory);
+}
+
+#[test]
+fn test_newtype_variant() {
+    let cache: Cache = facet_yaml::from_str("backend:\n  Redis: redis://cache").unwrap();
+    assert_eq!(cache.backend, Backend::Redis("redis://cache".to_string()));
+}
+
+#[test]
+fn test_tuple_variant() {
+    let cache: Cache = facet_yaml::from_str("backend:\n  Pair: [db, 5432]").unwrap();
+    assert_eq!(cache.backend, Backend::Pair("db".to_string(), 5432));
+
+    let err = facet_yaml::from_str::<Cache>("backend:\n  Pair: [db]").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+    assert_eq!(err.message(), "Expected 2 items for variant 'Pair', got 1");
+}
+
+#[test]
+fn test_struct_variant() {
+    let cache: Cache = facet_yaml::from_str("backend:\n  Postgres:\n    host: db\n").unwrap();
+    assert_eq!(
+        cache.backend,
+        Backend::Postgres {
+            host: "db".to_string(),
+            port: 0
+        }
+    );
+
+    let err = facet_yaml::from_str::<Cache>("backend:\n  Postgres:\n    port: 5432\n").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::MissingField);
+    assert_eq!(err.path(), "backend.Postgres");
+
+    let err = facet_yaml::from_str::<Cache>("backend:\n  Postgres:\n    host: db\n    user: app\n")
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UnknownField);
+    assert_eq!(err.path(), "backend.Postgres");
+}
+
+#[test]
+fn test_unknown_variant() {
+    let err = facet_yaml::from_str::<Cache>("backend: Sqlite").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UnknownVariant);
+    assert_eq!(
+        err.message(),
+        "Unknown variant 'Sqlite' of Backend, expected one of 'Memory', 'Redis', 'Pair', 'Postgres'"
+    );
+    assert_eq!(err.path(), "backend");
+}
+
+#[test]
+fn test_variant_with_fields_needs_content() {
+    let err = facet_yaml::from_str::<Cache>("backend: Redis").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+
+    let err = facet_yaml::from_str::<Cache>("backend:\n  Memory: 1").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+
+    let err = facet_yaml::from_str::<Cache>("backend:\n  Memory: ~\n  Redis: x").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+}
+
+#[derive(Debug, Facet, PartialEq, Eq, PartialOrd, Ord)]
+#[facet(rename_all = "snake_case")]
+#[repr(u8)]
+enum Level {
+    Debug,
+    Info,
+    Warn,
+}
+
+#[test]
+fn test_unit_variants_with_rename_all() {
+    #[derive(Debug, Facet)]
+    struct Logging {
+        level: Level,
+        overrides: std::collections::BTreeMap<String, Level>,
+        muted: Vec<Level>,
+    }
+
+    let logging: Logging =
+        facet_yaml::from_str("level: info\noverrides: {http: debug, db: 'warn'}\nmuted: [debug]")
+            .unwrap();
+    assert_eq!(logging.level, Level::Info);
+    assert_eq!(logging.overrides["http"], Level::Debug);
+    assert_eq!(logging.overrides["db"], Level::Warn);
+    assert_eq!(logging.muted, [Level::Debug]);
+
+    let err = facet_yaml::from_str::<Level>("Info").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UnknownVariant);
+    assert_eq!(
+        err.message(),
+        "Unknown variant 'Info' of Level, expected one of 'debug', 'info', 'warn'"
+    );
+}
+
+#[test]
+fn test_unit_variants_as_map_keys() {
+    let counts: std::collections::BTreeMap<Level, u32> =
+        facet_yaml::from_str("warn: 3\ninfo: 10").unwrap();
+    assert_eq!(
+        counts.into_iter().collect::<Vec<_>>(),
+        [(Level::Info, 10), (Level::Warn, 3)]
+    );
+}
+
+#[test]
+fn test_unit_variants_as_keys_of_structs() {
+    #[derive(Debug, Facet, PartialEq, Eq, Hash)]
+    #[facet(rename_all = "kebab-case")]
+    #[repr(u8)]
+    enum Environment {
+        Production,
+        LocalDev,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Settings {
+        replicas: u32,
+        #[facet(default)]
+        debug: bool,
+    }
+
+    let yaml = "production: {replicas: 3}\nlocal-dev: {replicas: 1, debug: true}";
+    let settings: std::collections::HashMap<Environment, Settings> =
+        facet_yaml::from_str(yaml).unwrap();
+    assert_eq!(settings[&Environment::Production].replicas, 3);
+    assert!(settings[&Environment::LocalDev].debug);
+
+    let yaml = "production: {replicas: 3}\nstaging: {replicas: 2}";
+    let err =
+        facet_yaml::from_str::<std::collections::HashMap<Environment, Settings>>(yaml).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UnknownVariant);
+    assert_eq!(err.path(), "staging");
+
+    let errors = facet_yaml::validate::<std::collections::HashMap<Environment, Settings>>(
+        "LocalDev: {replicas: 1}\nproduction: {replicas: many}",
+    )
+    .unwrap_err();
+    assert_eq!(errors.len(), 2);
+}
+
+#[test]
+fn test_unit_variants_renamed_to_numbers() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    enum HttpVersion {
+        #[facet(rename = "1.1")]
+        Http11,
+        #[facet(rename = "2")]
+        Http2,
+        #[facet(rename = "true")]
+        Latest,
+    }
+
+    let versions: Vec<HttpVersion> = facet_yaml::from_str("[1.1, 2, '2', true]").unwrap();
+    assert_eq!(
+        versions,
+        [
+            HttpVersion::Http11,
+            HttpVersion::Http2,
+            HttpVersion::Http2,
+            HttpVersion::Latest
+        ]
+    );
+}
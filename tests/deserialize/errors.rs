@@ -0,0 +1,251 @@
+use facet::Facet;
+use facet_testhelpers::test;
+
+#[derive(Debug, Facet)]
+#[allow(dead_code)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, Facet)]
+#[allow(dead_code)]
+struct Config {
+    servers: Vec<Server>,
+}
+
+const OUT_OF_RANGE: &str = "servers:
+  - host: a
+    port: 80
+  - host: b
+    port: 70000
+";
+
+#[test]
+fn test_error_accessors() {
+    let err = facet_yaml::from_str::<Config>(OUT_OF_RANGE).unwrap_err();
+
+    assert_eq!(err.message(), "Value 70000 out of range for u16");
+    assert_eq!(err.path(), "servers[1].port");
+    assert_eq!(err.expected(), Some("u16"));
+
+    let span = err.span().unwrap();
+    assert_eq!((span.line, span.column, span.offset), (5, 11, 56));
+
+    assert_eq!(
+        format!("{err:#}"),
+        "Value 70000 out of range for u16 at `servers[1].port` (line 5, column 11)"
+    );
+}
+
+#[test]
+fn test_error_display_is_one_line() {
+    let err = facet_yaml::from_str::<Config>(OUT_OF_RANGE).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Value 70000 out of range for u16 at `servers[1].port` (line 5, column 11)"
+    );
+}
+
+// The source line is only kept with `std`
+#[cfg(feature = "std")]
+#[test]
+fn test_error_verbose_shows_source_line() {
+    let err = facet_yaml::from_str::<Config>(OUT_OF_RANGE).unwrap_err();
+    assert_eq!(
+        err.verbose().to_string(),
+        "E004: Value 70000 out of range for u16
+ --> `servers[1].port` (line 5, column 11)
+  |
+5 |     port: 70000
+  |           ^
+  = expected: u16
+  = help: use a value that fits in the type, or a larger type"
+    );
+
+    // Tabs before the column are kept, so the caret lines up
+    let err = facet_yaml::from_str::<Server>("host: a\nport: \t70000\n").unwrap_err();
+    let verbose = err.verbose().to_string();
+    assert!(
+        verbose.contains("2 | port: \t70000\n  |       \t^\n"),
+        "{verbose}"
+    );
+
+    // The gutter is as wide as the line number
+    let yaml = format!("{}servers: a\n", "# comment\n".repeat(9));
+    let err = facet_yaml::from_str::<Config>(&yaml).unwrap_err();
+    let verbose = err.verbose().to_string();
+    assert!(
+        verbose.contains("\n   |\n10 | servers: a\n   |          ^\n"),
+        "{verbose}"
+    );
+}
+
+#[test]
+fn test_error_without_source_line() {
+    // The parser reports unterminated collections after the last line
+    let err = facet_yaml::from_str::<Config>("servers: [1, 2\n").unwrap_err();
+    assert!(!err.verbose().to_string().contains('|'), "{err}");
+
+    let err = facet_yaml::from_str::<Config>("servers: []\n---\nservers: []\n").unwrap_err();
+    assert_eq!(err.to_string(), "Expected exactly one YAML document");
+    assert_eq!(
+        err.verbose().to_string(),
+        "E007: Expected exactly one YAML document
+ = help: remove the `---` separators between documents"
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_validate_errors_show_source_line() {
+    let errors = facet_yaml::validate::<Config>(OUT_OF_RANGE).unwrap_err();
+    let verbose = errors[0].verbose().to_string();
+    assert!(
+        verbose.contains("5 |     port: 70000\n  |           ^\n"),
+        "{verbose}"
+    );
+}
+
+#[test]
+fn test_error_unknown_field_points_at_key() {
+    let err = facet_yaml::from_str::<Config>("servers:\n  - host: a\n    prot: 80\n").unwrap_err();
+
+    assert_eq!(err.message(), "Field 'prot' not found");
+    assert_eq!(err.path(), "servers[0]");
+    let span = err.span().unwrap();
+    assert_eq!((span.line, span.column), (3, 5));
+}
+
+#[test]
+fn test_error_from_parser() {
+    let err = facet_yaml::from_str::<Config>("servers: [1, 2\n").unwrap_err();
+
+    assert_eq!(
+        err.message(),
+        "while parsing a flow sequence, expected ',' or ']'"
+    );
+    assert_eq!(err.path(), "");
+    assert_eq!(err.expected(), None);
+    assert_eq!(err.span().unwrap().line, 2);
+}
+
+#[test]
+fn test_error_to_json() {
+    let err = facet_yaml::from_str::<Config>(OUT_OF_RANGE).unwrap_err();
+    assert_eq!(
+        err.to_json(),
+        r#"{"code":"E004","message":"Value 70000 out of range for u16","path":"servers[1].port","line":5,"column":11,"offset":56,"expected":"u16"}"#
+    );
+
+    let err = facet_yaml::from_str::<Config>("servers: \"\n").unwrap_err();
+    let json = err.to_json();
+    assert!(json.starts_with(r#"{"code":"E006","message":"#), "{json}");
+    assert!(!json.contains('\n'), "{json}");
+}
+
+#[test]
+fn test_error_to_github_annotation() {
+    let err = facet_yaml::from_str::<Config>(OUT_OF_RANGE).unwrap_err();
+    assert_eq!(
+        err.to_github_annotation("deploy/config.yaml"),
+        "::error file=deploy/config.yaml,line=5,col=11::`servers[1].port`: Value 70000 out of range for u16"
+    );
+
+    // Properties can't contain the separators of the command
+    let annotation = err.to_github_annotation("a,b:c.yaml");
+    assert!(
+        annotation.starts_with("::error file=a%2Cb%3Ac.yaml,line=5,"),
+        "{annotation}"
+    );
+}
+
+#[test]
+fn test_error_codes() {
+    use facet_yaml::ErrorCode;
+
+    let code = |yaml: &str| facet_yaml::from_str::<Config>(yaml).unwrap_err().code();
+
+    assert_eq!(code(OUT_OF_RANGE), ErrorCode::OutOfRange);
+    assert_eq!(
+        code("servers:\n  - host: a\n    prot: 80\n"),
+        ErrorCode::UnknownField
+    );
+    assert_eq!(code("servers:\n  - host: a\n"), ErrorCode::MissingField);
+    assert_eq!(code("servers: a\n"), ErrorCode::TypeMismatch);
+    assert_eq!(code("servers: [1, 2\n"), ErrorCode::Syntax);
+    assert_eq!(
+        code("servers: []\n---\nservers: []\n"),
+        ErrorCode::DocumentCount
+    );
+    assert_eq!(code(""), ErrorCode::NoDefault);
+
+    assert_eq!(ErrorCode::UnknownField.as_str(), "E001");
+    assert_eq!(ErrorCode::OutOfRange.to_string(), "E004");
+    assert_eq!(ErrorCode::DepthLimit.as_str(), "E014");
+}
+
+#[test]
+fn test_error_offsets_count_characters() {
+    // Offsets count characters, not bytes
+    let err = facet_yaml::from_str::<Server>("host: a\nport: x\n").unwrap_err();
+    let span = err.span().unwrap();
+    assert_eq!((span.line, span.column, span.offset), (2, 7, 14));
+
+    let err = facet_yaml::from_str::<Server>("host: é\nport: x\n").unwrap_err();
+    let span = err.span().unwrap();
+    assert_eq!((span.line, span.column, span.offset), (2, 7, 14));
+}
+
+#[test]
+fn test_error_reports_field_states() {
+    use facet_yaml::FieldState;
+
+    #[derive(Debug, Facet)]
+    #[allow(dead_code)]
+    struct Tls {
+        cert: String,
+        key: String,
+        #[facet(default)]
+        verify: bool,
+    }
+
+    #[derive(Debug, Facet)]
+    #[allow(dead_code)]
+    struct Listener {
+        port: u16,
+        tls: Tls,
+        name: Option<String>,
+    }
+
+    // The innermost struct that couldn't be completed is reported
+    let err = facet_yaml::from_str::<Listener>("port: 443\ntls:\n  cert: a.pem\n").unwrap_err();
+    let fields = err.fields().unwrap();
+    assert_eq!(fields.type_name(), "Tls");
+    assert_eq!(
+        fields.fields(),
+        [
+            ("cert", FieldState::Set),
+            ("key", FieldState::Missing),
+            ("verify", FieldState::Default),
+        ]
+    );
+    // Notes are indented past the gutter of the source line, which is only kept with `std`
+    #[cfg(feature = "std")]
+    assert!(
+        err.verbose()
+            .to_string()
+            .contains("\n  = fields: Tls { cert: set, key: missing, verify: default }\n"),
+        "{}",
+        err.verbose()
+    );
+
+    let err = facet_yaml::from_str::<Listener>("port: 443\n").unwrap_err();
+    let fields = err.fields().unwrap();
+    assert_eq!(fields.state("tls"), Some(FieldState::Missing));
+    assert_eq!(fields.state("name"), Some(FieldState::Default));
+
+    // Errors in values aren't about the fields of the struct
+    let err = facet_yaml::from_str::<Listener>("port: x\n").unwrap_err();
+    assert!(err.fields().is_none());
+}
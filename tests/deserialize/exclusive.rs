@@ -0,0 +1,91 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::ErrorCode;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Database {
+    user: String,
+    #[facet(default, yaml(exclusive = "password"))]
+    password: Option<String>,
+    #[facet(default, yaml(exclusive = "password"))]
+    password_file: Option<String>,
+}
+
+#[test]
+fn test_exclusive_one_set() {
+    let database: Database =
+        facet_yaml::from_str("user: app\npassword_file: /run/secrets/db").unwrap();
+    assert_eq!(
+        database,
+        Database {
+            user: "app".to_string(),
+            password: None,
+            password_file: Some("/run/secrets/db".to_string()),
+        }
+    );
+}
+
+#[test]
+fn test_exclusive_none_set() {
+    let database: Database = facet_yaml::from_str("user: app").unwrap();
+    assert_eq!(database.password, None);
+    assert_eq!(database.password_file, None);
+}
+
+#[test]
+fn test_exclusive_both_set() {
+    let err = facet_yaml::from_str::<Database>(
+        "user: app\npassword: hunter2\npassword_file: /run/secrets/db",
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::ConflictingFields);
+    assert_eq!(
+        err.message(),
+        "Only one of 'password' (line 2, column 1) and 'password_file' (line 3, column 1) may be set"
+    );
+    assert_eq!(err.span().map(|span| span.line), Some(3));
+}
+
+#[test]
+fn test_exclusive_groups_are_separate() {
+    #[derive(Debug, Facet)]
+    struct Tls {
+        #[facet(default, yaml(exclusive = "cert"))]
+        cert: Option<String>,
+        #[facet(default, yaml(exclusive = "cert"))]
+        cert_file: Option<String>,
+        #[facet(default, yaml(exclusive = "key"))]
+        key: Option<String>,
+        #[facet(default, yaml(exclusive = "key"))]
+        key_file: Option<String>,
+    }
+
+    facet_yaml::from_str::<Tls>("cert: abc\nkey_file: key.pem").unwrap();
+    let err = facet_yaml::from_str::<Tls>("cert: abc\nkey: def\nkey_file: key.pem").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::ConflictingFields);
+}
+
+#[test]
+fn test_exclusive_in_nested_struct() {
+    #[derive(Debug, Facet)]
+    struct Config {
+        databases: Vec<Database>,
+    }
+
+    let err = facet_yaml::from_str::<Config>(
+        "databases:\n  - user: a\n    password: x\n    password_file: y\n",
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::ConflictingFields);
+    assert_eq!(err.path(), "databases[0]");
+}
+
+#[test]
+fn test_exclusive_validate() {
+    let errors = facet_yaml::validate::<Database>(
+        "user: app\npassword: hunter2\npassword_file: /run/secrets/db",
+    )
+    .unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].code(), ErrorCode::ConflictingFields);
+}
@@ -0,0 +1,116 @@
+use facet::Facet;
+use facet_testhelpers::test;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Floats {
+    double: f64,
+    single: f32,
+}
+
+#[test]
+fn test_deserialize_decimal_and_exponent_forms() {
+    let values: Vec<f64> =
+        facet_yaml::from_str("[1.5, 1.5e3, 1.5E+3, -2.5e-3, 7, '0.25']").unwrap();
+    assert_eq!(values, vec![1.5, 1500.0, 1500.0, -0.0025, 7.0, 0.25]);
+}
+
+#[test]
+fn test_deserialize_non_finite_floats() {
+    let values: Vec<f64> = facet_yaml::from_str("[.inf, -.inf, +.INF, .nan]").unwrap();
+    assert_eq!(values[0], f64::INFINITY);
+    assert_eq!(values[1], f64::NEG_INFINITY);
+    assert_eq!(values[2], f64::INFINITY);
+    assert!(values[3].is_nan());
+}
+
+#[test]
+fn test_deserialize_f32_without_double_rounding() {
+    // Rounds differently when parsed as an f64 first and then narrowed to an f32
+    let floats: Floats = facet_yaml::from_str(
+        r#"
+        double: 0.1
+        single: 1.00000017881393432617187499
+    "#,
+    )
+    .unwrap();
+    assert_eq!(floats.double, 0.1);
+    assert_eq!(
+        floats.single.to_bits(),
+        "1.00000017881393432617187499"
+            .parse::<f32>()
+            .unwrap()
+            .to_bits()
+    );
+}
+
+#[test]
+fn test_deserialize_leading_dot() {
+    let values: Vec<f64> = facet_yaml::from_str("[.5, -.5, +.5, '.25']").unwrap();
+    assert_eq!(values, vec![0.5, -0.5, 0.5, 0.25]);
+
+    // Strict mode wants a digit before the dot, plain or quoted
+    let options = facet_yaml::DeserializeOptions::new().lenient(false);
+    let values: Vec<f64> = facet_yaml::from_str_with_options("[0.5, -0.5]", &options).unwrap();
+    assert_eq!(values, vec![0.5, -0.5]);
+    let err = facet_yaml::from_str_with_options::<Vec<f64>>("[0.5, -.5]", &options).unwrap_err();
+    assert_eq!(err.code(), facet_yaml::ErrorCode::InvalidScalar);
+    assert_eq!(
+        err.message(),
+        "Floats without a digit before the dot, like `-.5`, are only accepted in lenient mode"
+    );
+    assert_eq!(err.path(), "[1]");
+    assert!(facet_yaml::from_str_with_options::<f64>("'.25'", &options).is_err());
+
+    // The non-finite spellings aren't decimals
+    let values: Vec<f64> = facet_yaml::from_str_with_options("[.inf, -.inf]", &options).unwrap();
+    assert_eq!(values, vec![f64::INFINITY, f64::NEG_INFINITY]);
+}
+
+#[test]
+fn test_deserialize_digit_separators_leniently() {
+    let floats: Floats = facet_yaml::from_str("double: 1_000.5\nsingle: -1_0.2_5").unwrap();
+    assert_eq!(floats.double, 1000.5);
+    assert_eq!(floats.single, -10.25);
+
+    // Separators have to be between digits
+    assert!(facet_yaml::from_str::<f64>("_1.5").is_err());
+    assert!(facet_yaml::from_str::<f64>("1__0.5").is_err());
+    assert!(facet_yaml::from_str::<f64>("1_.5").is_err());
+}
+
+#[test]
+fn test_deserialize_digit_separators_strictly() {
+    let options = facet_yaml::DeserializeOptions::new().lenient(false);
+    let err = facet_yaml::from_str_with_options::<Floats>("double: 1_000.5\nsingle: 1.0", &options)
+        .unwrap_err();
+
+    assert_eq!(
+        err.message(),
+        "Digit separators in `1_000.5` are only accepted in lenient mode"
+    );
+    assert_eq!(err.path(), "double");
+}
+
+#[test]
+fn test_float_error_has_text_type_and_path() {
+    let err = facet_yaml::from_str::<Floats>("double: 1.0\nsingle: fast").unwrap_err();
+    assert_eq!(err.message(), "Failed to parse `fast` as f32");
+    assert_eq!(err.path(), "single");
+    assert_eq!(err.code(), facet_yaml::ErrorCode::InvalidScalar);
+}
+
+#[test]
+fn test_float_error_hints() {
+    let err = facet_yaml::from_str::<Floats>("double: 1,5\nsingle: 1.0").unwrap_err();
+    assert_eq!(
+        err.message(),
+        "Failed to parse `1,5` as f64: the decimal separator is a dot, e.g. `1.5`, not a comma"
+    );
+    assert_eq!(err.path(), "double");
+
+    let err = facet_yaml::from_str::<f64>("'0o17'").unwrap_err();
+    assert_eq!(
+        err.message(),
+        "Failed to parse `0o17` as f64: octal numbers aren't accepted for floats, write it in decimal"
+    );
+}
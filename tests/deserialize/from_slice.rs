@@ -0,0 +1,36 @@
+use facet::Facet;
+use facet_testhelpers::test;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Person {
+    name: String,
+    age: u64,
+}
+
+#[test]
+fn test_deserialize_from_slice() {
+    let bytes = "name: Zoë\nage: 30\n".as_bytes();
+
+    let person: Person = facet_yaml::from_slice(bytes).unwrap();
+    assert_eq!(
+        person,
+        Person {
+            name: "Zoë".to_string(),
+            age: 30
+        }
+    );
+}
+
+#[test]
+fn test_deserialize_from_slice_with_byte_order_mark() {
+    let bytes = b"\xef\xbb\xbfname: Alice\nage: 30\n";
+
+    let person: Person = facet_yaml::from_slice(bytes).unwrap();
+    assert_eq!(person.name, "Alice");
+}
+
+#[test]
+fn test_deserialize_from_invalid_utf8() {
+    let err = facet_yaml::from_slice::<Person>(b"name: \xff\nage: 30\n").unwrap_err();
+    assert!(err.to_string().contains("not valid UTF-8"), "{err}");
+}
@@ -0,0 +1,106 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::DeserializeOptions;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Counts {
+    unsigned: u32,
+    signed: i16,
+}
+
+#[test]
+fn test_deserialize_plus_sign_and_whitespace_leniently() {
+    let yaml = r#"
+        unsigned: " +42 "
+        signed: "  -7"
+    "#;
+
+    let counts: Counts = facet_yaml::from_str(yaml).unwrap();
+    assert_eq!(
+        counts,
+        Counts {
+            unsigned: 42,
+            signed: -7
+        }
+    );
+}
+
+#[test]
+fn test_deserialize_plus_sign_and_whitespace_strictly() {
+    let options = DeserializeOptions::new().lenient(false);
+
+    assert!(
+        facet_yaml::from_str_with_options::<Counts>("unsigned: '+42'\nsigned: 1", &options)
+            .is_err()
+    );
+    assert!(
+        facet_yaml::from_str_with_options::<Counts>("unsigned: 42\nsigned: ' 1'", &options)
+            .is_err()
+    );
+
+    let counts: Counts =
+        facet_yaml::from_str_with_options("unsigned: '42'\nsigned: '-1'", &options).unwrap();
+    assert_eq!(
+        counts,
+        Counts {
+            unsigned: 42,
+            signed: -1
+        }
+    );
+}
+
+#[test]
+fn test_deserialize_plain_plus_sign() {
+    // Part of the core schema, so accepted in both modes
+    let options = DeserializeOptions::new().lenient(false);
+    let counts: Counts =
+        facet_yaml::from_str_with_options("unsigned: +42\nsigned: +1", &options).unwrap();
+    assert_eq!(
+        counts,
+        Counts {
+            unsigned: 42,
+            signed: 1
+        }
+    );
+}
+
+#[test]
+fn test_big_integers_as_strings() {
+    use facet_yaml::Value;
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Account {
+        id: String,
+        balance: Value,
+        max: u64,
+        ratio: f64,
+    }
+
+    let yaml = "id: 99999999999999999999999\nbalance: -99999999999999999999999\nmax: 18446744073709551615\nratio: 1e30";
+
+    // Parsers read them as floats, which strings don't accept
+    let err = facet_yaml::from_str::<Account>(yaml).unwrap_err();
+    assert_eq!(err.code(), facet_yaml::ErrorCode::TypeMismatch);
+    assert_eq!(err.path(), "id");
+
+    let options = DeserializeOptions::new().big_integers_as_strings(true);
+    let account: Account = facet_yaml::from_str_with_options(yaml, &options).unwrap();
+    assert_eq!(account.id, "99999999999999999999999");
+    assert_eq!(
+        account.balance,
+        Value::String("-99999999999999999999999".to_string())
+    );
+    assert_eq!(account.max, u64::MAX);
+    assert_eq!(account.ratio, 1e30);
+
+    // Tagged and quoted scalars keep their meaning
+    let value: Value =
+        facet_yaml::from_str_with_options("[!!float 99999999999999999999999, '1']", &options)
+            .unwrap();
+    assert_eq!(value.as_sequence().unwrap()[0].as_f64(), Some(1e23));
+
+    // Integer fields still reject them
+    let err =
+        facet_yaml::from_str_with_options::<u64>("99999999999999999999999", &options).unwrap_err();
+    assert_eq!(err.code(), facet_yaml::ErrorCode::InvalidScalar);
+}
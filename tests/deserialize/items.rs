@@ -0,0 +1,64 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::{DeserializeOptions, ErrorCode};
+
+#[derive(Debug, Facet, PartialEq)]
+struct Record {
+    id: u32,
+    tags: Vec<String>,
+}
+
+fn items(yaml: &str) -> (Vec<Record>, Vec<facet_yaml::DeserializeError>) {
+    facet_yaml::from_str_items(yaml, &DeserializeOptions::default()).unwrap()
+}
+
+#[test]
+fn test_keep_the_items_that_deserialize() {
+    let (records, errors) = items(
+        "
+- {id: 1, tags: [a]}
+- {id: 5000000000, tags: [b]}
+- {id: 3, tags: [c, d]}
+- {id: 4, tags: ok}
+- {id: 5, tags: []}
+",
+    );
+    assert_eq!(
+        records.iter().map(|record| record.id).collect::<Vec<_>>(),
+        [1, 3, 5]
+    );
+    assert_eq!(records[1].tags, ["c", "d"]);
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].path(), "[1].id");
+    assert_eq!(errors[0].code(), ErrorCode::OutOfRange);
+    assert_eq!(errors[1].path(), "[3].tags");
+    assert_eq!(errors[1].code(), ErrorCode::TypeMismatch);
+    assert_eq!(errors[1].span().map(|span| span.line), Some(5));
+}
+
+#[test]
+fn test_all_items_valid() {
+    let (records, errors) = items("- {id: 1, tags: []}\n- {id: 2, tags: [x]}");
+    assert_eq!(records.len(), 2);
+    assert!(errors.is_empty());
+
+    let (records, errors) = items("# nothing yet\n");
+    assert!(records.is_empty());
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn test_document_errors() {
+    let options = DeserializeOptions::default();
+
+    let err = facet_yaml::from_str_items::<Record>("id: 1", &options).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+
+    let err = facet_yaml::from_str_items::<Record>("- {id: 1", &options).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Syntax);
+
+    let options = options.cancel_if(|| true);
+    let err = facet_yaml::from_str_items::<Record>("- {id: 1, tags: []}", &options).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Cancelled);
+}
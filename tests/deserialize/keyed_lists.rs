@@ -0,0 +1,121 @@
+use std::collections::{BTreeMap, HashMap};
+
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::ErrorCode;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Pod {
+    #[facet(yaml(key = "name"))]
+    containers: HashMap<String, Container>,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+#[facet(deny_unknown_fields)]
+struct Container {
+    image: String,
+    #[facet(default)]
+    ports: Vec<u16>,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Services {
+    #[facet(yaml(key = "name"))]
+    services: BTreeMap<String, Service>,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Service {
+    name: String,
+    port: u16,
+}
+
+#[test]
+fn test_keyed_list() {
+    let pod: Pod = facet_yaml::from_str(
+        "containers:\n  - name: web\n    image: nginx\n    ports: [80, 443]\n  - name: cache\n    image: redis\n",
+    )
+    .unwrap();
+    assert_eq!(pod.containers.len(), 2);
+    assert_eq!(
+        pod.containers["web"],
+        Container {
+            image: "nginx".into(),
+            ports: vec![80, 443],
+        }
+    );
+    assert_eq!(pod.containers["cache"].image, "redis");
+}
+
+#[test]
+fn test_keyed_list_keeps_the_key_in_structs_with_its_field() {
+    let services: Services =
+        facet_yaml::from_str("services:\n  - name: api\n    port: 8080\n").unwrap();
+    assert_eq!(
+        services.services["api"],
+        Service {
+            name: "api".into(),
+            port: 8080,
+        }
+    );
+}
+
+#[test]
+fn test_keyed_list_from_a_mapping() {
+    let pod: Pod = facet_yaml::from_str("containers:\n  web:\n    image: nginx\n").unwrap();
+    assert_eq!(pod.containers["web"].image, "nginx");
+}
+
+#[test]
+fn test_keyed_list_errors() {
+    let err = facet_yaml::from_str::<Pod>("containers:\n  - image: nginx\n").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::MissingField);
+    assert_eq!(err.path(), "containers[0]");
+    assert_eq!(
+        err.message(),
+        "Missing `name` key of an item of a keyed list"
+    );
+
+    let err = facet_yaml::from_str::<Pod>("containers:\n  - nginx\n").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+    assert_eq!(err.path(), "containers[0]");
+
+    let yaml = "containers:\n  - name: web\n    image: nginx\n  - name: web\n    image: httpd\n";
+    let err = facet_yaml::from_str::<Pod>(yaml).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::DuplicateItem);
+    assert_eq!(err.path(), "containers[1]");
+    assert_eq!(err.span().unwrap().line, 4);
+    assert_eq!(err.message(), "Item has the same `name` as item 0");
+
+    let errors = facet_yaml::validate::<Pod>(yaml).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].code(), ErrorCode::DuplicateItem);
+}
+
+#[test]
+fn test_errors_in_keyed_list_items() {
+    let yaml = "containers:\n  - name: web\n    image: nginx\n    ports: [http]\n";
+    let err = facet_yaml::from_str::<Pod>(yaml).unwrap_err();
+    assert_eq!(err.path(), "containers.web.ports[0]");
+    assert_eq!(err.span().unwrap().line, 4);
+
+    let errors = facet_yaml::validate::<Pod>(yaml).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path(), "containers.web.ports[0]");
+}
+
+#[test]
+fn test_keyed_list_needs_a_map() {
+    #[derive(Debug, Facet)]
+    struct Invalid {
+        #[facet(yaml(key = "name"))]
+        containers: Vec<Container>,
+    }
+
+    let err = facet_yaml::from_str::<Invalid>("containers: []\n").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::InvalidAttribute);
+    assert_eq!(
+        err.message(),
+        "Field 'containers' is read from a keyed list, so it needs to be a map"
+    );
+}
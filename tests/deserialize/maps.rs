@@ -1,5 +1,7 @@
+use facet::Facet;
 use facet_testhelpers::test;
-use std::collections::HashMap;
+use facet_yaml::Value;
+use std::collections::{BTreeMap, HashMap};
 
 #[test]
 fn test_deserialize_string_to_string_map() {
@@ -63,3 +65,258 @@ fn test_deserialize_nested_maps() {
     assert_eq!(inner2.get("inner3"), Some(&"value3".to_string()));
     assert_eq!(inner2.get("inner4"), Some(&"value4".to_string()));
 }
+
+#[test]
+fn test_deserialize_maps_of_maps_of_values() {
+    let yaml = r#"
+        web:
+            ports: [80, 443]
+            env: [{name: MODE, value: prod}, ~]
+        worker:
+            ports: []
+    "#;
+
+    let map: HashMap<String, HashMap<String, Vec<Value>>> = facet_yaml::from_str(yaml).unwrap();
+    assert_eq!(map.len(), 2);
+
+    let web = &map["web"];
+    assert_eq!(
+        web["ports"],
+        vec![Value::Number(80_i64.into()), Value::Number(443_i64.into())]
+    );
+    assert_eq!(web["env"].len(), 2);
+    assert_eq!(
+        web["env"][0].get("name").and_then(Value::as_str),
+        Some("MODE")
+    );
+    assert!(web["env"][1].is_null());
+
+    let worker = &map["worker"];
+    assert_eq!(worker.len(), 1);
+    assert!(worker["ports"].is_empty());
+}
+
+#[test]
+fn test_deserialize_btree_map_of_values() {
+    let yaml = r#"
+        name: web
+        replicas: 3
+        ratio: 0.5
+        enabled: true
+        labels: {tier: frontend}
+        nothing: ~
+    "#;
+
+    let map: BTreeMap<String, Value> = facet_yaml::from_str(yaml).unwrap();
+    assert_eq!(map["name"], Value::String("web".to_string()));
+    assert_eq!(map["replicas"], Value::Number(3_i64.into()));
+    assert_eq!(map["ratio"], Value::Number(0.5_f64.into()));
+    assert_eq!(map["enabled"], Value::Bool(true));
+    assert_eq!(
+        map["labels"].get("tier").and_then(Value::as_str),
+        Some("frontend")
+    );
+    assert!(map["nothing"].is_null());
+}
+
+#[test]
+fn test_deserialize_three_levels_of_maps() {
+    let yaml = r#"
+        a:
+            b:
+                c: 1
+                d: ~
+            e: {}
+        f: {}
+    "#;
+
+    let map: BTreeMap<String, BTreeMap<String, BTreeMap<String, Option<u32>>>> =
+        facet_yaml::from_str(yaml).unwrap();
+    assert_eq!(map["a"]["b"]["c"], Some(1));
+    assert_eq!(map["a"]["b"]["d"], None);
+    assert!(map["a"]["e"].is_empty());
+    assert!(map["f"].is_empty());
+}
+
+#[test]
+fn test_deserialize_nested_map_error_path() {
+    let yaml = r#"
+        a:
+            b:
+                c: -1
+    "#;
+
+    let err = facet_yaml::from_str::<HashMap<String, HashMap<String, HashMap<String, u8>>>>(yaml)
+        .unwrap_err();
+    assert_eq!(err.path(), "a.b.c");
+}
+
+#[test]
+fn test_deserialize_integer_keys() {
+    let yaml = r#"
+        1: one
+        20: twenty
+    "#;
+
+    let map: BTreeMap<u32, String> = facet_yaml::from_str(yaml).unwrap();
+    assert_eq!(map[&1], "one");
+    assert_eq!(map[&20], "twenty");
+}
+
+#[test]
+fn test_deserialize_struct_with_value_field() {
+    #[derive(Debug, Facet)]
+    struct Manifest {
+        kind: String,
+        spec: Value,
+    }
+
+    let yaml = r#"
+        kind: Deployment
+        spec:
+            replicas: 3
+            containers: [{image: nginx}]
+    "#;
+
+    let manifest: Manifest = facet_yaml::from_str(yaml).unwrap();
+    assert_eq!(manifest.kind, "Deployment");
+    assert_eq!(
+        manifest.spec.get("replicas"),
+        Some(&Value::Number(3_i64.into()))
+    );
+    assert_eq!(
+        manifest
+            .spec
+            .get("containers")
+            .and_then(Value::as_sequence)
+            .map(<[_]>::len),
+        Some(1)
+    );
+}
+
+#[test]
+fn test_deserialize_numeric_keys() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Service {
+        name: String,
+        #[facet(default)]
+        tls: bool,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Gateway {
+        ports: HashMap<u16, Service>,
+        offsets: BTreeMap<i64, String>,
+    }
+
+    let yaml = r#"
+        ports:
+            80: {name: web}
+            "443": {name: web, tls: true}
+            '8080': {name: admin}
+        offsets:
+            -3600: before
+            0x10: hex
+    "#;
+
+    let gateway: Gateway = facet_yaml::from_str(yaml).unwrap();
+    assert_eq!(gateway.ports.len(), 3);
+    assert!(gateway.ports[&443].tls);
+    assert_eq!(gateway.ports[&8080].name, "admin");
+    assert_eq!(gateway.offsets[&-3600], "before");
+    assert_eq!(gateway.offsets[&16], "hex");
+}
+
+#[test]
+fn test_deserialize_numeric_key_errors() {
+    let err = facet_yaml::from_str::<HashMap<u16, String>>("80: web\nhttp: web").unwrap_err();
+    assert_eq!(err.path(), "http");
+
+    let err = facet_yaml::from_str::<HashMap<u16, String>>("80: web\n70000: web").unwrap_err();
+    assert_eq!(err.code(), facet_yaml::ErrorCode::OutOfRange);
+    assert_eq!(err.path(), "70000");
+}
+
+#[test]
+fn test_deserialize_parsed_keys() {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    #[derive(Debug, Facet, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    struct UserId(u64);
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Acl {
+        hosts: BTreeMap<IpAddr, String>,
+        users: HashMap<UserId, bool>,
+        versions: BTreeMap<Ipv4Addr, u8>,
+    }
+
+    let yaml = r#"
+        hosts:
+            10.0.0.1: gateway
+            "::1": loopback
+        users:
+            42: true
+            "7": false
+        versions:
+            192.168.0.1: 4
+    "#;
+
+    let acl: Acl = facet_yaml::from_str(yaml).unwrap();
+    assert_eq!(acl.hosts[&IpAddr::from([10, 0, 0, 1])], "gateway");
+    assert_eq!(acl.hosts[&"::1".parse::<IpAddr>().unwrap()], "loopback");
+    assert!(acl.users[&UserId(42)]);
+    assert!(!acl.users[&UserId(7)]);
+    assert_eq!(acl.versions[&Ipv4Addr::new(192, 168, 0, 1)], 4);
+
+    // Keys that YAML reads as numbers are parsed from their text
+    let grades: BTreeMap<char, String> = facet_yaml::from_str("7: seven\na: letter").unwrap();
+    assert_eq!(grades[&'7'], "seven");
+    assert_eq!(grades[&'a'], "letter");
+
+    let err =
+        facet_yaml::from_str::<BTreeMap<IpAddr, String>>("10.0.0.1: a\nlocalhost: b").unwrap_err();
+    assert_eq!(err.code(), facet_yaml::ErrorCode::InvalidScalar);
+    assert_eq!(err.path(), "localhost");
+
+    let err = facet_yaml::from_str::<BTreeMap<IpAddr, String>>("42: a").unwrap_err();
+    assert_eq!(err.code(), facet_yaml::ErrorCode::InvalidScalar);
+}
+
+#[test]
+fn test_deserialize_complex_keys() {
+    #[derive(Debug, Facet, PartialEq, Eq, PartialOrd, Ord)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    let ranges: BTreeMap<(u16, u16), String> =
+        facet_yaml::from_str("? [80, 89]\n: web\n? [8000, 8099]\n: admin").unwrap();
+    assert_eq!(ranges[&(8000, 8099)], "admin");
+
+    let labels: BTreeMap<Point, String> =
+        facet_yaml::from_str("? {x: 1, y: 2}\n: a\n? x: 3\n  y: 4\n: b").unwrap();
+    assert_eq!(labels[&Point { x: 3, y: 4 }], "b");
+
+    let groups: BTreeMap<Vec<String>, u8> =
+        facet_yaml::from_str("? [a, b]\n: 1\n? []\n: 0").unwrap();
+    assert_eq!(groups[&vec!["a".to_string(), "b".to_string()]], 1);
+    assert_eq!(groups[&Vec::new()], 0);
+}
+
+#[test]
+fn test_deserialize_complex_key_into_scalar() {
+    let yaml = "web: 1\n? [a, b]\n: 2";
+    let err = facet_yaml::from_str::<HashMap<String, u8>>(yaml).unwrap_err();
+    assert_eq!(err.code(), facet_yaml::ErrorCode::InvalidKey);
+    assert_eq!(
+        err.message(),
+        "Complex keys, like `? [a, b]`, can only be read into keys that are structs, tuples or collections, not String"
+    );
+    assert_eq!(err.span().map(|span| span.line), Some(2));
+
+    let errors = facet_yaml::validate::<HashMap<String, u8>>(yaml).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].code(), facet_yaml::ErrorCode::InvalidKey);
+}
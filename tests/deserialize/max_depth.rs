@@ -0,0 +1,61 @@
+use facet_testhelpers::test;
+use facet_yaml::{DeserializeOptions, ErrorCode, Value};
+
+/// A document of block sequences nested `depth` deep, `- - - leaf`.
+fn nested_sequences(depth: usize) -> String {
+    format!("{}leaf\n", "- ".repeat(depth))
+}
+
+#[test]
+fn test_depth_within_limit() {
+    facet_yaml::from_str::<Value>(&nested_sequences(128)).unwrap();
+}
+
+#[test]
+fn test_deep_block_nesting() {
+    let err = facet_yaml::from_str::<Value>(&nested_sequences(129)).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::DepthLimit);
+    assert_eq!(
+        err.message(),
+        "collections are nested deeper than the limit of 128"
+    );
+
+    // Far deeper than the stack would allow if nodes were read without a limit
+    let err = facet_yaml::from_str::<Value>(&nested_sequences(100_000)).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::DepthLimit);
+}
+
+#[test]
+fn test_deep_flow_nesting() {
+    // The scanner itself doesn't nest flow collections more than 255 deep
+    let yaml = format!("{}{}", "[".repeat(200), "]".repeat(200));
+    let err = facet_yaml::from_str::<Value>(&yaml).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::DepthLimit);
+}
+
+#[test]
+fn test_max_depth() {
+    let yaml = "a: {b: [1]}\n";
+    let options = DeserializeOptions::new().max_depth(3);
+    facet_yaml::from_str_with_options::<Value>(yaml, &options).unwrap();
+
+    let options = DeserializeOptions::new().max_depth(2);
+    let err = facet_yaml::from_str_with_options::<Value>(yaml, &options).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::DepthLimit);
+    assert_eq!(
+        err.span().map(|span| (span.line, span.column)),
+        Some((1, 8))
+    );
+}
+
+#[test]
+fn test_aliases_are_nested_where_they_are() {
+    let yaml = "a: &a [[1]]\nb: [*a]\n";
+    let options = DeserializeOptions::new().max_depth(3);
+    let err = facet_yaml::from_str_with_options::<Value>(yaml, &options).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::DepthLimit);
+    assert_eq!(err.span().map(|span| span.line), Some(2));
+
+    let options = DeserializeOptions::new().max_depth(4);
+    facet_yaml::from_str_with_options::<Value>(yaml, &options).unwrap();
+}
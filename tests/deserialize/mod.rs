@@ -1,7 +1,54 @@
+mod alias_depth;
+mod arrays;
+#[cfg(feature = "std")]
+mod batch;
+mod binary;
+mod cancel;
+mod cron;
+mod customize;
 mod datetime;
 mod default;
+mod embedded;
+mod empty_document;
+mod enums;
+mod errors;
+mod exclusive;
+mod floats;
+mod from_slice;
 mod from_str;
+mod integers;
 mod ip_test;
+mod items;
+mod keyed_lists;
 mod lists;
 mod maps;
+mod max_depth;
+mod option;
+mod ordered_maps;
+mod pipeline;
+mod pointers;
+mod projected;
+#[cfg(feature = "figment")]
+mod provider;
+mod recovering;
+mod rename_all;
+mod required;
+mod scalar_resolver;
+mod schema;
+mod sets;
+mod skip;
+mod small_documents;
+mod stream;
+mod tagged_enums;
+mod tags;
+mod template;
 mod transparent;
+mod tuple_structs;
+mod tuples;
+mod unit;
+mod unit_from_null;
+mod untagged;
+mod validate;
+mod value;
+mod yaml_deserializer;
+mod yaml_path;
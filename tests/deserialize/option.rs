@@ -0,0 +1,136 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use std::collections::HashMap;
+
+#[test]
+fn test_deserialize_list_with_null_items() {
+    let values: Vec<Option<u32>> = facet_yaml::from_str("[1, ~, 3, null, Null]").unwrap();
+    assert_eq!(values, vec![Some(1), None, Some(3), None, None]);
+}
+
+#[test]
+fn test_deserialize_block_list_with_empty_items() {
+    let yaml = r#"
+        - first
+        -
+        - third
+    "#;
+
+    let values: Vec<Option<String>> = facet_yaml::from_str(yaml).unwrap();
+    assert_eq!(
+        values,
+        vec![Some("first".to_string()), None, Some("third".to_string())]
+    );
+}
+
+#[test]
+fn test_deserialize_map_with_null_values() {
+    let yaml = r#"
+        present: value
+        tilde: ~
+        explicit: null
+        empty:
+    "#;
+
+    let map: HashMap<String, Option<String>> = facet_yaml::from_str(yaml).unwrap();
+    assert_eq!(map.len(), 4);
+    assert_eq!(map.get("present"), Some(&Some("value".to_string())));
+    assert_eq!(map.get("tilde"), Some(&None));
+    assert_eq!(map.get("explicit"), Some(&None));
+    assert_eq!(map.get("empty"), Some(&None));
+}
+
+#[test]
+fn test_deserialize_nested_optional_lists() {
+    let values: Vec<Option<Vec<Option<u32>>>> = facet_yaml::from_str("[[1, ~], ~, []]").unwrap();
+    assert_eq!(values, vec![Some(vec![Some(1), None]), None, Some(vec![])]);
+}
+
+#[test]
+fn test_deserialize_explicit_null_struct_field() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Config {
+        name: Option<String>,
+        retries: Option<u32>,
+    }
+
+    let config: Config = facet_yaml::from_str("name: ~\nretries: 3").unwrap();
+    assert_eq!(
+        config,
+        Config {
+            name: None,
+            retries: Some(3)
+        }
+    );
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Server {
+    host: String,
+    port: Option<u16>,
+    #[facet(yaml(path = "tls.cert"))]
+    cert: Option<String>,
+    timeouts: Option<Vec<u32>>,
+}
+
+#[test]
+fn test_missing_option_fields_are_none() {
+    let server: Server = facet_yaml::from_str("host: localhost").unwrap();
+    assert_eq!(
+        server,
+        Server {
+            host: "localhost".to_string(),
+            port: None,
+            cert: None,
+            timeouts: None,
+        }
+    );
+
+    // Other fields are still required
+    let err = facet_yaml::from_str::<Server>("port: 80").unwrap_err();
+    assert_eq!(err.code(), facet_yaml::ErrorCode::MissingField);
+    assert!(facet_yaml::validate::<Server>("host: localhost").is_ok());
+}
+
+#[test]
+fn test_missing_option_fields_keep_defaults() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[facet(default)]
+    struct Retry {
+        attempts: Option<u32>,
+        backoff_ms: Option<u64>,
+    }
+
+    impl Default for Retry {
+        fn default() -> Self {
+            Self {
+                attempts: Some(3),
+                backoff_ms: None,
+            }
+        }
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    enum Source {
+        Git { url: String, branch: Option<String> },
+    }
+
+    let retry: Retry = facet_yaml::from_str("backoff_ms: 250").unwrap();
+    assert_eq!(
+        retry,
+        Retry {
+            attempts: Some(3),
+            backoff_ms: Some(250),
+        }
+    );
+
+    let source: Source = facet_yaml::from_str("Git:\n  url: https://example.com").unwrap();
+    assert_eq!(
+        source,
+        Source::Git {
+            url: "https://example.com".to_string(),
+            branch: None,
+        }
+    );
+}
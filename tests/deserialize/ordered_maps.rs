@@ -0,0 +1,101 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::{DeserializeOptions, ErrorCode};
+use std::collections::BTreeMap;
+
+const TASKS: &str = "
+- install: apt-get install -y nginx
+- configure: cp nginx.conf /etc/nginx/
+- start: systemctl start nginx
+";
+
+#[test]
+fn test_ordered_maps_into_pairs() {
+    let options = DeserializeOptions::new().ordered_maps(true);
+    let tasks: Vec<(String, String)> = facet_yaml::from_str_with_options(TASKS, &options).unwrap();
+    assert_eq!(
+        tasks,
+        [
+            (
+                "install".to_string(),
+                "apt-get install -y nginx".to_string()
+            ),
+            (
+                "configure".to_string(),
+                "cp nginx.conf /etc/nginx/".to_string()
+            ),
+            ("start".to_string(), "systemctl start nginx".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_ordered_maps_into_map() {
+    let options = DeserializeOptions::new().ordered_maps(true);
+    let tasks: BTreeMap<String, String> =
+        facet_yaml::from_str_with_options(TASKS, &options).unwrap();
+    assert_eq!(tasks.len(), 3);
+    assert_eq!(tasks["configure"], "cp nginx.conf /etc/nginx/");
+
+    // Mappings are still read as they are
+    let tasks: BTreeMap<String, String> =
+        facet_yaml::from_str_with_options("start: systemctl start nginx", &options).unwrap();
+    assert_eq!(tasks["start"], "systemctl start nginx");
+}
+
+#[test]
+fn test_ordered_maps_with_structs() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Limits {
+        cpu: u32,
+        memory: String,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Pod {
+        containers: Vec<(String, Limits)>,
+    }
+
+    let options = DeserializeOptions::new().ordered_maps(true);
+    let pod: Pod = facet_yaml::from_str_with_options(
+        "containers:\n  - web: {cpu: 2, memory: 1Gi}\n  - sidecar: {cpu: 1, memory: 256Mi}\n",
+        &options,
+    )
+    .unwrap();
+    assert_eq!(pod.containers[0].0, "web");
+    assert_eq!(
+        pod.containers[1],
+        (
+            "sidecar".to_string(),
+            Limits {
+                cpu: 1,
+                memory: "256Mi".to_string(),
+            }
+        )
+    );
+
+    let err = facet_yaml::from_str_with_options::<Pod>(
+        "containers:\n  - web: {cpu: two, memory: 1Gi}\n",
+        &options,
+    )
+    .unwrap_err();
+    assert_eq!(err.path(), "containers[0].web.cpu");
+}
+
+#[test]
+fn test_ordered_maps_with_several_keys() {
+    let options = DeserializeOptions::new().ordered_maps(true);
+    let err = facet_yaml::from_str_with_options::<BTreeMap<String, u32>>(
+        "- a: 1\n- b: 2\n  c: 3\n",
+        &options,
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+    assert_eq!(err.path(), "[1]");
+}
+
+#[test]
+fn test_ordered_maps_are_off_by_default() {
+    let err = facet_yaml::from_str::<BTreeMap<String, String>>(TASKS).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+}
@@ -0,0 +1,231 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::{DropPath, InjectDefault, Pipeline, PipelineEvent, RenameKey, TransformContext};
+
+const MANIFEST: &str = r#"
+metadata:
+  name: web
+  labels: &labels
+    app: web
+spec:
+  replicas: 3
+  selector: *labels
+  containers:
+    - name: web
+      image: nginx:1.27
+    - name: sidecar
+      image: envoy
+      pullPolicy: Always
+status:
+  ready: true
+"#;
+
+#[test]
+fn test_pipeline_roundtrip() {
+    let yaml = Pipeline::new(MANIFEST).to_yaml().unwrap();
+    // Quotes are only kept where they're needed
+    assert_eq!(
+        yaml,
+        r#"metadata:
+  name: web
+  labels:
+    app: web
+spec:
+  replicas: 3
+  selector:
+    app: web
+  containers:
+    - name: web
+      image: nginx:1.27
+    - name: sidecar
+      image: envoy
+      pullPolicy: Always
+status:
+  ready: true
+"#
+    );
+    assert_eq!(Pipeline::new(&yaml).to_yaml().unwrap(), yaml);
+}
+
+#[test]
+fn test_pipeline_quotes_strings() {
+    let yaml = Pipeline::new("a: '3'\nb: \"x: y\"\nc: ''\nd: plain\ne: |\n  two\n  lines\n")
+        .to_yaml()
+        .unwrap();
+    assert_eq!(
+        yaml,
+        "a: \"3\"\nb: \"x: y\"\nc: \"\"\nd: plain\ne: \"two\\nlines\\n\"\n"
+    );
+}
+
+#[test]
+fn test_pipeline_empty_collections() {
+    let yaml = Pipeline::new("a: {}\nb: []\nc: [[], [1]]\n")
+        .to_yaml()
+        .unwrap();
+    assert_eq!(yaml, "a: {}\nb: []\nc:\n  - []\n  - - 1\n");
+}
+
+#[test]
+fn test_pipeline_drop_path() {
+    let yaml = Pipeline::new(MANIFEST)
+        .transform(DropPath::new("status").unwrap())
+        .transform(DropPath::new("metadata.labels").unwrap())
+        .transform(DropPath::new("spec.containers.*.pullPolicy").unwrap())
+        .to_yaml()
+        .unwrap();
+    assert_eq!(
+        yaml,
+        r#"metadata:
+  name: web
+spec:
+  replicas: 3
+  selector:
+    app: web
+  containers:
+    - name: web
+      image: nginx:1.27
+    - name: sidecar
+      image: envoy
+"#
+    );
+}
+
+#[test]
+fn test_pipeline_rename_key() {
+    let yaml = Pipeline::new("spec:\n  replicas: 3\n  items: [{name: a}, {name: b}]\n")
+        .transform(RenameKey::new("spec.replicas", "count").unwrap())
+        .transform(RenameKey::new("spec.items.*.name", "id").unwrap())
+        .to_yaml()
+        .unwrap();
+    assert_eq!(
+        yaml,
+        "spec:\n  count: 3\n  items:\n    - id: a\n    - id: b\n"
+    );
+}
+
+#[test]
+fn test_pipeline_inject_default() {
+    let yaml = Pipeline::new("containers:\n  - name: a\n  - name: b\n    pullPolicy: Always\n")
+        .transform(InjectDefault::new("containers.*.pullPolicy", "IfNotPresent").unwrap())
+        .transform(InjectDefault::new("resources", "{cpu: 100m}").unwrap())
+        .to_yaml()
+        .unwrap();
+    assert_eq!(
+        yaml,
+        "containers:\n  - name: a\n    pullPolicy: IfNotPresent\n  - name: b\n    pullPolicy: Always\nresources:\n  cpu: 100m\n"
+    );
+}
+
+#[test]
+fn test_pipeline_later_transforms_see_earlier_output() {
+    let yaml = Pipeline::new("old: 1\n")
+        .transform(RenameKey::new("old", "new").unwrap())
+        .transform(InjectDefault::new("new", "2").unwrap())
+        .transform(InjectDefault::new("old", "3").unwrap())
+        .to_yaml()
+        .unwrap();
+    assert_eq!(yaml, "new: 1\nold: 3\n");
+}
+
+#[test]
+fn test_pipeline_closure_transform() {
+    let yaml = Pipeline::new("a: 1\nb: [1, 2]\n")
+        .transform(
+            |event: PipelineEvent, context: &TransformContext<'_>, out: &mut Vec<PipelineEvent>| {
+                match event {
+                    PipelineEvent::Scalar { value, plain } if !context.is_key() => {
+                        let value = value.parse::<i64>().map_or(value, |i| (i * 10).to_string());
+                        out.push(PipelineEvent::Scalar { value, plain });
+                    }
+                    event => out.push(event),
+                }
+            },
+        )
+        .to_yaml()
+        .unwrap();
+    assert_eq!(yaml, "a: 10\nb:\n  - 10\n  - 20\n");
+}
+
+#[test]
+fn test_pipeline_events() {
+    let events = Pipeline::new("[a, 'b']")
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        events,
+        vec![
+            PipelineEvent::SequenceStart,
+            PipelineEvent::plain("a"),
+            PipelineEvent::string("b"),
+            PipelineEvent::SequenceEnd,
+        ]
+    );
+}
+
+#[test]
+fn test_pipeline_deserialize() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Spec {
+        count: u32,
+        strategy: String,
+    }
+
+    let spec: Spec = Pipeline::new("replicas: 3\nstatus: {ready: true}\n")
+        .transform(DropPath::new("status").unwrap())
+        .transform(RenameKey::new("replicas", "count").unwrap())
+        .transform(InjectDefault::new("strategy", "Recreate").unwrap())
+        .deserialize()
+        .unwrap();
+    assert_eq!(
+        spec,
+        Spec {
+            count: 3,
+            strategy: "Recreate".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_pipeline_deserialize_error_location() {
+    #[derive(Debug, Facet)]
+    struct Spec {
+        #[allow(dead_code)]
+        count: u8,
+    }
+
+    let err = Pipeline::new("\nreplicas: 300\n")
+        .transform(RenameKey::new("replicas", "count").unwrap())
+        .deserialize::<Spec>()
+        .unwrap_err();
+    assert_eq!(err.path(), "count");
+    assert_eq!(err.span().map(|span| span.line), Some(2));
+}
+
+#[test]
+fn test_pipeline_unbalanced_output() {
+    let err = Pipeline::new("a: 1\nb: 2\n")
+        .transform(
+            |event: PipelineEvent, context: &TransformContext<'_>, out: &mut Vec<PipelineEvent>| {
+                // Drops the key but not its value
+                if !(context.is_key() && context.matches("a")) {
+                    out.push(event);
+                }
+            },
+        )
+        .to_yaml()
+        .unwrap_err();
+    assert!(err.message().starts_with("Unexpected MappingEnd"), "{err}");
+}
+
+#[test]
+fn test_pipeline_invalid_path() {
+    assert!(DropPath::new("a..b").is_err());
+    assert!(RenameKey::new("", "b").is_err());
+}
+
+#[test]
+fn test_pipeline_multiple_documents() {
+    let err = Pipeline::new("a: 1\n---\nb: 2\n").to_yaml().unwrap_err();
+    assert_eq!(err.message(), "Expected exactly one YAML document");
+}
@@ -0,0 +1,38 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use std::{path::PathBuf, rc::Rc, sync::Arc};
+
+#[derive(Debug, Facet, PartialEq)]
+struct Site {
+    name: Arc<str>,
+    title: Box<str>,
+    theme: Rc<str>,
+    root: PathBuf,
+    #[facet(default)]
+    aliases: Vec<Arc<str>>,
+}
+
+#[test]
+fn test_str_pointees() {
+    let site: Site = facet_yaml::from_str(
+        "name: docs\ntitle: 'Docs: home'\ntheme: dark\nroot: /srv/docs\naliases: [help, manual]",
+    )
+    .unwrap();
+    assert_eq!(&*site.name, "docs");
+    assert_eq!(&*site.title, "Docs: home");
+    assert_eq!(&*site.theme, "dark");
+    assert_eq!(site.root, PathBuf::from("/srv/docs"));
+    assert_eq!(site.aliases, [Arc::from("help"), Arc::from("manual")]);
+
+    let name: Arc<str> = facet_yaml::from_str("'42'").unwrap();
+    assert_eq!(&*name, "42");
+}
+
+#[test]
+fn test_str_pointee_errors() {
+    let err =
+        facet_yaml::from_str::<Site>("name: [docs]\ntitle: Docs\ntheme: dark\nroot: /srv/docs")
+            .unwrap_err();
+    assert_eq!(err.code(), facet_yaml::ErrorCode::TypeMismatch);
+    assert_eq!(err.path(), "name");
+}
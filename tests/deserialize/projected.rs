@@ -0,0 +1,136 @@
+use facet::Facet;
+use facet_testhelpers::test;
+
+const MANIFEST: &str = r#"
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+  labels: &labels
+    app: web
+spec:
+  replicas: 3
+  selector:
+    matchLabels: *labels
+  template:
+    spec:
+      containers:
+        - name: web
+          image: nginx:1.27
+          ports: [{containerPort: 80}]
+        - name: sidecar
+          image: envoy:1.30
+status:
+  observedGeneration: 99999999999
+"#;
+
+#[derive(Debug, Default, Facet, PartialEq)]
+struct Manifest {
+    #[facet(default)]
+    kind: String,
+    #[facet(default)]
+    metadata: Metadata,
+    #[facet(default)]
+    spec: Spec,
+    #[facet(default)]
+    status: Status,
+}
+
+#[derive(Debug, Default, Facet, PartialEq)]
+struct Metadata {
+    #[facet(default)]
+    name: String,
+}
+
+#[derive(Debug, Default, Facet, PartialEq)]
+struct Spec {
+    #[facet(default)]
+    replicas: u32,
+    #[facet(default)]
+    selector: Selector,
+    #[facet(default)]
+    template: Template,
+}
+
+#[derive(Debug, Default, Facet, PartialEq)]
+struct Selector {
+    #[facet(default, rename = "matchLabels")]
+    match_labels: std::collections::HashMap<String, String>,
+}
+
+#[derive(Debug, Default, Facet, PartialEq)]
+struct Template {
+    #[facet(default)]
+    spec: PodSpec,
+}
+
+#[derive(Debug, Default, Facet, PartialEq)]
+struct PodSpec {
+    #[facet(default)]
+    containers: Vec<Container>,
+}
+
+#[derive(Debug, Default, Facet, PartialEq)]
+struct Container {
+    #[facet(default)]
+    image: String,
+}
+
+#[derive(Debug, Default, Facet, PartialEq)]
+struct Status {
+    // Too large, but never read
+    #[facet(default, rename = "observedGeneration")]
+    observed_generation: u8,
+}
+
+#[test]
+fn test_deserialize_projected_fields() {
+    let manifest: Manifest =
+        facet_yaml::from_str_projected(MANIFEST, &["spec.replicas", "metadata.name"]).unwrap();
+
+    assert_eq!(manifest.metadata.name, "web");
+    assert_eq!(manifest.spec.replicas, 3);
+    assert_eq!(manifest.kind, "");
+    assert!(manifest.spec.template.spec.containers.is_empty());
+}
+
+#[test]
+fn test_deserialize_projected_wildcard() {
+    let manifest: Manifest =
+        facet_yaml::from_str_projected(MANIFEST, &["spec.template.spec.containers.*.image"])
+            .unwrap();
+
+    let images: Vec<&str> = manifest
+        .spec
+        .template
+        .spec
+        .containers
+        .iter()
+        .map(|container| container.image.as_str())
+        .collect();
+    assert_eq!(images, vec!["nginx:1.27", "envoy:1.30"]);
+}
+
+#[test]
+fn test_deserialize_projected_alias_to_skipped_anchor() {
+    let manifest: Manifest = facet_yaml::from_str_projected(MANIFEST, &["spec.selector"]).unwrap();
+
+    assert_eq!(
+        manifest.spec.selector.match_labels.get("app"),
+        Some(&"web".to_string())
+    );
+    assert_eq!(manifest.metadata, Metadata::default());
+}
+
+#[test]
+fn test_deserialize_projected_skips_invalid_values() {
+    assert!(facet_yaml::from_str::<Manifest>(MANIFEST).is_err());
+    assert!(facet_yaml::from_str_projected::<Manifest>(MANIFEST, &["kind"]).is_ok());
+}
+
+#[test]
+fn test_deserialize_projected_invalid_path() {
+    let err =
+        facet_yaml::from_str_projected::<Manifest>(MANIFEST, &["spec..replicas"]).unwrap_err();
+    assert!(err.message().contains("spec..replicas"), "{err}");
+}
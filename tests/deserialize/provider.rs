@@ -0,0 +1,91 @@
+use facet_testhelpers::test;
+use facet_yaml::YamlProvider;
+use figment::{Figment, Profile, providers::Serialized};
+
+const CONFIG: &str = "server:
+  host: localhost
+  port: 8080
+workers: [1, 2.5]
+debug: ~
+";
+
+#[test]
+fn test_provider_values() {
+    let figment = Figment::new().merge(YamlProvider::string(CONFIG));
+
+    assert_eq!(
+        figment.extract_inner::<String>("server.host").unwrap(),
+        "localhost"
+    );
+    assert_eq!(figment.extract_inner::<u16>("server.port").unwrap(), 8080);
+    assert_eq!(
+        figment.extract_inner::<Vec<f64>>("workers").unwrap(),
+        [1.0, 2.5]
+    );
+    assert_eq!(
+        figment.extract_inner::<Option<bool>>("debug").unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_provider_merges_with_other_providers() {
+    let figment = Figment::new()
+        .merge(Serialized::default("server.port", 80))
+        .merge(Serialized::default("server.tls", true))
+        .merge(YamlProvider::string(CONFIG));
+
+    assert_eq!(figment.extract_inner::<u16>("server.port").unwrap(), 8080);
+    assert!(figment.extract_inner::<bool>("server.tls").unwrap());
+}
+
+#[test]
+fn test_provider_error_has_line() {
+    let figment = Figment::new().merge(YamlProvider::string(CONFIG));
+
+    let err = figment.extract_inner::<u16>("server.host").unwrap_err();
+    let message = err.to_string();
+    assert!(
+        message.contains("default.server.host (line 2, column 3)"),
+        "{message}"
+    );
+}
+
+#[test]
+fn test_provider_profile() {
+    let figment = Figment::new()
+        .merge(YamlProvider::string("port: 1").profile("staging"))
+        .select("staging");
+
+    assert_eq!(figment.extract_inner::<u16>("port").unwrap(), 1);
+    assert_eq!(figment.profile(), &Profile::new("staging"));
+}
+
+#[test]
+fn test_provider_missing_file() {
+    let path = std::env::temp_dir().join("facet-yaml-provider-missing.yaml");
+
+    let figment = Figment::new().merge(YamlProvider::file(&path));
+    assert!(figment.find_value("port").is_err());
+    assert!(figment.extract_inner::<u16>("port").is_err());
+
+    let figment = Figment::new().merge(YamlProvider::file(&path).required(true));
+    let message = figment
+        .extract_inner::<u16>("port")
+        .unwrap_err()
+        .to_string();
+    assert!(message.contains("Failed to read"), "{message}");
+}
+
+#[test]
+fn test_provider_invalid_document() {
+    let figment = Figment::new().merge(YamlProvider::string("port: [1\n"));
+    assert!(figment.extract_inner::<u16>("port").is_err());
+
+    let figment = Figment::new().merge(YamlProvider::string("- 1\n"));
+    let message = figment
+        .extract_inner::<u16>("port")
+        .unwrap_err()
+        .to_string();
+    assert!(message.contains("Expected a YAML hash"), "{message}");
+}
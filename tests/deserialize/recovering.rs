@@ -0,0 +1,98 @@
+use facet_testhelpers::test;
+use facet_yaml::Value;
+
+#[test]
+fn test_recovering_valid_document() {
+    let value = facet_yaml::from_str_recovering("name: server\nports: [80, 443]\nratio: 0.5\n");
+
+    assert_eq!(value.get("name").and_then(Value::as_str), Some("server"));
+    let ports: Vec<i64> = value
+        .get("ports")
+        .and_then(Value::as_sequence)
+        .unwrap()
+        .iter()
+        .filter_map(Value::as_i64)
+        .collect();
+    assert_eq!(ports, vec![80, 443]);
+    assert_eq!(value.get("ratio").and_then(Value::as_f64), Some(0.5));
+    assert!(value.errors().is_empty());
+}
+
+#[test]
+fn test_recovering_keeps_document_order() {
+    let value = facet_yaml::from_str_recovering("zebra: 1\napple: 2\nmango: 3\n");
+
+    let keys: Vec<&str> = value
+        .as_map()
+        .unwrap()
+        .keys()
+        .filter_map(Value::as_str)
+        .collect();
+    assert_eq!(keys, vec!["zebra", "apple", "mango"]);
+}
+
+#[test]
+fn test_recovering_duplicate_keys() {
+    let yaml = "
+servers:
+  - host: a
+    port: 80
+    port: 8080
+name: test
+";
+
+    let value = facet_yaml::from_str_recovering(yaml);
+
+    // The rest of the document is still there
+    assert_eq!(value.get("name").and_then(Value::as_str), Some("test"));
+    let server = &value.get("servers").and_then(Value::as_sequence).unwrap()[0];
+    assert_eq!(server.get("port").and_then(Value::as_i64), Some(80));
+
+    let errors = value.errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path(), "servers[0].port");
+    assert!(
+        errors[0].message().contains("duplicated key"),
+        "{}",
+        errors[0]
+    );
+    assert_eq!(errors[0].span().unwrap().line, 5);
+}
+
+#[test]
+fn test_recovering_mismatched_tags() {
+    let value = facet_yaml::from_str_recovering("a: !!int ten\nb: !!bool 1\nc: !!int 10\n");
+
+    assert!(value.get("a").unwrap().is_invalid());
+    assert!(value.get("b").unwrap().is_invalid());
+    assert_eq!(value.get("c").and_then(Value::as_i64), Some(10));
+
+    let paths: Vec<String> = value
+        .errors()
+        .iter()
+        .map(|err| err.path().to_string())
+        .collect();
+    assert_eq!(paths, vec!["a", "b"]);
+}
+
+#[test]
+fn test_recovering_syntax_error() {
+    let value = facet_yaml::from_str_recovering("a: 1\nb: [1, 2\nc: 3\n");
+
+    // Everything before the error is kept
+    assert_eq!(value.get("a").and_then(Value::as_i64), Some(1));
+    let b = value.get("b").and_then(Value::as_sequence).unwrap();
+    assert_eq!(b[0], Value::Number(1_i64.into()));
+    assert!(b.last().unwrap().is_invalid());
+
+    let errors = value.errors();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].path().starts_with("b["), "{}", errors[0]);
+    assert_eq!(errors[0].span().unwrap().line, 3);
+}
+
+#[test]
+fn test_recovering_empty_document() {
+    assert_eq!(facet_yaml::from_str_recovering(""), Value::Null);
+    assert_eq!(facet_yaml::from_str_recovering("# comment\n"), Value::Null);
+}
@@ -0,0 +1,167 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::{DeserializeOptions, ErrorCode, RenameRule};
+
+#[derive(Debug, Facet, PartialEq)]
+struct Endpoint {
+    host_name: String,
+    port: u16,
+    #[facet(default)]
+    max_retries: u32,
+    tls: Option<TlsConfig>,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct TlsConfig {
+    cert_path: String,
+}
+
+#[test]
+fn test_rename_all_camel_case() {
+    let options = DeserializeOptions::new().rename_all(RenameRule::CamelCase);
+    let endpoint: Endpoint = facet_yaml::from_str_with_options(
+        "hostName: example.com\nport: 443\nmaxRetries: 3\ntls:\n  certPath: /etc/cert.pem\n",
+        &options,
+    )
+    .unwrap();
+    assert_eq!(
+        endpoint,
+        Endpoint {
+            host_name: "example.com".to_string(),
+            port: 443,
+            max_retries: 3,
+            tls: Some(TlsConfig {
+                cert_path: "/etc/cert.pem".to_string(),
+            }),
+        }
+    );
+}
+
+#[test]
+fn test_rename_all_keeps_field_names() {
+    let options = DeserializeOptions::new().rename_all(RenameRule::KebabCase);
+    let endpoint: Endpoint = facet_yaml::from_str_with_options(
+        "host_name: example.com\nmax-retries: 1\nport: 80",
+        &options,
+    )
+    .unwrap();
+    assert_eq!(endpoint.host_name, "example.com");
+    assert_eq!(endpoint.max_retries, 1);
+}
+
+#[test]
+fn test_rename_all_from_camel_case_fields() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[allow(non_snake_case)]
+    struct Generated {
+        maxRetries: u32,
+        HTTPProxy: String,
+    }
+
+    let options = DeserializeOptions::new().rename_all(RenameRule::SnakeCase);
+    let generated: Generated =
+        facet_yaml::from_str_with_options("max_retries: 2\nhttp_proxy: proxy:3128", &options)
+            .unwrap();
+    assert_eq!(
+        generated,
+        Generated {
+            maxRetries: 2,
+            HTTPProxy: "proxy:3128".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_rename_all_is_off_by_default() {
+    let err = facet_yaml::from_str::<Endpoint>("hostName: example.com\nport: 443").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UnknownField);
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Deployment {
+    #[facet(yaml(rename_all = "camelCase"))]
+    service: Service,
+    #[facet(default)]
+    labels: Vec<String>,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Service {
+    service_name: String,
+    health_check: HealthCheck,
+    #[facet(yaml(rename_all = "kebab-case"))]
+    legacy: Option<HealthCheck>,
+    #[facet(yaml(rename_all = "none"))]
+    raw: Option<HealthCheck>,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct HealthCheck {
+    interval_secs: u32,
+}
+
+#[test]
+fn test_rename_all_attribute_is_inherited() {
+    let deployment: Deployment = facet_yaml::from_str(
+        "service:\n  serviceName: api\n  healthCheck:\n    intervalSecs: 5\nlabels: [web]\n",
+    )
+    .unwrap();
+    assert_eq!(
+        deployment,
+        Deployment {
+            service: Service {
+                service_name: "api".to_string(),
+                health_check: HealthCheck { interval_secs: 5 },
+                legacy: None,
+                raw: None,
+            },
+            labels: vec!["web".to_string()],
+        }
+    );
+}
+
+#[test]
+fn test_rename_all_attribute_is_overridden() {
+    let deployment: Deployment = facet_yaml::from_str(
+        "service:\n  serviceName: api\n  healthCheck: {intervalSecs: 5}\n  legacy: {interval-secs: 10}\n  raw: {interval_secs: 15}\n",
+    )
+    .unwrap();
+    assert_eq!(
+        deployment.service.legacy,
+        Some(HealthCheck { interval_secs: 10 })
+    );
+    assert_eq!(
+        deployment.service.raw,
+        Some(HealthCheck { interval_secs: 15 })
+    );
+
+    let err = facet_yaml::from_str::<Deployment>(
+        "service:\n  serviceName: api\n  healthCheck: {intervalSecs: 5}\n  raw: {intervalSecs: 15}\n",
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UnknownField);
+}
+
+#[test]
+fn test_rename_all_attribute_takes_precedence_over_option() {
+    let options = DeserializeOptions::new().rename_all(RenameRule::ScreamingSnakeCase);
+    let deployment: Deployment = facet_yaml::from_str_with_options(
+        "service:\n  serviceName: api\n  healthCheck: {intervalSecs: 5}\nLABELS: [web]\n",
+        &options,
+    )
+    .unwrap();
+    assert_eq!(deployment.service.health_check.interval_secs, 5);
+    assert_eq!(deployment.labels, ["web"]);
+}
+
+#[test]
+fn test_rename_all_attribute_with_unknown_convention() {
+    #[derive(Debug, Facet)]
+    struct Invalid {
+        #[facet(yaml(rename_all = "camel"))]
+        check: HealthCheck,
+    }
+
+    let err = facet_yaml::from_str::<Invalid>("check: {interval_secs: 5}").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::InvalidAttribute);
+}
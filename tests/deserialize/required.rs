@@ -0,0 +1,76 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::ErrorCode;
+
+#[derive(Debug, Default, Facet, PartialEq)]
+#[facet(default)]
+struct Cluster {
+    #[facet(yaml(required))]
+    cluster_name: String,
+    nodes: u32,
+}
+
+#[test]
+fn test_required_field_present() {
+    let cluster: Cluster = facet_yaml::from_str("cluster_name: prod").unwrap();
+    assert_eq!(
+        cluster,
+        Cluster {
+            cluster_name: "prod".to_string(),
+            nodes: 0,
+        }
+    );
+}
+
+#[test]
+fn test_required_field_missing_with_container_default() {
+    let err = facet_yaml::from_str::<Cluster>("nodes: 3").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::MissingField);
+    assert_eq!(err.message(), "Missing field 'cluster_name'");
+    assert_eq!(err.span().map(|span| span.line), Some(1));
+}
+
+#[test]
+fn test_required_field_missing_with_field_default() {
+    #[derive(Debug, Facet)]
+    struct Deployment {
+        #[facet(default, yaml(required))]
+        replicas: u32,
+    }
+
+    let err = facet_yaml::from_str::<Deployment>("{}").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::MissingField);
+}
+
+#[test]
+fn test_required_field_in_nested_struct() {
+    #[derive(Debug, Facet)]
+    struct Config {
+        clusters: Vec<Cluster>,
+    }
+
+    let err = facet_yaml::from_str::<Config>("clusters:\n  - cluster_name: prod\n  - nodes: 2\n")
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::MissingField);
+    assert_eq!(err.span().map(|span| span.line), Some(3));
+}
+
+#[test]
+fn test_required_field_validate() {
+    let errors = facet_yaml::validate::<Cluster>("nodes: 3").unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].code(), ErrorCode::MissingField);
+    assert_eq!(errors[0].message(), "Missing field 'cluster_name'");
+}
+
+#[test]
+fn test_required_and_skipped() {
+    #[derive(Debug, Facet)]
+    struct Invalid {
+        #[facet(default, yaml(required, skip_deserializing))]
+        checksum: String,
+    }
+
+    let err = facet_yaml::from_str::<Invalid>("checksum: abc").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::InvalidAttribute);
+}
@@ -0,0 +1,89 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::{DeserializeOptions, PlainScalar, Value};
+
+/// YAML 1.1 booleans, and nothing that looks like a number is read as one.
+fn options() -> DeserializeOptions {
+    DeserializeOptions::new().resolve_plain_scalars(|text| match text {
+        "on" | "yes" => Some(PlainScalar::Bool(true)),
+        "off" | "no" => Some(PlainScalar::Bool(false)),
+        "nothing" => Some(PlainScalar::Null),
+        text if text.starts_with('v') => Some(PlainScalar::String),
+        text => text
+            .strip_suffix('k')
+            .and_then(|n| n.parse::<i64>().ok())
+            .map(|n| PlainScalar::Integer(n * 1000)),
+    })
+}
+
+#[test]
+fn test_resolver_decides_plain_scalars() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        enabled: bool,
+        disabled: bool,
+        limit: u32,
+        comment: Option<String>,
+        port: u16,
+    }
+
+    let root: Root = facet_yaml::from_str_with_options(
+        "enabled: on\ndisabled: no\nlimit: 10k\ncomment: nothing\nport: 8080\n",
+        &options(),
+    )
+    .unwrap();
+    assert_eq!(
+        root,
+        Root {
+            enabled: true,
+            disabled: false,
+            limit: 10_000,
+            comment: None,
+            port: 8080,
+        }
+    );
+}
+
+#[test]
+fn test_resolver_skips_quoted_and_tagged_scalars() {
+    let value: Value =
+        facet_yaml::from_str_with_options("[on, 'on', !!str off, \"10k\", 10k]", &options())
+            .unwrap();
+    assert_eq!(
+        value,
+        Value::Sequence(vec![
+            Value::Bool(true),
+            Value::String("on".to_string()),
+            Value::String("off".to_string()),
+            Value::String("10k".to_string()),
+            Value::Number(10_000_i64.into()),
+        ])
+    );
+}
+
+#[test]
+fn test_resolver_can_keep_strings() {
+    let value: Value =
+        facet_yaml::from_str_with_options("- v1\n- 1.0\n- true", &options()).unwrap();
+    assert_eq!(
+        value,
+        Value::Sequence(vec![
+            Value::String("v1".to_string()),
+            Value::Number(1.0_f64.into()),
+            Value::Bool(true),
+        ])
+    );
+}
+
+#[test]
+fn test_resolver_float() {
+    let options = DeserializeOptions::new().resolve_plain_scalars(|text| {
+        text.strip_suffix('%')
+            .and_then(|n| n.parse::<f64>().ok())
+            .map(|n| PlainScalar::Float(n / 100.0))
+    });
+
+    let ratios: Vec<f32> =
+        facet_yaml::from_str_with_options("[50%, 12.5%, 0.3]", &options).unwrap();
+    assert_eq!(ratios, [0.5, 0.125, 0.3]);
+}
@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::{Value, schema::compatible};
+
+fn paths(result: Result<(), Vec<facet_yaml::schema::Incompatibility>>) -> Vec<String> {
+    result
+        .unwrap_err()
+        .iter()
+        .map(|incompatibility| incompatibility.path().to_string())
+        .collect()
+}
+
+#[test]
+fn test_same_type_is_compatible() {
+    #[derive(Facet)]
+    struct Config {
+        name: String,
+        ports: Vec<u16>,
+    }
+
+    assert!(compatible::<Config, Config>().is_ok());
+}
+
+#[test]
+fn test_added_field_needs_default() {
+    #[derive(Facet)]
+    struct Old {
+        name: String,
+    }
+
+    #[derive(Facet)]
+    struct WithDefault {
+        name: String,
+        #[facet(default)]
+        replicas: u32,
+    }
+
+    #[derive(Facet)]
+    struct Required {
+        name: String,
+        replicas: u32,
+    }
+
+    assert!(compatible::<Old, WithDefault>().is_ok());
+    let errors = compatible::<Old, Required>().unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path(), "replicas");
+    assert_eq!(
+        errors[0].to_string(),
+        "Field 'replicas' is required, but documents of the old type may not have it at `replicas`"
+    );
+}
+
+#[test]
+fn test_removed_field_is_incompatible() {
+    #[derive(Facet)]
+    struct Old {
+        name: String,
+        legacy: bool,
+    }
+
+    #[derive(Facet)]
+    struct New {
+        name: String,
+    }
+
+    assert_eq!(paths(compatible::<Old, New>()), ["legacy"]);
+}
+
+#[test]
+fn test_renamed_field_with_alias() {
+    #[derive(Facet)]
+    struct Old {
+        host: String,
+    }
+
+    #[derive(Facet)]
+    struct New {
+        #[facet(yaml(alias = "host"))]
+        hostname: String,
+    }
+
+    #[derive(Facet)]
+    struct Renamed {
+        hostname: String,
+    }
+
+    assert!(compatible::<Old, New>().is_ok());
+    assert_eq!(paths(compatible::<Old, Renamed>()), ["host", "hostname"]);
+}
+
+#[test]
+fn test_optional_field_becoming_required() {
+    #[derive(Facet)]
+    struct Old {
+        #[facet(default)]
+        port: u16,
+    }
+
+    #[derive(Facet)]
+    struct New {
+        port: u16,
+    }
+
+    assert_eq!(paths(compatible::<Old, New>()), ["port"]);
+    assert!(compatible::<New, Old>().is_ok());
+}
+
+#[test]
+fn test_field_becoming_yaml_required() {
+    #[derive(Default, Facet)]
+    #[facet(default)]
+    struct Old {
+        cluster_name: String,
+    }
+
+    #[derive(Default, Facet)]
+    #[facet(default)]
+    struct New {
+        #[facet(yaml(required))]
+        cluster_name: String,
+    }
+
+    assert_eq!(paths(compatible::<Old, New>()), ["cluster_name"]);
+    assert!(compatible::<New, Old>().is_ok());
+}
+
+#[test]
+fn test_numbers_may_only_widen() {
+    #[derive(Facet)]
+    struct Small {
+        a: u16,
+        b: i16,
+        c: u16,
+        d: i32,
+    }
+
+    #[derive(Facet)]
+    struct Large {
+        a: u32,
+        b: i64,
+        c: i32,
+        d: f64,
+    }
+
+    assert!(compatible::<Small, Large>().is_ok());
+    assert_eq!(paths(compatible::<Large, Small>()), ["a", "b", "c", "d"]);
+
+    #[derive(Facet)]
+    struct Unsigned {
+        value: u32,
+    }
+
+    #[derive(Facet)]
+    struct Signed {
+        value: i32,
+    }
+
+    assert_eq!(paths(compatible::<Unsigned, Signed>()), ["value"]);
+    assert_eq!(paths(compatible::<Signed, Unsigned>()), ["value"]);
+}
+
+#[test]
+fn test_changed_type_is_incompatible() {
+    #[derive(Facet)]
+    struct Old {
+        port: u16,
+    }
+
+    #[derive(Facet)]
+    struct New {
+        port: String,
+    }
+
+    let errors = compatible::<Old, New>().unwrap_err();
+    assert_eq!(errors[0].message(), "Type changed from u16 to String");
+}
+
+#[test]
+fn test_options() {
+    #[derive(Facet)]
+    struct Required {
+        name: String,
+    }
+
+    #[derive(Facet)]
+    struct Optional {
+        name: Option<String>,
+    }
+
+    #[derive(Facet)]
+    struct WithDefault {
+        #[facet(default)]
+        name: Option<String>,
+    }
+
+    assert!(compatible::<Required, Optional>().is_ok());
+    assert_eq!(paths(compatible::<Optional, Required>()), ["name"]);
+    // Documents with `name: null` are rejected too, not just the ones without it
+    assert_eq!(
+        paths(compatible::<WithDefault, Required>()),
+        ["name", "name"]
+    );
+}
+
+#[test]
+fn test_nested_collections() {
+    #[derive(Facet)]
+    struct OldServer {
+        port: u32,
+    }
+
+    #[derive(Facet)]
+    struct NewServer {
+        port: u16,
+    }
+
+    #[derive(Facet)]
+    struct Old {
+        servers: Vec<OldServer>,
+        named: HashMap<String, OldServer>,
+    }
+
+    #[derive(Facet)]
+    struct New {
+        servers: Vec<NewServer>,
+        named: HashMap<String, NewServer>,
+    }
+
+    assert_eq!(
+        paths(compatible::<Old, New>()),
+        ["servers[].port", "named{}.port"]
+    );
+}
+
+#[test]
+fn test_anything_fits_value() {
+    #[derive(Facet)]
+    struct Old {
+        settings: HashMap<String, u32>,
+    }
+
+    #[derive(Facet)]
+    struct New {
+        settings: Value,
+    }
+
+    assert!(compatible::<Old, New>().is_ok());
+    assert_eq!(paths(compatible::<New, Old>()), ["settings"]);
+}
+
+#[test]
+fn test_paths() {
+    #[derive(Facet)]
+    struct Old {
+        #[facet(yaml(path = "server.port"))]
+        port: u16,
+    }
+
+    #[derive(Facet)]
+    struct New {
+        #[facet(yaml(path = "server.port"))]
+        port: u32,
+    }
+
+    #[derive(Facet)]
+    struct Moved {
+        #[facet(yaml(path = "listen.port"))]
+        port: u16,
+    }
+
+    assert!(compatible::<Old, New>().is_ok());
+    assert_eq!(
+        paths(compatible::<Old, Moved>()),
+        ["server.port", "listen.port"]
+    );
+}
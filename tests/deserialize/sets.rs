@@ -0,0 +1,22 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::ErrorCode;
+use std::collections::{BTreeSet, HashSet};
+
+#[derive(Debug, Facet, PartialEq)]
+struct Policy {
+    roles: BTreeSet<String>,
+    ports: HashSet<u16>,
+}
+
+#[test]
+fn test_sets_are_unsupported() {
+    // facet-reflect can't build sets yet, so they're rejected where they are
+    let err = facet_yaml::from_str::<Policy>("roles: [user, admin]\nports: [80, 443]").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Unsupported);
+    assert_eq!(err.path(), "roles");
+    assert_eq!(err.span().unwrap().line, 1);
+
+    let err = facet_yaml::from_str::<BTreeSet<u16>>("[80, 443]").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Unsupported);
+}
@@ -0,0 +1,58 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::ErrorCode;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Rendered {
+    name: String,
+    #[facet(default, yaml(skip_deserializing))]
+    checksum: String,
+}
+
+#[test]
+fn test_skip_deserializing_ignores_key() {
+    let rendered: Rendered = facet_yaml::from_str("name: web\nchecksum: abc123").unwrap();
+    assert_eq!(
+        rendered,
+        Rendered {
+            name: "web".to_string(),
+            checksum: String::new(),
+        }
+    );
+}
+
+#[test]
+fn test_skip_deserializing_missing_key() {
+    let rendered: Rendered = facet_yaml::from_str("name: web").unwrap();
+    assert_eq!(rendered.checksum, "");
+}
+
+#[test]
+fn test_skip_deserializing_validate() {
+    facet_yaml::validate::<Rendered>("name: web\nchecksum: [not, a, string]").unwrap();
+}
+
+#[test]
+fn test_skip_deserializing_needs_default() {
+    #[derive(Debug, Facet)]
+    struct Rendered {
+        #[facet(yaml(skip_deserializing))]
+        checksum: String,
+    }
+
+    let err = facet_yaml::from_str::<Rendered>("checksum: abc123").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::InvalidAttribute);
+}
+
+#[test]
+fn test_skip_serializing_is_still_read() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Persisted {
+        name: String,
+        #[facet(skip_serializing)]
+        secret: String,
+    }
+
+    let persisted: Persisted = facet_yaml::from_str("name: web\nsecret: hunter2").unwrap();
+    assert_eq!(persisted.secret, "hunter2");
+}
@@ -0,0 +1,71 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::{DeserializeOptions, ErrorCode, RenameRule};
+
+#[derive(Debug, Facet, PartialEq)]
+struct Event {
+    kind: String,
+    #[facet(yaml(alias = "identifier"))]
+    event_id: u64,
+    #[facet(default)]
+    labels: Vec<String>,
+}
+
+/// Options that put every document on one side of the threshold.
+fn options(small: bool) -> DeserializeOptions {
+    let threshold = if small { usize::MAX } else { 0 };
+    DeserializeOptions::new()
+        .rename_all(RenameRule::CamelCase)
+        .small_document_threshold(threshold)
+}
+
+#[test]
+fn test_same_values_on_both_sides_of_the_threshold() {
+    for yaml in [
+        "kind: push\neventId: 7\nlabels: [ci]",
+        "{kind: push, identifier: 7, labels: [ci]}",
+        "kind: push\nevent_id: 7\nlabels: [ci]",
+    ] {
+        let small: Event = facet_yaml::from_str_with_options(yaml, &options(true)).unwrap();
+        let large: Event = facet_yaml::from_str_with_options(yaml, &options(false)).unwrap();
+        assert_eq!(small, large);
+        assert_eq!(small.event_id, 7);
+        assert_eq!(small.labels, ["ci"]);
+    }
+}
+
+#[test]
+fn test_same_errors_on_both_sides_of_the_threshold() {
+    for yaml in [
+        "kind: push\neventId: seven",
+        "kind: push",
+        "kind: [push",
+        "kind: push\neventId: 7\nkind_of: push",
+    ] {
+        let small = facet_yaml::from_str_with_options::<Event>(yaml, &options(true)).unwrap_err();
+        let large = facet_yaml::from_str_with_options::<Event>(yaml, &options(false)).unwrap_err();
+        assert_eq!(small.code(), large.code());
+        assert_eq!(small.message(), large.message());
+        assert_eq!(small.path(), large.path());
+    }
+}
+
+#[test]
+fn test_repeated_keys_below_the_threshold() {
+    let yaml = "- {kind: push, eventId: 1}\n- {kind: tag, eventId: 2}\n- {kind: push, eventId: 3}";
+    let events: Vec<Event> = facet_yaml::from_str_with_options(yaml, &options(true)).unwrap();
+    assert_eq!(
+        events
+            .iter()
+            .map(|event| event.event_id)
+            .collect::<Vec<_>>(),
+        [1, 2, 3]
+    );
+
+    let err = facet_yaml::from_str_with_options::<Vec<Event>>(
+        "- {kind: push, eventId: 1}\n- {eventId: 2}",
+        &options(true),
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::MissingField);
+}
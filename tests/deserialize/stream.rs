@@ -0,0 +1,136 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::DocumentStream;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Service {
+    name: String,
+    replicas: u32,
+}
+
+const STREAM: &str = "\
+%YAML 1.2
+# The web frontend
+---
+name: web
+replicas: 2 # peak traffic
+...
+
+# The background worker
+--- # queue consumer
+name: worker
+replicas: 1
+
+# keep it small
+---
+name: cron
+replicas: 0
+";
+
+#[test]
+fn test_stream_round_trips_byte_identical() {
+    for yaml in [
+        STREAM,
+        "",
+        "a: 1",
+        "# only a comment\n",
+        "---\n---\n",
+        "--- |\n  text\n\n# comment\n--- >\n  folded\n",
+        "x: |+\n  keep\n\n\n---\ny\n",
+        "a\n...\nb\n...\n# after\n",
+    ] {
+        assert_eq!(DocumentStream::new(yaml).to_string(), yaml);
+    }
+}
+
+#[test]
+fn test_stream_framing() {
+    let stream = DocumentStream::new(STREAM);
+    assert_eq!(stream.len(), 3);
+
+    let [web, worker, cron] = stream.documents() else {
+        panic!("expected 3 documents");
+    };
+    assert_eq!(web.header(), "%YAML 1.2\n# The web frontend\n---\n");
+    assert_eq!(web.body(), "name: web\nreplicas: 2 # peak traffic\n");
+    assert_eq!(web.footer(), "...\n");
+    assert_eq!(
+        worker.header(),
+        "\n# The background worker\n--- # queue consumer\n"
+    );
+    assert_eq!(worker.body(), "name: worker\nreplicas: 1\n");
+    assert_eq!(worker.footer(), "\n# keep it small\n");
+    assert_eq!(cron.header(), "---\n");
+    assert_eq!(cron.footer(), "");
+}
+
+#[test]
+fn test_stream_deserialize_documents() {
+    let stream = DocumentStream::new(STREAM);
+    let services: Vec<Service> = stream
+        .documents()
+        .iter()
+        .map(|document| document.deserialize().unwrap())
+        .collect();
+    assert_eq!(
+        services,
+        [
+            Service {
+                name: "web".to_string(),
+                replicas: 2
+            },
+            Service {
+                name: "worker".to_string(),
+                replicas: 1
+            },
+            Service {
+                name: "cron".to_string(),
+                replicas: 0
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_stream_edit_one_document() {
+    let mut stream = DocumentStream::new(STREAM);
+    stream.documents_mut()[1].set_body("---\nname: worker\nreplicas: 3");
+
+    assert_eq!(
+        stream.to_string(),
+        STREAM.replace("name: worker\nreplicas: 1\n", "name: worker\nreplicas: 3\n")
+    );
+}
+
+#[test]
+fn test_stream_content_on_marker_line() {
+    let stream = DocumentStream::new("--- |\n  text\n--- plain\n");
+    assert_eq!(stream.len(), 2);
+    assert_eq!(stream.documents()[0].header(), "--- ");
+    assert_eq!(stream.documents()[0].body(), "|\n  text\n");
+    assert_eq!(
+        stream.documents()[0].deserialize::<String>().unwrap(),
+        "text\n"
+    );
+    assert_eq!(
+        stream.documents()[1].deserialize::<String>().unwrap(),
+        "plain"
+    );
+}
+
+#[cfg(feature = "serialize")]
+#[test]
+fn test_stream_set_value() {
+    let mut stream = DocumentStream::new(STREAM);
+    stream.documents_mut()[2]
+        .set_value(&Service {
+            name: "cron".to_string(),
+            replicas: 1,
+        })
+        .unwrap();
+
+    assert_eq!(
+        stream.to_string(),
+        STREAM.replace("name: cron\nreplicas: 0\n", "name: cron\nreplicas: 1\n")
+    );
+}
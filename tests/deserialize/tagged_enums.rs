@@ -0,0 +1,105 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::ErrorCode;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Deployment {
+    replicas: u32,
+    image: String,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+#[facet(tag = "kind")]
+#[repr(u8)]
+enum Resource {
+    Namespace,
+    Service {
+        port: u16,
+        #[facet(default)]
+        selector: Vec<String>,
+    },
+    Deployment(Deployment),
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Manifest {
+    resources: Vec<Resource>,
+}
+
+#[test]
+fn test_tagged_variants() {
+    let manifest: Manifest = facet_yaml::from_str(
+        r#"
+resources:
+  - kind: Namespace
+  - kind: Service
+    port: 80
+  - replicas: 3
+    kind: Deployment
+    image: "shop:1"
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        manifest.resources,
+        vec![
+            Resource::Namespace,
+            Resource::Service {
+                port: 80,
+                selector: vec![]
+            },
+            Resource::Deployment(Deployment {
+                replicas: 3,
+                image: "shop:1".to_string()
+            }),
+        ]
+    );
+}
+
+#[test]
+fn test_missing_tag() {
+    let err = facet_yaml::from_str::<Manifest>("resources:\n  - port: 80").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::MissingField);
+    assert_eq!(err.message(), "Missing tag 'kind' of Resource");
+    assert_eq!(err.path(), "resources[0]");
+}
+
+#[test]
+fn test_unknown_tag() {
+    let err = facet_yaml::from_str::<Manifest>("resources:\n  - kind: Ingress").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UnknownVariant);
+    assert_eq!(
+        err.message(),
+        "Unknown variant 'Ingress' of Resource, expected one of 'Namespace', 'Service', 'Deployment'"
+    );
+}
+
+#[test]
+fn test_fields_next_to_tag() {
+    // Errors in the fields have the path of the fields, next to the tag
+    let err = facet_yaml::from_str::<Manifest>("resources:\n  - kind: Service\n    port: http")
+        .unwrap_err();
+    assert_eq!(err.path(), "resources[0].port");
+
+    let err = facet_yaml::from_str::<Manifest>("resources:\n  - kind: Service").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::MissingField);
+    assert_eq!(err.path(), "resources[0]");
+
+    let err = facet_yaml::from_str::<Manifest>("resources:\n  - kind: Namespace\n    port: 80")
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UnknownField);
+    assert_eq!(
+        err.message(),
+        "Variant 'Namespace' has no fields, got: port"
+    );
+}
+
+#[test]
+fn test_tagged_enum_is_a_hash() {
+    let err = facet_yaml::from_str::<Manifest>("resources:\n  - Namespace").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+    assert_eq!(
+        err.message(),
+        "Expected a YAML hash with a 'kind' key for Resource, got: string"
+    );
+}
@@ -0,0 +1,151 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::{DeserializeOptions, ErrorCode, Value};
+
+#[test]
+fn test_str_tag_binds_as_string() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        date: String,
+        hex: String,
+        boolean: String,
+        tilde: Option<String>,
+        empty: String,
+    }
+
+    let root: Root = facet_yaml::from_str(
+        "date: !!str 2024-01-01\nhex: !!str 0x1F\nboolean: !!str true\ntilde: !!str ~\nempty: !!str\n",
+    )
+    .unwrap();
+    assert_eq!(
+        root,
+        Root {
+            date: "2024-01-01".to_string(),
+            hex: "0x1F".to_string(),
+            boolean: "true".to_string(),
+            tilde: Some("~".to_string()),
+            empty: String::new(),
+        }
+    );
+}
+
+#[test]
+fn test_str_tag_in_value() {
+    let value: Value = facet_yaml::from_str("- !!str 0x1F\n- 0x1F\n- !!str 1.5").unwrap();
+    assert_eq!(
+        value,
+        Value::Sequence(vec![
+            Value::String("0x1F".to_string()),
+            Value::Number(31_i64.into()),
+            Value::String("1.5".to_string()),
+        ])
+    );
+}
+
+#[test]
+fn test_str_tag_on_key() {
+    let map: std::collections::HashMap<String, u32> =
+        facet_yaml::from_str("!!str 2024-01-01: 1\n!!str 10: 2").unwrap();
+    assert_eq!(map["2024-01-01"], 1);
+    assert_eq!(map["10"], 2);
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Http {
+    port: u16,
+    #[facet(default)]
+    tls: bool,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Grpc {
+    port: u16,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+#[repr(u8)]
+enum Plugin {
+    Web(Http),
+    Rpc(Grpc),
+    #[allow(dead_code)]
+    Other(Value),
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Plugins {
+    plugins: Vec<Plugin>,
+}
+
+fn plugin_options() -> DeserializeOptions {
+    DeserializeOptions::new()
+        .register_tag::<Http>("!http")
+        .register_tag::<Grpc>("tag:example.com,2024:grpc")
+}
+
+#[test]
+fn test_registered_tags_select_variants() {
+    let plugins: Plugins = facet_yaml::from_str_with_options(
+        r#"
+%TAG !e! tag:example.com,2024:
+---
+plugins:
+  - !http {port: 80}
+  - !e!grpc
+    port: 50051
+  - Web: {port: 443, tls: true}
+"#,
+        &plugin_options(),
+    )
+    .unwrap();
+    assert_eq!(
+        plugins.plugins,
+        vec![
+            Plugin::Web(Http {
+                port: 80,
+                tls: false
+            }),
+            Plugin::Rpc(Grpc { port: 50051 }),
+            Plugin::Web(Http {
+                port: 443,
+                tls: true
+            }),
+        ]
+    );
+
+    let err = facet_yaml::from_str_with_options::<Plugins>(
+        "plugins:\n  - !http {port: eighty}",
+        &plugin_options(),
+    )
+    .unwrap_err();
+    assert_eq!(err.path(), "plugins[0].port");
+}
+
+#[test]
+fn test_unknown_tags_select_value_variant() {
+    let plugins: Plugins =
+        facet_yaml::from_str_with_options("plugins:\n  - !cache {size: 10}", &plugin_options())
+            .unwrap();
+    let Plugin::Other(Value::Map(map)) = &plugins.plugins[0] else {
+        panic!("expected a dynamic value, got {:?}", plugins.plugins[0]);
+    };
+    assert_eq!(map.len(), 1);
+
+    // Without registration, tags only select the variant holding a dynamic value
+    let plugins: Plugins = facet_yaml::from_str("plugins:\n  - !http {port: 80}").unwrap();
+    assert!(matches!(plugins.plugins[0], Plugin::Other(Value::Map(_))));
+}
+
+#[test]
+fn test_tags_without_matching_variant() {
+    #[allow(dead_code)]
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    enum Listener {
+        Web(Http),
+    }
+
+    let options = DeserializeOptions::new().register_tag::<Grpc>("!grpc");
+    let err =
+        facet_yaml::from_str_with_options::<Listener>("!grpc {port: 80}", &options).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UnknownVariant);
+}
@@ -0,0 +1,133 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::ErrorCode;
+
+#[derive(Facet)]
+struct Container {
+    /// Image to run.
+    image: String,
+    #[facet(default)]
+    args: Vec<String>,
+}
+
+#[derive(Facet)]
+#[repr(u8)]
+#[allow(dead_code)]
+enum Level {
+    Debug,
+    Info,
+}
+
+#[derive(Facet)]
+struct Tls {
+    cert: String,
+    #[facet(default)]
+    key: Option<String>,
+}
+
+#[derive(Facet)]
+struct Service {
+    /// Name of the service.
+    ///
+    /// Shown in the dashboard.
+    name: String,
+    containers: Vec<Container>,
+    level: Level,
+    /// Certificates, if served over HTTPS.
+    #[facet(default)]
+    tls: Option<Tls>,
+    #[facet(default, yaml(required))]
+    owner: String,
+    #[facet(default, yaml(skip_deserializing))]
+    revision: u64,
+}
+
+#[test]
+fn test_template() {
+    assert_eq!(
+        facet_yaml::template::<Service>().unwrap(),
+        "\
+# Name of the service.
+#
+# Shown in the dashboard.
+name: # <REQUIRED: String>
+containers:
+  # Image to run.
+  - image: # <REQUIRED: String>
+    # args: <Vec<String>>
+level: # <REQUIRED: Level, one of Debug, Info>
+# Certificates, if served over HTTPS.
+# tls:
+#   cert: # <REQUIRED: String>
+#   # key: <String>
+owner: # <REQUIRED: String>
+"
+    );
+}
+
+#[test]
+fn test_template_paths() {
+    #[derive(Facet)]
+    struct Deployment {
+        kind: String,
+        #[facet(yaml(path = "metadata.name"))]
+        name: String,
+        #[facet(default, yaml(path = "metadata.labels.team"))]
+        team: String,
+        #[facet(yaml(path = "spec.containers.0.image"))]
+        image: String,
+    }
+
+    assert_eq!(
+        facet_yaml::template::<Deployment>().unwrap(),
+        "\
+kind: # <REQUIRED: String>
+metadata:
+  name: # <REQUIRED: String>
+  # labels:
+  #   # team: <String>
+spec:
+  containers:
+    - image: # <REQUIRED: String>
+"
+    );
+}
+
+#[test]
+fn test_template_recursive_type() {
+    #[derive(Facet)]
+    struct Node {
+        name: String,
+        children: Vec<Node>,
+    }
+
+    // The nested nodes aren't expanded again
+    assert_eq!(
+        facet_yaml::template::<Node>().unwrap(),
+        "name: # <REQUIRED: String>\nchildren: # <REQUIRED: Vec<Node>>\n"
+    );
+}
+
+#[test]
+fn test_template_of_list_and_scalar() {
+    assert_eq!(
+        facet_yaml::template::<Vec<Tls>>().unwrap(),
+        "- cert: # <REQUIRED: String>\n  # key: <String>\n"
+    );
+    assert_eq!(
+        facet_yaml::template::<u16>().unwrap(),
+        "# <REQUIRED: u16>\n"
+    );
+}
+
+#[test]
+fn test_template_invalid_attribute() {
+    #[derive(Facet)]
+    struct Config {
+        #[facet(yaml(unknown))]
+        name: String,
+    }
+
+    let err = facet_yaml::template::<Config>().unwrap_err();
+    assert_eq!(err.code(), ErrorCode::InvalidAttribute);
+}
@@ -0,0 +1,56 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::ErrorCode;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Point(f64, f64);
+
+#[derive(Debug, Facet, PartialEq)]
+struct Meters(f64);
+
+#[derive(Debug, Facet, PartialEq)]
+struct Shape {
+    origin: Point,
+    size: Meters,
+    corners: Vec<(i32, i32)>,
+}
+
+#[test]
+fn test_tuple_struct_from_sequence() {
+    let point: Point = facet_yaml::from_str("[1.0, 2.5]").unwrap();
+    assert_eq!(point, Point(1.0, 2.5));
+
+    let point: Point = facet_yaml::from_str("- 3\n- -4\n").unwrap();
+    assert_eq!(point, Point(3.0, -4.0));
+}
+
+#[test]
+fn test_newtype_from_scalar() {
+    let shape: Shape =
+        facet_yaml::from_str("origin: [0, 0]\nsize: 3.5\ncorners: [[0, 0], [1, 1]]\n").unwrap();
+    assert_eq!(
+        shape,
+        Shape {
+            origin: Point(0.0, 0.0),
+            size: Meters(3.5),
+            corners: vec![(0, 0), (1, 1)],
+        }
+    );
+}
+
+#[test]
+fn test_tuple_struct_errors() {
+    let err = facet_yaml::from_str::<Point>("[1.0]").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+    assert_eq!(err.message(), "Expected 2 items for Point, got 1");
+
+    let err = facet_yaml::from_str::<Point>("1.0").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+
+    let err = facet_yaml::from_str::<Shape>("origin: [0, x]\nsize: 1\ncorners: []\n").unwrap_err();
+    assert_eq!(err.path(), "origin[1]");
+
+    let errors =
+        facet_yaml::validate::<Shape>("origin: [0]\nsize: big\ncorners: []\n").unwrap_err();
+    assert_eq!(errors.len(), 2);
+}
@@ -0,0 +1,80 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::ErrorCode;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Route {
+    waypoints: Vec<(f64, f64)>,
+    limit: (String, u16, bool),
+    #[facet(default)]
+    headers: Vec<(String, Option<String>)>,
+}
+
+#[test]
+fn test_tuples_from_sequences() {
+    let pair: (String, u16) = facet_yaml::from_str("[web, 8080]").unwrap();
+    assert_eq!(pair, ("web".into(), 8080));
+
+    let triple: (u8, String, bool) = facet_yaml::from_str("- 1\n- one\n- true\n").unwrap();
+    assert_eq!(triple, (1, "one".into(), true));
+
+    let single: (i32,) = facet_yaml::from_str("[-3]").unwrap();
+    assert_eq!(single, (-3,));
+}
+
+#[test]
+fn test_tuples_in_structs_and_maps() {
+    let yaml = "
+waypoints: [[52.5, 13.4], [48.1, 11.6]]
+limit: [requests, 100, true]
+headers:
+  - [accept, application/yaml]
+  - [x-debug, ~]
+";
+    let route: Route = facet_yaml::from_str(yaml).unwrap();
+    assert_eq!(
+        route,
+        Route {
+            waypoints: vec![(52.5, 13.4), (48.1, 11.6)],
+            limit: ("requests".into(), 100, true),
+            headers: vec![
+                ("accept".into(), Some("application/yaml".into())),
+                ("x-debug".into(), None),
+            ],
+        }
+    );
+
+    let ranges: BTreeMap<String, (u16, u16)> =
+        facet_yaml::from_str("http: [80, 80]\nephemeral: [49152, 65535]").unwrap();
+    assert_eq!(ranges["ephemeral"], (49152, 65535));
+}
+
+#[test]
+fn test_nested_tuples() {
+    let nested: ((u8, u8), Vec<(String, u8)>) =
+        facet_yaml::from_str("[[1, 2], [[a, 1], [b, 2]]]").unwrap();
+    assert_eq!(nested, ((1, 2), vec![("a".into(), 1), ("b".into(), 2)]));
+}
+
+#[test]
+fn test_tuple_errors() {
+    let err = facet_yaml::from_str::<(String, u16, bool)>("[web, 80]").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+    assert!(err.message().starts_with("Expected 3 items for "));
+
+    let err = facet_yaml::from_str::<(String, u16)>("web").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+
+    // Mappings are read by the field names of the tuple, `0` and `1`
+    let err = facet_yaml::from_str::<(String, u16)>("{web: 80, api: 81}").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::UnknownField);
+
+    let err = facet_yaml::from_str::<Route>("waypoints: [[1, 2], [3, north]]\nlimit: [a, 1, true]")
+        .unwrap_err();
+    assert_eq!(err.path(), "waypoints[1][1]");
+
+    let errors =
+        facet_yaml::validate::<Route>("waypoints: [[1]]\nlimit: [a, many, true]").unwrap_err();
+    assert_eq!(errors.len(), 2);
+}
@@ -0,0 +1,70 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Marker;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Config {
+    marker: Marker,
+    nothing: (),
+    features: BTreeMap<String, Marker>,
+}
+
+#[test]
+fn test_unit_values_from_null() {
+    let expected = Config {
+        marker: Marker,
+        nothing: (),
+        features: BTreeMap::from([("fast_path".into(), Marker), ("telemetry".into(), Marker)]),
+    };
+
+    let config: Config =
+        facet_yaml::from_str("marker: ~\nnothing: null\nfeatures:\n  fast_path:\n  telemetry: ~\n")
+            .unwrap();
+    assert_eq!(config, expected);
+    assert!(
+        facet_yaml::validate::<Config>("marker:\nnothing: ~\nfeatures: {fast_path: ~}\n").is_ok()
+    );
+
+    #[cfg(feature = "serialize")]
+    {
+        let yaml = facet_yaml::to_string(&expected).unwrap();
+        assert_eq!(
+            yaml,
+            "---\nmarker: ~\nnothing: ~\nfeatures:\n  fast_path: ~\n  telemetry: ~"
+        );
+        assert_eq!(facet_yaml::from_str::<Config>(&yaml).unwrap(), expected);
+    }
+
+    facet_yaml::from_str::<Marker>("~").unwrap();
+    facet_yaml::from_str::<()>("").unwrap();
+}
+
+#[test]
+fn test_unit_structs_in_tagged_unions() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    enum Backend {
+        Disabled(Marker),
+        Memory { size: u32 },
+    }
+
+    let backends: Vec<Backend> =
+        facet_yaml::from_str("- Disabled: ~\n- Memory:\n    size: 64\n").unwrap();
+    assert_eq!(
+        backends,
+        [Backend::Disabled(Marker), Backend::Memory { size: 64 }]
+    );
+}
+
+#[test]
+fn test_unit_structs_reject_other_values() {
+    let err = facet_yaml::from_str::<Marker>("marker: 1").unwrap_err();
+    assert_eq!(err.code(), facet_yaml::ErrorCode::UnknownField);
+
+    let err = facet_yaml::from_str::<Config>("marker: 1\nnothing: ~\nfeatures: {}\n").unwrap_err();
+    assert_eq!(err.code(), facet_yaml::ErrorCode::TypeMismatch);
+    assert_eq!(err.path(), "marker");
+}
@@ -0,0 +1,46 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::{DeserializeOptions, ErrorCode};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Debug, Facet, PartialEq)]
+struct Enabled {}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Config {
+    features: BTreeMap<String, Enabled>,
+}
+
+fn options() -> DeserializeOptions {
+    DeserializeOptions::new().unit_from_null(true)
+}
+
+#[test]
+fn test_structs_without_fields_from_null() {
+    let config: Config = facet_yaml::from_str_with_options(
+        "features: {fast_path: ~, telemetry: null}",
+        &options(),
+    )
+    .unwrap();
+    assert_eq!(
+        config.features.keys().collect::<Vec<_>>(),
+        ["fast_path", "telemetry"]
+    );
+}
+
+#[test]
+fn test_sets_are_unsupported() {
+    // facet-reflect can't build sets yet, whether they're written as sequences or mappings
+    let err = facet_yaml::from_str_with_options::<BTreeSet<String>>(
+        "fast_path: ~\ntelemetry: ~\n",
+        &options(),
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Unsupported);
+}
+
+#[test]
+fn test_unit_from_null_is_off_by_default() {
+    let err = facet_yaml::from_str::<Config>("features: {fast_path: ~}").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+}
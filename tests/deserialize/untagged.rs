@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::ErrorCode;
+
+#[derive(Debug, Facet, PartialEq)]
+#[facet(untagged)]
+#[repr(u8)]
+enum StringOrList {
+    String(String),
+    List(Vec<String>),
+}
+
+#[derive(Debug, Facet, PartialEq)]
+#[facet(untagged)]
+#[repr(u8)]
+enum IntOrMap {
+    Int(u32),
+    Map(HashMap<String, u32>),
+}
+
+#[derive(Debug, Facet, PartialEq)]
+#[facet(untagged)]
+#[repr(u8)]
+enum Limit {
+    Unlimited,
+    Bytes(u64),
+    Range { min: u64, max: u64 },
+    Pair(u64, u64),
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Job {
+    command: StringOrList,
+    workers: IntOrMap,
+    memory: Limit,
+}
+
+#[test]
+fn test_untagged_variants() {
+    let job: Job =
+        facet_yaml::from_str("command: make test\nworkers: 4\nmemory: Unlimited").unwrap();
+    assert_eq!(
+        job,
+        Job {
+            command: StringOrList::String("make test".to_string()),
+            workers: IntOrMap::Int(4),
+            memory: Limit::Unlimited,
+        }
+    );
+
+    let job: Job = facet_yaml::from_str(
+        "command: [make, test]\nworkers: {linux: 4, macos: 2}\nmemory: {min: 1, max: 8}",
+    )
+    .unwrap();
+    assert_eq!(
+        job.command,
+        StringOrList::List(vec!["make".to_string(), "test".to_string()])
+    );
+    assert_eq!(
+        job.workers,
+        IntOrMap::Map(HashMap::from([
+            ("linux".to_string(), 4),
+            ("macos".to_string(), 2)
+        ]))
+    );
+    assert_eq!(job.memory, Limit::Range { min: 1, max: 8 });
+}
+
+#[test]
+fn test_first_matching_variant() {
+    // Variants are tried in declaration order, so null is the unit variant. A document that is
+    // only null is empty, so the null is an item here
+    let limits: Vec<Limit> = facet_yaml::from_str("[~]").unwrap();
+    assert_eq!(limits, [Limit::Unlimited]);
+    let limit: Limit = facet_yaml::from_str("512").unwrap();
+    assert_eq!(limit, Limit::Bytes(512));
+    let limit: Limit = facet_yaml::from_str("[1, 2]").unwrap();
+    assert_eq!(limit, Limit::Pair(1, 2));
+
+    // A variant only matches when all of its fields do
+    let err = facet_yaml::from_str::<Limit>("{min: 1}").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+    assert_eq!(
+        err.message(),
+        "The YAML hash/map doesn't match any variant of Limit"
+    );
+}
+
+#[test]
+fn test_no_matching_variant() {
+    let err = facet_yaml::from_str::<Job>("command: make\nworkers: many\nmemory: 1").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::TypeMismatch);
+    assert_eq!(err.path(), "workers");
+}
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use facet::Facet;
+use facet_testhelpers::test;
+
+#[derive(Debug, Facet)]
+#[allow(dead_code)]
+struct Server {
+    host: String,
+    port: u16,
+    #[facet(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Facet)]
+#[allow(dead_code)]
+struct Config {
+    name: String,
+    servers: Vec<Server>,
+    limits: HashMap<String, i8>,
+    timeout: Option<f32>,
+}
+
+#[test]
+fn test_validate_valid() {
+    let yaml = "
+name: prod
+servers:
+  - host: a
+    port: 80
+  - {host: b, port: 443, tags: [tls]}
+limits: {cpu: 4, memory: -8}
+timeout: ~
+";
+    facet_yaml::validate::<Config>(yaml).unwrap();
+}
+
+#[test]
+fn test_validate_reports_every_error() {
+    let yaml = "
+name: [prod]
+servers:
+  - host: a
+    port: 70000
+  - host: b
+    port: 443
+    weight: 3
+limits: {cpu: 200}
+timeout: soon
+";
+    let errors = facet_yaml::validate::<Config>(yaml).unwrap_err();
+    let found: Vec<(&str, &str)> = errors.iter().map(|e| (e.path(), e.message())).collect();
+    assert_eq!(
+        found,
+        vec![
+            ("name", "Expected string, got: array"),
+            ("servers[0].port", "Value 70000 out of range for u16"),
+            ("servers[1]", "Field 'weight' not found"),
+            ("limits.cpu", "Value 200 out of range for i8"),
+            ("timeout", "Failed to parse `soon` as f32"),
+        ]
+    );
+    assert_eq!(errors[1].span().map(|span| span.line), Some(5));
+}
+
+#[test]
+fn test_validate_missing_field() {
+    let errors = facet_yaml::validate::<Server>("host: a").unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].message(), "Missing field 'port'");
+}
+
+#[test]
+fn test_validate_empty_document() {
+    facet_yaml::validate::<Option<Server>>("# nothing here").unwrap();
+
+    let errors = facet_yaml::validate::<Server>("").unwrap_err();
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn test_validate_syntax_error() {
+    let errors = facet_yaml::validate::<Server>("host: [a").unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].span().is_some());
+}
+
+#[test]
+fn test_validate_matches_from_str() {
+    for yaml in [
+        "host: a\nport: 80",
+        "host: a\nport: -1",
+        "host: 1\nport: 80",
+        "host: a\nport: '80'",
+        "host: a\nport: 80\nextra: 1",
+    ] {
+        assert_eq!(
+            facet_yaml::validate::<Server>(yaml).is_ok(),
+            facet_yaml::from_str::<Server>(yaml).is_ok(),
+            "{yaml}"
+        );
+    }
+}
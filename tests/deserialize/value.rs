@@ -0,0 +1,176 @@
+use facet_testhelpers::test;
+use facet_yaml::{Map, Number, Value};
+
+fn map(yaml: &str) -> Map {
+    match facet_yaml::from_str::<Value>(yaml).unwrap() {
+        Value::Map(map) => map,
+        value => panic!("expected a map, got {value:?}"),
+    }
+}
+
+fn string_keys(map: &Map) -> Vec<&str> {
+    map.keys().filter_map(Value::as_str).collect()
+}
+
+#[test]
+fn test_map_keeps_document_order() {
+    let map = map("zeta: 1\nalpha: 2\nmid: {b: 1, a: 2}\nbeta: 3");
+    assert_eq!(string_keys(&map), ["zeta", "alpha", "mid", "beta"]);
+    assert_eq!(
+        string_keys(map.get("mid").and_then(Value::as_map).unwrap()),
+        ["b", "a"]
+    );
+}
+
+#[test]
+fn test_map_get_index() {
+    let map = map("b: 1\na: 2");
+    assert_eq!(
+        map.get_index(1),
+        Some((&Value::String("a".into()), &Value::Number(2_i64.into())))
+    );
+    assert_eq!(map.get_index(2), None);
+    assert_eq!(map.index_of("b"), Some(0));
+    assert_eq!(map.index_of("c"), None);
+}
+
+#[test]
+fn test_map_insert_and_remove_keep_order() {
+    let mut map = map("b: 1\na: 2\nc: 3");
+    assert_eq!(
+        map.insert(Value::String("a".into()), Value::Number(20_i64.into())),
+        Some(Value::Number(2_i64.into()))
+    );
+    map.insert(Value::String("d".into()), Value::Number(4_i64.into()));
+    assert_eq!(string_keys(&map), ["b", "a", "c", "d"]);
+
+    assert_eq!(map.remove("a"), Some(Value::Number(20_i64.into())));
+    assert_eq!(map.remove("a"), None);
+    assert_eq!(string_keys(&map), ["b", "c", "d"]);
+
+    *map.get_mut("c").unwrap() = Value::Null;
+    assert!(map.get("c").unwrap().is_null());
+}
+
+#[test]
+fn test_map_sort_keys() {
+    let mut map = map("b: 1\n10: x\ntrue: y\na: 2\n~: z\n2.5: w\n? [1]\n: v");
+    map.sort_keys();
+    assert_eq!(
+        map.keys().cloned().collect::<Vec<_>>(),
+        vec![
+            Value::Null,
+            Value::Bool(true),
+            Value::Number(2.5_f64.into()),
+            Value::Number(10_i64.into()),
+            Value::String("a".into()),
+            Value::String("b".into()),
+            Value::Sequence(vec![Value::Number(1_i64.into())]),
+        ]
+    );
+}
+
+#[test]
+fn test_map_sort_keys_by() {
+    let mut map = map("a: 1\nccc: 2\nbb: 3");
+    map.sort_keys_by(|a, b| {
+        let len = |value: &Value| value.as_str().map_or(0, str::len);
+        len(b).cmp(&len(a))
+    });
+    assert_eq!(string_keys(&map), ["ccc", "bb", "a"]);
+}
+
+#[test]
+fn test_number_keeps_integers_and_floats_apart() {
+    let value: Value = facet_yaml::from_str("[42, -7, 42.0, 0x1F, 1e3]").unwrap();
+    let numbers: Vec<&Number> = value
+        .as_sequence()
+        .unwrap()
+        .iter()
+        .filter_map(Value::as_number)
+        .collect();
+
+    assert!(numbers[0].is_i64() && numbers[0].is_u64() && !numbers[0].is_f64());
+    assert_eq!(numbers[1].as_i64(), Some(-7));
+    assert_eq!(numbers[1].as_u64(), None);
+    assert!(numbers[2].is_f64());
+    assert_eq!(numbers[2].as_i64(), None);
+    assert_eq!(numbers[2].as_f64(), Some(42.0));
+    assert_ne!(numbers[0], numbers[2]);
+    assert_eq!(numbers[3].as_i64(), Some(31));
+    assert_eq!(numbers[3].raw(), "31");
+    assert_eq!(numbers[4].as_f64(), Some(1000.0));
+    assert_eq!(numbers[4].raw(), "1e3");
+}
+
+#[test]
+fn test_number_larger_than_i64() {
+    let value: Value = facet_yaml::from_str("18446744073709551615").unwrap();
+    assert_eq!(value.as_i64(), None);
+    assert_eq!(value.as_u64(), Some(u64::MAX));
+    assert_eq!(value, Value::Number(u64::MAX.into()));
+
+    let value: Value = facet_yaml::from_str("9223372036854775807").unwrap();
+    assert_eq!(value.as_i64(), Some(i64::MAX));
+}
+
+#[cfg(feature = "serialize")]
+#[test]
+fn test_number_raw_text_is_written_back() {
+    let value: Value = facet_yaml::from_str("ratio: 2.50\nmax: 18446744073709551615").unwrap();
+    assert_eq!(
+        value.get("ratio").and_then(Value::as_number),
+        Some(&Number::from(2.5))
+    );
+
+    let options = facet_yaml::SerializeOptions::new().document_start(false);
+    assert_eq!(
+        facet_yaml::to_string_with_options(&value, &options).unwrap(),
+        "ratio: 2.50\nmax: 18446744073709551615"
+    );
+}
+
+#[test]
+fn test_map_sort_keys_compares_numbers_by_value() {
+    let mut map = map("2.5: a\n18446744073709551615: b\n-1: c\n2: d");
+    map.sort_keys();
+    let keys: Vec<&str> = map
+        .keys()
+        .filter_map(Value::as_number)
+        .map(Number::raw)
+        .collect();
+    assert_eq!(keys, ["-1", "2", "2.5", "18446744073709551615"]);
+}
+
+#[test]
+fn test_from_value_ref() {
+    use facet::Facet;
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Deployment {
+        kind: String,
+        replicas: u32,
+        labels: Vec<String>,
+    }
+
+    let value: Value =
+        facet_yaml::from_str("kind: Deployment\nreplicas: 3\nlabels: [web, prod]").unwrap();
+    let deployment: Deployment = facet_yaml::from_value_ref(&value).unwrap();
+    assert_eq!(deployment.replicas, 3);
+    assert_eq!(deployment.labels, ["web", "prod"]);
+
+    // The value is left as it was
+    assert_eq!(
+        value.get("kind").and_then(Value::as_str),
+        Some(deployment.kind.as_str())
+    );
+
+    let value: Value =
+        facet_yaml::from_str("kind: Deployment\nreplicas: lots\nlabels: []").unwrap();
+    let err = facet_yaml::from_value_ref::<Deployment>(&value).unwrap_err();
+    assert_eq!(err.path(), "replicas");
+    assert_eq!(err.span(), None);
+
+    let nothing: Option<Deployment> = facet_yaml::from_value_ref(&Value::Null).unwrap();
+    assert_eq!(nothing, None);
+}
@@ -0,0 +1,108 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::{DeserializeOptions, YamlDeserializer};
+
+#[derive(Debug, Facet, PartialEq)]
+struct Server {
+    host: String,
+    #[facet(rename = "listenPort")]
+    port: u16,
+    #[facet(default, yaml(alias = "worker_count", alias = "threads"))]
+    workers: u32,
+    #[facet(default, yaml(path = "tls.enabled"))]
+    tls: bool,
+}
+
+#[test]
+fn test_reuse_across_documents() {
+    let deserializer = YamlDeserializer::new(DeserializeOptions::default());
+
+    for (yaml, expected) in [
+        (
+            "host: a\nlistenPort: 80\nworkers: 2",
+            Server {
+                host: "a".into(),
+                port: 80,
+                workers: 2,
+                tls: false,
+            },
+        ),
+        (
+            "listenPort: 443\nhost: b\nworker_count: 4\ntls: {enabled: true}",
+            Server {
+                host: "b".into(),
+                port: 443,
+                workers: 4,
+                tls: true,
+            },
+        ),
+        (
+            "host: c\nthreads: 8\nlistenPort: 8080",
+            Server {
+                host: "c".into(),
+                port: 8080,
+                workers: 8,
+                tls: false,
+            },
+        ),
+    ] {
+        assert_eq!(deserializer.from_str::<Server>(yaml).unwrap(), expected);
+    }
+}
+
+#[test]
+fn test_reuse_after_errors() {
+    let deserializer = YamlDeserializer::default();
+
+    // Unknown keys are remembered as such, and still rejected the next time
+    for _ in 0..2 {
+        let err = deserializer
+            .from_str::<Server>("host: a\nlistenPort: 80\nport: 80")
+            .unwrap_err();
+        assert_eq!(err.message(), "Field 'port' not found");
+    }
+    let server: Server = deserializer.from_str("host: a\nlistenPort: 80").unwrap();
+    assert_eq!(server.port, 80);
+}
+
+#[test]
+fn test_reuse_with_options() {
+    let deserializer = YamlDeserializer::new(DeserializeOptions::new().lenient(false));
+    assert!(
+        deserializer
+            .from_str::<Server>("host: a\nlistenPort: ' 80'")
+            .is_err()
+    );
+}
+
+#[test]
+fn test_alias_with_from_str() {
+    let server: Server = facet_yaml::from_str("host: a\nlistenPort: 80\nthreads: 3").unwrap();
+    assert_eq!(server.workers, 3);
+}
+
+#[test]
+fn test_reuse_buffers_of_earlier_documents() {
+    let deserializer = YamlDeserializer::default();
+
+    // Each document is loaded into the sequences and mappings of the ones before it
+    for _ in 0..3 {
+        let servers: Vec<Server> = deserializer
+            .from_str(
+                "- {host: a, listenPort: 80, tls: {enabled: true}}\n- {host: b, listenPort: 81}",
+            )
+            .unwrap();
+        assert_eq!(servers.len(), 2);
+        assert!(servers[0].tls);
+        assert!(!servers[1].tls);
+
+        let ports: Vec<Vec<u16>> = deserializer.from_str("[[1, 2, 3], [], [4]]").unwrap();
+        assert_eq!(ports, [vec![1, 2, 3], vec![], vec![4]]);
+
+        let server: Server = deserializer.from_str("host: c\nlistenPort: 8080").unwrap();
+        assert_eq!(server.workers, 0);
+
+        let err = deserializer.from_str::<Vec<u16>>("[1, [2]]").unwrap_err();
+        assert_eq!(err.path(), "[1]");
+    }
+}
@@ -0,0 +1,90 @@
+use facet::Facet;
+use facet_testhelpers::test;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Deployment {
+    kind: String,
+    #[facet(yaml(path = "metadata.name"))]
+    name: String,
+    #[facet(yaml(path = "metadata.annotations.team"))]
+    team: String,
+    #[facet(yaml(path = "spec.replicas"))]
+    replicas: u32,
+    #[facet(yaml(path = "spec.containers.0.image"))]
+    image: String,
+}
+
+const MANIFEST: &str = r#"
+kind: Deployment
+metadata:
+  name: web
+  labels:
+    app: web
+  annotations:
+    team: platform
+spec:
+  replicas: 3
+  containers:
+    - name: web
+      image: nginx:1.27
+    - name: sidecar
+      image: envoy:1.30
+"#;
+
+#[test]
+fn test_deserialize_nested_paths() {
+    let deployment: Deployment = facet_yaml::from_str(MANIFEST).unwrap();
+    assert_eq!(
+        deployment,
+        Deployment {
+            kind: "Deployment".to_string(),
+            name: "web".to_string(),
+            team: "platform".to_string(),
+            replicas: 3,
+            image: "nginx:1.27".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_deserialize_missing_path_uses_default() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Summary {
+        #[facet(yaml(path = "metadata.name"))]
+        name: String,
+        #[facet(default, yaml(path = "metadata.annotations.owner"))]
+        owner: String,
+    }
+
+    let summary: Summary =
+        facet_yaml::from_str("metadata:\n  name: web\n  annotations: {}\n").unwrap();
+    assert_eq!(summary.name, "web");
+    assert_eq!(summary.owner, "");
+}
+
+#[test]
+fn test_deserialize_path_error_location() {
+    #[derive(Debug, Facet)]
+    #[allow(dead_code)]
+    struct Replicas {
+        #[facet(yaml(path = "spec.replicas"))]
+        replicas: u8,
+    }
+
+    let err = facet_yaml::from_str::<Replicas>("spec:\n  replicas: 300\n").unwrap_err();
+    assert_eq!(err.path(), "spec.replicas");
+    assert_eq!(err.span().unwrap().line, 2);
+}
+
+#[test]
+fn test_deserialize_unknown_key_next_to_path() {
+    #[derive(Debug, Facet)]
+    #[allow(dead_code)]
+    struct Name {
+        #[facet(yaml(path = "metadata.name"))]
+        name: String,
+    }
+
+    let err = facet_yaml::from_str::<Name>("metadata:\n  name: web\nspec: {}\n").unwrap_err();
+    assert_eq!(err.message(), "Field 'spec' not found");
+}
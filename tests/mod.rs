@@ -7,3 +7,14 @@ mod deserialize;
 // We deserialize the serialized data as well so we need both feature flags
 #[cfg(all(feature = "alloc", feature = "serialize", feature = "deserialize"))]
 mod serialize;
+#[cfg(feature = "serde-bridge")]
+mod serde_bridge;
+// Watching needs a file system
+#[cfg(feature = "notify")]
+mod watch;
+// Takes a while, with many generated cases
+#[cfg(feature = "round-trip-fuzz")]
+mod round_trip_fuzz;
+// Needs a clone of the suite, which isn't part of the repository
+#[cfg(feature = "yaml-test-suite")]
+mod yaml_test_suite;
@@ -0,0 +1,253 @@
+//! Round trips of generated strings through the serializer and the deserializer, to find the
+//! ones that the emitter doesn't quote or escape well enough to be read back as they were.
+//!
+//! Strings are made of fragments that mean something to YAML, such as `- `, `? `, `: `, `#`,
+//! tabs, line breaks and Unicode separators, and are written as values, items and keys with
+//! several combinations of options. Every one must be read back to the same value.
+//!
+//! ```text
+//! cargo test --features round-trip-fuzz round_trip_fuzz -- --nocapture
+//! ```
+//!
+//! The cases are generated from a seed, so a failure can be reproduced. The
+//! `ROUND_TRIP_FUZZ_SEED` and `ROUND_TRIP_FUZZ_CASES` environment variables change the seed and
+//! the number of cases, which are printed before running them.
+
+use std::{collections::BTreeMap, env};
+
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::{MultilineStrings, QuoteStyle, SerializeOptions};
+
+/// Fragments of strings, which are the ones the emitter has to be careful with.
+const FRAGMENTS: &[&str] = &[
+    "- ",
+    "-",
+    "? ",
+    "?",
+    ": ",
+    ":",
+    " #",
+    "#",
+    "# ",
+    "&",
+    "*",
+    "!",
+    "|",
+    ">",
+    "%",
+    "@",
+    "`",
+    "'",
+    "\"",
+    "\\",
+    ",",
+    "[",
+    "]",
+    "{",
+    "}",
+    "---",
+    "...",
+    "\t",
+    "\n",
+    "\r\n",
+    "\r",
+    " ",
+    "  ",
+    "\u{85}",
+    "\u{2028}",
+    "\u{2029}",
+    "\u{a0}",
+    "\u{feff}",
+    "\u{7}",
+    "\u{1b}",
+    "\0",
+    "~",
+    "null",
+    "true",
+    "no",
+    "0x1F",
+    "1e3",
+    ".inf",
+    "-.5",
+    "012",
+    "1_000",
+    "2024-01-01",
+    "<<",
+    "=",
+    "web",
+    "a b",
+    "é",
+    "日本",
+    "🦀",
+];
+
+#[derive(Debug, Facet, PartialEq)]
+struct Record {
+    value: String,
+    items: Vec<String>,
+    labels: BTreeMap<String, String>,
+    nested: Vec<BTreeMap<String, Vec<String>>>,
+}
+
+/// Generates pseudo-random numbers with xorshift, which is plenty for picking fragments.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    /// A string of up to six fragments.
+    fn string(&mut self) -> String {
+        (0..self.below(7))
+            .map(|_| FRAGMENTS[self.below(FRAGMENTS.len())])
+            .collect()
+    }
+
+    fn record(&mut self) -> Record {
+        Record {
+            value: self.string(),
+            items: (0..self.below(3)).map(|_| self.string()).collect(),
+            labels: (0..self.below(3))
+                .map(|_| (self.string(), self.string()))
+                .collect(),
+            nested: vec![
+                [(self.string(), vec![self.string()])].into(),
+                BTreeMap::new(),
+            ],
+        }
+    }
+}
+
+/// The combinations of options that strings are written with.
+fn option_sets() -> Vec<(&'static str, SerializeOptions)> {
+    vec![
+        ("default", SerializeOptions::new()),
+        ("flow", SerializeOptions::new().flow_depth(0)),
+        (
+            "single quotes",
+            SerializeOptions::new().quote_style(QuoteStyle::Single),
+        ),
+        (
+            "double quotes",
+            SerializeOptions::new().quote_style(QuoteStyle::Double),
+        ),
+        (
+            "folded",
+            SerializeOptions::new()
+                .multiline_strings(MultilineStrings::Folded)
+                .line_width(20),
+        ),
+        (
+            "quoted multiline",
+            SerializeOptions::new().multiline_strings(MultilineStrings::Quoted),
+        ),
+    ]
+}
+
+/// Write a record and read it back, describing what went wrong if it isn't the same.
+fn round_trip(record: &Record, options: &SerializeOptions) -> Result<(), String> {
+    let yaml = facet_yaml::to_string_with_options(record, options)
+        .map_err(|err| format!("serializing failed: {err}"))?;
+    match facet_yaml::from_str::<Record>(&yaml) {
+        Ok(read) if read == *record => Ok(()),
+        Ok(read) => Err(format!("read back as {read:?} from:\n{yaml}")),
+        Err(err) => Err(format!("deserializing failed: {err}\n{yaml}")),
+    }
+}
+
+/// Make a failing string smaller, removing characters while the round trip still fails, so
+/// the report shows what the failure is about.
+fn shrink(value: String, fails: impl Fn(&str) -> bool) -> String {
+    let mut chars: Vec<char> = value.chars().collect();
+    let mut index = 0;
+    while index < chars.len() {
+        let mut smaller = chars.clone();
+        smaller.remove(index);
+        if fails(&smaller.iter().collect::<String>()) {
+            chars = smaller;
+        } else {
+            index += 1;
+        }
+    }
+    chars.into_iter().collect()
+}
+
+/// A record with a single string in each of its places.
+fn record_of(value: &str) -> Record {
+    Record {
+        value: value.into(),
+        items: vec![value.into()],
+        labels: [(value.into(), value.into())].into(),
+        nested: vec![[(value.into(), vec![value.into()])].into()],
+    }
+}
+
+#[test]
+fn test_round_trip_fuzz() {
+    let seed = env::var("ROUND_TRIP_FUZZ_SEED")
+        .ok()
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(0x5eed_f00d_u64);
+    let cases = env::var("ROUND_TRIP_FUZZ_CASES")
+        .ok()
+        .and_then(|cases| cases.parse().ok())
+        .unwrap_or(5_000);
+    println!("round-trip-fuzz: {cases} cases with seed {seed}");
+
+    // Xorshift never leaves zero
+    let mut rng = Rng(seed.max(1));
+    let options = option_sets();
+    let mut failures = Vec::new();
+    for case in 0..cases {
+        let record = rng.record();
+        for (name, options) in &options {
+            if let Err(reason) = round_trip(&record, options) {
+                failures.push((case, *name, record, reason));
+                break;
+            }
+        }
+        if failures.len() >= 10 {
+            break;
+        }
+    }
+
+    // Each string is tried on its own, since one of them is usually enough to fail
+    let mut report = String::new();
+    for (case, name, record, reason) in &failures {
+        let (_, options) = options.iter().find(|(n, _)| n == name).unwrap();
+        let fails = |value: &str| round_trip(&record_of(value), options).is_err();
+        let strings = [&record.value]
+            .into_iter()
+            .chain(&record.items)
+            .chain(record.labels.iter().flat_map(|(k, v)| [k, v]))
+            .chain(
+                record
+                    .nested
+                    .iter()
+                    .flat_map(|map| map.iter().flat_map(|(k, v)| [k].into_iter().chain(v))),
+            );
+        let smallest = strings
+            .filter(|value| fails(value))
+            .map(|value| shrink(value.clone(), fails))
+            .min_by_key(|value| value.len());
+        report += &format!("case {case}, {name} options: ");
+        match smallest {
+            Some(value) => report += &format!("{value:?} fails to round trip\n"),
+            None => report += &format!("{reason}\n"),
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} failing cases with seed {seed}:\n{report}",
+        failures.len()
+    );
+}
@@ -0,0 +1,127 @@
+use facet::Facet;
+use facet_testhelpers::test;
+use facet_yaml::{Value, serde_bridge::Serde};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Facet, PartialEq)]
+#[repr(u8)]
+enum Backend {
+    Memory,
+    Redis { url: String },
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Cache {
+    #[facet(yaml(alias = "size"))]
+    capacity: u32,
+    #[facet(default)]
+    ttl: Option<f64>,
+    backend: Backend,
+    key: Vec<u8>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct Service {
+    name: String,
+    #[serde(with = "facet_yaml::serde_bridge")]
+    cache: Cache,
+}
+
+#[test]
+fn test_facet_field_of_serde_type() {
+    let json = r#"{"name":"api","cache":{"size":64,"backend":{"Redis":{"url":"redis://cache"}},"key":[1,2]}}"#;
+    let service: Service = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        service.cache,
+        Cache {
+            capacity: 64,
+            ttl: None,
+            backend: Backend::Redis {
+                url: "redis://cache".to_string()
+            },
+            key: vec![1, 2],
+        }
+    );
+
+    assert_eq!(
+        serde_json::to_string(&service).unwrap(),
+        r#"{"name":"api","cache":{"capacity":64,"backend":{"Redis":{"url":"redis://cache"}},"key":[1,2]}}"#
+    );
+}
+
+#[test]
+fn test_wrapper_round_trip() {
+    let cache = Serde(Cache {
+        capacity: 8,
+        ttl: Some(1.5),
+        backend: Backend::Memory,
+        key: Vec::new(),
+    });
+    let json = serde_json::to_string(&cache).unwrap();
+    assert_eq!(
+        json,
+        r#"{"capacity":8,"ttl":1.5,"backend":"Memory","key":[]}"#
+    );
+
+    let back: Serde<Cache> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, cache);
+}
+
+#[test]
+fn test_errors_are_reported_to_serde() {
+    let err =
+        serde_json::from_str::<Serde<Cache>>(r#"{"capacity":-1,"backend":"Memory","key":[]}"#)
+            .unwrap_err();
+    assert!(err.to_string().contains("capacity"), "{err}");
+
+    let err = serde_json::from_str::<Serde<Cache>>(r#"{"capacity":1,"backend":"Disk","key":[]}"#)
+        .unwrap_err();
+    assert!(
+        err.to_string().starts_with("Unknown variant 'Disk'"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_value_with_serde() {
+    let value: Value = serde_json::from_str(r#"{"b":[1,2.5,null,true],"a":"x"}"#).unwrap();
+    let keys: Vec<_> = value
+        .as_map()
+        .unwrap()
+        .keys()
+        .filter_map(Value::as_str)
+        .collect();
+    assert_eq!(keys, ["b", "a"]);
+    assert_eq!(
+        value
+            .get("b")
+            .and_then(Value::as_sequence)
+            .map(<[Value]>::len),
+        Some(4)
+    );
+
+    assert_eq!(
+        serde_json::to_string(&value).unwrap(),
+        r#"{"b":[1,2.5,null,true],"a":"x"}"#
+    );
+}
+
+#[test]
+fn test_value_field_is_written_as_its_content() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Plugin {
+        name: String,
+        settings: Value,
+    }
+
+    let plugin: Plugin =
+        facet_yaml::from_str("name: lint\nsettings: {level: 2, rules: [a]}").unwrap();
+    assert_eq!(
+        facet_yaml::to_string(&plugin).unwrap(),
+        "---\nname: lint\nsettings:\n  level: 2\n  rules:\n    - a"
+    );
+    assert_eq!(
+        serde_json::to_string(&Serde(plugin)).unwrap(),
+        r#"{"name":"lint","settings":{"level":2,"rules":["a"]}}"#
+    );
+}
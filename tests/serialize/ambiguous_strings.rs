@@ -0,0 +1,103 @@
+//! Tests for strings that would be read as another type.
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::{AmbiguousStrings, SerializeOptions};
+
+use crate::assert_serialize;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Root {
+    date: String,
+    hex: String,
+    octal: String,
+    separated: String,
+    boolean: String,
+    version: String,
+}
+
+fn root() -> Root {
+    Root {
+        date: "2024-01-01".to_string(),
+        hex: "0x1F".to_string(),
+        octal: "0o17".to_string(),
+        separated: "1_000".to_string(),
+        boolean: "yes".to_string(),
+        version: "1.0.0".to_string(),
+    }
+}
+
+fn to_string(mode: AmbiguousStrings) -> Result<String> {
+    let options = SerializeOptions::new().ambiguous_strings(mode);
+    Ok(facet_yaml::to_string_with_options(&root(), &options)?)
+}
+
+#[test]
+fn test_ambiguous_strings_round_trip() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_serialize!(Root, root());
+
+    Ok(())
+}
+
+#[test]
+fn test_ambiguous_strings_plain() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        to_string(AmbiguousStrings::Plain)?,
+        "---\ndate: 2024-01-01\nhex: \"0x1F\"\noctal: \"0o17\"\nseparated: 1_000\nboolean: \"yes\"\nversion: 1.0.0"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_ambiguous_strings_quote() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let yaml = to_string(AmbiguousStrings::Quote)?;
+    assert_eq!(
+        yaml,
+        "---\ndate: \"2024-01-01\"\nhex: \"0x1F\"\noctal: \"0o17\"\nseparated: \"1_000\"\nboolean: \"yes\"\nversion: 1.0.0"
+    );
+    assert_eq!(facet_yaml::from_str::<Root>(&yaml)?, root());
+
+    Ok(())
+}
+
+#[test]
+fn test_ambiguous_strings_tag() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let yaml = to_string(AmbiguousStrings::Tag)?;
+    assert_eq!(
+        yaml,
+        "---\ndate: !!str 2024-01-01\nhex: \"0x1F\"\noctal: \"0o17\"\nseparated: !!str 1_000\nboolean: \"yes\"\nversion: 1.0.0"
+    );
+    assert_eq!(facet_yaml::from_str::<Root>(&yaml)?, root());
+
+    Ok(())
+}
+
+#[test]
+fn test_control_characters_are_escaped() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        value: String,
+    }
+
+    let value = Root {
+        value: "bell\x07 escape\x1b".to_string(),
+    };
+    assert_eq!(
+        facet_yaml::to_string(&value)?,
+        "---\nvalue: \"bell\\u0007 escape\\u001b\""
+    );
+    assert_serialize!(Root, value);
+
+    Ok(())
+}
@@ -23,3 +23,23 @@ fn test_serialize_person() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_serialize_root_option() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(facet_yaml::to_string(&None::<Person>)?, "---\n~");
+    assert_eq!(facet_yaml::to_string(&Some(1u8))?, "---\n1");
+
+    crate::assert_serialize!(Option<Person>, None);
+    crate::assert_serialize!(
+        Option<Person>,
+        Some(Person {
+            name: "Bob".to_string(),
+            age: 41,
+        }),
+    );
+
+    Ok(())
+}
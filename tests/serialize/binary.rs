@@ -0,0 +1,85 @@
+//! Tests for byte buffers, written as `!!binary` scalars.
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::SerializeOptions;
+
+use crate::assert_serialize;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Tls {
+    name: String,
+    certificate: Vec<u8>,
+    key: Option<Vec<u8>>,
+}
+
+fn tls() -> Tls {
+    Tls {
+        name: "web".to_string(),
+        certificate: b"-----BEGIN CERTIFICATE-----".to_vec(),
+        key: Some(vec![0, 1, 2, 254, 255]),
+    }
+}
+
+#[test]
+fn test_binary() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        facet_yaml::to_string(&tls())?,
+        "---\nname: web\ncertificate: !!binary LS0tLS1CRUdJTiBDRVJUSUZJQ0FURS0tLS0t\nkey: !!binary AAEC/v8="
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_binary_round_trip() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_serialize!(Tls, tls());
+    assert_serialize!(Vec<u8>, Vec::<u8>::new());
+    assert_serialize!(Vec<u8>, (0..=255).collect::<Vec<u8>>());
+    assert_serialize!(Vec<Vec<u8>>, vec![b"a".to_vec(), b"bc".to_vec()]);
+
+    Ok(())
+}
+
+#[test]
+fn test_binary_empty() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        facet_yaml::to_string(&Vec::<u8>::new())?,
+        "---\n!!binary \"\""
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_binary_in_flow_style() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = SerializeOptions::new().flow_depth(0);
+    assert_eq!(
+        facet_yaml::to_string_with_options(&vec![b"hi".to_vec()], &options)?,
+        "---\n[!!binary aGk=]"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_binary_disabled() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = SerializeOptions::new().binary(false);
+    let yaml = facet_yaml::to_string_with_options(&tls(), &options)?;
+    assert!(yaml.ends_with("key:\n  - 0\n  - 1\n  - 2\n  - 254\n  - 255"));
+
+    let deserialized: Tls = facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, tls());
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+//! Tests for strings with line breaks, written as block scalars.
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::{MultilineStrings, SerializeOptions};
+
+use crate::assert_serialize;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Job {
+    name: String,
+    script: String,
+    steps: Vec<String>,
+}
+
+fn job() -> Job {
+    Job {
+        name: "build".to_string(),
+        script: "set -e\nmake\nmake install\n".to_string(),
+        steps: vec!["echo one\necho two".to_string(), "done\n\n".to_string()],
+    }
+}
+
+#[test]
+fn test_literal_block_scalars() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        facet_yaml::to_string(&job())?,
+        "---\nname: build\nscript: |\n  set -e\n  make\n  make install\nsteps:\n  - |-\n    echo one\n    echo two\n  - |+\n    done\n\n"
+    );
+    assert_serialize!(Job, job());
+
+    Ok(())
+}
+
+#[test]
+fn test_folded_block_scalars() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Package {
+        description: String,
+    }
+
+    let package = Package {
+        description: "A library to read and write YAML documents, with the types of your program \
+                      and nothing else.\nIt has no unsafe code."
+            .to_string(),
+    };
+    let options = SerializeOptions::new().multiline_strings(MultilineStrings::Folded);
+    let yaml = facet_yaml::to_string_with_options(&package, &options)?;
+    assert_eq!(
+        yaml,
+        "---\ndescription: >-\n  A library to read and write YAML documents, with the types of your program and\n  nothing else.\n\n  It has no unsafe code."
+    );
+
+    let deserialized: Package = facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, package);
+
+    let yaml = facet_yaml::to_string_with_options(&package, &options.line_width(40))?;
+    assert_eq!(
+        yaml,
+        "---\ndescription: >-\n  A library to read and write YAML\n  documents, with the types of your\n  program and nothing else.\n\n  It has no unsafe code."
+    );
+    let deserialized: Package = facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, package);
+
+    Ok(())
+}
+
+#[test]
+fn test_multiline_strings_quoted() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = SerializeOptions::new().multiline_strings(MultilineStrings::Quoted);
+    assert_eq!(
+        facet_yaml::to_string_with_options(&job(), &options)?,
+        "---\nname: build\nscript: \"set -e\\nmake\\nmake install\\n\"\nsteps:\n  - \"echo one\\necho two\"\n  - \"done\\n\\n\""
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_multiline_strings_that_stay_quoted() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        indented: String,
+        carriage_return: String,
+        #[facet(yaml(flow))]
+        flow: Vec<String>,
+    }
+
+    let root = Root {
+        indented: "  code\nmore".to_string(),
+        carriage_return: "a\r\nb".to_string(),
+        flow: vec!["a\nb".to_string()],
+    };
+    assert_eq!(
+        facet_yaml::to_string(&root)?,
+        "---\nindented: \"  code\\nmore\"\ncarriage_return: \"a\\r\\nb\"\nflow: [\"a\\nb\"]"
+    );
+    assert_serialize!(Root, root);
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+//! Tests for values that borrow their data.
+
+use eyre::Result;
+use facet::Facet;
+
+#[derive(Debug, Clone, Facet, PartialEq)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+#[test]
+fn test_references() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct View<'a> {
+        name: &'a str,
+        ports: &'a [u16],
+        primary: &'a Server,
+    }
+
+    let name = String::from("web");
+    let ports = vec![80, 443];
+    let primary = Server {
+        host: "a".to_string(),
+        port: 80,
+    };
+    let view = View {
+        name: &name,
+        ports: &ports,
+        primary: &primary,
+    };
+
+    assert_eq!(
+        facet_yaml::to_string(&view)?,
+        "---\nname: web\nports:\n  - 80\n  - 443\nprimary:\n  host: a\n  port: 80"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_borrowed_slices() -> Result<()> {
+    facet_testhelpers::setup();
+
+    // `Cow<'_, [T]>` has no `Facet` implementation in facet-core 0.30, so only plain slices
+    // can be written here
+    #[derive(Debug, Facet)]
+    struct View<'a> {
+        servers: &'a [Server],
+        tags: &'a [String],
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Owned {
+        servers: Vec<Server>,
+        tags: Vec<String>,
+    }
+
+    let servers = [Server {
+        host: "a".to_string(),
+        port: 80,
+    }];
+    let tags = vec!["blue".to_string()];
+    let view = View {
+        servers: &servers,
+        tags: &tags,
+    };
+
+    let yaml = facet_yaml::to_string(&view)?;
+    assert_eq!(
+        yaml,
+        "---\nservers:\n  - host: a\n    port: 80\ntags:\n  - blue"
+    );
+
+    // The owned counterpart reads it back
+    let owned: Owned = facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(owned.servers, servers);
+    assert_eq!(owned.tags, ["blue"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_borrowed_quoted_field() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Release<'a> {
+        #[facet(yaml(quote))]
+        versions: &'a [&'a str],
+    }
+
+    let release = Release {
+        versions: &["v1", "v2"],
+    };
+
+    assert_eq!(
+        facet_yaml::to_string(&release)?,
+        "---\nversions:\n  - \"v1\"\n  - \"v2\""
+    );
+
+    Ok(())
+}
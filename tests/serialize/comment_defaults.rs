@@ -0,0 +1,117 @@
+//! Tests for writing fields that are the same as their default as comments.
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::SerializeOptions;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Server {
+    host: String,
+    #[facet(default = 8080)]
+    port: u16,
+    #[facet(default = vec!["user".to_string(), "admin".to_string()])]
+    roles: Vec<String>,
+    pool: Pool,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+#[facet(default)]
+struct Pool {
+    size: u32,
+    timeout: u64,
+}
+
+impl Default for Pool {
+    fn default() -> Self {
+        Self {
+            size: 8,
+            timeout: 30,
+        }
+    }
+}
+
+fn server(pool: Pool) -> Server {
+    Server {
+        host: "web".to_string(),
+        port: 8080,
+        roles: vec!["user".to_string(), "admin".to_string()],
+        pool,
+    }
+}
+
+#[test]
+fn test_defaults_are_written_by_default() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let value = server(Pool {
+        size: 8,
+        timeout: 5,
+    });
+    assert_eq!(
+        facet_yaml::to_string(&value)?,
+        "---\nhost: web\nport: 8080\nroles:\n  - user\n  - admin\npool:\n  size: 8\n  timeout: 5"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_comment_defaults() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let value = server(Pool {
+        size: 8,
+        timeout: 5,
+    });
+    let options = SerializeOptions::new().comment_defaults(true);
+    let yaml = facet_yaml::to_string_with_options(&value, &options)?;
+    assert_eq!(
+        yaml,
+        "---\nhost: web\n# port: 8080\n# roles:\n#   - user\n#   - admin\npool:\n  # size: 8\n  timeout: 5"
+    );
+
+    let deserialized: Server = facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, value);
+
+    Ok(())
+}
+
+#[test]
+fn test_comment_defaults_of_every_field() -> Result<()> {
+    facet_testhelpers::setup();
+
+    // The struct is still written, since its field has no default
+    let value = server(Pool::default());
+    let options = SerializeOptions::new().comment_defaults(true);
+    let yaml = facet_yaml::to_string_with_options(&value, &options)?;
+    assert_eq!(
+        yaml,
+        "---\nhost: web\n# port: 8080\n# roles:\n#   - user\n#   - admin\npool: {}\n  # size: 8\n  # timeout: 30"
+    );
+
+    let deserialized: Server = facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, value);
+
+    Ok(())
+}
+
+#[test]
+fn test_comment_defaults_in_flow_style() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let value = server(Pool {
+        size: 8,
+        timeout: 5,
+    });
+    let options = SerializeOptions::new().comment_defaults(true).flow_depth(1);
+    let yaml = facet_yaml::to_string_with_options(&value, &options)?;
+    assert_eq!(
+        yaml,
+        "---\nhost: web\n# port: 8080\n# roles: [user, admin]\npool: {timeout: 5}"
+    );
+
+    let deserialized: Server = facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, value);
+
+    Ok(())
+}
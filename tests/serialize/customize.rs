@@ -0,0 +1,124 @@
+//! Tests for types written with their `YamlCustomize` implementation.
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::{DeserializeOptions, Map, SerializeOptions, Value, YamlCustomize};
+
+use crate::assert_serialize;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Duration {
+    seconds: i64,
+}
+
+impl YamlCustomize for Duration {
+    fn to_yaml(&self) -> Value {
+        Value::String(format!("{}s", self.seconds))
+    }
+
+    fn from_yaml(value: Value) -> Result<Self, String> {
+        value
+            .as_str()
+            .and_then(|text| text.strip_suffix('s'))
+            .and_then(|seconds| seconds.parse().ok())
+            .map(|seconds| Duration { seconds })
+            .ok_or_else(|| "Expected a duration".to_string())
+    }
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Limits {
+    cpu: u32,
+    memory: u32,
+}
+
+impl YamlCustomize for Limits {
+    const TAG: Option<&'static str> = Some("!limits");
+
+    fn to_yaml(&self) -> Value {
+        Value::Map(Map::from_iter([
+            (
+                Value::String("cpu".into()),
+                Value::String(format!("{}m", self.cpu)),
+            ),
+            (
+                Value::String("memory".into()),
+                Value::String(format!("{}Mi", self.memory)),
+            ),
+        ]))
+    }
+
+    fn from_yaml(value: Value) -> Result<Self, String> {
+        let field = |key: &str, unit: &str| {
+            value
+                .get(key)
+                .and_then(Value::as_str)
+                .and_then(|text| text.strip_suffix(unit))
+                .and_then(|number| number.parse().ok())
+                .ok_or_else(|| format!("Invalid `{key}`"))
+        };
+        Ok(Limits {
+            cpu: field("cpu", "m")?,
+            memory: field("memory", "Mi")?,
+        })
+    }
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Container {
+    name: String,
+    timeout: Duration,
+    limits: Option<Limits>,
+}
+
+#[test]
+fn test_customized_types() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let container = Container {
+        name: "web".to_string(),
+        timeout: Duration { seconds: 30 },
+        limits: Some(Limits {
+            cpu: 500,
+            memory: 256,
+        }),
+    };
+    let options = SerializeOptions::new()
+        .customize::<Duration>()
+        .customize::<Limits>();
+    let yaml = facet_yaml::to_string_with_options(&container, &options)?;
+    assert_eq!(
+        yaml,
+        "---\nname: web\ntimeout: 30s\nlimits: !limits {cpu: 500m, memory: 256Mi}"
+    );
+
+    // The same customizations read it back
+    let options = DeserializeOptions::new()
+        .customize::<Duration>()
+        .customize::<Limits>();
+    let deserialized: Container =
+        facet_yaml::from_str_with_options(&yaml, &options).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, container);
+
+    // Without them the types are written by their shape
+    assert_serialize!(Container, container);
+
+    Ok(())
+}
+
+#[test]
+fn test_customized_root() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = SerializeOptions::new().customize::<Limits>();
+    let limits = Limits {
+        cpu: 250,
+        memory: 64,
+    };
+    assert_eq!(
+        facet_yaml::to_string_with_options(&limits, &options)?,
+        "---\n!limits {cpu: 250m, memory: 64Mi}"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,164 @@
+//! Tests for values that are shared or contain themselves through pointers.
+
+use std::{rc::Rc, sync::Arc};
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::{ErrorCode, SerializeOptions};
+
+#[derive(Debug, Facet)]
+struct Node {
+    name: String,
+    children: Vec<Rc<Node>>,
+}
+
+fn node(name: &str, children: Vec<Rc<Node>>) -> Rc<Node> {
+    Rc::new(Node {
+        name: name.to_string(),
+        children,
+    })
+}
+
+#[test]
+fn test_shared_values_are_written_in_full() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let leaf = node("leaf", Vec::new());
+    let root = node("root", vec![leaf.clone(), leaf]);
+
+    assert_eq!(
+        facet_yaml::to_string(&root)?,
+        "---\nname: root\nchildren:\n  - name: leaf\n    children: []\n  - name: leaf\n    children: []"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_shared_values_with_anchors() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let leaf = node("leaf", Vec::new());
+    let other = node("other", Vec::new());
+    let root = node("root", vec![leaf.clone(), other, leaf]);
+
+    let options = SerializeOptions::new().anchors(true);
+    assert_eq!(
+        facet_yaml::to_string_with_options(&root, &options)?,
+        "---\nname: root\nchildren:\n  - &id001\n    name: leaf\n    children: []\n  - name: other\n    children: []\n  - *id001"
+    );
+
+    Ok(())
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Upstream {
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Routes {
+    api: Arc<Upstream>,
+    web: Arc<Upstream>,
+    fallback: Option<Arc<Upstream>>,
+    name: Arc<String>,
+    title: Arc<String>,
+}
+
+#[test]
+fn test_anchors_are_read_back_as_copies() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let upstream = Arc::new(Upstream {
+        host: "10.0.0.1".to_string(),
+        port: 80,
+    });
+    let name: Arc<String> = Arc::new("edge".to_string());
+    let routes = Routes {
+        api: upstream.clone(),
+        web: upstream.clone(),
+        fallback: Some(upstream),
+        name: name.clone(),
+        title: name,
+    };
+
+    let options = SerializeOptions::new().anchors(true);
+    let yaml = facet_yaml::to_string_with_options(&routes, &options)?;
+    assert_eq!(
+        yaml,
+        "---\napi: &id001\n  host: 10.0.0.1\n  port: 80\nweb: *id001\nfallback: *id001\nname: &id002 edge\ntitle: *id002"
+    );
+
+    let deserialized: Routes = facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, routes);
+
+    Ok(())
+}
+
+#[test]
+fn test_anchors_in_flow_style() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let leaf = node("leaf", Vec::new());
+    let root = node("root", vec![leaf.clone(), leaf]);
+
+    let options = SerializeOptions::new().anchors(true).flow_depth(0);
+    assert_eq!(
+        facet_yaml::to_string_with_options(&root, &options)?,
+        "---\n{name: root, children: [&id001 {name: leaf, children: []}, *id001]}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_anchored_sequences_in_sequences_are_indented() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let ports = Arc::new(vec![80u16, 443]);
+    let groups = vec![ports.clone(), ports];
+
+    let options = SerializeOptions::new()
+        .anchors(true)
+        .indent_sequences(false);
+    let yaml = facet_yaml::to_string_with_options(&groups, &options)?;
+    assert_eq!(yaml, "---\n- &id001\n  - 80\n  - 443\n- *id001");
+
+    let back: Vec<Vec<u16>> = facet_yaml::from_str(&yaml)?;
+    assert_eq!(back, [[80, 443], [80, 443]]);
+
+    Ok(())
+}
+
+/// A value that contains itself, through statics that refer to each other. Facet types can't
+/// form such cycles through `Rc`, since `RefCell` doesn't implement `Facet`.
+#[derive(Debug, Facet)]
+struct Link {
+    name: &'static str,
+    next: &'static Link,
+}
+
+static FIRST: Link = Link {
+    name: "first",
+    next: &SECOND,
+};
+static SECOND: Link = Link {
+    name: "second",
+    next: &FIRST,
+};
+
+#[test]
+fn test_cycle_is_an_error() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let first: &Link = &FIRST;
+    let err = facet_yaml::to_string(&first).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Cycle);
+    assert_eq!(
+        err.to_string(),
+        "The value contains itself at `next.next`, so it can't be serialized to YAML"
+    );
+
+    Ok(())
+}
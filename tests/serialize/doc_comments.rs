@@ -0,0 +1,149 @@
+//! Tests for writing the doc comments of fields as comments.
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::SerializeOptions;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Service {
+    /// Name of the service.
+    ///
+    /// Shown in the dashboard.
+    name: String,
+    /// Containers to run.
+    containers: Vec<Container>,
+    /// Ports to listen on.
+    #[facet(yaml(flow))]
+    ports: Vec<u16>,
+    limits: Limits,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Container {
+    /// Image to run.
+    image: String,
+    args: Vec<String>,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Limits {
+    /// Number of CPUs.
+    cpu: f64,
+}
+
+fn service() -> Service {
+    Service {
+        name: "web".to_string(),
+        containers: vec![Container {
+            image: "nginx".to_string(),
+            args: vec!["serve".to_string()],
+        }],
+        ports: vec![80, 443],
+        limits: Limits { cpu: 0.5 },
+    }
+}
+
+#[test]
+fn test_doc_comments() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = SerializeOptions::new().doc_comments(true);
+    let yaml = facet_yaml::to_string_with_options(&service(), &options)?;
+    assert_eq!(
+        yaml,
+        "---
+# Name of the service.
+#
+# Shown in the dashboard.
+name: web
+# Containers to run.
+containers:
+  - # Image to run.
+    image: nginx
+    args:
+      - serve
+# Ports to listen on.
+ports: [80, 443]
+limits:
+  # Number of CPUs.
+  cpu: 0.5"
+    );
+
+    let deserialized: Service = facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, service());
+
+    Ok(())
+}
+
+#[test]
+fn test_doc_comments_off_and_in_flow_style() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert!(!facet_yaml::to_string(&service())?.contains('#'));
+
+    // Flow collections are written on a single line, without comments
+    let options = SerializeOptions::new().doc_comments(true).flow_depth(1);
+    assert_eq!(
+        facet_yaml::to_string_with_options(&service(), &options)?,
+        "---
+# Name of the service.
+#
+# Shown in the dashboard.
+name: web
+# Containers to run.
+containers: [{image: nginx, args: [serve]}]
+# Ports to listen on.
+ports: [80, 443]
+limits: {cpu: 0.5}"
+    );
+
+    Ok(())
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Retry {
+    /// Number of times a request is sent again after it fails, before giving up.
+    ///
+    ///     attempts: 3
+    attempts: u32,
+    backoff: Backoff,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Backoff {
+    /// Delay before the first retry, in milliseconds, doubled after each one.
+    initial_ms: u64,
+}
+
+#[test]
+fn test_doc_comments_are_wrapped() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let retry = Retry {
+        attempts: 3,
+        backoff: Backoff { initial_ms: 500 },
+    };
+    let options = SerializeOptions::new().doc_comments(true).line_width(40);
+    let yaml = facet_yaml::to_string_with_options(&retry, &options)?;
+    assert_eq!(
+        yaml,
+        "---
+# Number of times a request is sent
+# again after it fails, before giving
+# up.
+#
+#     attempts: 3
+attempts: 3
+backoff:
+  # Delay before the first retry, in
+  # milliseconds, doubled after each
+  # one.
+  initial_ms: 500"
+    );
+    assert!(yaml.lines().all(|line| line.chars().count() <= 40));
+
+    let deserialized: Retry = facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, retry);
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+//! Tests for fields written as strings with their own YAML document.
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::{MultilineStrings, SerializeOptions};
+
+#[derive(Debug, Facet, PartialEq)]
+struct Release {
+    name: String,
+    #[facet(yaml(embedded))]
+    values: Values,
+    #[facet(yaml(embedded))]
+    overrides: Option<Values>,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Values {
+    image: Image,
+    replicas: u32,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Image {
+    repository: String,
+    tag: String,
+}
+
+fn release() -> Release {
+    Release {
+        name: "web".to_string(),
+        values: Values {
+            image: Image {
+                repository: "nginx".to_string(),
+                tag: "1.25".to_string(),
+            },
+            replicas: 3,
+        },
+        overrides: None,
+    }
+}
+
+#[test]
+fn test_embedded() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let yaml = facet_yaml::to_string(&release())?;
+    assert_eq!(
+        yaml,
+        "---\nname: web\nvalues: |\n  image:\n    repository: nginx\n    tag: \"1.25\"\n  replicas: 3\n"
+    );
+
+    let deserialized: Release = facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, release());
+
+    Ok(())
+}
+
+#[test]
+fn test_embedded_is_always_literal() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let value = Release {
+        overrides: Some(Values {
+            image: Image {
+                repository: "nginx".to_string(),
+                tag: "latest".to_string(),
+            },
+            replicas: 5,
+        }),
+        ..release()
+    };
+    let options = SerializeOptions::new()
+        .multiline_strings(MultilineStrings::Folded)
+        .indent(4);
+    let yaml = facet_yaml::to_string_with_options(&value, &options)?;
+    assert_eq!(
+        yaml,
+        "---\nname: web\nvalues: |\n    image:\n        repository: nginx\n        tag: \"1.25\"\n    replicas: 3\noverrides: |\n    image:\n        repository: nginx\n        tag: latest\n    replicas: 5\n"
+    );
+
+    let deserialized: Release = facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, value);
+
+    Ok(())
+}
@@ -0,0 +1,203 @@
+//! Tests for enums, written as the name of unit variants or a mapping from the name of the
+//! variant to its content, or a mapping of the fields next to the tag of the variant, or the
+//! content alone.
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::{SerializeOptions, SortKeys};
+
+use crate::assert_serialize;
+
+#[derive(Debug, Facet, PartialEq)]
+#[repr(u8)]
+enum Backend {
+    Memory,
+    Redis(String),
+    Pair(String, u16),
+    Postgres { host: String, port: u16 },
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Cache {
+    name: String,
+    backend: Backend,
+}
+
+#[test]
+fn test_enum_variants() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(facet_yaml::to_string(&Backend::Memory)?, "---\nMemory");
+    assert_eq!(
+        facet_yaml::to_string(&Backend::Redis("redis://cache".to_string()))?,
+        "---\nRedis: \"redis://cache\""
+    );
+    assert_eq!(
+        facet_yaml::to_string(&Backend::Pair("db".to_string(), 5432))?,
+        "---\nPair:\n  - db\n  - 5432"
+    );
+    assert_eq!(
+        facet_yaml::to_string(&Backend::Postgres {
+            host: "db".to_string(),
+            port: 5432
+        })?,
+        "---\nPostgres:\n  host: db\n  port: 5432"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_enum_round_trip() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_serialize!(Backend, Backend::Memory);
+    assert_serialize!(Backend, Backend::Redis("redis://cache".to_string()));
+    assert_serialize!(Backend, Backend::Pair("db".to_string(), 5432));
+    assert_serialize!(
+        Cache,
+        Cache {
+            name: "sessions".to_string(),
+            backend: Backend::Postgres {
+                host: "db".to_string(),
+                port: 5432,
+            },
+        }
+    );
+    assert_serialize!(
+        Vec<Backend>,
+        vec![Backend::Memory, Backend::Redis("null".to_string())]
+    );
+
+    Ok(())
+}
+
+#[derive(Debug, Facet, PartialEq)]
+#[repr(u8)]
+enum Credential {
+    Token(Vec<u8>),
+    Keys {
+        public: Vec<u8>,
+        #[facet(yaml(quote))]
+        comment: String,
+    },
+}
+
+#[test]
+fn test_enum_fields_keep_their_attributes() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let keys = Credential::Keys {
+        public: b"hi".to_vec(),
+        comment: "laptop".to_string(),
+    };
+    assert_eq!(
+        facet_yaml::to_string(&keys)?,
+        "---\nKeys:\n  public: !!binary aGk=\n  comment: \"laptop\""
+    );
+    assert_eq!(
+        facet_yaml::to_string(&Credential::Token(b"hi".to_vec()))?,
+        "---\nToken: !!binary aGk="
+    );
+
+    assert_serialize!(Credential, keys);
+    assert_serialize!(Credential, Credential::Token(vec![0, 255]));
+
+    Ok(())
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Deployment {
+    replicas: u32,
+    #[facet(yaml(quote))]
+    image: String,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+#[facet(tag = "kind")]
+#[repr(u8)]
+enum Resource {
+    Namespace,
+    Service { port: u16, selector: Vec<String> },
+    Deployment(Deployment),
+}
+
+#[test]
+fn test_internally_tagged_enum() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        facet_yaml::to_string(&Resource::Namespace)?,
+        "---\nkind: Namespace"
+    );
+    assert_eq!(
+        facet_yaml::to_string(&Resource::Service {
+            port: 80,
+            selector: vec!["app=shop".to_string()]
+        })?,
+        "---\nkind: Service\nport: 80\nselector:\n  - app=shop"
+    );
+    // The fields keep their attributes, and their order even when keys are sorted
+    let options = SerializeOptions::new().sort_keys(SortKeys::Maps);
+    assert_eq!(
+        facet_yaml::to_string_with_options(
+            &Resource::Deployment(Deployment {
+                replicas: 3,
+                image: "shop".to_string()
+            }),
+            &options
+        )?,
+        "---\nkind: Deployment\nreplicas: 3\nimage: \"shop\""
+    );
+
+    assert_serialize!(
+        Vec<Resource>,
+        vec![
+            Resource::Namespace,
+            Resource::Service {
+                port: 80,
+                selector: vec![]
+            },
+            Resource::Deployment(Deployment {
+                replicas: 3,
+                image: "shop:1".to_string()
+            }),
+        ]
+    );
+
+    Ok(())
+}
+
+#[derive(Debug, Facet, PartialEq)]
+#[facet(untagged)]
+#[repr(u8)]
+enum Limit {
+    Unlimited,
+    Bytes(u64),
+    Range { min: u64, max: u64 },
+    Paths(Vec<String>),
+}
+
+#[test]
+fn test_untagged_enum() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(facet_yaml::to_string(&Limit::Unlimited)?, "---\nUnlimited");
+    assert_eq!(facet_yaml::to_string(&Limit::Bytes(512))?, "---\n512");
+    assert_eq!(
+        facet_yaml::to_string(&Limit::Range { min: 1, max: 8 })?,
+        "---\nmin: 1\nmax: 8"
+    );
+
+    assert_serialize!(
+        Vec<Limit>,
+        vec![
+            Limit::Unlimited,
+            Limit::Bytes(512),
+            Limit::Range { min: 1, max: 8 },
+            Limit::Paths(vec!["/tmp".to_string()]),
+        ]
+    );
+
+    Ok(())
+}
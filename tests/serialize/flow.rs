@@ -0,0 +1,107 @@
+//! Tests for collections written in flow style.
+
+use std::collections::BTreeMap;
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::SerializeOptions;
+
+use crate::assert_serialize;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Shape {
+    name: String,
+    #[facet(yaml(flow))]
+    tags: Vec<String>,
+    #[facet(yaml(flow))]
+    origin: Point,
+    points: Vec<Point>,
+}
+
+fn shape() -> Shape {
+    Shape {
+        name: "triangle".to_string(),
+        tags: vec!["closed".to_string(), "a, b".to_string()],
+        origin: Point { x: 0, y: 0 },
+        points: vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }],
+    }
+}
+
+#[test]
+fn test_flow_field() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        facet_yaml::to_string(&shape())?,
+        "---\nname: triangle\ntags: [closed, \"a, b\"]\norigin: {x: 0, \"y\": 0}\npoints:\n  - x: 1\n    \"y\": 2\n  - x: 3\n    \"y\": 4"
+    );
+    assert_serialize!(Shape, shape());
+
+    Ok(())
+}
+
+#[test]
+fn test_flow_depth() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = SerializeOptions::new().flow_depth(2);
+    assert_eq!(
+        facet_yaml::to_string_with_options(&shape(), &options)?,
+        "---\nname: triangle\ntags: [closed, \"a, b\"]\norigin: {x: 0, \"y\": 0}\npoints:\n  - {x: 1, \"y\": 2}\n  - {x: 3, \"y\": 4}"
+    );
+
+    let options = SerializeOptions::new().flow_depth(1);
+    assert_eq!(
+        facet_yaml::to_string_with_options(&shape(), &options)?,
+        "---\nname: triangle\ntags: [closed, \"a, b\"]\norigin: {x: 0, \"y\": 0}\npoints: [{x: 1, \"y\": 2}, {x: 3, \"y\": 4}]"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_flow_whole_document() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let mut map = BTreeMap::new();
+    map.insert("empty".to_string(), Vec::<u16>::new());
+    map.insert("ports".to_string(), vec![80, 443]);
+
+    let options = SerializeOptions::new().flow_depth(0);
+    let yaml = facet_yaml::to_string_with_options(&map, &options)?;
+    assert_eq!(yaml, "---\n{empty: [], ports: [80, 443]}");
+
+    let deserialized: BTreeMap<String, Vec<u16>> =
+        facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, map);
+
+    Ok(())
+}
+
+#[test]
+fn test_flow_quoted_field() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Package {
+        #[facet(yaml(flow, quote))]
+        versions: Vec<String>,
+    }
+
+    let package = Package {
+        versions: vec!["1.0".to_string(), "beta".to_string()],
+    };
+    assert_eq!(
+        facet_yaml::to_string(&package)?,
+        "---\nversions: [\"1.0\", \"beta\"]"
+    );
+    assert_serialize!(Package, package);
+
+    Ok(())
+}
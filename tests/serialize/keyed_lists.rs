@@ -0,0 +1,96 @@
+//! Tests for map fields written as sequences of their values, keyed by one of their keys.
+
+use std::collections::{BTreeMap, HashMap};
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::SerializeOptions;
+
+use crate::assert_serialize;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Pod {
+    #[facet(yaml(key = "name"))]
+    containers: HashMap<String, Container>,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Container {
+    image: String,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Services {
+    #[facet(yaml(key = "name"))]
+    services: BTreeMap<String, Service>,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Service {
+    port: u16,
+    name: String,
+}
+
+#[test]
+fn test_keyed_list() -> Result<()> {
+    facet_testhelpers::setup();
+
+    // Items of a `HashMap` are sorted by their key, with it first
+    let pod = Pod {
+        containers: HashMap::from([
+            (
+                "web".to_string(),
+                Container {
+                    image: "nginx".to_string(),
+                },
+            ),
+            (
+                "cache".to_string(),
+                Container {
+                    image: "redis".to_string(),
+                },
+            ),
+        ]),
+    };
+    let yaml = facet_yaml::to_string(&pod)?;
+    assert_eq!(
+        yaml,
+        "---\ncontainers:\n  - name: cache\n    image: redis\n  - name: web\n    image: nginx"
+    );
+
+    let yaml = facet_yaml::to_string_with_options(&pod, &SerializeOptions::new().flow_depth(0))?;
+    let deserialized: Pod = facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, pod);
+    assert_serialize!(Pod, pod);
+
+    Ok(())
+}
+
+#[test]
+fn test_keyed_list_of_structs_with_the_key() -> Result<()> {
+    facet_testhelpers::setup();
+
+    // The key isn't repeated when the values have it already
+    let services = Services {
+        services: BTreeMap::from([(
+            "api".to_string(),
+            Service {
+                port: 8080,
+                name: "api".to_string(),
+            },
+        )]),
+    };
+    let yaml = facet_yaml::to_string(&services)?;
+    assert_eq!(yaml, "---\nservices:\n  - port: 8080\n    name: api");
+    assert_serialize!(Services, services);
+
+    // An empty map is an empty sequence
+    assert_eq!(
+        facet_yaml::to_string(&Services {
+            services: BTreeMap::new(),
+        })?,
+        "---\nservices: []"
+    );
+
+    Ok(())
+}
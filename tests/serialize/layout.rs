@@ -0,0 +1,123 @@
+//! Tests for the layout options of the serializer.
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::SerializeOptions;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Container {
+    name: String,
+    ports: Vec<u16>,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Pod {
+    containers: Vec<Container>,
+}
+
+fn pod() -> Pod {
+    Pod {
+        containers: vec![Container {
+            name: "web".to_string(),
+            ports: vec![80, 443],
+        }],
+    }
+}
+
+/// Serialize the pod, checking that it's read back the same.
+fn serialize(options: &SerializeOptions) -> Result<String> {
+    let yaml = facet_yaml::to_string_with_options(&pod(), options)?;
+    let deserialized: Pod = facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, pod(), "{yaml}");
+    Ok(yaml)
+}
+
+#[test]
+fn test_default_layout() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        serialize(&SerializeOptions::new())?,
+        "---\ncontainers:\n  - name: web\n    ports:\n      - 80\n      - 443"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_indent() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        serialize(&SerializeOptions::new().indent(4))?,
+        "---\ncontainers:\n    -   name: web\n        ports:\n            - 80\n            - 443"
+    );
+
+    // Narrower indentation would misalign the entries of sequence items
+    assert_eq!(
+        serialize(&SerializeOptions::new().indent(1))?,
+        serialize(&SerializeOptions::new())?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_indentless_sequences() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        serialize(&SerializeOptions::new().indent_sequences(false))?,
+        "---\ncontainers:\n- name: web\n  ports:\n  - 80\n  - 443"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_document_markers_and_trailing_newline() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = SerializeOptions::new()
+        .document_start(false)
+        .trailing_newline(true);
+    assert_eq!(
+        serialize(&options)?,
+        "containers:\n  - name: web\n    ports:\n      - 80\n      - 443\n"
+    );
+
+    assert_eq!(facet_yaml::to_string_with_options(&1u8, &options)?, "1\n");
+
+    Ok(())
+}
+
+#[test]
+fn test_document_end_and_yaml_directive() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = SerializeOptions::new().document_end(true);
+    assert_eq!(
+        serialize(&options)?,
+        "---\ncontainers:\n  - name: web\n    ports:\n      - 80\n      - 443\n..."
+    );
+
+    // The directive needs the `---` marker after it
+    let options = SerializeOptions::new()
+        .yaml_directive(true)
+        .document_start(false)
+        .document_end(true)
+        .trailing_newline(true);
+    assert_eq!(
+        serialize(&options)?,
+        "%YAML 1.2\n---\ncontainers:\n  - name: web\n    ports:\n      - 80\n      - 443\n...\n"
+    );
+
+    // Block scalars keep their final line break before the marker
+    let script = "set -e\nmake\n".to_string();
+    let yaml = facet_yaml::to_string_with_options(&script, &options)?;
+    assert_eq!(yaml, "%YAML 1.2\n---\n|\n  set -e\n  make\n...\n");
+    let deserialized: String = facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, script);
+
+    Ok(())
+}
@@ -1,5 +1,7 @@
 //! Tests for YAML values to lists.
 
+use std::collections::{BTreeSet, HashSet};
+
 use eyre::Result;
 use facet::Facet;
 
@@ -29,7 +31,6 @@ fn test_scalar_list() -> Result<()> {
 }
 
 #[test]
-#[ignore = "Must be fixed in facet-yaml deserialize"]
 fn test_option_scalar_list() -> Result<()> {
     facet_testhelpers::setup();
 
@@ -61,3 +62,55 @@ fn test_option_scalar_list() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_fixed_size_arrays() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        color: [f32; 3],
+        hash: [u8; 4],
+        empty: [String; 0],
+    }
+
+    assert_serialize!(
+        Root,
+        Root {
+            color: [0.5, 1.0, 0.25],
+            hash: [222, 173, 190, 239],
+            empty: [],
+        },
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_sets() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        roles: BTreeSet<String>,
+        ports: HashSet<u16>,
+    }
+
+    let root = Root {
+        roles: BTreeSet::from(["user".into(), "admin".into()]),
+        ports: HashSet::from([443]),
+    };
+    assert_eq!(
+        facet_yaml::to_string(&root)?,
+        "---\nroles:\n  - admin\n  - user\nports:\n  - 443"
+    );
+
+    // Sets can't be read back yet, since facet-reflect can't build them
+    let root = Root {
+        roles: BTreeSet::new(),
+        ports: HashSet::new(),
+    };
+    assert_eq!(facet_yaml::to_string(&root)?, "---\nroles: []\nports: []");
+
+    Ok(())
+}
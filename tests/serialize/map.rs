@@ -1,10 +1,9 @@
 //! Tests for TOML values to maps.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use eyre::Result;
 use facet::Facet;
-use facet_yaml::YamlSerError;
 
 use crate::assert_serialize;
 
@@ -35,7 +34,6 @@ fn test_scalar_map() -> Result<()> {
 }
 
 #[test]
-#[ignore = "must be fixed in deserialize"]
 fn test_optional_scalar_map() -> Result<()> {
     facet_testhelpers::setup();
 
@@ -93,7 +91,6 @@ fn test_scalar_map_with_other_fields() -> Result<()> {
 }
 
 #[test]
-#[ignore = "must be fixed in deserialize"]
 fn test_unit_struct_map() -> Result<()> {
     facet_testhelpers::setup();
 
@@ -168,7 +165,6 @@ fn test_struct_map() -> Result<()> {
 }
 
 #[test]
-#[ignore = "Must be fixed in facet-yaml deserialize"]
 fn test_optional_struct_map() -> Result<()> {
     facet_testhelpers::setup();
 
@@ -216,22 +212,148 @@ fn test_optional_struct_map() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_scalar_map_keys() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq, PartialOrd, Eq, Ord)]
+    #[repr(u8)]
+    enum Level {
+        Debug,
+        Info,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        ports: BTreeMap<u16, String>,
+        flags: HashMap<bool, i32>,
+        levels: BTreeMap<Level, u8>,
+    }
+
+    let root = Root {
+        ports: [(80, "http".to_string()), (443, "https".to_string())].into(),
+        flags: [(true, 1)].into(),
+        levels: [(Level::Debug, 1), (Level::Info, 2)].into(),
+    };
+    let yaml = facet_yaml::to_string(&root)?;
+    assert_eq!(
+        yaml,
+        "---\nports:\n  80: http\n  443: https\nflags:\n  true: 1\nlevels:\n  Debug: 1\n  Info: 2"
+    );
+    assert_serialize!(Root, root);
+
+    Ok(())
+}
+
+#[test]
+fn test_complex_map_keys() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq, PartialOrd, Eq, Ord)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    // Keys that aren't scalars are written after a `?`
+    let labels: BTreeMap<Point, String> = [
+        (Point { x: 1, y: 2 }, "a".to_string()),
+        (Point { x: 3, y: 4 }, "b".to_string()),
+    ]
+    .into();
+    assert_eq!(
+        facet_yaml::to_string(&labels)?,
+        "---\n? x: 1\n  \"y\": 2\n: a\n? x: 3\n  \"y\": 4\n: b"
+    );
+    assert_serialize!(BTreeMap<Point, String>, labels);
+
+    Ok(())
+}
+
 #[test]
 fn test_invalid_map_key() -> Result<()> {
     facet_testhelpers::setup();
 
+    #[derive(Debug, Facet, PartialEq, PartialOrd, Eq, Ord)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
     #[derive(Debug, Facet, PartialEq)]
     struct Root {
         value: HashMap<bool, i32>,
+        labels: BTreeMap<Point, String>,
     }
 
-    assert!(matches!(
-        facet_yaml::to_string(&Root {
-            value: [(true, 0)].into()
-        })
-        .unwrap_err(),
-        YamlSerError::InvalidKeyConversion { .. }
-    ));
+    // Keys that used to be rejected are written as scalars, or after a `?` when they're
+    // collections, indented with the map they're in
+    let root = Root {
+        value: [(true, 0)].into(),
+        labels: [(Point { x: 1, y: 2 }, "a".to_string())].into(),
+    };
+    assert_eq!(
+        facet_yaml::to_string(&root)?,
+        "---\nvalue:\n  true: 0\nlabels:\n  ? x: 1\n    \"y\": 2\n  : a"
+    );
+    assert_serialize!(Root, root);
+
+    Ok(())
+}
+
+#[test]
+fn test_parsed_map_keys() -> Result<()> {
+    facet_testhelpers::setup();
+
+    use std::net::IpAddr;
+
+    #[derive(Debug, Facet, PartialEq, PartialOrd, Eq, Ord)]
+    struct UserId(u64);
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        hosts: BTreeMap<IpAddr, String>,
+        users: BTreeMap<UserId, bool>,
+    }
+
+    // Keys are written the way they're displayed, and parsed back from it
+    let root = Root {
+        hosts: [
+            (IpAddr::from([10, 0, 0, 1]), "gateway".to_string()),
+            ("::1".parse()?, "loopback".to_string()),
+        ]
+        .into(),
+        users: [(UserId(7), false), (UserId(42), true)].into(),
+    };
+    assert_eq!(
+        facet_yaml::to_string(&root)?,
+        "---\nhosts:\n  10.0.0.1: gateway\n  \"::1\": loopback\nusers:\n  7: false\n  42: true"
+    );
+    assert_serialize!(Root, root);
+
+    Ok(())
+}
+
+#[test]
+fn test_renamed_enum_map_keys() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq, Eq, Hash)]
+    #[facet(rename_all = "kebab-case")]
+    #[repr(u8)]
+    enum Environment {
+        Production,
+        LocalDev,
+    }
+
+    let replicas: HashMap<Environment, u32> = [(Environment::LocalDev, 1)].into();
+    assert_eq!(facet_yaml::to_string(&replicas)?, "---\nlocal-dev: 1");
+    assert_serialize!(
+        HashMap<Environment, u32>,
+        [(Environment::Production, 3), (Environment::LocalDev, 1)]
+            .into_iter()
+            .collect::<HashMap<_, _>>(),
+    );
 
     Ok(())
 }
@@ -1,8 +1,31 @@
+mod ambiguous_strings;
 mod basic;
+mod binary;
+mod block_scalar;
+mod borrowed;
+mod comment_defaults;
+mod customize;
+mod cycle;
+mod doc_comments;
+mod embedded;
+mod enum_;
+mod flow;
+mod keyed_lists;
+mod layout;
 mod list;
 mod map;
+mod multi_document;
+mod null_style;
+mod pack_numbers;
+mod previous;
+mod quote;
+mod quote_style;
 mod scalar;
+mod skip_empty;
+mod sort_keys;
 mod struct_;
+#[cfg(feature = "std")]
+mod writer;
 
 /// Assert that the YAML used to serialize a value can be used to deserialize the value too.
 #[macro_export]
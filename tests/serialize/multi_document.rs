@@ -0,0 +1,126 @@
+//! Tests for serializing streams of documents.
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::{DocumentStream, SerializeOptions};
+
+#[derive(Debug, Facet, PartialEq)]
+struct Manifest {
+    kind: String,
+    name: String,
+    script: Option<String>,
+}
+
+fn manifests() -> Vec<Manifest> {
+    vec![
+        Manifest {
+            kind: "Deployment".to_string(),
+            name: "web".to_string(),
+            script: None,
+        },
+        Manifest {
+            kind: "Job".to_string(),
+            name: "migrate".to_string(),
+            script: Some("set -e\nmigrate up\n".to_string()),
+        },
+        Manifest {
+            kind: "Service".to_string(),
+            name: "web".to_string(),
+            script: None,
+        },
+    ]
+}
+
+#[test]
+fn test_to_string_multi() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        facet_yaml::to_string_multi(&manifests())?,
+        "---\nkind: Deployment\nname: web\n---\nkind: Job\nname: migrate\nscript: |\n  set -e\n  migrate up\n---\nkind: Service\nname: web"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_to_string_multi_round_trip() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let stream = DocumentStream::new(&facet_yaml::to_string_multi(&manifests())?);
+    let deserialized = stream
+        .documents()
+        .iter()
+        .map(|document| document.deserialize::<Manifest>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, manifests());
+
+    Ok(())
+}
+
+#[test]
+fn test_to_string_multi_without_document_start() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = SerializeOptions::new()
+        .document_start(false)
+        .trailing_newline(true);
+    assert_eq!(
+        facet_yaml::to_string_multi_with_options(&[1, 2], &options)?,
+        "1\n---\n2\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_to_string_multi_with_yaml_directive() -> Result<()> {
+    facet_testhelpers::setup();
+
+    // Every document has the directive, so the ones before it must end with `...`
+    let options = SerializeOptions::new().yaml_directive(true);
+    let yaml = facet_yaml::to_string_multi_with_options(&[1, 2], &options)?;
+    assert_eq!(yaml, "%YAML 1.2\n---\n1\n...\n%YAML 1.2\n---\n2");
+
+    let stream = DocumentStream::new(&yaml);
+    assert_eq!(stream.len(), 2);
+    let second: i32 = stream.documents()[1]
+        .deserialize()
+        .map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(second, 2);
+
+    let options = options.document_end(true);
+    assert_eq!(
+        facet_yaml::to_string_multi_with_options(&[1, 2], &options)?,
+        "%YAML 1.2\n---\n1\n...\n%YAML 1.2\n---\n2\n..."
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_to_string_multi_empty() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let manifests: [Manifest; 0] = [];
+    assert_eq!(facet_yaml::to_string_multi(&manifests)?, "");
+
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_to_writer_multi_matches_to_string_multi() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let mut out = Vec::new();
+    facet_yaml::to_writer_multi(&mut out, &manifests())?;
+
+    assert_eq!(
+        String::from_utf8(out)?,
+        facet_yaml::to_string_multi(&manifests())?
+    );
+
+    Ok(())
+}
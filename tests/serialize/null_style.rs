@@ -0,0 +1,101 @@
+//! Tests for the styles of null values.
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::{NullStyle, SerializeOptions};
+
+#[derive(Debug, Facet, PartialEq)]
+struct Samples {
+    name: String,
+    values: Vec<Option<u32>>,
+    #[facet(yaml(flow))]
+    pairs: Vec<Option<u32>>,
+    #[facet(default)]
+    comment: Option<String>,
+}
+
+fn samples() -> Samples {
+    Samples {
+        name: "cpu".to_string(),
+        values: vec![Some(1), None, Some(3)],
+        pairs: vec![None, Some(2)],
+        comment: None,
+    }
+}
+
+fn round_trip(options: &SerializeOptions) -> Result<String> {
+    let yaml = facet_yaml::to_string_with_options(&samples(), options)?;
+    let deserialized: Samples = facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, samples());
+    Ok(yaml)
+}
+
+#[test]
+fn test_null_style_tilde() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        round_trip(&SerializeOptions::new())?,
+        "---\nname: cpu\nvalues:\n  - 1\n  - ~\n  - 3\npairs: [~, 2]"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_null_style_null() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = SerializeOptions::new().null_style(NullStyle::Null);
+    assert_eq!(
+        round_trip(&options)?,
+        "---\nname: cpu\nvalues:\n  - 1\n  - null\n  - 3\npairs: [null, 2]"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_null_style_empty() -> Result<()> {
+    facet_testhelpers::setup();
+
+    // Nulls in flow collections can't be empty
+    let options = SerializeOptions::new().null_style(NullStyle::Empty);
+    assert_eq!(
+        round_trip(&options)?,
+        "---\nname: cpu\nvalues:\n  - 1\n  -\n  - 3\npairs: [null, 2]"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_null_style_of_the_root() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let value: Option<u32> = None;
+    for (null_style, expected) in [
+        (NullStyle::Tilde, "---\n~"),
+        (NullStyle::Null, "---\nnull"),
+        (NullStyle::Empty, "---\n"),
+    ] {
+        let options = SerializeOptions::new().null_style(null_style);
+        let yaml = facet_yaml::to_string_with_options(&value, &options)?;
+        assert_eq!(yaml, expected);
+
+        let deserialized: Option<u32> =
+            facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+        assert_eq!(deserialized, value);
+    }
+
+    // A document in flow style has nothing to be empty after
+    let options = SerializeOptions::new()
+        .null_style(NullStyle::Empty)
+        .flow_depth(0);
+    assert_eq!(
+        facet_yaml::to_string_with_options(&value, &options)?,
+        "---\nnull"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+//! Tests for sequences of numbers packed on as few lines as fit.
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::SerializeOptions;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Tensor {
+    name: String,
+    embedding: Vec<f32>,
+    shape: Vec<u32>,
+    matrix: Vec<Vec<i64>>,
+    labels: Vec<String>,
+}
+
+fn tensor() -> Tensor {
+    Tensor {
+        name: "layer".into(),
+        embedding: vec![0.25, -1.5, 3.0, 0.125, 8.0, -0.5],
+        shape: vec![2, 12],
+        matrix: vec![(1..=12).collect(), (13..=24).collect()],
+        labels: vec!["a".into(), "b".into()],
+    }
+}
+
+fn serialize(options: &SerializeOptions) -> Result<String> {
+    let yaml = facet_yaml::to_string_with_options(&tensor(), options)?;
+    let deserialized: Tensor = facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, tensor(), "{yaml}");
+    Ok(yaml)
+}
+
+#[test]
+fn test_pack_numbers() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = SerializeOptions::new().pack_numbers(true).line_width(32);
+    assert_eq!(
+        serialize(&options)?,
+        "---
+name: layer
+embedding: [0.25, -1.5, 3.0,
+  0.125, 8.0, -0.5]
+shape: [2, 12]
+matrix:
+  - [1, 2, 3, 4, 5, 6, 7, 8, 9,
+    10, 11, 12]
+  - [13, 14, 15, 16, 17, 18, 19,
+    20, 21, 22, 23, 24]
+labels:
+  - a
+  - b"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_pack_numbers_with_default_width() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = SerializeOptions::new()
+        .pack_numbers(true)
+        .indent_sequences(false);
+    assert_eq!(
+        serialize(&options)?,
+        "---
+name: layer
+embedding: [0.25, -1.5, 3.0, 0.125, 8.0, -0.5]
+shape: [2, 12]
+matrix:
+- [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
+- [13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24]
+labels:
+- a
+- b"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_pack_numbers_at_the_root() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let values: Vec<u16> = (1..=12).collect();
+    let options = SerializeOptions::new().pack_numbers(true).line_width(20);
+    let yaml = facet_yaml::to_string_with_options(&values, &options)?;
+    assert_eq!(yaml, "---\n[1, 2, 3, 4, 5, 6,\n  7, 8, 9, 10, 11,\n  12]");
+
+    let deserialized: Vec<u16> = facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, values);
+
+    // Empty sequences are written as usual
+    let empty: Vec<u16> = Vec::new();
+    assert_eq!(
+        facet_yaml::to_string_with_options(&empty, &options)?,
+        "---\n[]"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,267 @@
+//! Tests for writing a new version of a document the way its previous version was written.
+
+use std::collections::BTreeMap;
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::ErrorCode;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Deployment {
+    name: String,
+    image: Image,
+    replicas: u32,
+    ports: Vec<u16>,
+    env: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Image {
+    repository: String,
+    tag: String,
+}
+
+const PREVIOUS: &str = "---\nname: \"web\"\nreplicas: 3\nimage:\n    repository: nginx\n    tag: '1.25'\nports:\n- 80\n- 443\nenv: {DEBUG: 'false'}\n";
+
+fn deployment() -> Deployment {
+    Deployment {
+        name: "web".to_string(),
+        image: Image {
+            repository: "nginx".to_string(),
+            tag: "1.25".to_string(),
+        },
+        replicas: 3,
+        ports: vec![80, 443],
+        env: BTreeMap::from([("DEBUG".to_string(), "false".to_string())]),
+    }
+}
+
+#[test]
+fn test_unchanged_document() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        facet_yaml::to_string_over(&deployment(), PREVIOUS)?,
+        PREVIOUS
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_only_changes_differ() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let deployment = Deployment {
+        name: "api".to_string(),
+        image: Image {
+            repository: "nginx".to_string(),
+            tag: "1.26".to_string(),
+        },
+        replicas: 5,
+        ports: vec![80, 8443],
+        ..deployment()
+    };
+    let yaml = facet_yaml::to_string_over(&deployment, PREVIOUS)?;
+    assert_eq!(
+        yaml,
+        "---\nname: \"api\"\nreplicas: 5\nimage:\n    repository: nginx\n    tag: '1.26'\nports:\n- 80\n- 8443\nenv: {DEBUG: 'false'}\n"
+    );
+    let deserialized: Deployment =
+        facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, deployment);
+
+    Ok(())
+}
+
+#[test]
+fn test_new_keys_follow_the_key_before_them() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let limits = BTreeMap::from([
+        ("alpha".to_string(), 2),
+        ("beta".to_string(), 3),
+        ("zeta".to_string(), 1),
+    ]);
+    assert_eq!(
+        facet_yaml::to_string_over(&limits, "zeta: 1\nomega: 4\nalpha: 2\n")?,
+        "zeta: 1\nalpha: 2\nbeta: 3\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_scalars_keep_their_spelling() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Job {
+        script: String,
+        marker: (),
+        mask: u32,
+    }
+
+    let job = Job {
+        script: "echo hi\necho bye\necho done\n".to_string(),
+        marker: (),
+        mask: 0o17,
+    };
+    let previous = "script: |\n  echo hi\n  echo bye\nmarker:\nmask: 0o17\n";
+    assert_eq!(
+        facet_yaml::to_string_over(&job, previous)?,
+        "script: |\n  echo hi\n  echo bye\n  echo done\nmarker:\nmask: 0o17\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_without_a_previous_document() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        facet_yaml::to_string_over(&deployment(), "")?,
+        facet_yaml::to_string(&deployment())?
+    );
+
+    let err = facet_yaml::to_string_over(&deployment(), "name: [web\n").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::InvalidPrevious);
+
+    Ok(())
+}
+
+#[test]
+fn test_comments_are_kept() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let previous = "# Deployed by CI\n---\n# The service name\nname: \"web\" # unique\nreplicas: 3\nimage: # pinned\n    # see the registry\n    repository: nginx\n    tag: '1.25'\nports:\n# plain http\n- 80\n- 443 # tls\nenv: {DEBUG: 'false'}\n# end of file\n";
+    let deployment = Deployment {
+        replicas: 4,
+        image: Image {
+            repository: "nginx".to_string(),
+            tag: "1.26".to_string(),
+        },
+        ..deployment()
+    };
+    assert_eq!(
+        facet_yaml::to_string_over(&deployment, previous)?,
+        "# Deployed by CI\n---\n# The service name\nname: \"web\" # unique\nreplicas: 4\nimage: # pinned\n    # see the registry\n    repository: nginx\n    tag: '1.26'\nports:\n# plain http\n- 80\n- 443 # tls\nenv: {DEBUG: 'false'}\n# end of file\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_comments_in_scalars_are_not_comments() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Job {
+        script: String,
+        url: String,
+        retries: u32,
+    }
+
+    let job = Job {
+        script: "# not a comment\necho hi\n".to_string(),
+        url: "http://example.com/#top".to_string(),
+        retries: 2,
+    };
+    let previous = "script: |\n  # not a comment\n  echo hi\nurl: 'http://example.com/#top' # anchor\n# no more than 3\nretries: 1\n";
+    assert_eq!(
+        facet_yaml::to_string_over(&job, previous)?,
+        "script: |\n  # not a comment\n  echo hi\nurl: 'http://example.com/#top' # anchor\n# no more than 3\nretries: 2\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_items_are_matched_by_content() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Service {
+        name: String,
+        port: u16,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Services {
+        services: Vec<Service>,
+        tags: Vec<String>,
+    }
+
+    let previous = "services:\n# the public one\n- name: 'web'\n  port: 80 # http\n- name: \"api\"\n  port: 8080\ntags:\n- 'b' # second\n- \"c\"\n";
+    let services = Services {
+        services: vec![
+            Service {
+                name: "web".to_string(),
+                port: 80,
+            },
+            Service {
+                name: "admin".to_string(),
+                port: 9000,
+            },
+            Service {
+                name: "api".to_string(),
+                port: 8081,
+            },
+        ],
+        tags: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+    };
+    assert_eq!(
+        facet_yaml::to_string_over(&services, previous)?,
+        "services:\n# the public one\n- name: 'web'\n  port: 80 # http\n- name: admin\n  port: 9000\n- name: \"api\"\n  port: 8081\ntags:\n- a\n- 'b' # second\n- \"c\"\n"
+    );
+
+    // Removing an item removes its comments, and leaves the others with their own
+    let services = Services {
+        services: vec![Service {
+            name: "api".to_string(),
+            port: 8080,
+        }],
+        tags: vec!["c".to_string()],
+    };
+    assert_eq!(
+        facet_yaml::to_string_over(&services, previous)?,
+        "services:\n- name: \"api\"\n  port: 8080\ntags:\n- \"c\"\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_multiple_documents() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Scaled {
+        name: String,
+        replicas: u32,
+    }
+
+    let scaled = |name: &str, replicas| Scaled {
+        name: name.to_string(),
+        replicas,
+    };
+    let previous = "# web\nname: web\nreplicas: 2 # scaled up\n...\n# worker\n---\nname: 'worker'\nreplicas: 1\n";
+
+    assert_eq!(
+        facet_yaml::to_string_multi_over(
+            &[scaled("web", 3), scaled("worker", 1), scaled("cron", 1)],
+            previous
+        )?,
+        "# web\nname: web\nreplicas: 3 # scaled up\n...\n# worker\n---\nname: 'worker'\nreplicas: 1\n---\nname: cron\nreplicas: 1\n"
+    );
+    assert_eq!(
+        facet_yaml::to_string_multi_over(&[scaled("web", 3)], previous)?,
+        "# web\nname: web\nreplicas: 3 # scaled up\n...\n"
+    );
+
+    let err = facet_yaml::to_string_over(&scaled("web", 3), previous).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::InvalidPrevious);
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+//! Tests for the `#[facet(yaml(quote))]` attribute.
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::{AmbiguousStrings, SerializeOptions};
+
+use crate::assert_serialize;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Contact {
+    name: String,
+    #[facet(yaml(quote))]
+    phone: String,
+    #[facet(yaml(quote))]
+    nickname: Option<String>,
+    #[facet(yaml(quote))]
+    tags: Vec<String>,
+    #[facet(yaml(quote))]
+    age: u32,
+}
+
+fn contact() -> Contact {
+    Contact {
+        name: "Alice".to_string(),
+        phone: "+31 6 12345678".to_string(),
+        nickname: Some("al".to_string()),
+        tags: vec!["friend".to_string(), "work".to_string()],
+        age: 30,
+    }
+}
+
+#[test]
+fn test_quote_field() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        facet_yaml::to_string(&contact())?,
+        "---\nname: Alice\nphone: \"+31 6 12345678\"\nnickname: \"al\"\ntags:\n  - \"friend\"\n  - \"work\"\nage: 30"
+    );
+    assert_serialize!(Contact, contact());
+
+    Ok(())
+}
+
+#[test]
+fn test_quote_field_regardless_of_options() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Package {
+        #[facet(yaml(quote))]
+        version: String,
+        released: String,
+    }
+
+    let options = SerializeOptions::new().ambiguous_strings(AmbiguousStrings::Tag);
+    let package = Package {
+        version: "2024-01-01".to_string(),
+        released: "2024-01-01".to_string(),
+    };
+    assert_eq!(
+        facet_yaml::to_string_with_options(&package, &options)?,
+        "---\nversion: \"2024-01-01\"\nreleased: !!str 2024-01-01"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_quote_nested_fields() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Book {
+        contacts: Vec<Contact>,
+        owner: Option<Contact>,
+    }
+
+    let book = Book {
+        contacts: vec![contact()],
+        owner: Some(contact()),
+    };
+    let yaml = facet_yaml::to_string(&book)?;
+    assert!(yaml.contains("\n  - name: Alice\n    phone: \"+31 6 12345678\""));
+    assert!(yaml.contains("\nowner:\n  name: Alice\n  phone: \"+31 6 12345678\""));
+    assert_serialize!(Book, book);
+
+    Ok(())
+}
+
+#[test]
+fn test_quote_only_applies_to_the_field() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Inner {
+        value: String,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Outer {
+        #[facet(yaml(quote))]
+        inner: Inner,
+    }
+
+    let outer = Outer {
+        inner: Inner {
+            value: "plain".to_string(),
+        },
+    };
+    assert_eq!(
+        facet_yaml::to_string(&outer)?,
+        "---\ninner:\n  value: plain"
+    );
+
+    Ok(())
+}
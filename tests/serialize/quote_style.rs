@@ -0,0 +1,119 @@
+//! Tests for the styles of quoted strings.
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::{AmbiguousStrings, QuoteStyle, SerializeOptions};
+
+#[derive(Debug, Facet, PartialEq)]
+struct Release {
+    name: String,
+    version: String,
+    enabled: String,
+    notes: Vec<String>,
+    build: u32,
+}
+
+fn release() -> Release {
+    Release {
+        name: "web".to_string(),
+        version: "1.0".to_string(),
+        enabled: "yes".to_string(),
+        notes: vec!["it's fast".to_string(), "tab\there".to_string()],
+        build: 7,
+    }
+}
+
+fn round_trip(options: &SerializeOptions) -> Result<String> {
+    let yaml = facet_yaml::to_string_with_options(&release(), options)?;
+    let deserialized: Release = facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, release());
+    Ok(yaml)
+}
+
+#[test]
+fn test_quote_style_plain() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        round_trip(&SerializeOptions::new())?,
+        "---\nname: web\nversion: \"1.0\"\nenabled: \"yes\"\nnotes:\n  - \"it's fast\"\n  - \"tab\\there\"\nbuild: 7"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_quote_style_single() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = SerializeOptions::new().quote_style(QuoteStyle::Single);
+    assert_eq!(
+        round_trip(&options)?,
+        "---\nname: 'web'\nversion: '1.0'\nenabled: 'yes'\nnotes:\n  - 'it''s fast'\n  - \"tab\\there\"\nbuild: 7"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_quote_style_double() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = SerializeOptions::new().quote_style(QuoteStyle::Double);
+    assert_eq!(
+        round_trip(&options)?,
+        "---\nname: \"web\"\nversion: \"1.0\"\nenabled: \"yes\"\nnotes:\n  - \"it's fast\"\n  - \"tab\\there\"\nbuild: 7"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_quote_style_only_when_ambiguous() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Versions {
+        released: String,
+        padded: String,
+        plain: String,
+    }
+
+    let options = SerializeOptions::new()
+        .quote_style(QuoteStyle::Plain)
+        .ambiguous_strings(AmbiguousStrings::Quote);
+    let versions = Versions {
+        released: "2024-01-01".to_string(),
+        padded: "007".to_string(),
+        plain: "v1".to_string(),
+    };
+    assert_eq!(
+        facet_yaml::to_string_with_options(&versions, &options)?,
+        "---\nreleased: \"2024-01-01\"\npadded: \"007\"\nplain: v1"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_quote_style_keys_only_when_needed() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Flags {
+        on: String,
+        name: String,
+    }
+
+    let options = SerializeOptions::new().quote_style(QuoteStyle::Single);
+    let flags = Flags {
+        on: "now".to_string(),
+        name: "web".to_string(),
+    };
+    assert_eq!(
+        facet_yaml::to_string_with_options(&flags, &options)?,
+        "---\n'on': 'now'\nname: 'web'"
+    );
+
+    Ok(())
+}
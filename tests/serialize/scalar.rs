@@ -29,7 +29,6 @@ fn test_string() -> Result<()> {
 }
 
 #[test]
-#[ignore = "Must be fixed in facet-serialize"]
 fn test_cow_string() -> Result<()> {
     facet_testhelpers::setup();
 
@@ -64,7 +63,6 @@ fn test_bool() -> Result<()> {
 }
 
 #[test]
-#[ignore = "Must be fixed in facet-serialize"]
 fn test_socket_addr() -> Result<()> {
     facet_testhelpers::setup();
 
@@ -84,7 +82,6 @@ fn test_socket_addr() -> Result<()> {
 }
 
 #[test]
-#[ignore = "Must be fixed in facet-serialize"]
 fn test_ip_addr() -> Result<()> {
     facet_testhelpers::setup();
 
@@ -110,7 +107,6 @@ fn test_ip_addr() -> Result<()> {
 }
 
 #[test]
-#[ignore = "Must be fixed in facet-serialize"]
 fn test_ipv4_addr() -> Result<()> {
     facet_testhelpers::setup();
 
@@ -130,7 +126,6 @@ fn test_ipv4_addr() -> Result<()> {
 }
 
 #[test]
-#[ignore = "Must be fixed in facet-serialize"]
 fn test_ipv6_addr() -> Result<()> {
     facet_testhelpers::setup();
 
@@ -177,6 +172,138 @@ fn test_f32() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_float_corpus_round_trips_bit_exactly() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        double: f64,
+        single: f32,
+    }
+
+    // Deterministic xorshift, so failures can be reproduced
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    let edge_cases = [
+        (0.0, 0.0),
+        (-0.0, -0.0),
+        (f64::MIN_POSITIVE, f32::MIN_POSITIVE),
+        (f64::from_bits(1), f32::from_bits(1)),
+        (f64::MIN_POSITIVE / 3.0, f32::MIN_POSITIVE / 3.0),
+        (f64::MAX, f32::MAX),
+        (f64::MIN, f32::MIN),
+        (f64::EPSILON, f32::EPSILON),
+        (1.5e3, 1.5e3),
+        (0.1, 0.1),
+    ];
+    let random = (0..10_000).map(|_| {
+        let bits = next();
+        (f64::from_bits(bits), f32::from_bits(bits as u32))
+    });
+
+    for (double, single) in edge_cases.into_iter().chain(random) {
+        if !double.is_finite() || !single.is_finite() {
+            continue;
+        }
+
+        let value = Root { double, single };
+        let serialized = facet_yaml::to_string(&value)?;
+        let deserialized: Root =
+            facet_yaml::from_str(&serialized).map_err(|err| eyre::eyre!("{err}: {serialized}"))?;
+
+        assert_eq!(
+            deserialized.double.to_bits(),
+            double.to_bits(),
+            "{serialized}"
+        );
+        assert_eq!(
+            deserialized.single.to_bits(),
+            single.to_bits(),
+            "{serialized}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_non_finite_floats() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Root {
+        double: Vec<f64>,
+        single: Vec<f32>,
+    }
+
+    let value = Root {
+        double: vec![f64::INFINITY, f64::NEG_INFINITY, f64::NAN],
+        single: vec![f32::INFINITY, f32::NEG_INFINITY, f32::NAN],
+    };
+    let serialized = facet_yaml::to_string(&value)?;
+    assert_eq!(
+        serialized,
+        "---\ndouble:\n  - .inf\n  - -.inf\n  - .nan\nsingle:\n  - .inf\n  - -.inf\n  - .nan"
+    );
+
+    let deserialized: Root =
+        facet_yaml::from_str(&serialized).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized.double[..2], value.double[..2]);
+    assert!(deserialized.double[2].is_nan());
+    assert_eq!(deserialized.single[..2], value.single[..2]);
+    assert!(deserialized.single[2].is_nan());
+
+    // Strings with the same text stay strings
+    assert_serialize!(Vec<String>, vec![".inf".to_string(), "-.nan".to_string()]);
+
+    Ok(())
+}
+
+#[test]
+fn test_non_finite_floats_disabled() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Limits {
+        ratios: Vec<f64>,
+    }
+
+    let options = facet_yaml::SerializeOptions::new().non_finite_floats(false);
+    let limits = Limits {
+        ratios: vec![0.5, f64::NEG_INFINITY],
+    };
+    let err = facet_yaml::to_string_with_options(&limits, &options).unwrap_err();
+    assert_eq!(err.code(), facet_yaml::ErrorCode::NonFiniteFloat);
+    assert_eq!(
+        err.to_string(),
+        "Float -inf at `ratios[1]` isn't finite, and non-finite floats are disabled"
+    );
+
+    let value: facet_yaml::Value =
+        facet_yaml::from_str("limits: {ratio: .nan}").map_err(|err| eyre::eyre!("{err}"))?;
+    let err = facet_yaml::to_string_with_options(&value, &options).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Float NaN at `limits.ratio` isn't finite, and non-finite floats are disabled"
+    );
+
+    // Finite floats are written as usual
+    let limits = Limits { ratios: vec![0.5] };
+    assert_eq!(
+        facet_yaml::to_string_with_options(&limits, &options)?,
+        "---\nratios:\n  - 0.5"
+    );
+
+    Ok(())
+}
+
 #[test]
 fn test_usize() -> Result<()> {
     facet_testhelpers::setup();
@@ -346,7 +473,6 @@ fn test_i8() -> Result<()> {
 }
 
 #[test]
-#[ignore = "Must be fixed in facet-yaml deserialize"]
 fn test_optional_scalar() -> Result<()> {
     facet_testhelpers::setup();
 
@@ -362,7 +488,6 @@ fn test_optional_scalar() -> Result<()> {
 }
 
 #[test]
-#[ignore = "Must be fixed in facet-yaml deserialize"]
 fn test_nested_optional_scalar() -> Result<()> {
     facet_testhelpers::setup();
 
@@ -383,7 +508,6 @@ fn test_nested_optional_scalar() -> Result<()> {
 }
 
 #[test]
-#[ignore = "Must be fixed in facet-yaml deserialize"]
 fn test_unit() -> Result<()> {
     facet_testhelpers::setup();
 
@@ -398,7 +522,6 @@ fn test_unit() -> Result<()> {
 }
 
 #[test]
-#[ignore = "Must be fixed in facet-yaml deserialize"]
 fn test_unit_option() -> Result<()> {
     facet_testhelpers::setup();
 
@@ -462,3 +585,30 @@ fn test_i128_out_of_range() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_str_pointers() -> Result<()> {
+    facet_testhelpers::setup();
+
+    use std::{rc::Rc, sync::Arc};
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        name: Arc<str>,
+        title: Box<str>,
+        theme: Rc<str>,
+    }
+
+    let root = Root {
+        name: "docs".into(),
+        title: "Docs: home".into(),
+        theme: "true".into(),
+    };
+    assert_eq!(
+        facet_yaml::to_string(&root)?,
+        "---\nname: docs\ntitle: \"Docs: home\"\ntheme: \"true\""
+    );
+    assert_serialize!(Root, root);
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+//! Tests for leaving out empty collections.
+
+use std::collections::BTreeMap;
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::SerializeOptions;
+
+#[derive(Debug, Default, Facet, PartialEq)]
+struct Container {
+    name: String,
+    #[facet(default)]
+    args: Vec<String>,
+    #[facet(default)]
+    env: BTreeMap<String, String>,
+    #[facet(default)]
+    command: Option<String>,
+    #[facet(default)]
+    volumes: Vec<Volume>,
+}
+
+#[derive(Debug, Default, Facet, PartialEq)]
+struct Volume {
+    name: String,
+    #[facet(default)]
+    mounts: Vec<String>,
+}
+
+fn sparse() -> Container {
+    Container {
+        name: "web".to_string(),
+        volumes: vec![Volume {
+            name: "data".to_string(),
+            mounts: Vec::new(),
+        }],
+        ..Container::default()
+    }
+}
+
+#[test]
+fn test_empty_collections_are_written_by_default() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        facet_yaml::to_string(&sparse())?,
+        "---\nname: web\nargs: []\nenv: {}\nvolumes:\n  - name: data\n    mounts: []"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_skip_empty() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = SerializeOptions::new().skip_empty(true);
+    let yaml = facet_yaml::to_string_with_options(&sparse(), &options)?;
+    assert_eq!(yaml, "---\nname: web\nvolumes:\n  - name: data");
+
+    let deserialized: Container =
+        facet_yaml::from_str(&yaml).map_err(|err| eyre::eyre!("{err}"))?;
+    assert_eq!(deserialized, sparse());
+
+    Ok(())
+}
+
+#[test]
+fn test_skip_empty_keeps_items_of_sequences() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = SerializeOptions::new().skip_empty(true);
+    let items: Vec<Vec<u16>> = vec![vec![], vec![1]];
+    assert_eq!(
+        facet_yaml::to_string_with_options(&items, &options)?,
+        "---\n- []\n- - 1"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_skip_empty_attribute() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Service {
+        name: String,
+        #[facet(default, yaml(skip_empty))]
+        ports: Vec<u16>,
+        labels: BTreeMap<String, String>,
+    }
+
+    let service = Service {
+        name: "web".to_string(),
+        ports: Vec::new(),
+        labels: BTreeMap::new(),
+    };
+    assert_eq!(
+        facet_yaml::to_string(&service)?,
+        "---\nname: web\nlabels: {}"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,210 @@
+//! Tests for sorting the keys of mappings.
+
+use std::collections::{BTreeMap, HashMap};
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::{SerializeOptions, SortKeys};
+
+#[derive(Debug, Facet, PartialEq)]
+struct Service {
+    name: String,
+    labels: HashMap<String, String>,
+    image: String,
+}
+
+fn service() -> Service {
+    Service {
+        name: "shop".to_string(),
+        labels: ["tier", "app", "env", "team", "version", "region"]
+            .into_iter()
+            .map(|key| (key.to_string(), format!("{key}-value")))
+            .collect(),
+        image: "shop:1".to_string(),
+    }
+}
+
+#[test]
+fn test_sort_map_keys() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = SerializeOptions::new().sort_keys(SortKeys::Maps);
+    assert_eq!(
+        facet_yaml::to_string_with_options(&service(), &options)?,
+        "---\nname: shop\nlabels:\n  app: app-value\n  env: env-value\n  region: region-value\n  team: team-value\n  tier: tier-value\n  version: version-value\nimage: \"shop:1\""
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_sort_map_keys_and_struct_fields() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = SerializeOptions::new()
+        .sort_keys(SortKeys::MapsAndStructs)
+        .flow_depth(1);
+    assert_eq!(
+        facet_yaml::to_string_with_options(&service(), &options)?,
+        "---\nimage: \"shop:1\"\nlabels: {app: app-value, env: env-value, region: region-value, team: team-value, tier: tier-value, version: version-value}\nname: shop"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_sorted_output_is_reproducible() -> Result<()> {
+    facet_testhelpers::setup();
+
+    // Every map has its own random iteration order
+    let options = SerializeOptions::new().sort_keys(SortKeys::Maps);
+    let first = facet_yaml::to_string_with_options(&service(), &options)?;
+    for _ in 0..10 {
+        assert_eq!(
+            facet_yaml::to_string_with_options(&service(), &options)?,
+            first
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_btree_map_keeps_key_order() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq, Eq, PartialOrd, Ord)]
+    #[repr(u8)]
+    enum Priority {
+        Low,
+        Medium,
+        High,
+    }
+
+    let queues = BTreeMap::from([
+        (Priority::High, 2),
+        (Priority::Low, 40),
+        (Priority::Medium, 7),
+    ]);
+    for sort_keys in [SortKeys::Never, SortKeys::Maps, SortKeys::MapsAndStructs] {
+        let options = SerializeOptions::new().sort_keys(sort_keys);
+        assert_eq!(
+            facet_yaml::to_string_with_options(&queues, &options)?,
+            "---\nLow: 40\nMedium: 7\nHigh: 2"
+        );
+    }
+
+    Ok(())
+}
+
+/// Keys that are written quoted or escaped, so they aren't read back as another value.
+const TRICKY_KEYS: &[&str] = &[
+    "",
+    "null",
+    "~",
+    "Null",
+    "true",
+    "false",
+    "yes",
+    "no",
+    "on",
+    "off",
+    "1",
+    "-1",
+    "0x10",
+    "1.5",
+    "1e3",
+    ".inf",
+    ".nan",
+    "2024-01-01",
+    "-",
+    "- a",
+    "a: b",
+    ":",
+    "?",
+    "#x",
+    "a #b",
+    "&a",
+    "*a",
+    "!tag",
+    "%",
+    "@",
+    "`",
+    "|",
+    ">",
+    "<<",
+    "---",
+    "...",
+    "'",
+    "\"",
+    "[",
+    "]",
+    "{",
+    "}",
+    ",",
+    "\\",
+    " lead",
+    "trail ",
+    "multi\nline",
+    "tab\there",
+    "é",
+    "日本語",
+    "🎉",
+];
+
+#[test]
+fn test_tricky_keys_round_trip() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let hash_map: HashMap<String, String> = TRICKY_KEYS
+        .iter()
+        .enumerate()
+        .map(|(index, key)| (key.to_string(), format!("{key}{index}")))
+        .collect();
+    let btree_map: BTreeMap<String, String> = hash_map.clone().into_iter().collect();
+
+    for sort_keys in [SortKeys::Never, SortKeys::Maps, SortKeys::MapsAndStructs] {
+        for flow_depth in [None, Some(0)] {
+            let mut options = SerializeOptions::new().sort_keys(sort_keys);
+            if let Some(depth) = flow_depth {
+                options = options.flow_depth(depth);
+            }
+
+            let yaml = facet_yaml::to_string_with_options(&hash_map, &options)?;
+            assert_eq!(
+                facet_yaml::from_str::<HashMap<String, String>>(&yaml)?,
+                hash_map,
+                "{yaml}"
+            );
+
+            let yaml = facet_yaml::to_string_with_options(&btree_map, &options)?;
+            assert_eq!(
+                facet_yaml::from_str::<BTreeMap<String, String>>(&yaml)?,
+                btree_map,
+                "{yaml}"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_sorted_hash_map_is_written_like_btree_map() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let hash_map: HashMap<String, u32> = TRICKY_KEYS
+        .iter()
+        .enumerate()
+        .map(|(index, key)| (key.to_string(), index as u32))
+        .collect();
+    let btree_map: BTreeMap<String, u32> = hash_map.clone().into_iter().collect();
+
+    let options = SerializeOptions::new().sort_keys(SortKeys::Maps);
+    assert_eq!(
+        facet_yaml::to_string_with_options(&hash_map, &options)?,
+        facet_yaml::to_string(&btree_map)?
+    );
+
+    Ok(())
+}
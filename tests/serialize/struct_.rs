@@ -165,7 +165,6 @@ fn test_default_struct_fields() -> Result<()> {
 }
 
 #[test]
-#[ignore = "must be fixed in deserialize"]
 fn test_optional_default_struct_fields() -> Result<()> {
     facet_testhelpers::setup();
 
@@ -190,3 +189,58 @@ fn test_optional_default_struct_fields() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_skip_serializing_fields() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Config {
+        name: String,
+        #[facet(skip_serializing)]
+        secret: String,
+        #[facet(default, yaml(skip_deserializing))]
+        checksum: String,
+    }
+
+    let config = Config {
+        name: "web".to_string(),
+        secret: "hunter2".to_string(),
+        checksum: "abc123".to_string(),
+    };
+    assert_eq!(
+        facet_yaml::to_string(&config)?,
+        "---\nname: web\nchecksum: abc123"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_tuple_structs() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Point(f64, f64);
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Meters(f64);
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        origin: Point,
+        size: Meters,
+    }
+
+    let root = Root {
+        origin: Point(1.0, 2.5),
+        size: Meters(3.5),
+    };
+    assert_eq!(
+        facet_yaml::to_string(&root)?,
+        "---\norigin:\n  - 1.0\n  - 2.5\nsize: 3.5"
+    );
+    assert_serialize!(Root, root);
+
+    Ok(())
+}
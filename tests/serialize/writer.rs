@@ -0,0 +1,159 @@
+//! Tests for serializing into writers.
+
+use std::{fmt, io, ops::ControlFlow};
+
+use eyre::Result;
+use facet::Facet;
+use facet_yaml::{ErrorCode, SerializeOptions, YamlSerError};
+
+#[derive(Debug, Facet, PartialEq)]
+struct Config {
+    name: String,
+    workers: Vec<u16>,
+}
+
+fn config() -> Config {
+    Config {
+        name: "web".to_string(),
+        workers: vec![1, 2],
+    }
+}
+
+#[test]
+fn test_to_writer_matches_to_string() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let mut out = Vec::new();
+    facet_yaml::to_writer(&mut out, &config())?;
+
+    assert_eq!(String::from_utf8(out)?, facet_yaml::to_string(&config())?);
+
+    Ok(())
+}
+
+#[test]
+fn test_to_writer_io_error() -> Result<()> {
+    facet_testhelpers::setup();
+
+    /// A writer that's always full.
+    struct Full;
+
+    impl io::Write for Full {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::StorageFull, "disk is full"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let err = facet_yaml::to_writer(&mut Full, &config()).unwrap_err();
+    assert!(
+        matches!(&err, YamlSerError::Io(err) if err.kind() == io::ErrorKind::StorageFull),
+        "{err}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_to_writer_with_progress() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = SerializeOptions::new().progress_interval(1);
+    let mut out = Vec::new();
+    let mut reports = Vec::new();
+    facet_yaml::to_writer_with_progress(&mut out, &config(), &options, |progress| {
+        reports.push((progress.bytes_written(), progress.path().to_string()));
+        ControlFlow::Continue(())
+    })?;
+
+    assert_eq!(String::from_utf8(out)?, facet_yaml::to_string(&config())?);
+    assert_eq!(
+        reports,
+        [
+            (4, "name".to_string()),
+            (13, "workers".to_string()),
+            (25, "workers[0]".to_string()),
+            (28, "workers[1]".to_string()),
+            (34, String::new()),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_to_writer_with_progress_cancelled() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = SerializeOptions::new().progress_interval(1);
+    let mut out = Vec::new();
+    let err = facet_yaml::to_writer_with_progress(&mut out, &config(), &options, |progress| {
+        if progress.path() == "workers[1]" {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    })
+    .unwrap_err();
+
+    assert_eq!(err.code(), ErrorCode::Cancelled);
+    assert_eq!(
+        err.to_string(),
+        "Serialization was cancelled before `workers[1]` was written"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_to_vec_matches_to_string() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let bytes = facet_yaml::to_vec(&config())?;
+    assert_eq!(String::from_utf8(bytes)?, facet_yaml::to_string(&config())?);
+
+    let options = SerializeOptions::new().document_start(false);
+    let bytes = facet_yaml::to_vec_with_options(&config(), &options)?;
+    assert_eq!(bytes, b"name: web\nworkers:\n  - 1\n  - 2");
+
+    Ok(())
+}
+
+#[test]
+fn test_write_yaml_appends() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let mut out = String::from("# generated\n");
+    facet_yaml::write_yaml(&mut out, &config())?;
+
+    assert_eq!(
+        out,
+        format!("# generated\n{}", facet_yaml::to_string(&config())?)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_write_yaml_error() -> Result<()> {
+    facet_testhelpers::setup();
+
+    /// A writer that takes a few bytes only.
+    struct Limited(usize);
+
+    impl fmt::Write for Limited {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.0 = self.0.checked_sub(s.len()).ok_or(fmt::Error)?;
+            Ok(())
+        }
+    }
+
+    let err = facet_yaml::write_yaml(&mut Limited(8), &config()).unwrap_err();
+    assert!(matches!(err, YamlSerError::Write), "{err}");
+    assert_eq!(err.code(), ErrorCode::Write);
+
+    Ok(())
+}
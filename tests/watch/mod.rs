@@ -0,0 +1,58 @@
+use std::{fs, path::Path, sync::mpsc, time::Duration};
+
+use facet::Facet;
+use facet_testhelpers::test;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Config {
+    workers: u16,
+}
+
+/// Replace the file the way editors do, so the watcher never sees it half written.
+fn save(path: &Path, contents: &str) {
+    let temporary = path.with_extension("yaml.tmp");
+    fs::write(&temporary, contents).unwrap();
+    fs::rename(&temporary, path).unwrap();
+}
+
+#[test]
+fn test_watch_reloads_on_change() {
+    let directory = std::env::temp_dir().join(format!("facet-yaml-watch-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("config.yaml");
+    fs::write(&path, "workers: 1\n").unwrap();
+
+    let (sender, receiver) = mpsc::channel();
+    let watcher = facet_yaml::watch::<Config, _>(&path, move |config| {
+        let _ = sender.send(config.map_err(|err| err.to_string()));
+    })
+    .unwrap();
+    let next = || receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+
+    // The current contents are loaded right away
+    assert_eq!(next(), Ok(Config { workers: 1 }));
+
+    save(&path, "workers: 4\n");
+    assert_eq!(next(), Ok(Config { workers: 4 }));
+
+    // Invalid contents are reported, so the previous value can be kept
+    save(&path, "workers: -1\n");
+    assert!(next().is_err());
+
+    save(&path, "workers: 8\n");
+    assert_eq!(next(), Ok(Config { workers: 8 }));
+
+    drop(watcher);
+    fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn test_watch_reports_missing_file() {
+    let path = std::env::temp_dir().join("facet-yaml-watch-missing/config.yaml");
+
+    let (sender, receiver) = mpsc::channel();
+    let _ = facet_yaml::watch::<Config, _>(&path, move |config| {
+        let _ = sender.send(config.is_err());
+    });
+    assert_eq!(receiver.recv_timeout(Duration::from_secs(10)), Ok(true));
+}
@@ -0,0 +1,359 @@
+//! Runs the [yaml-test-suite](https://github.com/yaml/yaml-test-suite) through the parser and
+//! the emitter, to see how much of the spec is covered.
+//!
+//! The suite isn't part of the repository. Clone it into `tests/yaml-test-suite`, or point the
+//! `YAML_TEST_SUITE` environment variable at a clone, and run:
+//!
+//! ```text
+//! cargo test --features yaml-test-suite yaml_test_suite -- --nocapture
+//! ```
+//!
+//! Every case is checked by two layers:
+//!
+//! - `parse`: the events of the parser match the ones in the suite, or the parser fails on
+//!   cases that are invalid YAML.
+//! - `emit`: a valid single document is loaded as a [`Value`], serialized, and loaded back to
+//!   the same value.
+//!
+//! Without a clone the test is skipped, with a message saying where the suite was looked for.
+//!
+//! Failures listed in `known_failures.txt` are reported but don't fail the test, while cases
+//! that are listed there and now pass do, so the list stays accurate. Setting
+//! `YAML_TEST_SUITE_BLESS=1` rewrites the list with the current failures.
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+};
+
+use facet_testhelpers::test;
+use facet_yaml::Value;
+use yaml_rust2::{
+    Yaml, YamlLoader,
+    parser::{Event, EventReceiver, Parser, Tag},
+    scanner::TScalarStyle,
+};
+
+/// A case of the suite, with the visual markers of its YAML replaced.
+struct Case {
+    /// Identifier of the test, followed by the index of the case for tests with several.
+    name: String,
+    /// The document.
+    yaml: String,
+    /// The events the parser should produce, one per line.
+    tree: String,
+    /// Whether the document is invalid YAML.
+    fail: bool,
+}
+
+#[test]
+fn test_yaml_test_suite() {
+    let Some(directory) = suite_directory() else {
+        return;
+    };
+    let cases = load_cases(&directory.join("src"));
+    let known = known_failures();
+
+    let mut failures = BTreeSet::new();
+    let mut unexpected = Vec::new();
+    let mut totals = [("parse", 0, 0), ("emit", 0, 0)];
+    for case in &cases {
+        let results = [
+            ("parse", Some(check_parse(case))),
+            ("emit", check_emit(case)),
+        ];
+        for ((layer, result), total) in results.into_iter().zip(&mut totals) {
+            let Some(result) = result else {
+                continue;
+            };
+            total.1 += 1;
+            let id = format!("{layer} {}", case.name);
+            match result {
+                Ok(()) => total.2 += 1,
+                Err(message) => {
+                    if !known.contains(&id) {
+                        unexpected.push(format!("{id}: {message}\n--- input:\n{}", case.yaml));
+                    }
+                    failures.insert(id);
+                }
+            }
+        }
+    }
+
+    for (layer, checked, passed) in totals {
+        println!("yaml-test-suite {layer}: {passed}/{checked} cases pass");
+    }
+
+    if std::env::var_os("YAML_TEST_SUITE_BLESS").is_some() {
+        let mut list = String::from(KNOWN_FAILURES_HEADER);
+        for id in &failures {
+            list += id;
+            list.push('\n');
+        }
+        fs::write(known_failures_path(), list).unwrap();
+        return;
+    }
+
+    let fixed: Vec<_> = known.difference(&failures).collect();
+    assert!(
+        unexpected.is_empty() && fixed.is_empty(),
+        "{} cases failed:\n\n{}\n\n{} known failures pass now, remove them from known_failures.txt: {fixed:?}",
+        unexpected.len(),
+        unexpected.join("\n\n"),
+        fixed.len(),
+    );
+}
+
+/// The first lines of `known_failures.txt`, kept when it's rewritten.
+const KNOWN_FAILURES_HEADER: &str = "\
+# Cases of the yaml-test-suite that are known to fail, as `<layer> <case>`.
+# Regenerate with `YAML_TEST_SUITE_BLESS=1 cargo test --features yaml-test-suite yaml_test_suite`.
+";
+
+fn known_failures_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/yaml_test_suite/known_failures.txt")
+}
+
+fn known_failures() -> BTreeSet<String> {
+    fs::read_to_string(known_failures_path())
+        .unwrap()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(Into::into)
+        .collect()
+}
+
+/// Find the clone of the suite, or say where it should be and get `None` if there isn't one.
+fn suite_directory() -> Option<PathBuf> {
+    let directory = std::env::var_os("YAML_TEST_SUITE").map_or_else(
+        || Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/yaml-test-suite"),
+        PathBuf::from,
+    );
+    if !directory.join("src").is_dir() {
+        eprintln!(
+            "Skipping the yaml-test-suite, which isn't in {}: clone \
+             https://github.com/yaml/yaml-test-suite there or set YAML_TEST_SUITE to a clone",
+            directory.display()
+        );
+        return None;
+    }
+    Some(directory)
+}
+
+/// Read the cases of every test, in the order of their names. The fields of a case are
+/// inherited by the following ones of the same test, except `fail`.
+fn load_cases(directory: &Path) -> Vec<Case> {
+    let mut paths: Vec<_> = fs::read_dir(directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "yaml")
+        })
+        .collect();
+    paths.sort();
+
+    let mut cases = Vec::new();
+    for path in paths {
+        let test = path.file_stem().unwrap().to_string_lossy().into_owned();
+        let docs = YamlLoader::load_from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let entries = docs[0].as_vec().unwrap();
+
+        let mut current = yaml_rust2::yaml::Hash::new();
+        for (index, entry) in entries.iter().enumerate() {
+            current.remove(&Yaml::String("fail".into()));
+            current.extend(entry.as_hash().unwrap().clone());
+            let current = Yaml::Hash(current.clone());
+            if !current["skip"].is_badvalue() {
+                continue;
+            }
+
+            let name = if entries.len() > 1 {
+                format!("{test}-{index:02}")
+            } else {
+                test.clone()
+            };
+            cases.push(Case {
+                name,
+                yaml: visual_to_raw(current["yaml"].as_str().unwrap()),
+                tree: visual_to_raw(current["tree"].as_str().unwrap_or_default()),
+                fail: current["fail"].as_bool() == Some(true),
+            });
+        }
+    }
+    cases
+}
+
+/// Replace the markers the suite uses to show whitespace and other invisible characters.
+fn visual_to_raw(yaml: &str) -> String {
+    [
+        ("␣", " "),
+        ("»", "\t"),
+        ("—", ""),
+        ("←", "\r"),
+        ("⇔", "\u{feff}"),
+        ("↵", ""),
+        ("∎\n", ""),
+    ]
+    .into_iter()
+    .fold(yaml.into(), |yaml, (marker, raw)| yaml.replace(marker, raw))
+}
+
+/// Compare the events of the parser with the tree of the case.
+fn check_parse(case: &Case) -> Result<(), String> {
+    let mut recorder = EventRecorder::default();
+    let parsed = catch(|| Parser::new_from_str(&case.yaml).load(&mut recorder, true))?;
+    match (parsed, case.fail) {
+        (Ok(()), true) => Err("Invalid YAML was parsed".into()),
+        (Err(_), true) => Ok(()),
+        (Err(err), false) => Err(format!("Valid YAML wasn't parsed: {err}")),
+        (Ok(()), false) => {
+            let expected = expected_events(&case.tree);
+            if recorder.events == expected {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Events differ\n--- expected:\n{}\n--- parsed:\n{}",
+                    expected.join("\n"),
+                    recorder.events.join("\n")
+                ))
+            }
+        }
+    }
+}
+
+/// Serialize the value of a single valid document and load it back, if the case has one.
+fn check_emit(case: &Case) -> Option<Result<(), String>> {
+    if case.fail || case.tree.matches("+DOC").count() != 1 {
+        return None;
+    }
+
+    Some(
+        catch(|| {
+            let value = facet_yaml::from_str::<Value>(&case.yaml)
+                .map_err(|err| format!("The document wasn't loaded: {err}"))?;
+            let emitted = facet_yaml::to_string(&value)
+                .map_err(|err| format!("The value wasn't serialized: {err}"))?;
+            let reloaded = facet_yaml::from_str::<Value>(&emitted)
+                .map_err(|err| format!("The serialized value wasn't loaded: {err}\n{emitted}"))?;
+            if reloaded == value {
+                Ok(())
+            } else {
+                Err(format!("The value changed when serialized as:\n{emitted}"))
+            }
+        })
+        .and_then(|result| result),
+    )
+}
+
+/// Run a check, reporting a panic as a failure of the case instead of the whole test.
+fn catch<T>(check: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(check)).map_err(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        format!("Panicked: {message}")
+    })
+}
+
+/// Records the events of the parser in the format of the suite.
+#[derive(Default)]
+struct EventRecorder {
+    events: Vec<String>,
+}
+
+impl EventReceiver for EventRecorder {
+    fn on_event(&mut self, event: Event) {
+        let line = match event {
+            Event::StreamStart => "+STR".into(),
+            Event::StreamEnd => "-STR".into(),
+            Event::DocumentStart => "+DOC".into(),
+            Event::DocumentEnd => "-DOC".into(),
+            Event::SequenceStart(anchor, tag) => {
+                format!("+SEQ{}{}", anchor_text(anchor), tag_text(tag.as_ref()))
+            }
+            Event::SequenceEnd => "-SEQ".into(),
+            Event::MappingStart(anchor, tag) => {
+                format!("+MAP{}{}", anchor_text(anchor), tag_text(tag.as_ref()))
+            }
+            Event::MappingEnd => "-MAP".into(),
+            Event::Scalar(value, style, anchor, tag) => {
+                let style = match style {
+                    TScalarStyle::Plain => ":",
+                    TScalarStyle::SingleQuoted => "'",
+                    TScalarStyle::DoubleQuoted => "\"",
+                    TScalarStyle::Literal => "|",
+                    TScalarStyle::Folded => ">",
+                };
+                let value = value
+                    .replace('\\', "\\\\")
+                    .replace('\n', "\\n")
+                    .replace('\r', "\\r")
+                    .replace('\x08', "\\b")
+                    .replace('\t', "\\t");
+                format!(
+                    "=VAL{}{} {style}{value}",
+                    anchor_text(anchor),
+                    tag_text(tag.as_ref())
+                )
+            }
+            Event::Alias(anchor) => format!("=ALI *{anchor}"),
+            Event::Nothing => return,
+        };
+        self.events.push(line);
+    }
+}
+
+fn anchor_text(anchor: usize) -> String {
+    if anchor == 0 {
+        String::new()
+    } else {
+        format!(" &{anchor}")
+    }
+}
+
+fn tag_text(tag: Option<&Tag>) -> String {
+    tag.map(|tag| format!(" <{}{}>", tag.handle, tag.suffix))
+        .unwrap_or_default()
+}
+
+/// The events of a tree, without what the parser doesn't report: whether collections are flow
+/// or block, whether document markers are explicit, and the names of anchors, which are
+/// numbered in order instead.
+fn expected_events(tree: &str) -> Vec<String> {
+    let mut anchors: Vec<String> = Vec::new();
+    let mut events = Vec::new();
+    for line in tree.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let mut event = match line {
+            "+DOC ---" => "+DOC".to_string(),
+            "-DOC ..." => "-DOC".to_string(),
+            _ => line
+                .replacen("+SEQ []", "+SEQ", 1)
+                .replacen("+MAP {}", "+MAP", 1),
+        };
+
+        if let Some(name) = event.strip_prefix("=ALI *") {
+            let index = anchors.iter().rposition(|anchor| anchor == name).unwrap();
+            event = format!("=ALI *{}", index + 1);
+        } else {
+            // Anchors come before the value of scalars, which starts with its style
+            let value_start = event
+                .find([':', '\'', '"', '|', '>'])
+                .unwrap_or(event.len());
+            if let Some(start) = event[..value_start].find('&') {
+                let end = event[start..]
+                    .find(' ')
+                    .map_or(event.len(), |end| start + end);
+                anchors.push(event[start + 1..end].into());
+                event.replace_range(start..end, &format!("&{}", anchors.len()));
+            }
+        }
+        events.push(event);
+    }
+    events
+}